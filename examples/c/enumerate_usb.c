@@ -0,0 +1,39 @@
+// Copyright 2025 NXP
+//
+// SPDX-License-Identifier: BSD-3-Clause
+/* List USB-HID devices with NXP VID and read CurrentVersion tag from each of them. */
+#include "../../include/mboot.h"
+#include <stdio.h>
+#include <stdlib.h>
+
+int main(void) {
+    size_t count;
+    MBOOT_CUsbDevice *devices = mboot_enumerate_usb(0x1FC9, 0, &count);
+    if (devices == NULL) {
+        fprintf(stderr, "USB devices could not be listed\n");
+        exit(EXIT_FAILURE);
+    }
+
+    for (size_t i = 0; i < count; i++) {
+        printf("found %04X:%04X serial %s at %s\n", devices[i].vid, devices[i].pid,
+               devices[i].serial != NULL ? devices[i].serial : "-", devices[i].path);
+
+        MBOOT_CMcuBoot *device = mboot_create(devices[i].identifier, MBOOT_C_PROTOCOL_USB);
+        if (device == NULL) {
+            fprintf(stderr, "device could not be opened\n");
+            continue;
+        }
+
+        MBOOT_CGetPropertyResponse response;
+        // tag number 1 is for current version
+        MBOOT_CStatus status = mboot_get_property(device, 1, 0, &response);
+        if (status >= 0) {
+            printf("current version word: 0x%X\n", response.response_words[0]);
+            mboot_free_response_words(response.response_words);
+        }
+        mboot_destroy(device);
+    }
+
+    mboot_free_usb_devices(devices, count);
+    return EXIT_SUCCESS;
+}
@@ -17,3 +17,8 @@ with McuBoot(device) as boot:
     print(f"Status code: {boot.status_code}")
     print(f"Status string: {boot.status_code_str}")
     print(f"Property value: {response}")
+
+    parsed = boot.get_property_parsed(PropertyTag.ReservedRegions)
+    print(parsed)
+    for start, end in parsed.reserved_regions:
+        print(f"Reserved region: {start:#010x} - {end:#010x}")
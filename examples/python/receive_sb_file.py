@@ -0,0 +1,25 @@
+# Copyright 2025 NXP
+#
+# SPDX-License-Identifier: BSD-3-Clause
+"""Send SB file given as the second argument to UART device, specified as the first argument to the script, while
+printing the progress."""
+import sys
+from pymboot import McuBoot
+
+if len(sys.argv) < 3:
+    print("specify a UART device and an SB file as arguments")
+    exit(1)
+
+
+def progress(sent, total):
+    print(f"\rsent {sent} of {total} bytes", end="", flush=True)
+
+
+with open(sys.argv[2], "rb") as sb_file:
+    data = sb_file.read()
+
+with McuBoot(sys.argv[1]) as boot:
+    success = boot.receive_sb_file(data, progress=progress)
+    print()
+    print(f"Status string: {boot.status_code_str}")
+    exit(0 if success else 1)
@@ -0,0 +1,16 @@
+# Copyright 2025 NXP
+#
+# SPDX-License-Identifier: BSD-3-Clause
+"""List USB-HID devices and read CurrentVersion tag from each of them, optionally only devices with VID given as the
+first argument to the script."""
+import sys
+from pymboot import McuBoot, PropertyTag, scan_usb
+
+vid = int(sys.argv[1], 0) if len(sys.argv) > 1 else None
+
+for device in scan_usb(vid):
+    print(f"found {device}")
+    with McuBoot(device.identifier, interface="usb", timeout=1000) as boot:
+        response = boot.get_property(PropertyTag.CurrentVersion)
+        print(f"Status string: {boot.status_code_str}")
+        print(f"Property value: {response}")
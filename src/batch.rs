@@ -0,0 +1,284 @@
+// Copyright 2025 NXP
+//
+// SPDX-License-Identifier: BSD-3-Clause
+//! Parsing of batch scripts and reporting of their results for the batch subcommand
+
+use std::time::Duration;
+
+use clap::Parser;
+use color_print::cformat;
+use log::info;
+use mboot::CommunicationError;
+
+use crate::{Commands, WriteData};
+
+/// Single command of a batch script
+#[derive(Debug)]
+pub struct BatchCommand {
+    /// Line number in the script, starting from 1
+    pub line: usize,
+    /// Command as written in the script
+    pub text: String,
+    /// Parsed command
+    pub command: Commands,
+}
+
+/// Parser of one script line, which contains just the subcommand with its arguments
+#[derive(Parser, Debug)]
+#[command(no_binary_name = true)]
+struct BatchLine {
+    #[command(subcommand)]
+    command: Commands,
+}
+
+/// Parses the whole script, so syntax errors are reported before any command is sent
+pub fn parse_batch_script(content: &str) -> Result<Vec<BatchCommand>, CommunicationError> {
+    let mut commands = Vec::new();
+    for (index, text) in content.lines().enumerate() {
+        let line = index + 1;
+        let text = text.trim();
+        if text.is_empty() || text.starts_with('#') {
+            continue;
+        }
+        let arguments =
+            split_arguments(text).map_err(|err| CommunicationError::ParseError(format!("line {line}: {err}")))?;
+        let command = BatchLine::try_parse_from(arguments.iter().filter(|arg| *arg != "--"))
+            .map_err(|err| CommunicationError::ParseError(format!("line {line}: {err}")))?
+            .command;
+        match command {
+            Commands::Batch { .. } => {
+                return Err(CommunicationError::ParseError(format!(
+                    "line {line}: batch scripts cannot be nested"
+                )));
+            }
+            Commands::Completions { .. }
+            | Commands::CompleteDevices { .. }
+            | Commands::Errors { .. }
+            | Commands::Describe { .. }
+            | Commands::ListPorts
+            | Commands::Config { .. } => {
+                return Err(CommunicationError::ParseError(format!(
+                    "line {line}: command does not communicate with the device"
+                )));
+            }
+            _ => {}
+        }
+        commands.push(BatchCommand {
+            line,
+            text: text.to_owned(),
+            command,
+        });
+    }
+    Ok(commands)
+}
+
+/// Merges consecutive writes of raw bytes to contiguous addresses of the same memory into one write
+///
+/// Only writes with the same --verify and --force options are merged.
+///
+/// The merged command keeps the line of the first write, its text lists the texts of all merged lines.
+/// Every command has its own overhead of the command packet and the responses, which dominates writes of
+/// a few bytes like option words on slow links.
+pub fn coalesce_writes(commands: Vec<BatchCommand>) -> Vec<BatchCommand> {
+    let mut coalesced: Vec<BatchCommand> = Vec::with_capacity(commands.len());
+    for command in commands {
+        if let Some(last) = coalesced.last_mut()
+            && let Commands::WriteMemory {
+                start_address,
+                data: WriteData::Bytes(bytes),
+                memory_id,
+                verify,
+                force,
+            } = &mut last.command
+            && let Commands::WriteMemory {
+                start_address: next_address,
+                data: WriteData::Bytes(next_bytes),
+                memory_id: next_memory_id,
+                verify: next_verify,
+                force: next_force,
+            } = &command.command
+            && next_memory_id == memory_id
+            && next_verify == verify
+            && next_force == force
+            && u64::from(*start_address) + bytes.len() as u64 == u64::from(*next_address)
+            && u32::try_from(bytes.len() + next_bytes.len()).is_ok()
+        {
+            info!("write on line {} is coalesced with line {}", command.line, last.line);
+            *bytes = [&bytes[..], &next_bytes[..]].concat().into_boxed_slice();
+            last.text = format!("{}; {}", last.text, command.text);
+            continue;
+        }
+        coalesced.push(command);
+    }
+    coalesced
+}
+
+/// Result of one executed batch command
+#[derive(Debug)]
+pub struct BatchResult {
+    /// Line number in the script
+    pub line: usize,
+    /// Command as written in the script
+    pub text: String,
+    /// How long the command took
+    pub duration: Duration,
+    /// Name of the final status or description of the error
+    pub status: String,
+    /// Whether the command succeeded
+    pub passed: bool,
+    /// Number of requests of the command sent again after a failure of the transport
+    pub retries: u32,
+    /// JSON document reported by the command, only collected with JSON output
+    pub output: Option<serde_json::Value>,
+}
+
+/// Maximal width of the command column in the summary table
+const COMMAND_WIDTH: usize = 40;
+
+/// Builds the JSON document summarizing executed commands
+pub fn summary_json(results: &[BatchResult], total: usize) -> serde_json::Value {
+    let passed = results.iter().filter(|result| result.passed).count();
+    let commands: Vec<_> = results
+        .iter()
+        .map(|result| {
+            serde_json::json!({
+                "line": result.line,
+                "command": result.text,
+                "duration_ms": result.duration.as_millis(),
+                "status": result.status,
+                "passed": result.passed,
+                "retries": result.retries,
+                "output": result.output,
+            })
+        })
+        .collect();
+    serde_json::json!({
+        "commands": commands,
+        "total": total,
+        "passed": passed,
+        "result": if passed == total { "PASSED" } else { "FAILED" },
+    })
+}
+
+/// Prints the summary table of executed commands
+pub fn print_summary(results: &[BatchResult], total: usize) {
+    let passed = results.iter().filter(|result| result.passed).count();
+    let all_passed = passed == total;
+
+    println!();
+    println!(
+        "{}",
+        cformat!(
+            "<bold>{:>4}  {:<COMMAND_WIDTH$}  {:>10}  {:<24}  {:>7}</>",
+            "Line",
+            "Command",
+            "Duration",
+            "Status",
+            "Retries"
+        )
+    );
+    for result in results {
+        let mut text = result.text.clone();
+        if text.chars().count() > COMMAND_WIDTH {
+            text = text.chars().take(COMMAND_WIDTH - 3).collect::<String>() + "...";
+        }
+        let status = if result.passed {
+            cformat!("<g>{:<24}</>", result.status)
+        } else {
+            cformat!("<r>{:<24}</>", result.status)
+        };
+        println!(
+            "{:>4}  {text:<COMMAND_WIDTH$}  {:>8.3} s  {status}  {:>7}",
+            result.line,
+            result.duration.as_secs_f64(),
+            result.retries
+        );
+    }
+    let verdict = if all_passed {
+        cformat!("<g!>PASSED</>")
+    } else {
+        cformat!("<r!>FAILED</>")
+    };
+    println!("Result: {verdict} ({passed} of {total} commands succeeded)");
+}
+
+/// Splits a line into arguments on whitespace, single and double quotes group words together
+fn split_arguments(text: &str) -> Result<Vec<String>, String> {
+    let mut arguments = Vec::new();
+    let mut current: Option<String> = None;
+    let mut quote = None;
+    for c in text.chars() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (None, '"' | '\'') => {
+                quote = Some(c);
+                current.get_or_insert_default();
+            }
+            (None, c) if c.is_whitespace() => arguments.extend(current.take()),
+            (_, c) => current.get_or_insert_default().push(c),
+        }
+    }
+    if quote.is_some() {
+        return Err("unterminated quote".to_owned());
+    }
+    arguments.extend(current);
+    Ok(arguments)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_script() {
+        let script = "# provisioning\n\nflash-erase-all\n-- read-memory 0x0 16 'dump file.bin'\n";
+        let commands = parse_batch_script(script).unwrap();
+        assert_eq!(commands.len(), 2);
+        assert_eq!(commands[0].line, 3);
+        assert!(matches!(
+            &commands[1].command,
+            Commands::ReadMemory { byte_count: 16, file: Some(file), .. } if file == "dump file.bin"
+        ));
+    }
+
+    #[test]
+    fn contiguous_writes_are_coalesced() {
+        let script = "write-memory 0x100 {{0102}}\nwrite-memory 0x102 {{03}}\nwrite-memory 0x103 {{04}} 1\n\
+                      write-memory 0x104 {{05}} 1\nwrite-memory 0x200 {{06}} 1\nreset\nwrite-memory 0x201 {{07}} 1\n";
+        let commands = coalesce_writes(parse_batch_script(script).unwrap());
+        let writes: Vec<_> = commands
+            .iter()
+            .map(|command| match &command.command {
+                Commands::WriteMemory {
+                    start_address,
+                    data: WriteData::Bytes(bytes),
+                    memory_id,
+                    ..
+                } => Some((command.line, *start_address, bytes.to_vec(), *memory_id)),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(
+            writes,
+            [
+                Some((1, 0x100, vec![1, 2, 3], 0)),
+                Some((3, 0x103, vec![4, 5], 1)),
+                Some((5, 0x200, vec![6], 1)),
+                None,
+                Some((7, 0x201, vec![7], 1)),
+            ]
+        );
+        assert_eq!(
+            commands[0].text,
+            "write-memory 0x100 {{0102}}; write-memory 0x102 {{03}}"
+        );
+    }
+
+    #[test]
+    fn reject_invalid_lines() {
+        assert!(parse_batch_script("reset\nunknown-command\n").is_err());
+        assert!(parse_batch_script("read-memory 0 4 \"unterminated\n").is_err());
+        assert!(parse_batch_script("batch other.txt\n").is_err());
+        assert!(parse_batch_script("completions bash\n").is_err());
+    }
+}
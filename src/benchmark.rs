@@ -0,0 +1,202 @@
+// Copyright 2025 NXP
+//
+// SPDX-License-Identifier: BSD-3-Clause
+//! Transfer measurements of the benchmark subcommand
+//!
+//! The same RAM buffer is written and read back with every tested packet size, the duration of the transfers
+//! gives the effective throughput of each size. Slow UART and USB-HID links often perform better with smaller
+//! packets than the device accepts, the fastest size is recommended as --max-packet-size.
+
+use std::time::{Duration, Instant};
+
+use mboot::{
+    CommunicationError, McuBoot, formatters::HexDiff, protocols::Protocol, scratch::ScratchAllocator,
+    tags::property::PropertyTagDiscriminants,
+};
+
+use crate::latency::LatencyStats;
+
+/// Smallest tested packet size
+const MIN_PACKET_SIZE: u32 = 32;
+
+/// Transfers of the buffer with one packet size
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Measurement {
+    /// Max packet size set for the transfers
+    pub packet_size: u32,
+    /// Duration of the write-memory command
+    pub write: Duration,
+    /// Duration of the read-memory command
+    pub read: Duration,
+}
+
+/// Results of the benchmark
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Benchmark {
+    /// Start address of the used RAM buffer
+    pub address: u32,
+    /// Size of the buffer, transferred in each direction with every packet size
+    pub byte_count: u32,
+    /// Round trips of get-property requests
+    pub latency: LatencyStats,
+    /// Measurements sorted by packet size
+    pub measurements: Vec<Measurement>,
+}
+
+impl Benchmark {
+    /// Packet size with the shortest write and read back, [`None`] if nothing was measured
+    pub fn recommended_packet_size(&self) -> Option<u32> {
+        self.measurements
+            .iter()
+            .min_by_key(|measurement| measurement.write + measurement.read)
+            .map(|measurement| measurement.packet_size)
+    }
+
+    /// Throughput of transferring the buffer in `duration`, in KiB/s
+    pub fn throughput(&self, duration: Duration) -> Option<f64> {
+        let seconds = duration.as_secs_f64();
+        (seconds > 0.0).then(|| f64::from(self.byte_count) / 1024.0 / seconds)
+    }
+}
+
+/// Packet sizes tested with the device accepting `max_packet_size`
+///
+/// Powers of two from 32 bytes up to the maximum, followed by the maximum itself.
+pub fn packet_sizes(max_packet_size: u32) -> Vec<u32> {
+    let mut sizes: Vec<u32> = (MIN_PACKET_SIZE.trailing_zeros()..u32::BITS)
+        .map(|shift| 1 << shift)
+        .take_while(|size| *size < max_packet_size)
+        .collect();
+    sizes.push(max_packet_size);
+    sizes
+}
+
+/// Measures `count` round trips and transfers of `byte_count` bytes at `address` with every packet size
+///
+/// Without `address`, the buffer is allocated in the free internal RAM. The packet size set in the session is
+/// restored afterwards.
+///
+/// # Errors
+/// Any [`CommunicationError`] of the commands, [`CommunicationError::VerifyMismatch`] if the data read back
+/// differ and [`CommunicationError::ParseError`] if there is no free RAM for the buffer.
+pub fn run<T: Protocol>(
+    boot: &mut McuBoot<T>,
+    address: Option<u32>,
+    byte_count: u32,
+    count: u32,
+) -> Result<Benchmark, CommunicationError> {
+    let mut samples = Vec::with_capacity(count as usize);
+    for _ in 0..count {
+        let start = Instant::now();
+        boot.get_property(PropertyTagDiscriminants::CurrentVersion, 0)?;
+        samples.push(start.elapsed());
+    }
+    let latency = LatencyStats::from_samples(&samples).ok_or(CommunicationError::InvalidData)?;
+
+    let max_packet_size = boot
+        .capabilities()?
+        .max_packet_size
+        .ok_or(CommunicationError::InvalidData)?;
+    let address = match address {
+        Some(address) => address,
+        None => ScratchAllocator::from_device(boot)?
+            .allocate(byte_count, 4)
+            .ok_or_else(|| CommunicationError::ParseError(format!("no free RAM for {byte_count} bytes")))?,
+    };
+
+    let configured = boot.config().transfer.max_packet_size;
+    let measurements = packet_sizes(max_packet_size)
+        .into_iter()
+        .enumerate()
+        .map(|(round, packet_size)| {
+            boot.config_mut().transfer.max_packet_size = Some(packet_size);
+            measure(boot, address, byte_count, round as u8).map(|(write, read)| Measurement {
+                packet_size,
+                write,
+                read,
+            })
+        })
+        .collect::<Result<Vec<_>, _>>();
+    boot.config_mut().transfer.max_packet_size = configured;
+
+    Ok(Benchmark {
+        address,
+        byte_count,
+        latency,
+        measurements: measurements?,
+    })
+}
+
+/// Writes and reads back the buffer, returns the durations of both commands
+///
+/// The content differs with every `round`, so data left by the previous round are not mistaken for read back.
+fn measure<T: Protocol>(
+    boot: &mut McuBoot<T>,
+    address: u32,
+    byte_count: u32,
+    round: u8,
+) -> Result<(Duration, Duration), CommunicationError> {
+    let data: Vec<u8> = (0..byte_count)
+        .map(|index| (index as u8).wrapping_mul(31) ^ round)
+        .collect();
+    let start = Instant::now();
+    boot.write_memory(address, 0, &data)?;
+    let write = start.elapsed();
+
+    let start = Instant::now();
+    let response = boot.read_memory(address, byte_count, 0)?;
+    let read = start.elapsed();
+    let diff = HexDiff {
+        address,
+        expected: &data,
+        actual: &response.bytes[..response.bytes.len().min(data.len())],
+    };
+    if let Some(mismatch) = CommunicationError::verify_mismatch(&diff) {
+        return Err(mismatch);
+    }
+    Ok((write, read))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn packet_sizes_up_to_maximum() {
+        assert_eq!(packet_sizes(256), [32, 64, 128, 256]);
+        assert_eq!(packet_sizes(1000), [32, 64, 128, 256, 512, 1000]);
+        assert_eq!(packet_sizes(32), [32]);
+        assert_eq!(packet_sizes(16), [16]);
+    }
+
+    #[test]
+    fn fastest_packet_size_is_recommended() {
+        let ms = Duration::from_millis;
+        let latency = LatencyStats::from_samples(&[ms(1)]).unwrap();
+        let benchmark = Benchmark {
+            address: 0x2000_0000,
+            byte_count: 1024,
+            latency,
+            measurements: vec![
+                Measurement {
+                    packet_size: 32,
+                    write: ms(400),
+                    read: ms(100),
+                },
+                Measurement {
+                    packet_size: 64,
+                    write: ms(200),
+                    read: ms(100),
+                },
+                Measurement {
+                    packet_size: 128,
+                    write: ms(250),
+                    read: ms(100),
+                },
+            ],
+        };
+        assert_eq!(benchmark.recommended_packet_size(), Some(64));
+        assert_eq!(benchmark.throughput(ms(500)), Some(2.0));
+        assert_eq!(benchmark.throughput(Duration::ZERO), None);
+    }
+}
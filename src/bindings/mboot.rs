@@ -7,15 +7,24 @@
 )]
 
 use core::panic;
-use std::sync::Mutex;
+use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+};
 
-use pyo3::{Py, prelude::*, types::PyType};
+use pyo3::{Py, exceptions::PyValueError, prelude::*, types::PyType};
 
 use crate::{
     CommunicationError, KeyProvisioningResponse, McuBoot,
-    bindings::NOT_OPENED_ERROR,
-    mboot::{ResultComm, ResultStatus},
-    protocols::{ProtocolOpen, protocol_impl::ProtocolImpl, uart::UARTProtocol},
+    bindings::{NOT_OPENED_ERROR, property::PropertyBaseValue},
+    mboot::{ResultComm, ResultStatus, progress::ProgressEvent},
+    protocols::{
+        ProtocolOpen,
+        i2c::I2CProtocol,
+        protocol_impl::ProtocolImpl,
+        uart::UARTProtocol,
+        usb::{self, USBProtocol, UsbDevice},
+    },
     tags::{
         command::{KeyProvOperation, KeyProvUserKeyType, TrustProvOperation},
         property::PropertyTagDiscriminants,
@@ -25,10 +34,45 @@ use crate::{
 
 use pyo3_stub_gen::derive::*;
 
+/// Polling interval used with all interfaces
+const POLLING_INTERVAL: Duration = Duration::from_millis(1);
+
+/// Interface selected by the `interface` argument of the constructor
+#[derive(Clone, Copy, Debug)]
+enum Interface {
+    Uart,
+    Usb,
+    I2c,
+}
+
+impl Interface {
+    fn parse(interface: &str) -> PyResult<Self> {
+        match interface.to_ascii_lowercase().as_str() {
+            "uart" => Ok(Interface::Uart),
+            "usb" => Ok(Interface::Usb),
+            "i2c" => Ok(Interface::I2c),
+            _ => Err(PyValueError::new_err(format!(
+                "unknown interface '{interface}', expected 'uart', 'usb' or 'i2c'"
+            ))),
+        }
+    }
+
+    fn open(self, identifier: &str, baudrate: u32, timeout: Duration) -> ResultComm<ProtocolImpl> {
+        Ok(match self {
+            Interface::Uart => UARTProtocol::open_with_options(identifier, baudrate, timeout, POLLING_INTERVAL)?.into(),
+            Interface::Usb => USBProtocol::open_with_options(identifier, baudrate, timeout, POLLING_INTERVAL)?.into(),
+            Interface::I2c => I2CProtocol::open_with_options(identifier, baudrate, timeout, POLLING_INTERVAL)?.into(),
+        })
+    }
+}
+
 #[gen_stub_pyclass]
 #[pyclass(name = "McuBoot")]
 struct McuBootPython {
     identifier: String,
+    interface_kind: Interface,
+    baudrate: u32,
+    timeout: Duration,
     // Python can (and frequently) does pass class between threads, therefore each class needs to
     // implement Sync; on serialport, that can only be achieved with a mutex
     // you could also do it by making it linux only, TTYPort does implement sync unlike COMPort
@@ -41,13 +85,25 @@ struct McuBootPython {
 #[gen_stub_pymethods]
 #[pymethods]
 impl McuBootPython {
+    /// Create a connection to the device, the device is opened by `open()`.
+    ///
+    /// :param identifier: Serial port, USB device as "vid,pid", "vid,pid,serial=<serial>" or "path=<hid path>"
+    ///     (see `scan_usb()`), or I2C bus as "/dev/i2c-X[:address]"
+    /// :param interface: One of "uart", "usb" or "i2c", defaults to "uart"
+    /// :param timeout: Timeout of a response in milliseconds, defaults to 5000
+    /// :param baudrate: Baudrate of the serial port, ignored by other interfaces, defaults to 57600
+    /// :raises ValueError: The interface is unknown
     #[new]
-    fn py_new(identifier: String) -> Self {
-        McuBootPython {
+    #[pyo3(signature = (identifier, interface = "uart", timeout = 5000, baudrate = 57600))]
+    fn py_new(identifier: String, interface: &str, timeout: u64, baudrate: u32) -> PyResult<Self> {
+        Ok(McuBootPython {
             identifier,
+            interface_kind: Interface::parse(interface)?,
+            baudrate,
+            timeout: Duration::from_millis(timeout),
             interface: None,
             status_code: StatusCode::Success,
-        }
+        })
     }
 
     #[getter]
@@ -62,9 +118,10 @@ impl McuBootPython {
 
     /// Connect to the device.
     fn open(&mut self) {
-        let device = UARTProtocol::open(&self.identifier)
-            .expect("device could not be opened")
-            .into();
+        let device = self
+            .interface_kind
+            .open(&self.identifier, self.baudrate, self.timeout)
+            .expect("device could not be opened");
         let boot = McuBoot::new(device);
         self.interface = Some(Mutex::new(boot));
     }
@@ -104,6 +161,24 @@ impl McuBootPython {
         Some(res.response_words.to_vec())
     }
 
+    /// Get specified property value parsed according to its TAG.
+    ///
+    /// :param property: Property TAG (see `PropertyTag` Enum)
+    /// :param index: External memory ID or internal memory region index (depends on property type), defaults to 0
+    /// :return: parsed property value; None in case no response from device
+    #[pyo3(signature = (property, index = None))]
+    fn get_property_parsed(
+        &mut self,
+        property: PropertyTagDiscriminants,
+        index: Option<u32>,
+    ) -> Option<PropertyBaseValue> {
+        let index = index.unwrap_or(0);
+        let res = self.get_mut_interface().get_property(property, index);
+        let res = self.process_result(res)?;
+        self.status_code = res.status;
+        Some(PropertyBaseValue(res.response_words.to_vec(), res.property))
+    }
+
     /// Set value of specified property.
     ///
     /// :param property: Property TAG (see `PropertyTag` enum)
@@ -261,10 +336,37 @@ impl McuBootPython {
     /// Receive and process a Secure Binary (SB) file.
     ///
     /// :param data: SB file data as list of bytes
+    /// :param progress: Callable invoked with (bytes_sent, total) as the file is sent, defaults to None
     /// :return: False in case of any problem; True otherwise
-    fn receive_sb_file(&mut self, data: Vec<u8>) -> bool {
-        let res = self.get_mut_interface().receive_sb_file(&data);
-        self.process_status_res(res)
+    /// :raises: The first exception raised by `progress`, after the transfer ends
+    #[pyo3(signature = (data, progress = None))]
+    fn receive_sb_file(&mut self, py: Python<'_>, data: Vec<u8>, progress: Option<PyObject>) -> PyResult<bool> {
+        let boot = self.get_mut_interface();
+        let Some(progress) = progress else {
+            let res = py.allow_threads(|| boot.receive_sb_file(&data));
+            return Ok(self.process_status_res(res));
+        };
+        let callback_error = Arc::new(Mutex::new(None));
+        let first_error = Arc::clone(&callback_error);
+        boot.set_progress_callback(move |event| {
+            let (sent, total) = match event {
+                ProgressEvent::Started { total, .. } => (0, total),
+                ProgressEvent::Chunk { transferred, total } => (transferred, total),
+                ProgressEvent::Finished { .. } | ProgressEvent::Aborted { .. } => return,
+            };
+            Python::with_gil(|py| {
+                if let Err(err) = progress.call1(py, (sent, total)) {
+                    first_error.lock().unwrap().get_or_insert(err);
+                }
+            });
+        });
+        let res = py.allow_threads(|| boot.receive_sb_file(&data));
+        boot.clear_progress_callback();
+        let success = self.process_status_res(res);
+        match callback_error.lock().unwrap().take() {
+            Some(err) => Err(err),
+            None => Ok(success),
+        }
     }
 
     /// Execute trust provisioning operation.
@@ -390,10 +492,7 @@ impl McuBootPython {
     ///
     /// :return: Key store data as bytes; None in case of failure
     fn kp_read_key_store(&mut self) -> Option<Vec<u8>> {
-        let operation = KeyProvOperation::ReadKeyStore {
-            file: String::new(),
-            use_hexdump: false,
-        };
+        let operation = KeyProvOperation::ReadKeyStore;
         let res = self.get_mut_interface().key_provisioning(&operation);
         let (_, res) = self.process_keyprov_result(res);
         match res {
@@ -447,7 +546,71 @@ impl McuBootPython {
     }
 }
 
+/// USB-HID device found by `scan_usb()`
+#[gen_stub_pyclass]
+#[pyclass(name = "UsbDevice", frozen)]
+struct UsbDevicePython(UsbDevice);
+
+#[gen_stub_pymethods]
+#[pymethods]
+impl UsbDevicePython {
+    #[getter]
+    fn vid(&self) -> u16 {
+        self.0.vid
+    }
+
+    #[getter]
+    fn pid(&self) -> u16 {
+        self.0.pid
+    }
+
+    #[getter]
+    fn serial_number(&self) -> Option<String> {
+        self.0.serial.clone()
+    }
+
+    #[getter]
+    fn product_name(&self) -> Option<String> {
+        self.0.product.clone()
+    }
+
+    #[getter]
+    fn path(&self) -> String {
+        self.0.path.clone()
+    }
+
+    /// Identifier opening exactly this device with `McuBoot(identifier, interface="usb")`
+    #[getter]
+    fn identifier(&self) -> String {
+        self.0.identifier()
+    }
+
+    #[pyo3(name = "__str__")]
+    fn str(&self) -> String {
+        let product = self.0.product.as_deref().unwrap_or("unknown product");
+        format!("{:#06X},{:#06X} {product} ({})", self.0.vid, self.0.pid, self.0.path)
+    }
+}
+
+/// List connected USB-HID devices.
+///
+/// :param vid: List only devices with this vendor ID
+/// :param pid: List only devices with this product ID
+/// :return: Found devices
+#[gen_stub_pyfunction]
+#[pyfunction]
+#[pyo3(signature = (vid = None, pid = None))]
+fn scan_usb(vid: Option<u16>, pid: Option<u16>) -> Vec<UsbDevicePython> {
+    usb::scan_devices(vid, pid)
+        .expect("HID API could not be initialized")
+        .into_iter()
+        .map(UsbDevicePython)
+        .collect()
+}
+
 pub fn register(m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_class::<McuBootPython>()?;
+    m.add_class::<UsbDevicePython>()?;
+    m.add_function(wrap_pyfunction!(scan_usb, m)?)?;
     Ok(())
 }
@@ -2,11 +2,11 @@
 //
 // SPDX-License-Identifier: BSD-3-Clause
 
-use pyo3::prelude::*;
+use pyo3::{exceptions::PyValueError, prelude::*};
 
 use pyo3_stub_gen::derive::{gen_stub_pyclass, gen_stub_pyfunction, gen_stub_pymethods};
 
-use crate::mboot::tags::property::{PropertyTag, PropertyTagDiscriminants};
+use crate::mboot::tags::property::{Family, PropertyTag, PropertyTagDiscriminants};
 
 #[gen_stub_pyfunction]
 #[pyfunction]
@@ -17,17 +17,26 @@ fn parse_property_value(
     raw_values: Vec<u32>,
     ext_mem_id: Option<u32>,
     family: Option<String>,
-) -> PropertyBaseValue {
+) -> PyResult<PropertyBaseValue> {
+    let property_tag = match family {
+        Some(family) => Family::parse(&family)
+            .map_err(PyValueError::new_err)?
+            .property(property_tag),
+        None => property_tag,
+    };
     let property = PropertyTag::from_code(property_tag, &raw_values);
-    PropertyBaseValue(raw_values, property)
+    Ok(PropertyBaseValue(raw_values, property))
 }
 
 #[gen_stub_pyfunction]
 #[pyfunction]
 #[pyo3(signature = (property_tag, family = None))]
-#[allow(unused_variables, reason = "the unused arguments are for compatibility (for now)")]
-fn parse_property_tag(property_tag: String, family: Option<String>) -> PropertyTagDiscriminants {
-    PropertyTagDiscriminants::parse_property(&property_tag).unwrap()
+fn parse_property_tag(property_tag: String, family: Option<String>) -> PyResult<PropertyTagDiscriminants> {
+    let tag = PropertyTagDiscriminants::parse_property(&property_tag).map_err(PyValueError::new_err)?;
+    match family {
+        Some(family) => Ok(Family::parse(&family).map_err(PyValueError::new_err)?.property(tag)),
+        None => Ok(tag),
+    }
 }
 
 #[pymethods]
@@ -42,7 +51,7 @@ impl PropertyTagDiscriminants {
 
 #[gen_stub_pyclass]
 #[pyclass]
-struct PropertyBaseValue(Vec<u32>, PropertyTag);
+pub(super) struct PropertyBaseValue(pub(super) Vec<u32>, pub(super) PropertyTag);
 
 #[gen_stub_pymethods]
 #[pymethods]
@@ -55,6 +64,52 @@ impl PropertyBaseValue {
         self.1.to_string()
     }
 
+    /// Property TAG of the value
+    #[getter]
+    fn tag(&self) -> PropertyTagDiscriminants {
+        PropertyTagDiscriminants::from(&self.1)
+    }
+
+    /// Version string, e.g. "K3.1.0"; None if the property is not a version
+    #[getter]
+    fn version(&self) -> Option<String> {
+        match &self.1 {
+            PropertyTag::CurrentVersion(version) => Some(version.to_string()),
+            _ => None,
+        }
+    }
+
+    /// List of (start, end) pairs of the reserved regions, end addresses are inclusive; None for other properties
+    #[getter]
+    fn reserved_regions(&self) -> Option<Vec<(u32, u32)>> {
+        match &self.1 {
+            PropertyTag::ReservedRegions(regions) => Some(regions.regions().to_vec()),
+            _ => None,
+        }
+    }
+
+    /// Names of the available commands; None for other properties
+    #[getter]
+    fn available_commands(&self) -> Option<Vec<String>> {
+        match &self.1 {
+            PropertyTag::AvailableCommands(commands) => {
+                Some(commands.iter().map(|command| format!("{command:?}")).collect())
+            }
+            _ => None,
+        }
+    }
+
+    /// Names of the available peripherals; None for other properties
+    #[getter]
+    fn available_peripherals(&self) -> Option<Vec<String>> {
+        match &self.1 {
+            PropertyTag::AvailablePeripherals(peripherals) => {
+                Some(peripherals.iter().map(|peripheral| format!("{peripheral:?}")).collect())
+            }
+            _ => None,
+        }
+    }
+
     #[pyo3(name = "__str__")]
     fn str(&self) -> String {
         self.to_str()
@@ -65,5 +120,6 @@ pub fn register(m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_function(wrap_pyfunction!(parse_property_value, m)?)?;
     m.add_function(wrap_pyfunction!(parse_property_tag, m)?)?;
     m.add_class::<PropertyTagDiscriminants>()?;
+    m.add_class::<PropertyBaseValue>()?;
     Ok(())
 }
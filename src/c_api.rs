@@ -3,15 +3,24 @@
 // SPDX-License-Identifier: BSD-3-Clause
 #![warn(missing_docs)]
 
-use crate::mboot::{McuBoot, ResultStatus, protocols::ProtocolOpen, tags::property::PropertyTagDiscriminants};
+use crate::mboot::{
+    McuBoot, ReadMemoryResponse, ResultComm, ResultStatus, protocols::ProtocolOpen,
+    tags::property::PropertyTagDiscriminants,
+};
 use crate::{
-    protocols::{i2c::I2CProtocol, protocol_impl::ProtocolImpl, uart::UARTProtocol},
+    protocols::{
+        i2c::I2CProtocol,
+        protocol_impl::ProtocolImpl,
+        uart::UARTProtocol,
+        usb::{self, USBProtocol, UsbDevice},
+    },
     tags::status::StatusCode,
 };
 use std::{
     ffi::{CStr, CString},
     ptr, slice,
     str::FromStr,
+    time::Duration,
 };
 /// [`McuBoot`] type that you can use to communicate with the device using `mboot_` functions.
 ///
@@ -55,12 +64,46 @@ pub struct CReadMemoryResponse {
 }
 
 #[repr(C)]
+#[derive(Clone, Copy, Debug)]
 /// Indicates which protocol should be used when initializing.
 pub enum CProtocol {
     /// Use UART protocol
     UART,
     /// Use I2C protocol
     I2c,
+    /// Use USB-HID protocol, the device path is `vid:pid`, `vid,pid[,serial=XYZ]` or `path=<HID path>`
+    Usb,
+}
+
+#[repr(C)]
+#[derive(Debug)]
+/// USB-HID device listed by [`mboot_enumerate_usb`].
+pub struct CUsbDevice {
+    /// USB vendor ID
+    pub vid: u16,
+    /// USB product ID
+    pub pid: u16,
+    /// Serial number, NULL if the device does not report one
+    pub serial: *mut libc::c_char,
+    /// Platform-specific HID path
+    pub path: *mut libc::c_char,
+    /// Device path selecting this device, to be passed to [`mboot_create`] with `Usb` protocol
+    pub identifier: *mut libc::c_char,
+}
+
+impl From<UsbDevice> for CUsbDevice {
+    fn from(device: UsbDevice) -> Self {
+        // HID paths and USB strings do not contain NUL characters, such a device would only be harder to select
+        let into_raw = |text: String| CString::new(text).unwrap_or_default().into_raw();
+        let identifier = into_raw(device.identifier());
+        CUsbDevice {
+            vid: device.vid,
+            pid: device.pid,
+            serial: device.serial.map_or(ptr::null_mut(), into_raw),
+            path: into_raw(device.path),
+            identifier,
+        }
+    }
 }
 
 /// One of the passed pointers in function arguments was NULL.
@@ -70,12 +113,36 @@ pub const ERROR_INVALID_PROPERTY_TAG: CStatus = -2;
 /// Error occured while communication with the device.
 pub const ERROR_COMMUNICATION_ERROR: CStatus = -3;
 
-/// Get a mutable reference to [`McuBoot`] from mutable raw pointer.
+/// Data behind a [`CMcuBoot`] pointer.
+struct Instance {
+    boot: McuBoot<ProtocolImpl>,
+    /// Description of the last failure, empty if no command failed yet
+    last_error: CString,
+}
+
+impl Instance {
+    /// Runs `command`, the description of its error is kept for [`mboot_get_last_error`].
+    fn command<T>(&mut self, command: impl FnOnce(&mut McuBoot<ProtocolImpl>) -> ResultComm<T>) -> Result<T, CStatus> {
+        command(&mut self.boot).map_err(|err| {
+            // error messages do not contain NUL characters, an empty description is good enough if one does
+            self.last_error = CString::new(err.to_string()).unwrap_or_default();
+            ERROR_COMMUNICATION_ERROR
+        })
+    }
+
+    /// Runs `command` returning only a status code, see [`Instance::command`].
+    fn status(&mut self, command: impl FnOnce(&mut McuBoot<ProtocolImpl>) -> ResultStatus) -> CStatus {
+        self.command(command)
+            .map_or_else(|status| status, |status| status as CStatus)
+    }
+}
+
+/// Get a mutable reference to [`Instance`] from mutable raw pointer.
 ///
 /// # Safety
 /// `mboot` must be a valid non-freed pointer.
-unsafe fn get_mboot<'a>(mboot: *mut CMcuBoot) -> &'a mut McuBoot<ProtocolImpl> {
-    unsafe { &mut *mboot.cast::<McuBoot<ProtocolImpl>>() }
+unsafe fn get_mboot<'a>(mboot: *mut CMcuBoot) -> &'a mut Instance {
+    unsafe { &mut *mboot.cast::<Instance>() }
 }
 
 /// Get text description of the passed status code.
@@ -119,11 +186,42 @@ pub unsafe extern "C" fn mboot_free_status_text(status_text: *mut libc::c_char)
     }
 }
 
-/// Convert `ResultStatus` to `CStatus`.
-fn return_error(status: &ResultStatus) -> CStatus {
-    match status {
-        Ok(status) => *status as CStatus,
-        Err(_) => ERROR_COMMUNICATION_ERROR,
+/// Fill `response` with the result of a read command, returning its `CStatus`.
+fn fill_read_memory_response(
+    result: Result<ReadMemoryResponse, CStatus>,
+    response: &mut CReadMemoryResponse,
+) -> CStatus {
+    match result {
+        Ok(res) => {
+            // Create copies of the response data
+            let words = Box::new(res.response_words);
+            let words_len = words.len();
+            let words_ptr = Box::into_raw(words);
+
+            // Add explicit type annotation for bytes
+            let bytes: Box<[u8]> = if res.bytes.is_empty() {
+                // If empty, return a single zero byte
+                Box::new([0u8])
+            } else {
+                // Clone the existing bytes
+                res.bytes
+            };
+
+            let bytes_len = bytes.len();
+            let bytes_ptr = Box::into_raw(bytes).cast::<u8>();
+            let status = res.status as CStatus;
+
+            *response = CReadMemoryResponse {
+                status,
+                response_words: words_ptr.cast::<u32>(),
+                response_words_len: words_len,
+                bytes: bytes_ptr,
+                bytes_len,
+            };
+
+            status
+        }
+        Err(status) => status,
     }
 }
 
@@ -151,26 +249,117 @@ unsafe fn free_box_data<T>(value: *mut T) {
 /// Undefined behavior may occur if the pointer is invalid or the string is not properly terminated.
 /// If this function returns a valid [`CMcuBoot`] instance, it must be later freed.
 pub unsafe extern "C" fn mboot_create(device_path: *const libc::c_char, protocol: CProtocol) -> *mut CMcuBoot {
+    unsafe { create(device_path, protocol, None) }
+}
+
+#[unsafe(no_mangle)]
+/// Create a new [`CMcuBoot`] instance from a device path with communication options.
+///
+/// `baudrate` is used only by UART. `timeout_ms` limits waiting for a response and `polling_ms` is the
+/// interval of checking for received data.
+///
+/// Returns either a valid [`CMcuBoot`] instance or a NULL pointer, if any errors occur.
+///
+/// # Allocations
+/// A valid [`CMcuBoot`] instance must be freed when not used with [`mboot_destroy`] function.
+///
+/// # Safety
+///
+/// Same as [`mboot_create`].
+pub unsafe extern "C" fn mboot_create_with_options(
+    device_path: *const libc::c_char,
+    protocol: CProtocol,
+    baudrate: u32,
+    timeout_ms: u32,
+    polling_ms: u32,
+) -> *mut CMcuBoot {
+    let options = (
+        baudrate,
+        Duration::from_millis(timeout_ms.into()),
+        Duration::from_millis(polling_ms.into()),
+    );
+    unsafe { create(device_path, protocol, Some(options)) }
+}
+
+/// Open the device with baudrate, timeout and polling interval in `options`, or with defaults of the protocol.
+fn open_device<T>(device_path: &str, options: Option<(u32, Duration, Duration)>) -> ResultComm<ProtocolImpl>
+where
+    T: ProtocolOpen + Into<ProtocolImpl>,
+{
+    match options {
+        Some((baudrate, timeout, polling_interval)) => {
+            T::open_with_options(device_path, baudrate, timeout, polling_interval)
+        }
+        None => T::open(device_path),
+    }
+    .map(Into::into)
+}
+
+/// Create a new [`CMcuBoot`] instance, see [`open_device`] for `options`.
+///
+/// # Safety
+/// See [`mboot_create`].
+unsafe fn create(
+    device_path: *const libc::c_char,
+    protocol: CProtocol,
+    options: Option<(u32, Duration, Duration)>,
+) -> *mut CMcuBoot {
+    if device_path.is_null() {
+        return ptr::null_mut();
+    }
     let c_str = unsafe { CStr::from_ptr(device_path) };
     let Ok(device_path_str) = c_str.to_str() else {
         return ptr::null_mut();
     };
 
-    let device: ProtocolImpl = match protocol {
-        CProtocol::UART => match UARTProtocol::open(device_path_str) {
-            Ok(p) => p.into(),
-            Err(_) => return ptr::null_mut(),
-        },
-        CProtocol::I2c => match I2CProtocol::open(device_path_str) {
-            Ok(p) => p.into(),
-            Err(_) => return ptr::null_mut(),
-        },
+    let device = match protocol {
+        CProtocol::UART => open_device::<UARTProtocol>(device_path_str, options),
+        CProtocol::I2c => open_device::<I2CProtocol>(device_path_str, options),
+        CProtocol::Usb => open_device::<USBProtocol>(device_path_str, options),
+    };
+    let Ok(device) = device else {
+        return ptr::null_mut();
     };
 
-    let mboot = Box::new(McuBoot::new(device));
+    let mboot = Box::new(Instance {
+        boot: McuBoot::new(device),
+        last_error: CString::default(),
+    });
     Box::into_raw(mboot).cast::<CMcuBoot>()
 }
 
+#[unsafe(no_mangle)]
+/// Copy description of the last failed command of the instance into `buffer` of `length` bytes.
+///
+/// The description is truncated to fit the buffer and always NUL terminated, it is empty if no command
+/// failed yet. Successful commands do not clear it.
+///
+/// Returns the length of the whole description without the terminating NUL, so a buffer of that length
+/// plus one fits it, or a negative integer on error.
+///
+/// # Safety
+/// `mboot` should be non-null and must be a valid pointer. `buffer` must be valid for writes of `length`
+/// bytes, it may be NULL if `length` is zero.
+pub unsafe extern "C" fn mboot_get_last_error(
+    mboot: *mut CMcuBoot,
+    buffer: *mut libc::c_char,
+    length: usize,
+) -> CStatus {
+    if mboot.is_null() || (buffer.is_null() && length > 0) {
+        return ERROR_NULL_POINTER_ARG;
+    }
+    let mboot = unsafe { get_mboot(mboot) };
+    let description = mboot.last_error.as_bytes();
+    if length > 0 {
+        let copied = description.len().min(length - 1);
+        unsafe {
+            ptr::copy_nonoverlapping(description.as_ptr().cast::<libc::c_char>(), buffer, copied);
+            *buffer.add(copied) = 0;
+        }
+    }
+    CStatus::try_from(description.len()).unwrap_or(CStatus::MAX)
+}
+
 #[unsafe(no_mangle)]
 /// Destroys a [`CMcuBoot`] instance and frees its resources.
 ///
@@ -178,7 +367,7 @@ pub unsafe extern "C" fn mboot_create(device_path: *const libc::c_char, protocol
 /// If `mboot` is non-null, it must be a valid pointer returned by [`mboot_create`].
 /// Passing an invalid or already-freed pointer results in undefined behavior.
 pub unsafe extern "C" fn mboot_destroy(mboot: *mut CMcuBoot) {
-    unsafe { free_box_data(mboot.cast::<McuBoot<ProtocolImpl>>()) };
+    unsafe { free_box_data(mboot.cast::<Instance>()) };
 }
 
 #[unsafe(no_mangle)]
@@ -212,7 +401,7 @@ pub unsafe extern "C" fn mboot_get_property(
         return ERROR_INVALID_PROPERTY_TAG;
     };
 
-    match mboot.get_property(tag_enum, memory_index) {
+    match mboot.command(|boot| boot.get_property(tag_enum, memory_index)) {
         Ok(res) => {
             // Create a copy of the response words
             let words: Box<[u32]> = if res.response_words.is_empty() {
@@ -236,7 +425,7 @@ pub unsafe extern "C" fn mboot_get_property(
 
             status
         }
-        Err(_) => ERROR_COMMUNICATION_ERROR,
+        Err(status) => status,
     }
 }
 
@@ -270,39 +459,8 @@ pub unsafe extern "C" fn mboot_read_memory(
     *response = CReadMemoryResponse::default();
 
     let mboot = unsafe { get_mboot(mboot) };
-
-    match mboot.read_memory(start_address, byte_count, memory_id) {
-        Ok(res) => {
-            // Create copies of the response data
-            let words = Box::new(res.response_words);
-            let words_len = words.len();
-            let words_ptr = Box::into_raw(words);
-
-            // Add explicit type annotation for bytes
-            let bytes: Box<[u8]> = if res.bytes.is_empty() {
-                // If empty, return a single zero byte
-                Box::new([0u8])
-            } else {
-                // Clone the existing bytes
-                res.bytes
-            };
-
-            let bytes_len = bytes.len();
-            let bytes_ptr = Box::into_raw(bytes).cast::<u8>();
-            let status = res.status as CStatus;
-
-            *response = CReadMemoryResponse {
-                status,
-                response_words: words_ptr.cast::<u32>(),
-                response_words_len: words_len,
-                bytes: bytes_ptr,
-                bytes_len,
-            };
-
-            status
-        }
-        Err(_) => ERROR_COMMUNICATION_ERROR,
-    }
+    let result = mboot.command(|boot| boot.read_memory(start_address, byte_count, memory_id));
+    fill_read_memory_response(result, response)
 }
 
 #[unsafe(no_mangle)]
@@ -327,7 +485,7 @@ pub unsafe extern "C" fn mboot_write_memory(
     let mboot = unsafe { get_mboot(mboot) };
     let bytes = unsafe { slice::from_raw_parts(bytes, byte_count) };
 
-    return_error(&mboot.write_memory(start_address, memory_id, bytes))
+    mboot.status(|boot| boot.write_memory(start_address, memory_id, bytes))
 }
 
 #[unsafe(no_mangle)]
@@ -343,7 +501,7 @@ pub unsafe extern "C" fn mboot_flash_erase_all(mboot: *mut CMcuBoot, memory_id:
     }
 
     let mboot = unsafe { get_mboot(mboot) };
-    return_error(&mboot.flash_erase_all(memory_id))
+    mboot.status(|boot| boot.flash_erase_all(memory_id))
 }
 
 #[unsafe(no_mangle)]
@@ -360,7 +518,7 @@ pub unsafe extern "C" fn mboot_receive_sb_file(mboot: *mut CMcuBoot, bytes: *con
     }
     let bytes = unsafe { slice::from_raw_parts(bytes, byte_count) };
     let mboot = unsafe { get_mboot(mboot) };
-    return_error(&mboot.receive_sb_file(bytes))
+    mboot.status(|boot| boot.receive_sb_file(bytes))
 }
 
 #[unsafe(no_mangle)]
@@ -381,7 +539,7 @@ pub unsafe extern "C" fn mboot_flash_program_once(
         return ERROR_NULL_POINTER_ARG;
     }
     let mboot = unsafe { get_mboot(mboot) };
-    return_error(&mboot.flash_program_once(index, count, data, verify))
+    mboot.status(|boot| boot.flash_program_once(index, count, data, verify))
 }
 
 #[unsafe(no_mangle)]
@@ -397,9 +555,263 @@ pub unsafe extern "C" fn mboot_flash_read_once(mboot: *mut CMcuBoot, index: u32,
         return ERROR_NULL_POINTER_ARG.into();
     }
     let mboot = unsafe { get_mboot(mboot) };
-    match mboot.flash_read_once(index, count) {
+    match mboot.command(|boot| boot.flash_read_once(index, count)) {
         Ok(res) => res.into(),
-        Err(_) => ERROR_COMMUNICATION_ERROR.into(),
+        Err(status) => status.into(),
+    }
+}
+
+#[unsafe(no_mangle)]
+/// Set value of a bootloader property.
+///
+/// Returns a positive integer with a status code on success or a negative integer on error.
+///
+/// # Safety
+/// `mboot` should be non-null and must be a valid pointer.
+pub unsafe extern "C" fn mboot_set_property(mboot: *mut CMcuBoot, tag: u8, value: u32) -> CStatus {
+    if mboot.is_null() {
+        return ERROR_NULL_POINTER_ARG;
+    }
+    let Ok(tag) = PropertyTagDiscriminants::try_from(tag) else {
+        return ERROR_INVALID_PROPERTY_TAG;
+    };
+    let mboot = unsafe { get_mboot(mboot) };
+    mboot.status(|boot| boot.set_property(tag, value))
+}
+
+#[unsafe(no_mangle)]
+/// Reset the device.
+///
+/// Returns a positive integer with a status code on success or a negative integer on error.
+///
+/// # Safety
+/// `mboot` should be non-null and must be a valid pointer.
+pub unsafe extern "C" fn mboot_reset(mboot: *mut CMcuBoot) -> CStatus {
+    if mboot.is_null() {
+        return ERROR_NULL_POINTER_ARG;
+    }
+    let mboot = unsafe { get_mboot(mboot) };
+    mboot.status(McuBoot::reset)
+}
+
+#[unsafe(no_mangle)]
+/// Jump to code at `start_address` with `argument` and stack pointer set to `stackpointer`.
+///
+/// Returns a positive integer with a status code on success or a negative integer on error.
+///
+/// # Safety
+/// `mboot` should be non-null and must be a valid pointer.
+pub unsafe extern "C" fn mboot_execute(
+    mboot: *mut CMcuBoot,
+    start_address: u32,
+    argument: u32,
+    stackpointer: u32,
+) -> CStatus {
+    if mboot.is_null() {
+        return ERROR_NULL_POINTER_ARG;
+    }
+    let mboot = unsafe { get_mboot(mboot) };
+    mboot.status(|boot| boot.execute(start_address, argument, stackpointer))
+}
+
+#[unsafe(no_mangle)]
+/// Call function at `start_address` with `argument`.
+///
+/// Returns a positive integer with a status code on success or a negative integer on error.
+///
+/// # Safety
+/// `mboot` should be non-null and must be a valid pointer.
+pub unsafe extern "C" fn mboot_call(mboot: *mut CMcuBoot, start_address: u32, argument: u32) -> CStatus {
+    if mboot.is_null() {
+        return ERROR_NULL_POINTER_ARG;
+    }
+    let mboot = unsafe { get_mboot(mboot) };
+    mboot.status(|boot| boot.call(start_address, argument))
+}
+
+#[unsafe(no_mangle)]
+/// Fill `byte_count` bytes of memory from `start_address` with 32-bit `pattern`.
+///
+/// Returns a positive integer with a status code on success or a negative integer on error.
+///
+/// # Safety
+/// `mboot` should be non-null and must be a valid pointer.
+pub unsafe extern "C" fn mboot_fill_memory(
+    mboot: *mut CMcuBoot,
+    start_address: u32,
+    byte_count: u32,
+    pattern: u32,
+) -> CStatus {
+    if mboot.is_null() {
+        return ERROR_NULL_POINTER_ARG;
+    }
+    let mboot = unsafe { get_mboot(mboot) };
+    mboot.status(|boot| boot.fill_memory(start_address, byte_count, pattern))
+}
+
+#[unsafe(no_mangle)]
+/// Erase `byte_count` bytes of flash memory from `start_address`.
+///
+/// Returns a positive integer with a status code on success or a negative integer on error.
+///
+/// # Safety
+/// `mboot` should be non-null and must be a valid pointer.
+pub unsafe extern "C" fn mboot_flash_erase_region(
+    mboot: *mut CMcuBoot,
+    start_address: u32,
+    byte_count: u32,
+    memory_id: u32,
+) -> CStatus {
+    if mboot.is_null() {
+        return ERROR_NULL_POINTER_ARG;
+    }
+    let mboot = unsafe { get_mboot(mboot) };
+    mboot.status(|boot| boot.flash_erase_region(start_address, byte_count, memory_id))
+}
+
+#[unsafe(no_mangle)]
+/// Erase the entire flash memory including protected regions and recover flash security section.
+///
+/// Returns a positive integer with a status code on success or a negative integer on error.
+///
+/// # Safety
+/// `mboot` should be non-null and must be a valid pointer.
+pub unsafe extern "C" fn mboot_flash_erase_all_unsecure(mboot: *mut CMcuBoot) -> CStatus {
+    if mboot.is_null() {
+        return ERROR_NULL_POINTER_ARG;
+    }
+    let mboot = unsafe { get_mboot(mboot) };
+    mboot.status(McuBoot::flash_erase_all_unsecure)
+}
+
+#[unsafe(no_mangle)]
+/// Configure external memory `memory_id` with configuration data at `address`.
+///
+/// Returns a positive integer with a status code on success or a negative integer on error.
+///
+/// # Safety
+/// `mboot` should be non-null and must be a valid pointer.
+pub unsafe extern "C" fn mboot_configure_memory(mboot: *mut CMcuBoot, memory_id: u32, address: u32) -> CStatus {
+    if mboot.is_null() {
+        return ERROR_NULL_POINTER_ARG;
+    }
+    let mboot = unsafe { get_mboot(mboot) };
+    mboot.status(|boot| boot.configure_memory(memory_id, address))
+}
+
+#[unsafe(no_mangle)]
+/// Reads fuses from the device and writes the result to the response struct.
+///
+/// Returns a positive integer with a status code on success or a negative integer on error.
+///
+/// # Allocations
+/// This function allocates arrays in `response_words` and `bytes` fields in `response` parameter,
+/// free them the same way as after [`mboot_read_memory`].
+///
+/// # Safety
+/// `mboot` and `response` should be non-null and they must be valid pointers.
+/// `response` must point to writable memory. Invalid or misaligned pointers cause undefined behavior.
+pub unsafe extern "C" fn mboot_fuse_read(
+    mboot: *mut CMcuBoot,
+    start_address: u32,
+    byte_count: u32,
+    memory_id: u32,
+    response: *mut CReadMemoryResponse,
+) -> CStatus {
+    if mboot.is_null() || response.is_null() {
+        return ERROR_NULL_POINTER_ARG;
+    }
+    let response = unsafe { &mut *response };
+    *response = CReadMemoryResponse::default();
+
+    let mboot = unsafe { get_mboot(mboot) };
+    let result = mboot.command(|boot| boot.fuse_read(start_address, byte_count, memory_id));
+    fill_read_memory_response(result, response)
+}
+
+#[unsafe(no_mangle)]
+/// Program fuses on the device and returns status code.
+///
+/// Returns a positive integer with a status code on success or a negative integer on error.
+///
+/// # Safety
+/// `byte_count` must be lower or the same as the number of bytes in `bytes` array. `mboot` and
+/// `bytes`, should be non-null and must be valid pointers.
+pub unsafe extern "C" fn mboot_fuse_program(
+    mboot: *mut CMcuBoot,
+    start_address: u32,
+    memory_id: u32,
+    bytes: *const u8,
+    byte_count: usize,
+) -> CStatus {
+    if mboot.is_null() || bytes.is_null() {
+        return ERROR_NULL_POINTER_ARG;
+    }
+    let mboot = unsafe { get_mboot(mboot) };
+    let bytes = unsafe { slice::from_raw_parts(bytes, byte_count) };
+    mboot.status(|boot| boot.fuse_program(start_address, memory_id, bytes))
+}
+
+#[unsafe(no_mangle)]
+/// Send an image to the device without any command, e.g. to load a flashloader.
+///
+/// Returns a positive integer with a status code on success or a negative integer on error.
+///
+/// # Safety
+/// `byte_count` must be lower or the same as the number of bytes in `bytes` array. `mboot` and
+/// `bytes`, should be non-null and must be valid pointers.
+pub unsafe extern "C" fn mboot_load_image(mboot: *mut CMcuBoot, bytes: *const u8, byte_count: usize) -> CStatus {
+    if mboot.is_null() || bytes.is_null() {
+        return ERROR_NULL_POINTER_ARG;
+    }
+    let bytes = unsafe { slice::from_raw_parts(bytes, byte_count) };
+    let mboot = unsafe { get_mboot(mboot) };
+    mboot.status(|boot| boot.load_image(bytes))
+}
+
+#[unsafe(no_mangle)]
+/// List connected USB-HID devices with `vid` and `pid`, zero matches any VID or PID.
+///
+/// Returns an array of `count` devices or NULL if the HID API could not be initialized.
+///
+/// # Allocations
+/// The returned array and the strings in it are allocated, free them with [`mboot_free_usb_devices`].
+///
+/// # Safety
+/// `count` should be non-null and must be a valid pointer to writable memory.
+pub unsafe extern "C" fn mboot_enumerate_usb(vid: u16, pid: u16, count: *mut usize) -> *mut CUsbDevice {
+    if count.is_null() {
+        return ptr::null_mut();
+    }
+    let count = unsafe { &mut *count };
+    *count = 0;
+
+    let filter = |id| if id == 0 { None } else { Some(id) };
+    let Ok(devices) = usb::scan_devices(filter(vid), filter(pid)) else {
+        return ptr::null_mut();
+    };
+    let devices: Box<[CUsbDevice]> = devices.into_iter().map(CUsbDevice::from).collect();
+    *count = devices.len();
+    Box::into_raw(devices).cast::<CUsbDevice>()
+}
+
+#[unsafe(no_mangle)]
+/// Free devices returned by [`mboot_enumerate_usb`].
+///
+/// # Safety
+/// `devices` must be NULL or a pointer returned by [`mboot_enumerate_usb`] together with `count`, which has not
+/// been freed yet.
+pub unsafe extern "C" fn mboot_free_usb_devices(devices: *mut CUsbDevice, count: usize) {
+    if devices.is_null() {
+        return;
+    }
+    let devices = unsafe { Box::from_raw(ptr::slice_from_raw_parts_mut(devices, count)) };
+    for device in &devices {
+        for text in [device.serial, device.path, device.identifier] {
+            if !text.is_null() {
+                drop(unsafe { CString::from_raw(text) });
+            }
+        }
     }
 }
 
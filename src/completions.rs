@@ -0,0 +1,135 @@
+// Copyright 2025 NXP
+//
+// SPDX-License-Identifier: BSD-3-Clause
+//! Shell Completions
+//!
+//! Static completion scripts are generated by clap. For bash, zsh and fish, the scripts are extended to
+//! complete values of the device options with devices connected at the time of completion, listed by the
+//! hidden `__complete-devices` subcommand.
+
+use std::io::{self, Write};
+
+use clap::CommandFactory;
+use clap_complete::Shell;
+use hidapi::HidApi;
+
+use crate::Args;
+
+const BIN_NAME: &str = "rblhost";
+/// VID and PID of the MCP2221A USB to I2C bridge
+const MCP2221_ID: (u16, u16) = (0x04D8, 0x00DD);
+
+/// Kind of the device listed for completion
+#[derive(clap::ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DeviceKind {
+    /// Serial ports, values of --port
+    Port,
+    /// USB-HID devices, values of --usb
+    Usb,
+    /// I2C buses and USB to I2C bridges, values of --i2c
+    I2c,
+}
+
+/// Prints connected devices of the given kind, or of all kinds, one per line
+pub fn print_devices(kind: Option<DeviceKind>) {
+    let kinds = match kind {
+        Some(kind) => vec![kind],
+        None => vec![DeviceKind::Port, DeviceKind::Usb, DeviceKind::I2c],
+    };
+    for kind in kinds {
+        for device in list_devices(kind) {
+            println!("{device}");
+        }
+    }
+}
+
+/// Lists connected devices, errors are ignored as there is nothing to complete
+fn list_devices(kind: DeviceKind) -> Vec<String> {
+    match kind {
+        DeviceKind::Port => serialport::available_ports()
+            .unwrap_or_default()
+            .into_iter()
+            .map(|port| port.port_name)
+            .collect(),
+        DeviceKind::Usb => {
+            let Ok(api) = HidApi::new() else {
+                return Vec::new();
+            };
+            let mut devices: Vec<_> = api
+                .device_list()
+                .map(|device| format!("{:#06X},{:#06X}", device.vendor_id(), device.product_id()))
+                .collect();
+            devices.sort();
+            devices.dedup();
+            devices
+        }
+        DeviceKind::I2c => {
+            let mut buses: Vec<_> = std::fs::read_dir("/dev")
+                .map(|entries| {
+                    entries
+                        .filter_map(Result::ok)
+                        .map(|entry| entry.path().to_string_lossy().into_owned())
+                        .filter(|path| path.starts_with("/dev/i2c-"))
+                        .collect()
+                })
+                .unwrap_or_default();
+            if let Ok(api) = HidApi::new() {
+                buses.extend(
+                    api.device_list()
+                        .filter(|device| (device.vendor_id(), device.product_id()) == MCP2221_ID)
+                        .map(|device| format!("mcp2221:{}", device.serial_number().unwrap_or_default())),
+                );
+            }
+            buses
+        }
+    }
+}
+
+/// Prints the completion script for `shell` to stdout
+pub fn print_script(shell: Shell) -> io::Result<()> {
+    let mut script = Vec::new();
+    clap_complete::generate(shell, &mut Args::command(), BIN_NAME, &mut script);
+    let mut script = String::from_utf8_lossy(&script).into_owned();
+    match shell {
+        Shell::Bash => script.push_str(BASH_DEVICES),
+        Shell::Fish => script.push_str(FISH_DEVICES),
+        Shell::Zsh => {
+            for (option, kind) in [("PORT", "port"), ("USB", "usb"), ("I2C", "i2c")] {
+                script = script.replace(
+                    &format!(":{option}:_default'"),
+                    &format!(
+                        ":{option}:{{compadd -- ${{(f)\"$({BIN_NAME} __complete-devices {kind} 2>/dev/null)\"}}}}'"
+                    ),
+                );
+            }
+        }
+        _ => {}
+    }
+    io::stdout().write_all(script.as_bytes())
+}
+
+/// Wraps the generated bash completion, values of device options are completed first
+const BASH_DEVICES: &str = r#"
+_rblhost_devices() {
+    local kind
+    case "${COMP_WORDS[COMP_CWORD-1]}" in
+        -p|--port) kind=port ;;
+        -u|--usb) kind=usb ;;
+        --i2c) kind=i2c ;;
+        *)
+            _rblhost "$@"
+            return
+            ;;
+    esac
+    local IFS=$'\n'
+    COMPREPLY=($(compgen -W "$(rblhost __complete-devices "$kind" 2>/dev/null)" -- "${COMP_WORDS[COMP_CWORD]}"))
+}
+complete -F _rblhost_devices -o nosort -o bashdefault -o default rblhost
+"#;
+
+/// Adds device values to the generated fish completion
+const FISH_DEVICES: &str = r"
+complete -c rblhost -s p -l port -x -a '(rblhost __complete-devices port 2>/dev/null)'
+complete -c rblhost -s u -l usb -x -a '(rblhost __complete-devices usb 2>/dev/null)'
+complete -c rblhost -l i2c -x -a '(rblhost __complete-devices i2c 2>/dev/null)'
+";
@@ -0,0 +1,332 @@
+// Copyright 2025 NXP
+//
+// SPDX-License-Identifier: BSD-3-Clause
+//! Board configuration snapshots for the export-config and import-config subcommands
+//!
+//! A snapshot is a JSON document with all properties readable from the device and selected fuse words.
+//! Importing applies only its writable subset, properties settable by set-property and fuse words.
+
+use std::ops::RangeInclusive;
+
+use log::{debug, warn};
+use mboot::{
+    CommunicationError, McuBoot, parse,
+    protocols::Protocol,
+    tags::{property::PropertyTagDiscriminants, status::StatusCode},
+};
+use serde_json::{Value, json};
+
+/// Format identifier stored in snapshots
+const FORMAT: &str = "rblhost-config";
+/// Version of the snapshot format
+const VERSION: u64 = 1;
+/// Highest known property tag
+const LAST_PROPERTY: u8 = 0x26;
+
+/// Properties applied by import-config
+const WRITABLE_PROPERTIES: [PropertyTagDiscriminants; 7] = [
+    PropertyTagDiscriminants::VerifyWrites,
+    PropertyTagDiscriminants::FlashReadMargin,
+    PropertyTagDiscriminants::IrqNotifierPin,
+    PropertyTagDiscriminants::PFRKeystoreUpdateOpt,
+    PropertyTagDiscriminants::ByteWriteTimeoutMs,
+    PropertyTagDiscriminants::FuseProgramVoltage,
+    PropertyTagDiscriminants::VerifyErase,
+];
+
+/// Property value captured in a snapshot
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PropertyValue {
+    pub tag: PropertyTagDiscriminants,
+    /// Response words without the status
+    pub values: Box<[u32]>,
+}
+
+/// Fuse word captured in a snapshot
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FuseWord {
+    pub index: u32,
+    pub value: u32,
+}
+
+/// Configuration of a board
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ConfigSnapshot {
+    pub properties: Vec<PropertyValue>,
+    pub fuses: Vec<FuseWord>,
+}
+
+/// Single difference between a snapshot and the device
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Change {
+    Property {
+        tag: PropertyTagDiscriminants,
+        current: u32,
+        value: u32,
+    },
+    Fuse {
+        index: u32,
+        current: u32,
+        value: u32,
+    },
+}
+
+impl Change {
+    /// Fuse bits can only be programmed, a change clearing any bit cannot be applied
+    pub fn is_applicable(self) -> bool {
+        match self {
+            Change::Property { .. } => true,
+            Change::Fuse { current, value, .. } => current & !value == 0,
+        }
+    }
+
+    pub fn to_json(self) -> Value {
+        match self {
+            Change::Property { tag, current, value } => json!({
+                "property": format!("{tag:?}"),
+                "tag": u8::from(tag),
+                "current": current,
+                "value": value,
+            }),
+            Change::Fuse { index, current, value } => json!({
+                "fuse": index,
+                "current": current,
+                "value": value,
+                "applicable": self.is_applicable(),
+            }),
+        }
+    }
+}
+
+impl std::fmt::Display for Change {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match *self {
+            Change::Property { tag, current, value } => {
+                write!(f, "property {tag:?} ({}): {current:#x} -> {value:#x}", u8::from(tag))
+            }
+            Change::Fuse { index, current, value } => {
+                write!(f, "fuse {index:#x}: {current:#010x} -> {value:#010x}")?;
+                if !self.is_applicable() {
+                    write!(f, " (cannot clear programmed bits)")?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl ConfigSnapshot {
+    /// Reads all available properties and the fuse words in `fuses` from the device
+    ///
+    /// Properties the device does not support are left out of the snapshot.
+    pub fn capture<T: Protocol>(
+        boot: &mut McuBoot<T>,
+        fuses: &[RangeInclusive<u32>],
+    ) -> Result<ConfigSnapshot, CommunicationError> {
+        let mut snapshot = ConfigSnapshot::default();
+        for tag in (1..=LAST_PROPERTY).filter_map(|tag| PropertyTagDiscriminants::try_from(tag).ok()) {
+            if let Some(values) = read_property(boot, tag)? {
+                snapshot.properties.push(PropertyValue { tag, values });
+            }
+        }
+        for index in fuses.iter().cloned().flatten() {
+            let value = read_fuse(boot, index)?;
+            snapshot.fuses.push(FuseWord { index, value });
+        }
+        Ok(snapshot)
+    }
+
+    /// Compares the writable subset of the snapshot with the device
+    ///
+    /// Writable properties the device does not support are skipped, unchanged values are not listed.
+    pub fn diff<T: Protocol>(&self, boot: &mut McuBoot<T>) -> Result<Vec<Change>, CommunicationError> {
+        let mut changes = Vec::new();
+        for property in self.properties.iter().filter(|p| WRITABLE_PROPERTIES.contains(&p.tag)) {
+            let Some(&value) = property.values.first() else {
+                continue;
+            };
+            match read_property(boot, property.tag)? {
+                Some(current) => {
+                    let current = current.first().copied().unwrap_or_default();
+                    if current != value {
+                        changes.push(Change::Property {
+                            tag: property.tag,
+                            current,
+                            value,
+                        });
+                    }
+                }
+                None => warn!("property {:?} is not supported by the device, skipping", property.tag),
+            }
+        }
+        for fuse in &self.fuses {
+            let current = read_fuse(boot, fuse.index)?;
+            if current != fuse.value {
+                changes.push(Change::Fuse {
+                    index: fuse.index,
+                    current,
+                    value: fuse.value,
+                });
+            }
+        }
+        Ok(changes)
+    }
+
+    pub fn to_json(&self) -> Value {
+        let properties: Vec<_> = self
+            .properties
+            .iter()
+            .map(|property| {
+                json!({
+                    "tag": u8::from(property.tag),
+                    "name": format!("{:?}", property.tag),
+                    "values": property.values,
+                })
+            })
+            .collect();
+        let fuses: Vec<_> = self
+            .fuses
+            .iter()
+            .map(|fuse| json!({ "index": fuse.index, "value": fuse.value }))
+            .collect();
+        json!({
+            "format": FORMAT,
+            "version": VERSION,
+            "properties": properties,
+            "fuses": fuses,
+        })
+    }
+
+    /// Parses a snapshot, property names are informative only, tags identify the properties
+    pub fn from_json(document: &Value) -> Result<ConfigSnapshot, CommunicationError> {
+        let invalid = |what: &str| CommunicationError::ParseError(format!("invalid configuration snapshot: {what}"));
+        if document["format"] != FORMAT {
+            return Err(invalid("not an rblhost configuration snapshot"));
+        }
+        if document["version"] != VERSION {
+            return Err(invalid(&format!("unsupported version {}", document["version"])));
+        }
+        let word = |value: &Value| u32::try_from(value.as_u64()?).ok();
+        let array = |key: &str| {
+            document[key]
+                .as_array()
+                .ok_or_else(|| invalid(&format!("missing '{key}' array")))
+        };
+
+        let mut snapshot = ConfigSnapshot::default();
+        for property in array("properties")? {
+            let tag = property["tag"]
+                .as_u64()
+                .and_then(|tag| u8::try_from(tag).ok())
+                .and_then(|tag| PropertyTagDiscriminants::try_from(tag).ok())
+                .ok_or_else(|| invalid(&format!("unknown property tag {}", property["tag"])))?;
+            let values = property["values"]
+                .as_array()
+                .and_then(|values| values.iter().map(word).collect::<Option<Box<[u32]>>>())
+                .ok_or_else(|| invalid(&format!("invalid values of property {tag:?}")))?;
+            snapshot.properties.push(PropertyValue { tag, values });
+        }
+        for fuse in array("fuses")? {
+            let (Some(index), Some(value)) = (word(&fuse["index"]), word(&fuse["value"])) else {
+                return Err(invalid(&format!("invalid fuse {fuse}")));
+            };
+            snapshot.fuses.push(FuseWord { index, value });
+        }
+        Ok(snapshot)
+    }
+}
+
+/// Applies the changes in order, stopping at the first failure
+///
+/// Returns the status of the last applied change.
+pub fn apply<T: Protocol>(boot: &mut McuBoot<T>, changes: &[Change]) -> Result<StatusCode, CommunicationError> {
+    let mut status = StatusCode::Success;
+    for change in changes {
+        status = match *change {
+            Change::Property { tag, value, .. } => boot.set_property(tag, value)?,
+            Change::Fuse { index, value, .. } => boot.fuse_program(index, 0, &value.to_le_bytes())?,
+        };
+        if status != StatusCode::Success {
+            return Err(CommunicationError::UnexpectedStatus(status, 0));
+        }
+    }
+    Ok(status)
+}
+
+/// Reads a property, [`None`] if the device does not support it
+fn read_property<T: Protocol>(
+    boot: &mut McuBoot<T>,
+    tag: PropertyTagDiscriminants,
+) -> Result<Option<Box<[u32]>>, CommunicationError> {
+    match boot.get_property(tag, 0) {
+        Ok(response) => Ok(Some(response.response_words)),
+        Err(CommunicationError::UnexpectedStatus(status, _)) => {
+            debug!("property {tag:?} is not available: {status}");
+            Ok(None)
+        }
+        Err(err) => Err(err),
+    }
+}
+
+fn read_fuse<T: Protocol>(boot: &mut McuBoot<T>, index: u32) -> Result<u32, CommunicationError> {
+    let response = boot.fuse_read(index, 4, 0)?;
+    let bytes = response.bytes.get(..4).ok_or(CommunicationError::InvalidData)?;
+    Ok(u32::from_le_bytes(bytes.try_into().expect("slice has four bytes")))
+}
+
+/// Parses a fuse index or an inclusive range of indexes, e.g. "0x60" or "0x60-0x67"
+pub fn parse_fuse_range(s: &str) -> Result<RangeInclusive<u32>, String> {
+    let (first, last) = if let Some((first, last)) = s.split_once('-') {
+        (parse::parse_number(first.trim())?, parse::parse_number(last.trim())?)
+    } else {
+        let index = parse::parse_number(s.trim())?;
+        (index, index)
+    };
+    if first > last {
+        return Err(format!("fuse range {first:#x}-{last:#x} is empty"));
+    }
+    Ok(first..=last)
+}
+
+#[cfg(all(test, feature = "testing"))]
+mod tests {
+    use mboot::protocols::mock::VirtualDevice;
+
+    use super::*;
+
+    #[test]
+    fn capture_and_import() {
+        let mut golden = McuBoot::new(VirtualDevice::new().with_property(PropertyTagDiscriminants::VerifyWrites, &[0]));
+        golden.fuse_program(0x60, 0, &0x0000_0011u32.to_le_bytes()).unwrap();
+        let snapshot = ConfigSnapshot::capture(&mut golden, &[0x60..=0x61]).unwrap();
+        assert_eq!(ConfigSnapshot::from_json(&snapshot.to_json()).unwrap(), snapshot);
+
+        let mut board = McuBoot::new(VirtualDevice::new().with_property(PropertyTagDiscriminants::VerifyWrites, &[1]));
+        let changes = snapshot.diff(&mut board).unwrap();
+        assert_eq!(
+            changes,
+            [
+                Change::Property {
+                    tag: PropertyTagDiscriminants::VerifyWrites,
+                    current: 1,
+                    value: 0
+                },
+                Change::Fuse {
+                    index: 0x60,
+                    current: 0,
+                    value: 0x11
+                }
+            ]
+        );
+        apply(&mut board, &changes).unwrap();
+        assert!(snapshot.diff(&mut board).unwrap().is_empty());
+    }
+
+    #[test]
+    fn fuse_ranges() {
+        assert_eq!(parse_fuse_range("0x60"), Ok(0x60..=0x60));
+        assert_eq!(parse_fuse_range("0x60-0x67"), Ok(0x60..=0x67));
+        assert!(parse_fuse_range("0x67-0x60").is_err());
+        assert!(parse_fuse_range("x").is_err());
+    }
+}
@@ -0,0 +1,153 @@
+// Copyright 2025 NXP
+//
+// SPDX-License-Identifier: BSD-3-Clause
+//! Catalogs of properties, commands and status codes printed by the describe subcommand
+//!
+//! The tables are generated from the enums of the library, so documentation generators and external tools
+//! stay in sync with the installed version without scraping the sources.
+
+use std::io::{self, Write};
+
+use mboot::tags::{command::CommandTagDiscriminants, property::PropertyTagDiscriminants, status::StatusCode};
+use serde_json::{Value, json};
+use strum::{EnumMessage, IntoEnumIterator};
+
+/// Catalog printed by the describe subcommand
+#[derive(clap::ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Catalog {
+    /// Properties of get-property and set-property
+    Properties,
+    /// Commands of the bootloader protocol
+    Commands,
+    /// Status codes reported by the bootloader
+    StatusCodes,
+}
+
+/// Entry of a catalog
+struct Entry {
+    /// Number of the entry in the protocol
+    code: u32,
+    /// Name used on the command line, kebab-case
+    name: Option<&'static str>,
+    /// Name of the variant in the library
+    identifier: String,
+    /// First paragraph of the documentation, or the text of status codes
+    description: String,
+}
+
+/// Joins the first paragraph of the documentation of a variant into a single line
+fn summary(documentation: Option<&str>) -> String {
+    documentation
+        .and_then(|text| text.split("\n\n").next())
+        .map(|paragraph| paragraph.lines().map(str::trim).collect::<Vec<_>>().join(" "))
+        .unwrap_or_default()
+}
+
+fn entries(catalog: Catalog) -> Vec<Entry> {
+    match catalog {
+        Catalog::Properties => PropertyTagDiscriminants::iter()
+            .map(|tag| Entry {
+                code: u8::from(tag).into(),
+                name: tag.get_serializations().first().copied(),
+                identifier: format!("{tag:?}"),
+                description: summary(tag.get_documentation()),
+            })
+            .collect(),
+        Catalog::Commands => CommandTagDiscriminants::iter()
+            .map(|tag| Entry {
+                code: u8::from(tag).into(),
+                name: tag.get_serializations().first().copied(),
+                identifier: format!("{tag:?}"),
+                description: summary(tag.get_documentation()),
+            })
+            .collect(),
+        Catalog::StatusCodes => StatusCode::iter()
+            .map(|code| Entry {
+                code: code.into(),
+                name: None,
+                identifier: format!("{code:?}"),
+                description: code.to_string(),
+            })
+            .collect(),
+    }
+}
+
+/// Returns the catalog as a JSON document
+fn to_json(catalog: Catalog) -> Value {
+    let entries: Vec<_> = entries(catalog)
+        .into_iter()
+        .map(|entry| {
+            let mut value = json!({
+                "code": entry.code,
+                "identifier": entry.identifier,
+                "description": entry.description,
+            });
+            if let Some(name) = entry.name {
+                value["name"] = name.into();
+            }
+            value
+        })
+        .collect();
+    let key = match catalog {
+        Catalog::Properties => "properties",
+        Catalog::Commands => "commands",
+        Catalog::StatusCodes => "status_codes",
+    };
+    json!({
+        "version": env!("CARGO_PKG_VERSION"),
+        key: entries,
+    })
+}
+
+/// Writes the catalog to `out`, as a JSON document if `json` is set, as a table otherwise
+pub fn write(out: &mut impl Write, catalog: Catalog, json: bool) -> io::Result<()> {
+    if json {
+        serde_json::to_writer_pretty(&mut *out, &to_json(catalog))?;
+        return writeln!(out);
+    }
+    if catalog == Catalog::StatusCodes {
+        writeln!(out, "{:>10}  {:<40}  TEXT", "CODE", "IDENTIFIER")?;
+        for entry in entries(catalog) {
+            writeln!(
+                out,
+                "{:>10}  {:<40}  {}",
+                entry.code, entry.identifier, entry.description
+            )?;
+        }
+        return Ok(());
+    }
+    writeln!(out, "{:>4}  {:<34}  DESCRIPTION", "CODE", "NAME")?;
+    for entry in entries(catalog) {
+        writeln!(
+            out,
+            "{:#04X}  {:<34}  {}",
+            entry.code,
+            entry.name.unwrap_or_default(),
+            entry.description
+        )?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn catalogs_come_from_enums() {
+        let properties = to_json(Catalog::Properties);
+        let current_version = &properties["properties"][0];
+        assert_eq!(current_version["code"], 1);
+        assert_eq!(current_version["name"], "current-version");
+        assert_eq!(current_version["description"], "Current version of the bootloader");
+
+        let commands = to_json(Catalog::Commands);
+        let erase = &commands["commands"][1];
+        assert_eq!(erase["identifier"], "FlashEraseAll");
+        assert_eq!(erase["name"], "flash-erase-all");
+
+        let status_codes = to_json(Catalog::StatusCodes);
+        assert_eq!(status_codes["status_codes"][0]["identifier"], "Success");
+        assert!(status_codes["status_codes"][0].get("name").is_none());
+    }
+}
@@ -0,0 +1,65 @@
+// Copyright 2025 NXP
+//
+// SPDX-License-Identifier: BSD-3-Clause
+//! Log of the effective configuration
+//!
+//! With `-v`, every option which has a value is logged before the command is executed, together with the
+//! origin of the value, so it is clear whether the command line, the configuration file, the environment or a
+//! default won.
+
+use clap::{ArgMatches, CommandFactory, parser::ValueSource};
+use log::{Level, info, log_enabled};
+
+use crate::{Args, parse_port};
+
+/// Describes where the value of an option came from
+fn origin(source: ValueSource) -> &'static str {
+    match source {
+        ValueSource::CommandLine => "command line",
+        ValueSource::EnvVariable => "environment",
+        ValueSource::DefaultValue => "default",
+        _ => "unknown",
+    }
+}
+
+/// Formats the value of option `id` as it was given
+fn raw_value(matches: &ArgMatches, id: &str) -> String {
+    match matches.get_raw(id) {
+        Some(values) if values.len() > 0 => values
+            .map(|value| value.to_string_lossy().into_owned())
+            .collect::<Vec<_>>()
+            .join(" "),
+        // counted flags have no raw values
+        _ => matches
+            .try_get_one::<u8>(id)
+            .ok()
+            .flatten()
+            .map_or_else(String::new, ToString::to_string),
+    }
+}
+
+/// Logs options of the invocation with their values and origins, values derived from the options are logged too
+///
+/// Options with IDs in `from_config` were taken from the configuration file.
+pub fn log(matches: &ArgMatches, args: &Args, from_config: &[String]) {
+    if !log_enabled!(Level::Info) {
+        return;
+    }
+    info!("Effective configuration:");
+    for arg in Args::command().get_arguments().filter(|arg| !arg.is_hide_set()) {
+        let id = arg.get_id().as_str();
+        if let Some(source) = matches.value_source(id) {
+            let origin = if from_config.iter().any(|config_id| config_id == id) {
+                "config file"
+            } else {
+                origin(source)
+            };
+            info!("  {id} = {} ({origin})", raw_value(matches, id));
+        }
+    }
+    if let Some(port) = &args.device.port {
+        let (_, baudrate) = parse_port(port);
+        let source = if port.contains(',') { "port" } else { "default" };
+        info!("  baudrate = {baudrate} ({source})");
+    }
+}
@@ -0,0 +1,230 @@
+// Copyright 2025 NXP
+//
+// SPDX-License-Identifier: BSD-3-Clause
+//! Catalog of errors printed by the errors subcommand
+//!
+//! External systems like MES or dashboards build their lookup tables from the catalog, so they always match
+//! the installed version. It lists all status codes known to this version, with their texts, subsystems and
+//! exit codes, followed by the kinds of errors detected on the host.
+
+use std::io::{self, Write};
+
+use mboot::{CommunicationError, tags::status::StatusCode};
+use serde_json::{Value, json};
+use strum::IntoEnumIterator;
+
+use crate::exit_code;
+
+/// Kind of error detected on the host, before or instead of a device status
+struct HostError {
+    /// Identifier of the kind, the variant of [`CommunicationError`] in most cases
+    identifier: &'static str,
+    /// What went wrong
+    description: &'static str,
+    /// Example of the error, only used to derive the exit code
+    example: fn() -> anyhow::Error,
+}
+
+const HOST_ERRORS: &[HostError] = &[
+    HostError {
+        identifier: "SerialPortError",
+        description: "serial port could not be opened or configured",
+        example: || {
+            CommunicationError::SerialPortError(serialport::Error::new(serialport::ErrorKind::NoDevice, "")).into()
+        },
+    },
+    HostError {
+        identifier: "IOError",
+        description: "reading from or writing to the device failed",
+        example: || CommunicationError::IOError(io::Error::other("")).into(),
+    },
+    HostError {
+        identifier: "FileError",
+        description: "file could not be read or written",
+        example: || CommunicationError::FileError(io::Error::other("")).into(),
+    },
+    HostError {
+        identifier: "NACKSent",
+        description: "device sent NACK",
+        example: || CommunicationError::NACKSent.into(),
+    },
+    HostError {
+        identifier: "InvalidCrc",
+        description: "received frame has an incorrect CRC",
+        example: || CommunicationError::InvalidCrc.into(),
+    },
+    HostError {
+        identifier: "InvalidHeader",
+        description: "received frame has an invalid header",
+        example: || CommunicationError::InvalidHeader.into(),
+    },
+    HostError {
+        identifier: "InvalidData",
+        description: "received packet contains invalid data",
+        example: || CommunicationError::InvalidData.into(),
+    },
+    HostError {
+        identifier: "InvalidPacketReceived",
+        description: "received another packet type than was expected",
+        example: || CommunicationError::InvalidPacketReceived.into(),
+    },
+    HostError {
+        identifier: "ParseError",
+        description: "argument, file or response could not be parsed",
+        example: || CommunicationError::ParseError(String::new()).into(),
+    },
+    HostError {
+        identifier: "Aborted",
+        description: "communication was aborted",
+        example: || CommunicationError::Aborted.into(),
+    },
+    HostError {
+        identifier: "UnsupportedPlatform",
+        description: "functionality is not supported on the current platform",
+        example: || CommunicationError::UnsupportedPlatform.into(),
+    },
+    HostError {
+        identifier: "Timeout",
+        description: "device did not respond in time",
+        example: || CommunicationError::Timeout.into(),
+    },
+    HostError {
+        identifier: "ImageFormatError",
+        description: "image file could not be parsed",
+        example: || CommunicationError::ImageFormatError(io::Error::other("").into()).into(),
+    },
+    HostError {
+        identifier: "PayloadTooLarge",
+        description: "payload does not fit into the length field of a packet",
+        example: || CommunicationError::PayloadTooLarge { size: 0, limit: 0 }.into(),
+    },
+    HostError {
+        identifier: "ReservedRegion",
+        description: "operation would change a memory region reserved by the bootloader",
+        example: || {
+            CommunicationError::ReservedRegion {
+                start_address: 0,
+                end_address: 0,
+            }
+            .into()
+        },
+    },
+    HostError {
+        identifier: "VerifyMismatch",
+        description: "memory read back after writing differs from the written data",
+        example: || {
+            CommunicationError::VerifyMismatch {
+                address: 0,
+                window_address: 0,
+                expected: Box::default(),
+                actual: Box::default(),
+            }
+            .into()
+        },
+    },
+    HostError {
+        identifier: "Other",
+        description: "any other host failure, e.g. invalid arguments or images",
+        example: || anyhow::anyhow!(""),
+    },
+];
+
+/// Returns the exit code of a command finished with status `code`
+fn status_exit_code(code: StatusCode) -> u8 {
+    if code.is_success() {
+        0
+    } else {
+        exit_code::of_status(code.into())
+    }
+}
+
+/// Returns the catalog as a JSON document
+fn to_json() -> Value {
+    let status_codes: Vec<_> = StatusCode::iter()
+        .map(|code| {
+            json!({
+                "code": u32::from(code),
+                "identifier": format!("{code:?}"),
+                "text": code.to_string(),
+                "subsystem": code.category().to_string(),
+                "exit_code": status_exit_code(code),
+            })
+        })
+        .collect();
+    let host_errors: Vec<_> = HOST_ERRORS
+        .iter()
+        .map(|error| {
+            json!({
+                "identifier": error.identifier,
+                "description": error.description,
+                "exit_code": exit_code::of(&(error.example)()),
+            })
+        })
+        .collect();
+    json!({
+        "version": env!("CARGO_PKG_VERSION"),
+        "status_codes": status_codes,
+        "host_errors": host_errors,
+    })
+}
+
+/// Writes the catalog to `out`, as a JSON document if `json` is set, as a table otherwise
+pub fn write(out: &mut impl Write, json: bool) -> io::Result<()> {
+    if json {
+        serde_json::to_writer_pretty(&mut *out, &to_json())?;
+        return writeln!(out);
+    }
+    writeln!(
+        out,
+        "{:>10}  {:>4}  {:<20}  {:<40}  TEXT",
+        "CODE", "EXIT", "SUBSYSTEM", "IDENTIFIER"
+    )?;
+    for code in StatusCode::iter() {
+        writeln!(
+            out,
+            "{:>10}  {:>4}  {:<20}  {:<40}  {code}",
+            u32::from(code),
+            status_exit_code(code),
+            code.category().to_string(),
+            format!("{code:?}"),
+        )?;
+    }
+    writeln!(out)?;
+    writeln!(out, "{:>4}  {:<22}  DESCRIPTION", "EXIT", "HOST ERROR")?;
+    for error in HOST_ERRORS {
+        writeln!(
+            out,
+            "{:>4}  {:<22}  {}",
+            exit_code::of(&(error.example)()),
+            error.identifier,
+            error.description
+        )?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn catalog_lists_codes_once() {
+        let catalog = to_json();
+        let codes = catalog["status_codes"].as_array().unwrap();
+        assert_eq!(codes[0]["identifier"], "Success");
+        assert_eq!(codes[0]["exit_code"], 0);
+        assert_eq!(codes[1]["exit_code"], 10);
+        let mut numbers: Vec<_> = codes.iter().map(|code| code["code"].as_u64().unwrap()).collect();
+        numbers.sort_unstable();
+        numbers.dedup();
+        assert_eq!(numbers.len(), codes.len());
+
+        let timeout = catalog["host_errors"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|error| error["identifier"] == "Timeout")
+            .unwrap();
+        assert_eq!(timeout["exit_code"], exit_code::COMMUNICATION_ERROR);
+    }
+}
@@ -0,0 +1,71 @@
+// Copyright 2025 NXP
+//
+// SPDX-License-Identifier: BSD-3-Clause
+//! Process exit codes
+//!
+//! Scripts tell the kind of failure from the exit code alone:
+//!
+//! - 0: success
+//! - 1: host side failure, e.g. invalid arguments, unreadable files or invalid images
+//! - 2: communication failure, the device did not respond or its response was invalid
+//! - 10 and higher: the device rejected the command, the code is 10 plus the [`StatusCategory`] of the status
+//! - 130: interrupted by Ctrl-C outside of a data phase, an aborted data phase is a communication failure
+
+use mboot::{CommunicationError, tags::status::StatusCategory};
+
+/// Host side failure
+pub const HOST_ERROR: u8 = 1;
+/// Communication failure
+pub const COMMUNICATION_ERROR: u8 = 2;
+/// Interrupted by the user, 128 plus SIGINT as set by shells
+pub const INTERRUPTED: u8 = 130;
+/// Exit code of the first status category
+const DEVICE_ERROR_BASE: u8 = 10;
+
+/// Returns the exit code for an error returned by the command
+pub fn of(err: &anyhow::Error) -> u8 {
+    err.downcast_ref::<CommunicationError>()
+        .map_or(HOST_ERROR, of_communication_error)
+}
+
+/// Returns the exit code for a command rejected by the device with status `code`
+pub fn of_status(code: u32) -> u8 {
+    DEVICE_ERROR_BASE + u8::from(StatusCategory::of(code))
+}
+
+fn of_communication_error(err: &CommunicationError) -> u8 {
+    match err {
+        CommunicationError::UnexpectedStatus(_, code) => of_status(*code),
+        CommunicationError::SerialPortError(_)
+        | CommunicationError::IOError(_)
+        | CommunicationError::NACKSent
+        | CommunicationError::InvalidCrc
+        | CommunicationError::InvalidHeader
+        | CommunicationError::InvalidData
+        | CommunicationError::InvalidPacketReceived
+        | CommunicationError::Aborted
+        | CommunicationError::Timeout => COMMUNICATION_ERROR,
+        // the remaining errors, including those added to the library later, happen on the host
+        _ => HOST_ERROR,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use mboot::tags::status::StatusCode;
+
+    use super::*;
+
+    #[test]
+    fn exit_codes() {
+        assert_eq!(of(&CommunicationError::Timeout.into()), COMMUNICATION_ERROR);
+        assert_eq!(of(&CommunicationError::ParseError(String::new()).into()), HOST_ERROR);
+        assert_eq!(of(&anyhow::anyhow!("other")), HOST_ERROR);
+        assert_eq!(of(&CommunicationError::from(StatusCode::Fail).into()), 10);
+        assert_eq!(of(&CommunicationError::from(StatusCode::MemoryRangeInvalid).into()), 17);
+        assert_eq!(
+            of(&CommunicationError::UnexpectedStatus(StatusCode::UnknownStatusCode, 10_950).into()),
+            25
+        );
+    }
+}
@@ -0,0 +1,136 @@
+// Copyright 2025 NXP
+//
+// SPDX-License-Identifier: BSD-3-Clause
+//! Identity check of the device before destructive commands
+//!
+//! On hosts with several boards connected, a wrong port erases or fuses the wrong board. With
+//! --expect-device, the identity of the device is read before every destructive command and the command
+//! is not sent if it does not match.
+
+use mboot::{
+    CommunicationError, McuBoot,
+    parse::parse_number,
+    protocols::Protocol,
+    tags::{
+        command::KeyProvOperation,
+        property::{PropertyTag, PropertyTagDiscriminants},
+    },
+};
+
+use crate::{Commands, KeyProvCommand};
+
+/// Identity the device is expected to report
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ExpectedDevice {
+    /// Value of the system-device-id property
+    SystemId(u32),
+    /// Value of the unique-device-id property, as lowercase hex digits printed by get-property
+    UniqueId(String),
+}
+
+impl ExpectedDevice {
+    /// Parses a 32-bit number as the system device ID, a longer hex string as the unique device ID
+    pub fn parse(s: &str) -> Result<ExpectedDevice, String> {
+        if let Ok(id) = parse_number::<u32>(s) {
+            return Ok(ExpectedDevice::SystemId(id));
+        }
+        let digits = s.strip_prefix("0x").unwrap_or(s);
+        if digits.len() > 8 && digits.len().is_multiple_of(2) && digits.chars().all(|c| c.is_ascii_hexdigit()) {
+            Ok(ExpectedDevice::UniqueId(digits.to_ascii_lowercase()))
+        } else {
+            Err(format!(
+                "'{s}' is neither a 32-bit system device ID nor a unique device ID in hex"
+            ))
+        }
+    }
+}
+
+/// Whether the command cannot be undone, so the identity is checked before it
+///
+/// Writing the key store to nonvolatile memory replaces the keys stored there. Trust provisioning
+/// operations only work with buffers in RAM, so they are not checked.
+pub fn is_destructive(command: &Commands) -> bool {
+    matches!(
+        command,
+        Commands::FlashEraseAll { .. }
+            | Commands::FlashEraseAllUnsecure
+            | Commands::FuseProgram { .. }
+            | Commands::FlashProgramOnce { .. }
+            | Commands::EfuseProgramOnce { .. }
+            | Commands::ImportConfig { dry_run: false, .. }
+            | Commands::KeyProvisioning(
+                KeyProvCommand::Persist { .. }
+                    | KeyProvCommand::Operation(KeyProvOperation::WriteKeyNonvolatile { .. })
+            )
+    )
+}
+
+/// Reads the identity of the device and fails if it is not the expected one
+///
+/// # Errors
+/// [`CommunicationError::ParseError`] describing the mismatch, any error of the property request otherwise.
+pub fn check<T: Protocol>(boot: &mut McuBoot<T>, expected: &ExpectedDevice) -> Result<(), CommunicationError> {
+    let (tag, expected) = match expected {
+        ExpectedDevice::SystemId(id) => (PropertyTagDiscriminants::SystemDeviceId, format!("{id:#010x}")),
+        ExpectedDevice::UniqueId(id) => (PropertyTagDiscriminants::UniqueDeviceId, id.clone()),
+    };
+    let actual = match boot.get_property(tag, 0)?.property {
+        PropertyTag::SystemDeviceId(id) => format!("{id:#010x}"),
+        PropertyTag::UniqueDeviceId(id) => id.to_string(),
+        _ => return Err(CommunicationError::InvalidData),
+    };
+    if actual == expected {
+        Ok(())
+    } else {
+        Err(CommunicationError::ParseError(format!(
+            "device reports {tag:?} {actual} instead of the expected {expected}, command was not sent"
+        )))
+    }
+}
+
+#[cfg(all(test, feature = "testing"))]
+mod tests {
+    use mboot::protocols::mock::VirtualDevice;
+
+    use super::*;
+
+    #[test]
+    fn identity_is_checked() {
+        assert_eq!(
+            ExpectedDevice::parse("0x01234567"),
+            Ok(ExpectedDevice::SystemId(0x0123_4567))
+        );
+        assert_eq!(
+            ExpectedDevice::parse("0x4433221188776655CCBBAA9900FFEEDD"),
+            Ok(ExpectedDevice::UniqueId("4433221188776655ccbbaa9900ffeedd".to_owned()))
+        );
+        assert!(ExpectedDevice::parse("board-1").is_err());
+
+        let mut boot = McuBoot::new(VirtualDevice::new());
+        check(&mut boot, &ExpectedDevice::SystemId(0x0123_4567)).unwrap();
+        check(
+            &mut boot,
+            &ExpectedDevice::UniqueId("04030201080706050c0b0a09100f0e0d".to_owned()),
+        )
+        .unwrap();
+        let err = check(&mut boot, &ExpectedDevice::SystemId(0x7654_3210)).unwrap_err();
+        assert!(matches!(err, CommunicationError::ParseError(_)));
+    }
+
+    #[test]
+    fn config_import_and_key_store_persist_are_destructive() {
+        let import = |dry_run| Commands::ImportConfig {
+            file: "config.json".to_owned(),
+            dry_run,
+        };
+        assert!(is_destructive(&import(false)));
+        assert!(!is_destructive(&import(true)));
+        assert!(is_destructive(&Commands::KeyProvisioning(KeyProvCommand::Persist {
+            memory_id: 0,
+            verify: false
+        })));
+        assert!(!is_destructive(&Commands::KeyProvisioning(KeyProvCommand::Operation(
+            KeyProvOperation::Enroll
+        ))));
+    }
+}
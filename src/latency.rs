@@ -0,0 +1,75 @@
+// Copyright 2025 NXP
+//
+// SPDX-License-Identifier: BSD-3-Clause
+//! Round-trip time statistics for the latency-test subcommand
+
+use std::time::Duration;
+
+/// Minimal suggested timeout, short timeouts are unreliable on loaded systems
+const MIN_SUGGESTED_TIMEOUT: Duration = Duration::from_millis(100);
+/// Minimal suggested polling interval
+const MIN_SUGGESTED_POLLING: Duration = Duration::from_millis(1);
+
+/// Statistics of measured round trips
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LatencyStats {
+    pub count: usize,
+    pub min: Duration,
+    pub avg: Duration,
+    pub max: Duration,
+    /// Mean difference between consecutive round trips
+    pub jitter: Duration,
+}
+
+impl LatencyStats {
+    /// Computes statistics of the samples, [`None`] if there are no samples
+    pub fn from_samples(samples: &[Duration]) -> Option<Self> {
+        let min = *samples.iter().min()?;
+        let max = *samples.iter().max()?;
+        let count = u32::try_from(samples.len()).ok()?;
+        let avg = samples.iter().sum::<Duration>() / count;
+        let jitter = match count {
+            1 => Duration::ZERO,
+            _ => {
+                samples
+                    .windows(2)
+                    .map(|pair| pair[0].abs_diff(pair[1]))
+                    .sum::<Duration>()
+                    / (count - 1)
+            }
+        };
+        Some(LatencyStats {
+            count: samples.len(),
+            min,
+            avg,
+            max,
+            jitter,
+        })
+    }
+
+    /// Suggested --timeout, ten times the slowest round trip
+    pub fn suggested_timeout(&self) -> Duration {
+        (self.max * 10).max(MIN_SUGGESTED_TIMEOUT)
+    }
+
+    /// Suggested --polling-interval, a tenth of the fastest round trip
+    pub fn suggested_polling_interval(&self) -> Duration {
+        (self.min / 10).max(MIN_SUGGESTED_POLLING)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn statistics() {
+        let ms = Duration::from_millis;
+        let stats = LatencyStats::from_samples(&[ms(10), ms(30), ms(20)]).unwrap();
+        assert_eq!((stats.min, stats.avg, stats.max), (ms(10), ms(20), ms(30)));
+        assert_eq!(stats.jitter, ms(15));
+        assert_eq!(stats.suggested_timeout(), ms(300));
+        assert_eq!(stats.suggested_polling_interval(), ms(1));
+        assert_eq!(LatencyStats::from_samples(&[]), None);
+    }
+}
@@ -2,9 +2,12 @@
 //
 // SPDX-License-Identifier: BSD-3-Clause
 pub use mboot::{
-    GetPropertyResponse, KeyProvisioningResponse, McuBoot, ReadMemoryResponse, memory, packets,
+    CommandOutcome, GetPropertyResponse, KeyProvisioningResponse, McuBoot, ReadMemoryResponse, TransferOptions, abort,
+    capabilities, checksum, formats, formatters, kw45, memory,
+    ops::{self, McuBootOps},
+    packets, progress,
     protocols::{self, CommunicationError},
-    tags,
+    sb, scratch, session, tags, warning,
 };
 
 #[cfg(feature = "python")]
@@ -16,7 +19,8 @@ use pyo3_stub_gen::define_stub_info_gatherer;
 define_stub_info_gatherer!(stub_info);
 
 mod mboot;
-mod parsers;
+pub mod parse;
+pub mod prelude;
 
 // Only include the c_api module when the c_api feature is enabled
 #[cfg(feature = "c_api")]
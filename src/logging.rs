@@ -0,0 +1,46 @@
+// Copyright 2025 NXP
+//
+// SPDX-License-Identifier: BSD-3-Clause
+//! Diagnostic log of the command line tool
+//!
+//! Log records of the library and the tool are collected by a `tracing` subscriber on stderr. Every command
+//! sent to the device runs in a `command` span and every data phase in a nested `data_phase` span, the span
+//! is logged when it closes with the status of the command and the time it took (`time.busy` and
+//! `time.idle`), so the log can be ingested by log collectors of manufacturing lines.
+
+use std::io::{self, IsTerminal};
+
+use tracing::level_filters::LevelFilter;
+use tracing_subscriber::{EnvFilter, fmt::format::FmtSpan};
+
+/// Format of the diagnostic log on stderr
+#[derive(clap::ValueEnum, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum LogFormat {
+    /// Human readable lines
+    #[default]
+    Text,
+    /// JSON object per line
+    Json,
+}
+
+/// Installs the subscriber of the log, `RUST_LOG` directives override the level given by `verbose`
+pub fn init(verbose: u8, format: LogFormat) {
+    let level = match verbose {
+        0 => LevelFilter::WARN,
+        1 => LevelFilter::INFO,
+        2 => LevelFilter::DEBUG,
+        _ => LevelFilter::TRACE,
+    };
+    let filter = EnvFilter::builder()
+        .with_default_directive(level.into())
+        .from_env_lossy();
+    let builder = tracing_subscriber::fmt()
+        .with_env_filter(filter)
+        .with_writer(io::stderr)
+        .with_ansi(io::stderr().is_terminal())
+        .with_span_events(FmtSpan::CLOSE);
+    match format {
+        LogFormat::Text => builder.init(),
+        LogFormat::Json => builder.json().init(),
+    }
+}
@@ -8,95 +8,555 @@
 )]
 
 use std::{
-    fs::File,
-    io::{Read, Write},
+    fs::{self, File},
+    hash::{BuildHasher, RandomState},
+    io::{self, BufWriter, Write},
+    iter,
+    ops::RangeInclusive,
+    path::{Path, PathBuf},
+    process::ExitCode,
+    sync::OnceLock,
+    thread,
+    time::{Duration, Instant},
 };
-mod parsers;
+mod batch;
+mod benchmark;
+mod completions;
+mod config;
+mod describe;
+mod effective_config;
+mod error_catalog;
+mod exit_code;
+mod expect_device;
+mod latency;
+mod logging;
+mod output_stream;
+mod profile;
+mod quick_verify;
+mod report;
+mod reporter;
+mod reserved_check;
 
-use clap::{Arg, ArgGroup, Parser, Subcommand};
-use log::{LevelFilter, debug, warn};
+use anyhow::Context;
+use clap::{
+    Arg, ArgGroup, ArgMatches, CommandFactory, FromArgMatches, Parser, Subcommand, builder::BoolishValueParser,
+    error::ErrorKind, parser::ValueSource,
+};
+use color_print::cformat;
+use config::ConfigSnapshot;
+use expect_device::ExpectedDevice;
+use latency::LatencyStats;
+use log::{debug, error, warn};
+use logging::LogFormat;
 use mboot::{
-    CommunicationError, GetPropertyResponse, KeyProvisioningResponse, McuBoot, ReadMemoryResponse,
-    protocols::{Protocol, ProtocolOpen, i2c::I2CProtocol, uart::UARTProtocol, usb::USBProtocol},
+    CommunicationError, GetPropertyResponse, KeyProvisioningResponse, McuBoot, ReadMemoryResponse, TransferOptions,
+    abort::AbortHandle,
+    checksum::ChecksumAlgorithm,
+    formats::{self, ImageFormat, Segment},
+    formatters::HexDiff,
+    kw45,
+    memory::PatternSize,
+    packets::Framing,
+    parse,
+    protocols::{
+        Protocol, ProtocolOpen,
+        buspal::{BusPalProtocol, BusPalTarget},
+        can::CANProtocol,
+        i2c::I2CProtocol,
+        lpcusbsio::{LpcUsbSioProtocol, LpcUsbSioTarget},
+        network::NetworkProtocol,
+        record::{RecordingProtocol, ReplayProtocol, Transcript},
+        uart::{self, FALLBACK_BAUDRATES, FlowControl, LineStep, SerialOptions, UARTProtocol},
+        usb::{USBProtocol, UsbLengthQuirk},
+    },
+    sb::{self, SbHeader},
+    session::PhaseTimeouts,
     tags::{
         command::{KeyProvOperation, TrustProvOperation},
-        property::PropertyTagDiscriminants,
-        status::StatusCode,
+        property::{Family, PropertyTag, PropertyTagDiscriminants},
+        status::{self, StatusCode},
     },
+    warning::Warning,
 };
-use pretty_hex::{HexConfig, PrettyHex};
+use output_stream::OutputStream;
+use profile::{ConfigAction, ProfileArguments};
+use report::Report;
+use reporter::{HexdumpLayout, OutputFormat, Reporter};
+
+fn main() -> ExitCode {
+    match run() {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("Error: {err:?}");
+            ExitCode::from(exit_code::of(&err))
+        }
+    }
+}
+
+/// Prints the error of the argument parser and exits, invalid arguments are host errors
+///
+/// Clap exits with 2 on its own, which is reserved for communication failures.
+fn exit_with_usage_error(err: &clap::Error) -> ! {
+    if !err.use_stderr() {
+        // help and version are printed to stdout and are not errors
+        err.exit();
+    }
+    // printing to the terminal cannot fail in any way worth reporting
+    let _ = err.print();
+    std::process::exit(exit_code::HOST_ERROR.into());
+}
 
-fn main() -> anyhow::Result<()> {
-    let args = std::env::args();
+fn run() -> anyhow::Result<()> {
     // FIXME this probably isn't the best solution to ignore "--", but it's the best I've come up with to stay compatible with the python version
-    let args = Args::parse_from(args.filter(|arg| arg != "--"));
-    env_logger::builder()
-        .filter_level(match args.verbose {
-            0 => LevelFilter::Warn,
-            1 => LevelFilter::Info,
-            2 => LevelFilter::Debug,
-            _ => LevelFilter::Trace,
-        })
-        .format_timestamp_millis()
-        .parse_default_env()
-        .init();
-
-    // clap ensures, that at least one of the device is Some
-    if args.device.port.is_some() {
-        let mut blhost = Blhost::new_from_uart(args)?;
-        run_blhost(&mut blhost)?;
+    let arguments: Vec<String> = std::env::args().filter(|arg| arg != "--").collect();
+    let mut matches = Args::command()
+        .try_get_matches_from(&arguments)
+        .unwrap_or_else(|err| exit_with_usage_error(&err));
+    let mut args = Args::from_arg_matches(&matches).unwrap_or_else(|err| exit_with_usage_error(&err));
+    match args.command {
+        Commands::Completions { shell } => return Ok(completions::print_script(shell)?),
+        Commands::CompleteDevices { kind } => {
+            completions::print_devices(kind);
+            return Ok(());
+        }
+        Commands::Config { ref action } => {
+            let format = if args.json { OutputFormat::Json } else { args.output };
+            return run_config(action, format, args.silent);
+        }
+        _ => {}
+    }
+    let config = match profile::path() {
+        Some(path) => profile::load(&path)?,
+        None => toml::Table::new(),
+    };
+    let from_config = ProfileArguments::new(&Args::command(), &matches, &config, args.profile.as_deref())?;
+    if !from_config.arguments.is_empty() {
+        matches = Args::command()
+            .try_get_matches_from(from_config.merge(&arguments))
+            .unwrap_or_else(|err| exit_with_usage_error(&err));
+        args = Args::from_arg_matches(&matches).unwrap_or_else(|err| exit_with_usage_error(&err));
+    }
+    args.device
+        .select(&matches)
+        .unwrap_or_else(|err| exit_with_usage_error(&err));
+    logging::init(args.verbose, args.log_format);
+    effective_config::log(&matches, &args, &from_config.ids);
+
+    if let Some(path) = &args.status_texts {
+        let count = status::load_status_texts(path)?;
+        debug!("loaded {count} status texts from {}", path.display());
+    }
+
+    if print_catalog(&args)? {
+        return Ok(());
+    }
+
+    if matches!(args.command, Commands::ListPorts) {
+        let format = if args.json { OutputFormat::Json } else { args.output };
+        let mut reporter = Reporter::new(format, args.silent, false);
+        reporter.ports(&uart::list_ports()?);
+        reporter.flush();
+        return Ok(());
+    }
+
+    if let Some(limit) = args.session_timeout {
+        start_session_watchdog(Duration::from_secs(limit));
+    }
+
+    if args.buspal.is_some() {
+        let blhost = Blhost::new_from_buspal(args)?;
+        run_blhost(blhost)?;
+    } else if args.device.port.is_some() {
+        let blhost = Blhost::new_from_uart(args)?;
+        run_blhost(blhost)?;
     } else if args.device.i2c.is_some() {
-        let mut blhost = Blhost::new_from_i2c(args)?;
-        run_blhost(&mut blhost)?;
+        let blhost = Blhost::new_from_i2c(args)?;
+        run_blhost(blhost)?;
     } else if args.device.usb.is_some() {
-        let mut blhost = Blhost::new_from_usb(args)?;
-        run_blhost(&mut blhost)?;
+        let blhost = Blhost::new_from_usb(args)?;
+        run_blhost(blhost)?;
+    } else if args.device.can.is_some() {
+        let blhost = Blhost::new_from_can(args)?;
+        run_blhost(blhost)?;
+    } else if args.device.lpcusbsio.is_some() {
+        let blhost = Blhost::new_from_lpcusbsio(args)?;
+        run_blhost(blhost)?;
+    } else if args.device.tcp.is_some() || args.device.udp.is_some() {
+        let blhost = Blhost::new_from_network(args)?;
+        run_blhost(blhost)?;
+    } else if args.device.replay.is_some() {
+        let blhost = Blhost::new_from_replay(args)?;
+        run_blhost(blhost)?;
+    } else {
+        exit_with_usage_error(&Args::command().error(
+            ErrorKind::MissingRequiredArgument,
+            "one of --port, --usb, --i2c, --can, --lpcusbsio, --tcp, --udp or --replay is required",
+        ));
+    }
+    Ok(())
+}
+
+/// Prints the catalog of the errors and describe commands, returns whether the command was one of them
+fn print_catalog(args: &Args) -> io::Result<bool> {
+    let output_json = args.json || args.output == OutputFormat::Json;
+    match args.command {
+        Commands::Errors { json } => error_catalog::write(&mut io::stdout().lock(), json || output_json)?,
+        Commands::Describe { catalog, json } => {
+            describe::write(&mut io::stdout().lock(), catalog, json || output_json)?;
+        }
+        _ => return Ok(false),
+    }
+    Ok(true)
+}
+
+/// Lists, prints or changes options of the configuration file
+fn run_config(action: &ConfigAction, format: OutputFormat, silent: bool) -> anyhow::Result<()> {
+    let path = profile::path().context("configuration directory is unknown, set HOME or XDG_CONFIG_HOME")?;
+    let mut config = profile::load(&path)?;
+    let mut reporter = Reporter::new(format, silent, false);
+    match action {
+        ConfigAction::List => reporter.config_entries(&profile::list(&config)),
+        ConfigAction::Get { key } => {
+            let value =
+                profile::get(&config, key).with_context(|| format!("'{key}' is not set in {}", path.display()))?;
+            reporter.config_entries(&[(key.clone(), value.clone())]);
+        }
+        ConfigAction::Set { key, value } => {
+            profile::set(&Args::command(), &mut config, key, value)?;
+            profile::save(&path, &config)?;
+        }
     }
+    reporter.flush();
     Ok(())
 }
 
-fn run_blhost<T>(blhost: &mut Blhost<T>) -> anyhow::Result<()>
+/// Format of a memory dump written into `file_name`
+///
+/// Without `format`, Intel HEX and S-record files are detected from the extension, other files are binary.
+fn dump_format(file_name: &str, format: Option<ImageFormat>) -> ImageFormat {
+    format.unwrap_or_else(|| match ImageFormat::from_path(Path::new(file_name)) {
+        detected @ (ImageFormat::IntelHex | ImageFormat::Srec) => detected,
+        _ => ImageFormat::Binary,
+    })
+}
+
+/// Content of a memory dump in `format`, Intel HEX and S-record files place it at `start_address`
+fn dump_content(format: ImageFormat, start_address: u32, bytes: &[u8]) -> Result<Vec<u8>, CommunicationError> {
+    let segment = Segment {
+        address: start_address,
+        data: bytes.to_vec(),
+    };
+    Ok(formats::write_segments(&[segment], format)?)
+}
+
+/// Abort of data phases of the session, set once the device is opened
+static SESSION_ABORT: OnceLock<AbortHandle> = OnceLock::new();
+
+/// Time the command aborted by the session watchdog has to fail and write its transcript and report
+const ABORT_GRACE_PERIOD: Duration = Duration::from_secs(2);
+
+/// Aborts the whole process once `limit` elapses, no matter what is rblhost currently waiting for
+///
+/// A data phase in progress is aborted first, the process exits after [`ABORT_GRACE_PERIOD`] if the command does
+/// not fail by then.
+fn start_session_watchdog(limit: Duration) {
+    thread::spawn(move || {
+        thread::sleep(limit);
+        error!("session timeout of {} s exceeded, aborting", limit.as_secs());
+        if SESSION_ABORT.get().is_some_and(AbortHandle::abort) {
+            thread::sleep(ABORT_GRACE_PERIOD);
+        }
+        std::process::exit(exit_code::COMMUNICATION_ERROR.into());
+    });
+}
+
+/// Aborts the data phase in progress on Ctrl-C, so the device is left ready for the next command
+///
+/// Without a data phase in progress, or on a second Ctrl-C, the process exits right away.
+fn install_interrupt_handler(abort: AbortHandle) {
+    let mut interrupted = false;
+    let result = ctrlc::set_handler(move || {
+        if interrupted || !abort.abort() {
+            std::process::exit(exit_code::INTERRUPTED.into());
+        }
+        interrupted = true;
+        eprintln!("Aborting the data phase, press Ctrl-C again to exit right away");
+    });
+    if let Err(err) = result {
+        debug!("Ctrl-C handler was not installed: {err}");
+    }
+}
+
+/// Lets Ctrl-C and the session watchdog abort data phases through `abort`
+fn install_abort_handlers(abort: AbortHandle) {
+    // the session has a single device, so the handle is set only once
+    let _ = SESSION_ABORT.set(abort.clone());
+    install_interrupt_handler(abort);
+}
+
+fn run_blhost<T>(mut blhost: Blhost<T>) -> anyhow::Result<()>
 where
     T: Protocol,
 {
-    blhost.execute()?;
+    let Some(path) = blhost.args.record.clone() else {
+        install_abort_handlers(blhost.boot.abort_handle());
+        blhost.execute()?;
+        return Ok(());
+    };
+    // the transcript is saved even if the command fails, failed sessions are the interesting ones
+    let mut blhost = blhost.recorded();
+    install_abort_handlers(blhost.boot.abort_handle());
+    let result = blhost.execute();
+    blhost.boot.device().transcript().save(&path)?;
+    result?;
     Ok(())
 }
 
 // TODO the original blhost can just *recover* the board when the program crashes and doesn't send ACK? would be nice to have that here too
 
 #[derive(clap::Args, Debug)]
-#[group(required = true, multiple = false)]
+// not required by clap, commands which do not communicate with the device can be used without it; multiple
+// devices are allowed by clap, so devices from the environment do not conflict with the command line, and
+// rejected by Device::select
 struct Device {
     /// I2C device identifier in format /dev/i2c-X[:0xYY] where X is the bus number
     /// and YY is the optional slave address [default: 0x10]
-    #[arg(long)]
+    ///
+    /// A MCP2221A USB to I2C bridge, usable also on Windows and macOS, is selected with
+    /// mcp2221[:<serial>[:0xYY]]; without the serial number the first connected bridge is used.
+    #[arg(long, env = "RBLHOST_I2C")]
     i2c: Option<String>,
+    /// I2C or SPI port of a LPC-Link2 or MCU-Link debug probe (LPCUSBSIO bridge)
+    ///
+    /// Format is i2c[<index>][,<address>[,<speed>]] or
+    /// spi[<index>][,<ssel port>,<ssel pin>[,<speed>[,<polarity>[,<phase>]]]], speed is in kHz. Defaults
+    /// are address 0x10 and 100 kHz for I2C, slave select P0_15, 1000 kHz, polarity 1 and phase 1 for SPI.
+    #[arg(long, short = 'L', value_name = "SPEC", value_parser = LpcUsbSioTarget::parse)]
+    lpcusbsio: Option<LpcUsbSioTarget>,
+    /// CAN interface identifier in format <interface>[,<rx_id>,<tx_id>[,<bitrate>]]
+    ///
+    /// The host receives frames with rx_id [default: 0x123] and sends frames with tx_id [default: 0x321].
+    /// On Linux, SocketCAN interfaces like can0 are used, their bitrate is set by the system, e.g.
+    /// "ip link set can0 type can bitrate 1000000".
+    #[arg(long)]
+    can: Option<String>,
+    /// Remote serial bridge in format <host>:<port>, e.g. a ser2net port in raw mode
+    ///
+    /// The bridge forwards the TCP connection to the UART of the device, the baudrate is configured on
+    /// the bridge.
+    #[arg(long, value_name = "HOST:PORT")]
+    tcp: Option<String>,
+    /// Remote serial bridge in format <host>:<port>, forwarding UDP datagrams to the UART of the device
+    #[arg(long, value_name = "HOST:PORT")]
+    udp: Option<String>,
     /// UART port identifier
     ///
     /// Baudrate can be optionally specified after a colon, e.g. "COM1,38400".
     /// Default baudrate is 57600.
-    #[arg(long, short)]
+    ///
+    /// Ports of terminal servers supporting RFC 2217 are selected with "rfc2217://<host>:<port>", the
+    /// baudrate is then set on the server.
+    #[arg(long, short, env = "RBLHOST_PORT")]
     port: Option<String>,
     /// USB-HID device identifier in format "vid,pid" (e.g., "0x1FC9,0x0135")
-    #[arg(long, short)]
+    ///
+    /// When multiple identical devices are attached, a specific one can be selected by its serial
+    /// number with "vid,pid,serial=XYZ" or by its HID path with "path=<hid path>".
+    #[arg(long, short, env = "RBLHOST_USB")]
     usb: Option<String>,
+    /// Replay a transcript saved with --record instead of communicating with a device
+    ///
+    /// The commands have to send the same frames as the recorded session, the replay fails at the first
+    /// difference.
+    #[arg(long, value_name = "FILE")]
+    replay: Option<PathBuf>,
+}
+
+impl Device {
+    /// Checks that a single device is selected, a device given on the command line or in the configuration
+    /// file replaces devices from the environment
+    fn select(&mut self, matches: &ArgMatches) -> Result<(), clap::Error> {
+        let mut command = Args::command();
+        let ids: Vec<String> = command
+            .get_groups()
+            .find(|group| group.get_id() == "Device")
+            .map(|group| group.get_args().map(ToString::to_string).collect())
+            .unwrap_or_default();
+        let from = |source| -> Vec<&str> {
+            ids.iter()
+                .map(String::as_str)
+                .filter(|&id| matches.value_source(id) == Some(source))
+                .collect()
+        };
+        let given = from(ValueSource::CommandLine);
+        let from_env = from(ValueSource::EnvVariable);
+        if let [first, second, ..] = given[..] {
+            return Err(command.error(
+                ErrorKind::ArgumentConflict,
+                format!("the argument '--{first}' cannot be used with '--{second}'"),
+            ));
+        }
+        if given.is_empty() {
+            if let [first, second, ..] = from_env[..] {
+                let variable = |id| {
+                    command
+                        .get_arguments()
+                        .find(|arg| arg.get_id() == id)
+                        .and_then(Arg::get_env)
+                        .map_or_else(String::new, |env| env.to_string_lossy().into_owned())
+                };
+                let message = format!(
+                    "environment variables {} and {} select different devices, only one can be set",
+                    variable(first),
+                    variable(second)
+                );
+                return Err(command.error(ErrorKind::ArgumentConflict, message));
+            }
+            return Ok(());
+        }
+        for id in from_env {
+            match id {
+                "port" => self.port = None,
+                "usb" => self.usb = None,
+                "i2c" => self.i2c = None,
+                _ => {}
+            }
+        }
+        Ok(())
+    }
 }
 
 #[derive(Parser, Debug)]
 #[command(version, about, long_about = None)]
+#[expect(clippy::struct_excessive_bools, reason = "these are independent command line flags")]
 pub struct Args {
     #[command(flatten)]
     device: Device,
 
+    /// Use the device and options of profile <NAME> from the configuration file
+    ///
+    /// The file is rblhost/config.toml in ~/.config (or $XDG_CONFIG_HOME) on Linux and macOS and in
+    /// %APPDATA% on Windows. Its top-level options apply to every invocation, options of profiles are
+    /// in [profiles.<NAME>] tables, see the config command. Options on the command line win.
+    #[arg(short = 'P', long, value_name = "NAME")]
+    profile: Option<String>,
+
+    /// Route the communication through a BusPal connected to --port, converting it to I2C or SPI
+    ///
+    /// Format is i2c[,<address>[,<speed>]] or spi[,<speed>[,<polarity>[,<phase>[,lsb|msb]]]], speed is
+    /// in kHz. Defaults are address 0x10, 100 kHz, polarity 1, phase 1 and msb.
+    #[arg(long, value_name = "SPEC", requires = "port", value_parser = BusPalTarget::parse)]
+    buspal: Option<BusPalTarget>,
+
     /// Serial read timeout in milliseconds
-    #[arg(short, long, default_value_t = 5000)]
+    #[arg(short, long, env = "RBLHOST_TIMEOUT", default_value_t = 5000)]
     timeout: u64,
 
+    /// Timeout of command ACKs and responses in milliseconds, defaults to --timeout
+    #[arg(long, value_name = "MS")]
+    ack_timeout: Option<u64>,
+
+    /// Timeout of data phase packets in milliseconds, defaults to --timeout
+    #[arg(long, value_name = "MS")]
+    data_timeout: Option<u64>,
+
+    /// Timeout of flash erase responses in milliseconds, defaults to --timeout
+    ///
+    /// Erasing a large external flash can take minutes.
+    #[arg(long, value_name = "MS")]
+    erase_timeout: Option<u64>,
+
     /// Polling interval for reading in milliseconds
     #[arg(long, default_value_t = 1)]
     polling_interval: u64,
 
+    /// Size of data packets, overrides the max packet size reported by the device
+    ///
+    /// Useful with USB-serial adapters dropping bytes when packets of the full size are sent.
+    #[arg(long, value_name = "BYTES", value_parser = clap::value_parser!(u32).range(1..))]
+    max_packet_size: Option<u32>,
+
+    /// Delay between data packets in milliseconds
+    #[arg(long, value_name = "MS", default_value_t = 0)]
+    inter_packet_delay_ms: u64,
+
+    /// Check the identity of the device before erasing the whole flash or programming fuses
+    ///
+    /// A 32-bit number is compared with system-device-id, a longer hex string with unique-device-id as
+    /// printed by get-property. The command is not sent to a device reporting another identity.
+    #[arg(long, value_name = "ID", value_parser = ExpectedDevice::parse)]
+    expect_device: Option<ExpectedDevice>,
+
+    /// Refuse flash-erase-region, fill-memory and write-memory overlapping regions reserved by the bootloader
+    ///
+    /// The reserved-regions property is requested before these commands, devices not reporting it are not
+    /// checked. Commands given --force are sent anyway.
+    #[arg(long, default_value_t = false)]
+    check_reserved: bool,
+
+    /// Device family, for families reporting properties under codes of other properties
+    ///
+    /// E.g. kw45xx reports verify-erase under the code of verify-writes and mcxa1xx reports
+    /// life-cycle-state under the code of flash-security-state, see get-property --help.
+    #[arg(long, value_parser = Family::parse)]
+    family: Option<Family>,
+
+    /// Start byte of frames, for bootloader and bridge variants not using the standard one
+    ///
+    /// Applies to UART and I2C, USB-HID reports have no start byte.
+    #[arg(long, value_name = "BYTE", value_parser = parse::parse_number::<u8>, default_value = "0x5A")]
+    frame_start_byte: u8,
+
+    /// Send and expect frames without CRC16, for bootloader and bridge variants omitting it
+    ///
+    /// Applies to UART and I2C, USB-HID reports have no CRC.
+    #[arg(long)]
+    no_crc: bool,
+
+    /// Try other baudrates when the device does not respond at the one given with --port
+    ///
+    /// Baudrates to try are given as --baud-fallback=<BAUDRATES>, separated by commas.
+    ///
+    /// Without a list, 115200, 57600, 19200 and 9600 are tried. The baudrate the device responded at
+    /// is reported when it differs from the requested one.
+    #[arg(long, value_name = "BAUDRATES", requires = "port", num_args = 0..=1, require_equals = true, value_delimiter = ',')]
+    baud_fallback: Option<Vec<u32>>,
+
+    /// Flow control of the UART port
+    #[arg(long, value_enum, requires = "port", default_value_t)]
+    flow_control: FlowControl,
+
+    /// Level of the DTR line set after opening the UART port, e.g. to hold the reset pin of the board
+    #[arg(long, value_name = "on|off", requires = "port", value_parser = BoolishValueParser::new(), hide_possible_values = true)]
+    uart_dtr: Option<bool>,
+
+    /// Level of the RTS line set after opening the UART port, e.g. to hold the ISP pin of the board
+    #[arg(long, value_name = "on|off", requires = "port", value_parser = BoolishValueParser::new(), hide_possible_values = true)]
+    uart_rts: Option<bool>,
+
+    /// Drive the UART control lines before connecting, to put the board into bootloader mode
+    ///
+    /// Steps are dtr:<duration> and rts:<duration>, asserting the line for the duration and returning
+    /// it to its level set by --uart-dtr or --uart-rts, or wait:<duration>. Durations are in ms, or in
+    /// seconds with the s suffix. Steps are run in order, e.g. "--uart-rts on --boot-pulse dtr:100ms,wait:50ms"
+    /// resets a board with ISP held.
+    #[arg(long, value_name = "STEP", requires = "port", value_delimiter = ',', value_parser = LineStep::parse)]
+    boot_pulse: Vec<LineStep>,
+
+    /// Handling of the length field of USB-HID reports, trust-length by default
+    ///
+    /// trust-length cuts reports padded with stale bytes to their length field, trust-report-size
+    /// ignores the length field for bootloaders reporting a wrong one.
+    #[arg(long, value_name = "QUIRK", requires = "usb", value_parser = UsbLengthQuirk::parse)]
+    usb_quirk: Option<UsbLengthQuirk>,
+
+    /// Upper bound of the whole invocation in seconds
+    ///
+    /// Covers opening the device and all commands, including retries. When exceeded, rblhost is
+    /// aborted with an error.
+    #[arg(long, value_parser = parse::parse_number::<u64>)]
+    session_timeout: Option<u64>,
+
     /// Surpress status response and response words
     #[arg(short, long)]
     silent: bool,
@@ -104,16 +564,70 @@ pub struct Args {
     ///
     /// -v means info, -vv means debug and -vvv and more is trace level. If RUST_LOG environment
     /// variable is set, it overrides this option. For more documentation about it, refer to
-    /// env_logger crate.
+    /// EnvFilter of the tracing-subscriber crate.
     #[arg(short, long, action = clap::ArgAction::Count, default_value_t = 0)]
     verbose: u8,
+    /// Format of the log on stderr
+    ///
+    /// JSON logs an object per line, commands and data phases are logged as spans with their timing.
+    #[arg(long, value_enum, value_name = "FORMAT", default_value_t = LogFormat::Text)]
+    log_format: LogFormat,
     /// Command to send to device
     #[command(subcommand)]
     command: Commands,
+    /// Format of the command output
+    ///
+    /// With json, each command prints a single JSON document with the status, response words and
+    /// the returned data instead of the human readable text.
+    #[arg(long, value_enum, default_value_t)]
+    output: OutputFormat,
+    /// Shorthand for --output json
+    #[arg(long, conflicts_with = "output")]
+    json: bool,
+    /// Write a report of all performed operations into <FILE>
+    ///
+    /// The report contains the device identity, images with their SHA-256 digests, fuse changes,
+    /// durations and final statuses of all commands. Format is chosen by the extension, either
+    /// .html or .md.
+    #[arg(long, value_name = "FILE", value_parser = report::parse_report_path)]
+    report: Option<PathBuf>,
+    /// Record all frames exchanged with the device into a transcript <FILE>
+    ///
+    /// The transcript is saved as JSON if the file name ends with .json, in a compact binary format
+    /// otherwise. It can be replayed without the device with --replay.
+    #[arg(long, value_name = "FILE")]
+    record: Option<PathBuf>,
+    /// Override or extend texts of status codes with <FILE>
+    ///
+    /// Each line of the file is "<code> = <text>", codes are decimal or hexadecimal with the 0x
+    /// prefix. Lines starting with # are ignored. Useful for new codes of fresh silicon or for
+    /// translated messages.
+    #[arg(long, value_name = "FILE")]
+    status_texts: Option<PathBuf>,
     #[arg(long, hide = true)]
     secret: bool,
 }
 
+impl Args {
+    /// Framing of UART and I2C frames
+    fn framing(&self) -> Framing {
+        Framing {
+            start_byte: self.frame_start_byte,
+            crc: !self.no_crc,
+        }
+    }
+
+    /// Flow control and control lines of the UART port
+    fn serial_options(&self) -> SerialOptions {
+        SerialOptions {
+            flow_control: self.flow_control,
+            dtr: self.uart_dtr,
+            rts: self.uart_rts,
+            boot_sequence: self.boot_pulse.clone(),
+        }
+    }
+}
+
 // this can't be CommandTag directly, some commands (like ReadMemory) provide additional options
 #[derive(Subcommand, Debug, Clone)]
 pub enum Commands {
@@ -163,12 +677,14 @@ pub enum Commands {
         /// for mcxa1xx devices:
         /// 17 or 'life-cycle'                  Life Cycle
         ///
+        /// 'all'                               All properties the device supports, e.g. for bug reports
+        ///
         /// Note: Not all the properties are available for all devices.
         // a value parser from clap could be used here; however, it can't convert from repr
-        #[arg(value_parser=PropertyTagDiscriminants::parse_property, verbatim_doc_comment)]
-        property_tag: PropertyTagDiscriminants,
+        #[arg(value_parser=parse_property_selection, verbatim_doc_comment)]
+        property_tag: PropertySelection,
         /// ID of the memory
-        #[arg(value_parser=parsers::parse_number::<u32>, default_value_t=0)]
+        #[arg(value_parser=parse::parse_number::<u32>, default_value_t=0)]
         memory_index: u32,
     },
     /// Reset the device.
@@ -180,24 +696,43 @@ pub enum Commands {
     /// The system is returned to a reset state before the jump.
     Execute {
         /// Jump address.
-        #[arg(value_parser=parsers::parse_number::<u32>)]
+        #[arg(value_parser=parse::parse_address)]
         start_address: u32,
         /// Function argument pointer passed to R0.
-        #[arg(value_parser=parsers::parse_number::<u32>)]
+        #[arg(value_parser=parse::parse_number::<u32>)]
         argument: u32,
         /// Stack pointer. If set to zero, the code being called should
         /// set the stack pointer before using the stack.
-        #[arg(value_parser=parsers::parse_number::<u32>)]
+        #[arg(value_parser=parse::parse_number::<u32>)]
+        stackpointer: u32,
+    },
+    /// Writes an argument blob to RAM and jumps to code with R0 pointing at it.
+    ///
+    /// The blob has to fit into the internal RAM outside of regions reserved by the bootloader. Without
+    /// --args-addr, the first free RAM large enough is used.
+    ExecuteWithArgs {
+        /// Jump address.
+        #[arg(value_parser=parse::parse_address)]
+        start_address: u32,
+        /// Binary file with the argument blob
+        #[arg(long, value_name = "FILE", value_parser = |s: &str| parse::parse_file(s, None))]
+        args_file: Box<[u8]>,
+        /// RAM address to write the argument blob to
+        #[arg(long, value_name = "ADDR", value_parser=parse::parse_number::<u32>)]
+        args_addr: Option<u32>,
+        /// Stack pointer. If set to zero, the code being called should
+        /// set the stack pointer before using the stack.
+        #[arg(long, value_parser=parse::parse_number::<u32>, default_value_t = 0)]
         stackpointer: u32,
     },
     /// Invokes code at an address, passing an argument to it.
     ///
     Call {
         /// Jump address.
-        #[arg(value_parser=parsers::parse_number::<u32>)]
+        #[arg(value_parser=parse::parse_address)]
         start_address: u32,
         /// Function argument pointer passed to R0.
-        #[arg(value_parser=parsers::parse_number::<u32>)]
+        #[arg(value_parser=parse::parse_number::<u32>)]
         argument: u32,
     },
     /// Perform an erase of the entire flash memory.
@@ -205,39 +740,69 @@ pub enum Commands {
     /// Note: Protected regions are excluded.
     FlashEraseAll {
         /// ID of the memory to erase
-        #[arg(value_parser=parsers::parse_number::<u32>, default_value_t=0)]
+        #[arg(value_parser=parse::parse_number::<u32>, default_value_t=0)]
         memory_id: u32,
     },
     /// Fills the memory with a pattern.
     FillMemory {
         /// Starting address
-        #[arg(value_parser=parsers::parse_number::<u32>)]
+        #[arg(value_parser=parse::parse_address)]
         start_address: u32,
         /// Number of bytes to fill
-        #[arg(value_parser=parsers::parse_number::<u32>)]
+        #[arg(value_parser=parse::parse_size::<u32>)]
         byte_count: u32,
         /// Pattern to fill
-        #[arg(value_parser=parsers::parse_number::<u32>)]
+        #[arg(value_parser=parse::parse_number::<u32>)]
         pattern: u32,
+        /// Width of <PATTERN> in bytes, narrower patterns are repeated to fill 32 bits
+        ///
+        /// <START_ADDRESS> and <BYTE_COUNT> must be multiples of the width.
+        #[arg(long, value_enum, value_name = "BYTES")]
+        pattern_size: Option<PatternSize>,
+        /// Send the command even if it overlaps a reserved region, see --check-reserved
+        #[arg(long, default_value_t = false)]
+        force: bool,
     },
     /// Reads the memory and writes it to a file or stdout.
     ReadMemory {
         /// Starting address
-        #[arg(value_parser=parsers::parse_number::<u32>)]
+        #[arg(value_parser=parse::parse_address)]
         start_address: u32,
         /// Number of bytes to read
-        #[arg(value_parser=parsers::parse_number::<u32>)]
+        #[arg(value_parser=parse::parse_size::<u32>)]
         byte_count: u32,
         /// Store read bytes into <FILE>
         ///
         /// If you need to specify [MEMORY_ID], use '-' instead of filename to print to stdout.
+        /// Files with .hex, .ihex or .ihx extension are written as Intel HEX, files with .srec, .s19,
+        /// .s28, .s37 or .mot extension as S-record, with records at the read addresses.
         file: Option<String>,
         /// ID of the memory to read from
-        #[arg(value_parser=parsers::parse_number::<u32>, default_value_t=0)]
+        #[arg(value_parser=parse::parse_number::<u32>, default_value_t=0)]
         memory_id: u32,
         /// Use hexdump format
         #[arg(long, short, default_value_t = false)]
         use_hexdump: bool,
+        /// Format of <FILE>, overrides the one detected from its extension
+        ///
+        /// Only binary, Intel HEX and S-record files can be written.
+        #[arg(long, value_enum, value_name = "FORMAT")]
+        format: Option<ImageFormat>,
+        /// Label lines of the hexdump with device addresses instead of offsets from zero
+        #[arg(long, requires = "use_hexdump")]
+        hexdump_addresses: bool,
+        #[command(flatten)]
+        hexdump: HexdumpLayout,
+        /// Write a <FILE>.json sidecar describing the dump, ignored when printing to stdout
+        #[arg(long, default_value_t = false)]
+        metadata: bool,
+        /// Read at most <BYTES> with a single command, for ROMs limiting the length of a read
+        ///
+        /// Larger reads are split into sequential commands and their data are joined.
+        #[arg(long, value_name = "BYTES", value_parser = parse::parse_size::<u32>)]
+        chunk_size: Option<u32>,
+        #[command(flatten)]
+        output: OutputStream,
     },
     /// Changes properties and options in the bootloader.
     ///
@@ -260,7 +825,7 @@ pub enum Commands {
         #[arg(value_parser=PropertyTagDiscriminants::parse_property, verbatim_doc_comment)]
         property_tag: PropertyTagDiscriminants,
         /// Value to set <PROPERTY_TAG> to
-        #[arg(value_parser=parsers::parse_number::<u32>)]
+        #[arg(value_parser=parse::parse_number::<u32>)]
         value: u32,
     },
     /// Sets a config at internal memory to memory with ID.
@@ -268,10 +833,10 @@ pub enum Commands {
     /// The specified configuration block must have been previously written to memory using the write-memory command.
     ConfigureMemory {
         /// ID of the memory
-        #[arg(value_parser=parsers::parse_number::<u32>)]
+        #[arg(value_parser=parse::parse_number::<u32>)]
         memory_id: u32,
         /// Starting address
-        #[arg(value_parser=parsers::parse_number::<u32>)]
+        #[arg(value_parser=parse::parse_address)]
         address: u32,
     },
     /// Erase Complete Flash and Unlock.
@@ -282,35 +847,48 @@ pub enum Commands {
     /// The entire sector(s) containing the start and end address is erased.
     FlashEraseRegion {
         /// Starting address
-        #[arg(value_parser=parsers::parse_number::<u32>)]
+        #[arg(value_parser=parse::parse_address)]
         start_address: u32,
         /// Number of bytes to erase
-        #[arg(value_parser=parsers::parse_number::<u32>)]
+        #[arg(value_parser=parse::parse_size::<u32>)]
         byte_count: u32,
         /// ID of the memory to erase
-        #[arg(value_parser=parsers::parse_number::<u32>, default_value_t=0)]
+        #[arg(value_parser=parse::parse_number::<u32>, default_value_t=0)]
         memory_id: u32,
+        /// Send the command even if it overlaps a reserved region, see --check-reserved
+        #[arg(long, default_value_t = false)]
+        force: bool,
     },
     /// Write memory from a file or CLI.
     ///
     /// Only one of <FILE> (with <LIMIT>) or <BYTES> must be specified.
+    ///
+    /// Intel HEX (.hex), Motorola S-record (.srec, .s19), ELF (.elf, .axf) and addressed hex text
+    /// (.txt with @address lines) files are written at the addresses encoded in them,
+    /// <START_ADDRESS> is ignored for these files.
     #[command(
         override_usage = concat!(color_print::cstr!("<bold>rblhost write-memory"), " <START_ADDRESS> FILE[,LIMIT] | {{HEX_DATA}} [MEMORY_ID]"),
         args=[
-            Arg::new("FILE").help("write the content of this file"),
-            Arg::new("LIMIT").help("If specified, load only first [LIMIT] bytes from FILE"),
+            Arg::new("FILE").help("write the content of this binary, Intel HEX, S-record, ELF or addressed hex text file"),
+            Arg::new("LIMIT").help("If specified, load only first [LIMIT] bytes from binary FILE"),
             Arg::new("HEX_DATA").help("A string of hex values: {{112233}}, {{11 22 33}}"),
         ]
     )]
     WriteMemory {
         /// Starting address
-        #[arg(value_parser=parsers::parse_number::<u32>, display_order=0)]
+        #[arg(value_parser=parse::parse_address, display_order=0)]
         start_address: u32,
-        #[arg(value_parser=parsers::parse_hex_values, hide = true)]
-        bytes: Box<[u8]>,
+        #[arg(value_parser=parse_write_data, hide = true)]
+        data: WriteData,
         /// ID of the memory to write
         #[arg(default_value_t = 0)]
         memory_id: u32,
+        /// Read the written memory back and compare it with the data
+        #[arg(long, default_value_t = false)]
+        verify: bool,
+        /// Send the command even if it overlaps a reserved region, see --check-reserved
+        #[arg(long, default_value_t = false)]
+        force: bool,
     },
     /// Program fuse.
     ///
@@ -329,18 +907,18 @@ pub enum Commands {
 )]
     FuseProgram {
         /// Start address.
-        #[arg(value_parser = parsers::parse_number::<u32>, display_order = 0)]
+        #[arg(value_parser = parse::parse_address, display_order = 0)]
         start_address: u32,
 
         /// Write the content of this file.
         file: Option<String>,
 
         /// If specified, load only first BYTE_COUNT number of bytes.
-        #[arg(requires = "file")]
+        #[arg(requires = "file", value_parser = parse::parse_size::<u32>)]
         byte_count: Option<u32>,
 
         /// A string of hex values: {{112233}}, {{11 22 33}}
-        #[arg(value_parser = parsers::parse_hex_values)]
+        #[arg(value_parser = parse::parse_hex_values)]
         hex_data: Option<Box<[u8]>>,
 
         /// ID of memory to read from (default: 0)
@@ -350,49 +928,71 @@ pub enum Commands {
     /// Reads the fuse and writes it to the file or stdout.
     FuseRead {
         /// Start address.
-        #[arg(value_parser=parsers::parse_number::<u32>)]
+        #[arg(value_parser=parse::parse_address)]
         start_address: u32,
         /// Number of bytes to read.
-        #[arg(value_parser=parsers::parse_number::<u32>)]
+        #[arg(value_parser=parse::parse_size::<u32>)]
         byte_count: u32,
         /// Store result into this file, if not specified use stdout.
         file: Option<String>,
         /// ID of memory to read from (default: 0)
-        #[arg(value_parser=parsers::parse_number::<u32>, default_value_t=0)]
+        #[arg(value_parser=parse::parse_number::<u32>, default_value_t=0)]
         memory_id: u32,
         /// Use hexdump format
         #[arg(long, short, default_value_t = false)]
         use_hexdump: bool,
+        #[command(flatten)]
+        hexdump: HexdumpLayout,
+        /// Write a <FILE>.json sidecar describing the dump, ignored when printing to stdout
+        #[arg(long, default_value_t = false)]
+        metadata: bool,
     },
     /// Receives a file in a Secure Binary (SB) format.
     ReceiveSbFile {
-        #[arg(value_parser=|s: &str| parsers::parse_file(s, None))]
+        #[arg(value_parser=|s: &str| parse::parse_file(s, None))]
+        bytes: Box<[u8]>,
+        /// Only check the file against the device, do not send it
+        #[arg(long, default_value_t = false, conflicts_with = "resume_from")]
+        dry_run: bool,
+        /// Continue a failed transfer, skipping the first <OFFSET> bytes already accepted by the device
+        ///
+        /// When a transfer times out, the offset to resume from is printed.
+        #[arg(long, value_name = "OFFSET", value_parser = parse::parse_number::<usize>)]
+        resume_from: Option<usize>,
+    },
+    /// Prints the header of a Secure Binary (SB) file and checks it against the device.
+    ///
+    /// The bootloader version must support the SB format of the file and the device must support
+    /// receive-sb-file command. Nothing is sent to the device except property queries.
+    SbInfo {
+        /// SB2.x or SB3.1 file
+        #[arg(value_parser=|s: &str| parse::parse_file(s, None))]
         bytes: Box<[u8]>,
     },
 
     /// Read from MCU flash program once region (eFuse/OTP)
     FlashReadOnce {
         /// Start index of the eFuse/OTP region
-        #[arg(value_parser=parsers::parse_number::<u32>)]
+        #[arg(value_parser=parse::parse_number::<u32>)]
         index: u32,
 
         /// Number of bytes to read (default: 4)
-        #[arg(value_parser=parsers::parse_number::<u32>, default_value_t=4)]
+        #[arg(value_parser=parse::parse_size::<u32>, default_value_t=4)]
         count: u32,
     },
 
     /// Write into MCU program once region (eFuse/OTP)
     FlashProgramOnce {
         /// Start index of the eFuse/OTP region
-        #[arg(value_parser=parsers::parse_number::<u32>)]
+        #[arg(value_parser=parse::parse_number::<u32>)]
         index: u32,
 
         /// Value to write (32-bit)
-        #[arg(value_parser=parsers::parse_number::<u32>)]
+        #[arg(value_parser=parse::parse_number::<u32>)]
         data: u32,
 
         /// Number of bytes to write (default: 4)
-        #[arg(value_parser=parsers::parse_number::<u32>, default_value_t=4)]
+        #[arg(value_parser=parse::parse_size::<u32>, default_value_t=4)]
         count: u32,
 
         /// Verify that data were written correctly
@@ -400,21 +1000,408 @@ pub enum Commands {
         verify: bool,
     },
 
+    /// Read a word from eFuse/OTP (blhost compatible)
+    EfuseReadOnce {
+        /// Index of the eFuse/OTP word
+        #[arg(value_parser=parse::parse_number::<u32>)]
+        index: u32,
+    },
+
+    /// Write a word into eFuse/OTP, optionally locking it (blhost compatible)
+    ///
+    /// The lock is requested by setting the lock bit in the index word, so the index does not
+    /// have to be masked manually.
+    EfuseProgramOnce {
+        /// Index of the eFuse/OTP word
+        #[arg(value_parser=parse::parse_number::<u32>)]
+        index: u32,
+
+        /// Value to write (32-bit)
+        #[arg(value_parser=parse::parse_number::<u32>)]
+        data: u32,
+
+        /// Whether to lock the word after programming
+        #[arg(value_enum, ignore_case = true, default_value_t = EfuseLock::Nolock)]
+        lock: EfuseLock,
+
+        /// Bit of the index word requesting the lock, 24 on most families, 31 on some
+        #[arg(long, default_value_t = 24, value_parser = clap::value_parser!(u8).range(0..32))]
+        lock_bit: u8,
+
+        /// Verify that data were written correctly
+        #[arg(long, default_value_t = false)]
+        verify: bool,
+    },
+
+    /// Writes an image file to the memory, optionally erasing it first.
+    ///
+    /// Intel HEX (.hex), Motorola S-record (.srec, .s19), ELF (.elf, .axf) and addressed hex text
+    /// (.txt with @address lines) files are written at the addresses encoded in them. Binary files
+    /// require the --address option.
+    FlashImage {
+        /// Image file to write
+        file: String,
+        /// Erase sectors covered by the image before writing
+        #[arg(value_enum, default_value_t = ImageErase::None)]
+        erase: ImageErase,
+        /// ID of the memory to write
+        #[arg(value_parser=parse::parse_number::<u32>, default_value_t=0)]
+        memory_id: u32,
+        /// Address of the binary image, ignored for image formats with addresses
+        #[arg(long, value_parser=parse::parse_number::<u32>)]
+        address: Option<u32>,
+        /// Read the written memory back and compare it with the image
+        #[arg(long, default_value_t = false)]
+        verify: bool,
+    },
+
+    /// Compares samples of the memory with a binary file.
+    ///
+    /// Only the first and last page of every sector covered by the file and a number of randomly chosen
+    /// pages are read, which is much faster than reading back large external flashes, but may miss
+    /// isolated differences. Use --full to compare everything.
+    QuickVerify {
+        /// Address of the first byte of the file in the memory
+        #[arg(value_parser=parse::parse_address)]
+        start_address: u32,
+        /// Binary file to compare, FILE[,LIMIT] compares only its first LIMIT bytes
+        #[arg(value_name = "FILE", value_parser=parse::parse_file_spec)]
+        bytes: Box<[u8]>,
+        /// ID of the memory to compare
+        #[arg(value_parser=parse::parse_number::<u32>, default_value_t=0)]
+        memory_id: u32,
+        /// Compare the whole file instead of samples
+        #[arg(long, default_value_t = false)]
+        full: bool,
+        /// Sector size, defaults to the Flash Sector Size property
+        #[arg(long, value_parser=parse::parse_size::<u32>)]
+        sector_size: Option<u32>,
+        /// Size of the compared pages
+        #[arg(long, value_parser=parse::parse_size::<u32>, default_value = "256")]
+        page_size: u32,
+        /// Number of randomly chosen pages compared in addition to the sector boundaries
+        #[arg(long, default_value_t = 16)]
+        random_pages: usize,
+    },
+
+    /// Checks that a memory region is erased, i.e. all its bytes read as 0xFF.
+    ///
+    /// Fails if a byte is not erased, reporting its address.
+    BlankCheck {
+        /// Start address
+        #[arg(value_parser=parse::parse_address)]
+        start_address: u32,
+        /// Number of bytes to check
+        #[arg(value_parser=parse::parse_size::<u32>)]
+        byte_count: u32,
+        /// ID of the memory to check
+        #[arg(value_parser=parse::parse_number::<u32>, default_value_t=0)]
+        memory_id: u32,
+    },
+
+    /// Computes a checksum of a memory region.
+    ///
+    /// The memory is read in chunks and only the checksum is printed, CRC-32 as a number.
+    Checksum {
+        /// Start address
+        #[arg(value_parser=parse::parse_address)]
+        start_address: u32,
+        /// Number of bytes to include
+        #[arg(value_parser=parse::parse_size::<u32>)]
+        byte_count: u32,
+        /// ID of the memory to read
+        #[arg(value_parser=parse::parse_number::<u32>, default_value_t=0)]
+        memory_id: u32,
+        /// Checksum algorithm
+        #[arg(long, value_enum, default_value_t = ChecksumAlgorithm::Crc32)]
+        algo: ChecksumAlgorithm,
+    },
+
+    /// Lists internal and configured external memories.
+    ///
+    /// Internal flash and RAM are read from the properties, external memories are queried for
+    /// their attributes by all known memory IDs.
+    ListMemory,
+
+    /// Saves the board configuration into a JSON snapshot.
+    ///
+    /// The snapshot contains all properties readable from the device and the fuse words selected with
+    /// --fuse. Use import-config to apply it to another board.
+    ExportConfig {
+        /// JSON file to write the snapshot to
+        file: String,
+        /// Fuse word index or inclusive range of indexes, e.g. 0x60 or 0x60-0x67, can be repeated
+        #[arg(long = "fuse", value_name = "INDEX[-LAST]", value_parser = config::parse_fuse_range)]
+        fuses: Vec<RangeInclusive<u32>>,
+    },
+
+    /// Applies the writable subset of a configuration snapshot to the device.
+    ///
+    /// Writable properties are changed by set-property and fuse words are programmed. All differences
+    /// are listed before anything is applied. Fuse bits cannot be cleared, so the import fails when a
+    /// fuse word of the snapshot lacks any bit already programmed on the device.
+    ImportConfig {
+        /// JSON snapshot created by export-config
+        file: String,
+        /// Only list the differences, do not apply them
+        #[arg(long, default_value_t = false)]
+        dry_run: bool,
+    },
+
+    /// Prints the framing protocol version and options reported by the device.
+    ///
+    /// The device is pinged when the connection is opened, this prints its response. USB-HID devices are
+    /// not pinged.
+    Ping,
+
+    /// Measures round-trip time of get-property requests.
+    ///
+    /// Reports minimal, average and maximal round-trip time and jitter of the transport, together
+    /// with suggested --timeout and --polling-interval values.
+    LatencyTest {
+        /// Number of requests to send
+        #[arg(long, short, default_value_t = 10, value_parser = clap::value_parser!(u32).range(1..))]
+        count: u32,
+    },
+
+    /// Measures round-trip latency and transfer throughput with every packet size.
+    ///
+    /// Writes <BYTE_COUNT> bytes into RAM and reads them back with packet sizes from 32 bytes up to the max
+    /// packet size reported by the device, then suggests the fastest one as --max-packet-size. Useful to
+    /// diagnose slow UART and USB-HID links. The content of the used RAM is overwritten.
+    Benchmark {
+        /// Number of bytes written and read back with every packet size
+        #[arg(value_parser=parse::parse_size::<u32>, default_value = "4k")]
+        byte_count: u32,
+        /// Start address of the RAM buffer, by default a free region of the internal RAM is used
+        #[arg(long, value_parser=parse::parse_address)]
+        address: Option<u32>,
+        /// Number of get-property requests measuring the round-trip time
+        #[arg(long, short, default_value_t = 10, value_parser = clap::value_parser!(u32).range(1..))]
+        count: u32,
+    },
+
+    /// Runs commands from a script file over a single connection.
+    ///
+    /// Each line of the script contains one command with its arguments, written the same way as
+    /// on the command line, e.g. "write-memory 0x20000000 image.bin". Empty lines and lines
+    /// starting with '#' are ignored. Execution stops at the first failed command by default.
+    ///
+    /// Consecutive write-memory commands of raw bytes to contiguous addresses of the same memory are
+    /// sent as a single command, their lines are reported together.
+    Batch {
+        /// Script with commands
+        script: String,
+        /// Run remaining commands even if a command fails
+        #[arg(long, default_value_t = false)]
+        continue_on_error: bool,
+        /// Send every write-memory command separately
+        #[arg(long, default_value_t = false)]
+        no_coalesce: bool,
+    },
+
     /// Group of subcommands related to trust provisioning
     #[command(subcommand)]
     TrustProvisioning(TrustProvOperation),
+
+    /// Group of subcommands specific to KW45 and K32W1 devices
+    #[command(subcommand)]
+    Kw45(Kw45Command),
     /// Group of subcommands related to key provisioning
     #[command(subcommand)]
-    KeyProvisioning(KeyProvOperation),
+    KeyProvisioning(KeyProvCommand),
     /// Sends a boot image file to the device.
     ///
-    /// Only binary files are supported. The <FILE> must be a bootable
-    /// image which contains the boot image header supported by the MCU
-    /// bootloader.
+    /// Binary, Intel HEX, S-record, ELF and addressed hex text files are supported. The <FILE> must
+    /// be a bootable image which contains the boot image header supported by the MCU
+    /// bootloader. Segments of files with addresses are joined into a single image, gaps between
+    /// them are filled with 0xFF.
     LoadImage {
         /// Boot file to load
         file: String,
     },
+    /// Prints the catalog of status codes and host errors.
+    ///
+    /// Each status code is listed with its identifier, text, subsystem and the exit code of a command
+    /// failed with it, followed by the kinds of errors detected on the host. No device is needed.
+    Errors {
+        /// Print the catalog as a JSON document
+        #[arg(long)]
+        json: bool,
+    },
+    /// Prints the catalog of properties, commands or status codes.
+    ///
+    /// Each entry is listed with its number, name and description as known to this version, so
+    /// documentation generators and external tools can stay in sync with it. No device is needed.
+    Describe {
+        /// Catalog to print
+        #[arg(value_enum)]
+        catalog: describe::Catalog,
+        /// Print the catalog as a JSON document
+        #[arg(long)]
+        json: bool,
+    },
+    /// Lists serial ports of the host usable with --port.
+    ///
+    /// USB-serial ports are listed with their VID, PID and descriptors, ports of bridges found on NXP
+    /// boards are highlighted. No device is needed.
+    ListPorts,
+    /// Shows or changes the configuration file with default options and profiles.
+    ///
+    /// Options are the long names of the options of rblhost, like "timeout" or "usb". Options of a
+    /// profile are prefixed by its name, e.g. "rblhost config set lpc55.usb 0x1fc9,0x0021" lets
+    /// "rblhost -P lpc55 get-property current-version" talk to the device. No device is needed.
+    Config {
+        #[command(subcommand)]
+        action: ConfigAction,
+    },
+    /// Print a shell completion script
+    ///
+    /// In bash, zsh and fish, values of --port, --usb and --i2c are completed with currently connected
+    /// devices.
+    Completions {
+        /// Shell to generate the script for
+        shell: clap_complete::Shell,
+    },
+    /// Commands for debugging the protocol
+    #[command(subcommand, hide = true)]
+    Debug(DebugCommand),
+    /// List connected devices, used by the completion scripts
+    #[command(name = "__complete-devices", hide = true)]
+    CompleteDevices {
+        /// Kind of the devices, all kinds are listed if omitted
+        kind: Option<completions::DeviceKind>,
+    },
+}
+
+/// Erase option of flash-image command
+#[derive(clap::ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ImageErase {
+    /// Erase sectors covered by the image
+    Erase,
+    /// Do not erase anything
+    None,
+}
+
+/// Lock argument of [`Commands::EfuseProgramOnce`]
+#[derive(clap::ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EfuseLock {
+    /// Lock the word after programming
+    Lock,
+    /// Keep the word unlocked
+    Nolock,
+}
+
+/// Subcommands of [`Commands::Kw45`]
+#[derive(Subcommand, Debug, Clone)]
+pub enum Kw45Command {
+    /// Loads NBU (radio) firmware from a signed SB3.1 file.
+    ///
+    /// The file header and the availability of receive-sb-file command are checked before the
+    /// file is sent. When the device rejects the file, the likely cause is explained.
+    LoadNbu {
+        /// SB3.1 file with the NBU firmware
+        #[arg(value_parser=|s: &str| parse::parse_file(s, None))]
+        sb_file: Box<[u8]>,
+    },
+}
+
+/// Subcommands of [`Commands::Debug`]
+#[derive(Subcommand, Debug, Clone)]
+pub enum DebugCommand {
+    /// Sends a raw frame and prints the frame the device responds with.
+    ///
+    /// The frame is given with the standard framing, including the start byte, length and CRC, which are
+    /// sent as they are. Only command frames get a response frame, data phases of the command are not read.
+    SendFrame {
+        /// Frame as hex digits, optionally in double braces, e.g. 5AA40C004B33070000020100000000000000
+        #[arg(value_parser = parse_frame)]
+        frame: Box<[u8]>,
+    },
+}
+
+fn parse_frame(s: &str) -> Result<Box<[u8]>, String> {
+    if s.starts_with("{{") {
+        parse::parse_hex_bytes(s)
+    } else {
+        parse::parse_hex_bytes(&format!("{{{{{s}}}}}"))
+    }
+}
+
+/// Subcommands of [`Commands::KeyProvisioning`]
+#[derive(Subcommand, Debug, Clone)]
+pub enum KeyProvCommand {
+    #[command(flatten)]
+    Operation(KeyProvOperation),
+    /// Reads the key store from the bootloader to host
+    #[command(name = "read_key_store")]
+    ReadKeyStore {
+        /// Binary file to save the key store
+        file: String,
+
+        /// Use hexdump format
+        #[arg(long, short, default_value_t = false)]
+        use_hexdump: bool,
+        #[command(flatten)]
+        hexdump: HexdumpLayout,
+    },
+    /// Writes the key store to nonvolatile memory.
+    ///
+    /// With --verify, the key store is read, written, loaded back from the nonvolatile memory and read
+    /// again. A difference is reported as a warning, instead of being noticed after the next boot.
+    Persist {
+        /// ID of the non-volatile memory
+        #[arg(value_parser=parse::parse_number::<u32>, default_value_t = 0)]
+        memory_id: u32,
+        /// Load the written key store back and compare it
+        #[arg(long)]
+        verify: bool,
+    },
+}
+
+/// Data to write with write-memory command
+#[derive(Clone, Debug)]
+pub enum WriteData {
+    /// Raw bytes written at the start address
+    Bytes(Box<[u8]>),
+    /// Image segments written at their own addresses
+    Segments(Vec<Segment>),
+}
+
+fn parse_write_data(s: &str) -> Result<WriteData, String> {
+    if s.starts_with("{{") {
+        return parse::parse_hex_values(s).map(WriteData::Bytes);
+    }
+    let (path, limit) = match s.split_once(',') {
+        Some((path, limit)) => (path, Some(limit)),
+        None => (s, None),
+    };
+    let format = ImageFormat::from_path(Path::new(path));
+    if !format.has_addresses() {
+        return parse::parse_hex_values(s).map(WriteData::Bytes);
+    }
+    if limit.is_some() {
+        return Err(format!("limit is not supported for {format:?} files"));
+    }
+    formats::read_segments(path, 0)
+        .map(WriteData::Segments)
+        .map_err(|err| err.to_string())
+}
+
+/// Properties requested by get-property command
+#[derive(Clone, Copy, Debug)]
+pub enum PropertySelection {
+    /// Single property
+    One(PropertyTagDiscriminants),
+    /// All properties the device supports
+    All,
+}
+
+fn parse_property_selection(s: &str) -> Result<PropertySelection, &'static str> {
+    if s.eq_ignore_ascii_case("all") {
+        return Ok(PropertySelection::All);
+    }
+    PropertyTagDiscriminants::parse_property(s).map(PropertySelection::One)
 }
 
 pub struct Blhost<T>
@@ -423,9 +1410,22 @@ where
 {
     args: Args,
     boot: McuBoot<T>,
+    reporter: Reporter,
+    report: Option<Report>,
 }
 
 const DEFAULT_BAUDRATE: u32 = 57600;
+
+/// Splits the UART port identifier into the port name and the optional baudrate
+fn parse_port(port_spec: &str) -> (&str, u32) {
+    let mut parts = port_spec.split(',');
+    let port_name = parts.next().unwrap();
+    let baudrate = parts
+        .next()
+        .map_or(DEFAULT_BAUDRATE, |v| v.parse().unwrap_or(DEFAULT_BAUDRATE));
+    (port_name, baudrate)
+}
+
 impl Blhost<UARTProtocol> {
     fn new_from_uart(args: Args) -> Result<Self, CommunicationError> {
         let port_spec = args
@@ -433,21 +1433,44 @@ impl Blhost<UARTProtocol> {
             .port
             .as_ref()
             .expect("open_uart called without UART argument");
-        // Parse port and optional baudrate
-        let mut parts = port_spec.split(',');
-        let port_name = parts.next().unwrap();
-        let baudrate = parts
-            .next()
-            .map_or(DEFAULT_BAUDRATE, |v| v.parse().unwrap_or(DEFAULT_BAUDRATE));
+        let (port_name, baudrate) = parse_port(port_spec);
 
         // Use UART protocol with specified baudrate and timeout
-        let boot = McuBoot::new(UARTProtocol::open_with_options(
-            port_name,
-            baudrate,
-            std::time::Duration::from_millis(args.timeout),
-            std::time::Duration::from_millis(args.polling_interval),
-        )?);
-        Ok(Blhost { args, boot })
+        let timeout = std::time::Duration::from_millis(args.timeout);
+        let polling_interval = std::time::Duration::from_millis(args.polling_interval);
+        let device = if let Some(fallback) = &args.baud_fallback {
+            let fallback = if fallback.is_empty() {
+                &FALLBACK_BAUDRATES[..]
+            } else {
+                fallback
+            };
+            let mut baudrates = vec![baudrate];
+            baudrates.extend(fallback.iter().filter(|&&rate| rate != baudrate));
+            let device = UARTProtocol::autodetect_baud(
+                port_name,
+                &baudrates,
+                timeout,
+                polling_interval,
+                args.framing(),
+                &args.serial_options(),
+            )?;
+            let detected = device.baudrate()?;
+            if detected != baudrate {
+                warn!("device does not respond at {baudrate} baud, using {detected} baud");
+            }
+            device
+        } else {
+            UARTProtocol::open_with_serial_options(
+                port_name,
+                baudrate,
+                timeout,
+                polling_interval,
+                args.framing(),
+                &args.serial_options(),
+            )?
+        };
+        let boot = McuBoot::new(device);
+        Ok(Blhost::with_boot(args, boot))
     }
 }
 
@@ -458,8 +1481,99 @@ impl Blhost<I2CProtocol> {
             .i2c
             .as_ref()
             .expect("new_from_i2c called without I2C argument");
-        let boot = McuBoot::new(I2CProtocol::open(i2c_device)?);
-        Ok(Blhost { args, boot })
+        let boot = McuBoot::new(I2CProtocol::open_with_framing(
+            i2c_device,
+            0, // Baudrate not used for I2C
+            std::time::Duration::from_millis(args.timeout),
+            std::time::Duration::from_millis(args.polling_interval),
+            args.framing(),
+        )?);
+        Ok(Blhost::with_boot(args, boot))
+    }
+}
+
+impl Blhost<BusPalProtocol> {
+    fn new_from_buspal(args: Args) -> Result<Self, CommunicationError> {
+        let port_spec = args
+            .device
+            .port
+            .as_ref()
+            .expect("new_from_buspal called without UART argument");
+        let target = args.buspal.expect("new_from_buspal called without BusPal argument");
+        let (port_name, baudrate) = parse_port(port_spec);
+        let boot = McuBoot::new(BusPalProtocol::open(
+            port_name,
+            baudrate,
+            target,
+            std::time::Duration::from_millis(args.timeout),
+            std::time::Duration::from_millis(args.polling_interval),
+            args.framing(),
+        )?);
+        Ok(Blhost::with_boot(args, boot))
+    }
+}
+
+impl Blhost<CANProtocol> {
+    fn new_from_can(args: Args) -> Result<Self, CommunicationError> {
+        let can_interface = args
+            .device
+            .can
+            .as_ref()
+            .expect("new_from_can called without CAN argument");
+        let boot = McuBoot::new(CANProtocol::open_with_framing(
+            can_interface,
+            0, // Bitrate is a part of the identifier
+            std::time::Duration::from_millis(args.timeout),
+            std::time::Duration::from_millis(args.polling_interval),
+            args.framing(),
+        )?);
+        Ok(Blhost::with_boot(args, boot))
+    }
+}
+
+impl Blhost<LpcUsbSioProtocol> {
+    fn new_from_lpcusbsio(args: Args) -> Result<Self, CommunicationError> {
+        let target = args
+            .device
+            .lpcusbsio
+            .expect("new_from_lpcusbsio called without LPCUSBSIO argument");
+        let boot = McuBoot::new(LpcUsbSioProtocol::open(
+            target,
+            std::time::Duration::from_millis(args.timeout),
+            std::time::Duration::from_millis(args.polling_interval),
+            args.framing(),
+        )?);
+        Ok(Blhost::with_boot(args, boot))
+    }
+}
+
+impl Blhost<NetworkProtocol> {
+    fn new_from_network(args: Args) -> Result<Self, CommunicationError> {
+        let identifier = match (&args.device.tcp, &args.device.udp) {
+            (Some(tcp), _) => format!("tcp://{tcp}"),
+            (None, Some(udp)) => format!("udp://{udp}"),
+            (None, None) => unreachable!("new_from_network called without network argument"),
+        };
+        let boot = McuBoot::new(NetworkProtocol::open_with_framing(
+            &identifier,
+            0, // Baudrate is configured on the bridge
+            std::time::Duration::from_millis(args.timeout),
+            std::time::Duration::from_millis(args.polling_interval),
+            args.framing(),
+        )?);
+        Ok(Blhost::with_boot(args, boot))
+    }
+}
+
+impl Blhost<ReplayProtocol> {
+    fn new_from_replay(args: Args) -> Result<Self, CommunicationError> {
+        let path = args
+            .device
+            .replay
+            .as_ref()
+            .expect("new_from_replay called without replay argument");
+        let boot = McuBoot::new(ReplayProtocol::new(Transcript::load(path)?));
+        Ok(Blhost::with_boot(args, boot))
     }
 }
 
@@ -470,13 +1584,13 @@ impl Blhost<USBProtocol> {
             .usb
             .as_ref()
             .expect("new_from_usb called without USB argument");
-        let boot = McuBoot::new(USBProtocol::open_with_options(
+        let protocol = USBProtocol::open_with_options(
             usb_device,
             0, // Baudrate not used for USB
             std::time::Duration::from_millis(args.timeout),
             std::time::Duration::from_millis(args.polling_interval),
-        )?);
-        Ok(Blhost { args, boot })
+        )?;
+        Ok(Blhost::with_boot(args, McuBoot::new(protocol)))
     }
 }
 
@@ -485,27 +1599,100 @@ where
     T: Protocol,
 {
     pub fn new(args: Args, device: T) -> Blhost<T> {
+        Blhost::with_boot(args, McuBoot::new(device))
+    }
+
+    /// Wraps the device into [`RecordingProtocol`], recording all frames of the following commands
+    fn recorded(self) -> Blhost<RecordingProtocol<T>> {
+        Blhost::with_boot(self.args, McuBoot::new(RecordingProtocol::new(self.boot.into_device())))
+    }
+
+    fn with_boot(args: Args, mut boot: McuBoot<T>) -> Blhost<T> {
+        let config = boot.config_mut();
+        config.transfer = TransferOptions {
+            max_packet_size: args.max_packet_size,
+            inter_packet_delay: Duration::from_millis(args.inter_packet_delay_ms),
+        };
+        config.family = args.family;
+        config.timeouts = PhaseTimeouts {
+            ack: args.ack_timeout.map(Duration::from_millis),
+            data: args.data_timeout.map(Duration::from_millis),
+            erase: args.erase_timeout.map(Duration::from_millis),
+        };
+        config.quirks.usb_length = args.usb_quirk;
+        let format = if args.json { OutputFormat::Json } else { args.output };
+        let reporter = Reporter::new(format, args.silent, args.verbose > 0);
+        let report = args
+            .report
+            .clone()
+            .map(|path| Report::new(path, std::env::args().collect::<Vec<_>>().join(" ")));
         Blhost {
             args,
-            boot: McuBoot::new(device),
+            boot,
+            reporter,
+            report,
         }
     }
 
-    #[allow(clippy::too_many_lines, reason = "match statement here will always be long")]
     pub fn execute(&mut self) -> Result<(), CommunicationError> {
-        self.boot.progress_bar = !self.args.silent;
+        if !self.args.silent {
+            self.boot.set_progress_callback(reporter::progress_bar());
+        }
+
+        if self.report.is_some() {
+            self.identify_device();
+        }
+
+        let command = self.args.command.clone();
+        let result = match command {
+            // commands of the script are recorded one by one
+            Commands::Batch { .. } => self.execute_command(&command),
+            _ => self.execute_recorded(&report::command_line(), &command).0,
+        };
+        if let Err(err) = &result {
+            self.report_error(err);
+        }
+        self.reporter.flush();
+        let written = self.report.as_ref().map_or(Ok(()), Report::write);
+        result?;
+        written.map_err(CommunicationError::FileError)?;
+
+        if self.args.secret {
+            println!("congratulations! you found the secret 🍨");
+        }
+
+        Ok(())
+    }
 
-        match self.args.command {
+    #[allow(clippy::too_many_lines, reason = "match statement here will always be long")]
+    fn execute_command(&mut self, command: &Commands) -> Result<(), CommunicationError> {
+        if let Some(expected) = &self.args.expect_device
+            && expect_device::is_destructive(command)
+        {
+            expect_device::check(&mut self.boot, expected)?;
+        }
+        if self.args.check_reserved {
+            for (start_address, byte_count, memory_id) in reserved_check::changed_ranges(command) {
+                self.boot.check_reserved(start_address, byte_count, memory_id)?;
+            }
+        }
+        match *command {
             Commands::GetProperty {
                 property_tag,
                 memory_index,
-            } => {
-                let response = &self.boot.get_property(property_tag, memory_index)?;
-                self.display_property(response);
-            }
+            } => match property_tag {
+                PropertySelection::One(tag) => {
+                    let response = &self.boot.get_property(tag, memory_index)?;
+                    self.reporter.property(response, self.boot.last_outcome());
+                }
+                PropertySelection::All => {
+                    let responses = self.boot.get_all_properties(memory_index)?;
+                    self.reporter.properties(&responses);
+                }
+            },
             Commands::Reset => {
                 let status = self.boot.reset()?;
-                self.display_status(status);
+                self.reporter.status(status, self.boot.last_outcome());
             }
             Commands::Execute {
                 start_address,
@@ -513,26 +1700,46 @@ where
                 stackpointer,
             } => {
                 let status = self.boot.execute(start_address, argument, stackpointer)?;
-                self.display_status(status);
+                self.reporter.status(status, self.boot.last_outcome());
+            }
+            Commands::ExecuteWithArgs {
+                start_address,
+                ref args_file,
+                args_addr,
+                stackpointer,
+            } => {
+                let status = self
+                    .boot
+                    .execute_with_args(start_address, args_file, args_addr, stackpointer)?;
+                self.reporter.status(status, self.boot.last_outcome());
             }
             Commands::Call {
                 start_address,
                 argument,
             } => {
                 let status = self.boot.call(start_address, argument)?;
-                self.display_status(status);
+                self.reporter.status(status, self.boot.last_outcome());
             }
             Commands::FlashEraseAll { memory_id } => {
                 let status = self.boot.flash_erase_all(memory_id)?;
-                self.display_status(status);
+                self.reporter.status(status, self.boot.last_outcome());
             }
             Commands::FillMemory {
                 start_address,
                 byte_count,
                 pattern,
+                pattern_size,
+                ..
             } => {
+                let pattern = match pattern_size {
+                    Some(size) => size
+                        .check_alignment(start_address, byte_count)
+                        .and_then(|()| size.expand(pattern))
+                        .map_err(CommunicationError::ParseError)?,
+                    None => pattern,
+                };
                 let status = self.boot.fill_memory(start_address, byte_count, pattern)?;
-                self.display_status(status);
+                self.reporter.status(status, self.boot.last_outcome());
             }
             Commands::ReadMemory {
                 start_address,
@@ -540,56 +1747,199 @@ where
                 ref file,
                 memory_id,
                 use_hexdump,
-            } => match file.as_deref() {
-                None | Some("-") => {
-                    let response = self.boot.read_memory(start_address, byte_count, memory_id)?;
-                    self.display_memory_bytes(&response, byte_count, use_hexdump);
-                }
-                Some(file_name) => {
-                    let response = self.boot.read_memory(start_address, byte_count, memory_id)?;
-                    let mut file = File::create(file_name).map_err(CommunicationError::FileError)?;
-                    file.write_all(&response.bytes)?;
-                    self.display_memory(&response, byte_count);
+                format,
+                hexdump_addresses,
+                hexdump,
+                metadata,
+                chunk_size,
+                ref output,
+            } => {
+                let chunk_size = chunk_size.unwrap_or_default();
+                let stream = output.open().map_err(CommunicationError::FileError)?;
+                let streamed = stream.is_some();
+                let file_name = file.as_deref().filter(|name| *name != "-");
+                let format = file_name.map(|name| dump_format(name, format));
+                // output streams and binary files are written as the data packets arrive
+                let mut sink = match (stream, file_name) {
+                    (Some(stream), _) => Some(stream),
+                    (None, Some(name)) if format == Some(ImageFormat::Binary) => {
+                        let file = File::create(name).map_err(CommunicationError::FileError)?;
+                        Some(Box::new(BufWriter::new(file)) as Box<dyn Write>)
+                    }
+                    (None, _) => None,
+                };
+                let started = Instant::now();
+                let response = match sink.as_mut() {
+                    Some(sink) => {
+                        let response = self.boot.read_memory_large_into(
+                            start_address,
+                            byte_count,
+                            memory_id,
+                            chunk_size,
+                            sink.as_mut(),
+                        )?;
+                        sink.flush()?;
+                        response
+                    }
+                    None => self
+                        .boot
+                        .read_memory_large(start_address, byte_count, memory_id, chunk_size)?,
+                };
+                let elapsed = started.elapsed();
+                match (file_name, format) {
+                    _ if streamed => self.reporter.memory(&response, self.boot.last_outcome(), byte_count),
+                    (None, _) | (_, None) => {
+                        let base_address = if hexdump_addresses { start_address } else { 0 };
+                        self.reporter.memory_bytes(
+                            &response,
+                            self.boot.last_outcome(),
+                            byte_count,
+                            use_hexdump.then(|| hexdump.config(base_address)),
+                        );
+                    }
+                    (Some(file_name), Some(format)) => {
+                        if format != ImageFormat::Binary {
+                            let content = dump_content(format, start_address, &response.bytes)?;
+                            fs::write(file_name, content).map_err(CommunicationError::FileError)?;
+                        }
+                        if metadata {
+                            write_metadata(
+                                &mut self.boot,
+                                &mut self.reporter,
+                                file_name,
+                                start_address,
+                                memory_id,
+                                &response,
+                            )?;
+                        }
+                        self.reporter.memory(&response, self.boot.last_outcome(), byte_count);
+                    }
                 }
-            },
+                let bytes_read = response.response_words.first().copied().unwrap_or_default();
+                self.reporter.transfer_stats(bytes_read as usize, elapsed);
+            }
             Commands::SetProperty { property_tag, value } => {
                 let status = self.boot.set_property(property_tag, value)?;
-                self.display_status(status);
+                self.reporter.status(status, self.boot.last_outcome());
             }
             Commands::ConfigureMemory { memory_id, address } => {
                 let status = self.boot.configure_memory(memory_id, address)?;
-                self.display_status(status);
+                self.reporter.status(status, self.boot.last_outcome());
             }
             Commands::FlashEraseAllUnsecure => {
                 let status = self.boot.flash_erase_all_unsecure()?;
-                self.display_status(status);
+                self.reporter.status(status, self.boot.last_outcome());
             }
             Commands::FlashEraseRegion {
                 start_address,
                 byte_count,
                 memory_id,
+                ..
             } => {
                 let status = self.boot.flash_erase_region(start_address, byte_count, memory_id)?;
-                self.display_status(status);
+                self.reporter.status(status, self.boot.last_outcome());
             }
             Commands::WriteMemory {
                 start_address,
-                ref bytes,
+                ref data,
                 memory_id,
+                verify,
+                ..
             } => {
-                let status = self.boot.write_memory(start_address, memory_id, bytes)?;
-                self.display_status(status);
+                let status = match data {
+                    WriteData::Bytes(bytes) => self.boot.write_memory(start_address, memory_id, bytes)?,
+                    WriteData::Segments(segments) => self.boot.write_image(segments, memory_id)?,
+                };
+                self.reporter.status(status, self.boot.last_outcome());
+                if verify {
+                    let written = match data {
+                        WriteData::Bytes(bytes) => vec![Segment {
+                            address: start_address,
+                            data: bytes.to_vec(),
+                        }],
+                        WriteData::Segments(segments) => segments.clone(),
+                    };
+                    self.verify_segments(&written, memory_id)?;
+                }
             }
-            Commands::ReceiveSbFile { ref bytes } => {
-                let status = self.boot.receive_sb_file(bytes)?;
-                self.display_status(status);
+            Commands::ReceiveSbFile {
+                ref bytes,
+                dry_run,
+                resume_from,
+            } => {
+                if dry_run {
+                    self.check_sb_file(bytes)?;
+                    self.reporter.insert("dry_run", serde_json::Value::Bool(true));
+                    self.reporter.line("Dry run, SB file was not sent.");
+                } else {
+                    let offset = resume_from.unwrap_or(0);
+                    let status = self.boot.receive_sb_file_resume(bytes, offset).inspect_err(|err| {
+                        let acked = self.boot.acked_bytes();
+                        if matches!(err, CommunicationError::Timeout) && acked > 0 {
+                            let offset = offset as u64 + acked;
+                            self.reporter.insert("resume_from", serde_json::json!(offset));
+                            self.reporter.hint(&format!(
+                                "{offset} bytes were accepted by the device, continue with --resume-from {offset}"
+                            ));
+                        }
+                    })?;
+                    self.reporter.status(status, self.boot.last_outcome());
+                }
+            }
+            Commands::SbInfo { ref bytes } => self.check_sb_file(bytes)?,
+            Commands::Kw45(Kw45Command::LoadNbu { ref sb_file }) => {
+                let status = kw45::load_nbu(&mut self.boot, sb_file).inspect_err(|err| {
+                    if let CommunicationError::UnexpectedStatus(status, _) = err
+                        && let Some(hint) = kw45::status_hint(*status)
+                    {
+                        self.reporter.hint(hint);
+                    }
+                })?;
+                self.reporter.status(status, self.boot.last_outcome());
             }
             Commands::TrustProvisioning(ref operation) => {
                 let (status, data) = self.boot.trust_provisioning(operation)?;
-                self.display_status_words(status, &data);
-                self.display_trust_prov(operation, &data);
+                self.reporter.status_words(status, self.boot.last_outcome(), &data);
+                self.reporter.trust_prov(operation, &data);
             }
-            Commands::KeyProvisioning(ref operation) => match operation {
+            Commands::KeyProvisioning(KeyProvCommand::Persist { memory_id, verify }) => {
+                let status = self.boot.write_key_nonvolatile(memory_id, verify)?;
+                self.reporter.status(status, self.boot.last_outcome());
+            }
+            Commands::KeyProvisioning(KeyProvCommand::ReadKeyStore {
+                ref file,
+                use_hexdump,
+                hexdump,
+            }) => {
+                debug!("Reading key store from device");
+
+                // Execute the key provisioning command
+                let response = self.boot.key_provisioning(&KeyProvOperation::ReadKeyStore)?;
+
+                match response {
+                    KeyProvisioningResponse::KeyStore {
+                        status,
+                        response_words,
+                        bytes,
+                    } => {
+                        if status.is_success() {
+                            // Write to file
+                            let mut output_file = File::create(file).map_err(CommunicationError::FileError)?;
+                            output_file.write_all(&bytes)?;
+
+                            self.reporter.file(file, &bytes, use_hexdump.then(|| hexdump.config(0)));
+                            self.reporter
+                                .status_words(status, self.boot.last_outcome(), &response_words);
+                        } else {
+                            self.reporter.status(status, self.boot.last_outcome());
+                        }
+                    }
+                    KeyProvisioningResponse::Status(status) => {
+                        self.reporter.status(status, self.boot.last_outcome());
+                    }
+                }
+            }
+            Commands::KeyProvisioning(KeyProvCommand::Operation(ref operation)) => match operation {
                 KeyProvOperation::SetUserKey { key_type, key_data } => {
                     if !self.args.silent {
                         debug!(
@@ -601,7 +1951,7 @@ where
                     let response = self.boot.key_provisioning(operation)?;
                     match response {
                         KeyProvisioningResponse::KeyStore { status, .. } | KeyProvisioningResponse::Status(status) => {
-                            self.display_status(status);
+                            self.reporter.status(status, self.boot.last_outcome());
                         }
                     }
                 }
@@ -612,50 +1962,7 @@ where
                     let response = self.boot.key_provisioning(operation)?;
                     match response {
                         KeyProvisioningResponse::KeyStore { status, .. } | KeyProvisioningResponse::Status(status) => {
-                            self.display_status(status);
-                        }
-                    }
-                }
-                KeyProvOperation::ReadKeyStore { file, use_hexdump } => {
-                    debug!("Reading key store from device");
-
-                    // Execute the key provisioning command
-                    let response = self.boot.key_provisioning(operation)?;
-
-                    match response {
-                        KeyProvisioningResponse::KeyStore {
-                            status,
-                            response_words,
-                            bytes,
-                        } => {
-                            if status.is_success() {
-                                // Write to file
-                                let mut output_file = File::create(file).map_err(CommunicationError::FileError)?;
-                                output_file.write_all(&bytes)?;
-
-                                if !self.args.silent {
-                                    println!("Successfully wrote {} bytes to file: {}", bytes.len(), file);
-
-                                    if *use_hexdump {
-                                        // Display the data in hexdump format
-                                        let cfg = HexConfig {
-                                            title: false,
-                                            group: 8,
-                                            width: 16,
-                                            ascii: true,
-                                            ..HexConfig::default()
-                                        };
-                                        println!("{:?}", bytes.hex_conf(cfg));
-                                    }
-                                }
-
-                                self.display_status_words(status, &response_words);
-                            } else {
-                                self.display_status(status);
-                            }
-                        }
-                        KeyProvisioningResponse::Status(status) => {
-                            self.display_status(status);
+                            self.reporter.status(status, self.boot.last_outcome());
                         }
                     }
                 }
@@ -663,16 +1970,14 @@ where
                     let response = self.boot.key_provisioning(operation)?;
                     match response {
                         KeyProvisioningResponse::KeyStore { status, .. } | KeyProvisioningResponse::Status(status) => {
-                            self.display_status(status);
+                            self.reporter.status(status, self.boot.last_outcome());
                         }
                     }
                 }
             },
             Commands::FlashReadOnce { index, count } => {
                 let value = self.boot.flash_read_once(index, count)?;
-                if !self.args.silent {
-                    println!("Read value: {value} (0x{value:X})");
-                }
+                self.reporter.value(value);
             }
             Commands::FlashProgramOnce {
                 index,
@@ -681,11 +1986,22 @@ where
                 verify,
             } => {
                 let status = self.boot.flash_program_once(index, count, data, verify)?;
-                self.display_status(status);
-
-                if status == StatusCode::OtpVerifyFail {
-                    warn!("Verification failed - written value doesn't match read value");
-                }
+                self.reporter.status(status, self.boot.last_outcome());
+            }
+            Commands::EfuseReadOnce { index } => {
+                let value = self.boot.efuse_read_once(index)?;
+                self.reporter.value(value);
+            }
+            Commands::EfuseProgramOnce {
+                index,
+                data,
+                lock,
+                lock_bit,
+                verify,
+            } => {
+                let lock_bit = (lock == EfuseLock::Lock).then_some(lock_bit);
+                let status = self.boot.efuse_program_once(index, data, lock_bit, verify)?;
+                self.reporter.status(status, self.boot.last_outcome());
             }
             Commands::FuseRead {
                 start_address,
@@ -693,16 +2009,33 @@ where
                 ref file,
                 memory_id,
                 use_hexdump,
+                hexdump,
+                metadata,
             } => match file.as_deref() {
                 None | Some("-") => {
                     let response = self.boot.fuse_read(start_address, byte_count, memory_id)?;
-                    self.display_memory_bytes(&response, byte_count, use_hexdump);
+                    self.reporter.memory_bytes(
+                        &response,
+                        self.boot.last_outcome(),
+                        byte_count,
+                        use_hexdump.then(|| hexdump.config(0)),
+                    );
                 }
                 Some(file_name) => {
                     let response = self.boot.fuse_read(start_address, byte_count, memory_id)?;
                     let mut file = File::create(file_name).map_err(CommunicationError::FileError)?;
                     file.write_all(&response.bytes)?;
-                    self.display_memory(&response, byte_count);
+                    if metadata {
+                        write_metadata(
+                            &mut self.boot,
+                            &mut self.reporter,
+                            file_name,
+                            start_address,
+                            memory_id,
+                            &response,
+                        )?;
+                    }
+                    self.reporter.memory(&response, self.boot.last_outcome(), byte_count);
                 }
             },
             Commands::FuseProgram {
@@ -715,106 +2048,481 @@ where
                 let bytes: Vec<u8> = if let Some(hex) = hex_data {
                     hex.to_vec()
                 } else if let Some(file_path) = file {
-                    let mut file = File::open(file_path).map_err(CommunicationError::FileError)?;
-                    let mut buffer = Vec::new();
-                    match byte_count {
-                        Some(limit) => {
-                            buffer.resize(limit as usize, 0);
-                            file.read_exact(&mut buffer).map_err(CommunicationError::FileError)?;
-                        }
-                        None => {
-                            file.read_to_end(&mut buffer).map_err(CommunicationError::FileError)?;
-                        }
-                    }
-                    buffer
+                    parse::parse_file(file_path, byte_count.map(|count| count as usize))
+                        .map_err(CommunicationError::ParseError)?
+                        .into_vec()
                 } else {
                     return Err(CommunicationError::InvalidData);
                 };
+                if let Some(report) = &mut self.report {
+                    report.programmed_fuses(start_address, &bytes);
+                }
                 let status = self.boot.fuse_program(start_address, memory_id, &bytes)?;
-                self.display_status(status);
+                self.reporter.status(status, self.boot.last_outcome());
+            }
+            Commands::FlashImage {
+                ref file,
+                erase,
+                memory_id,
+                address,
+                verify,
+            } => {
+                let segments = match address {
+                    Some(address) => formats::read_segments(file, address)?,
+                    None if ImageFormat::from_path(Path::new(file)).has_addresses() => formats::read_segments(file, 0)?,
+                    None => {
+                        return Err(CommunicationError::ParseError(
+                            "binary image requires --address option".to_owned(),
+                        ));
+                    }
+                };
+                if let Some(report) = &mut self.report {
+                    for segment in &segments {
+                        report.sent_image(format!("{file} at {:#010X}", segment.address), &segment.data);
+                    }
+                }
+                let status = self
+                    .boot
+                    .flash_image(&segments, erase == ImageErase::Erase, memory_id)?;
+                self.reporter.status(status, self.boot.last_outcome());
+                if verify {
+                    self.verify_segments(&segments, memory_id)?;
+                }
+            }
+            Commands::QuickVerify {
+                start_address,
+                ref bytes,
+                memory_id,
+                full,
+                sector_size,
+                page_size,
+                random_pages,
+            } => {
+                let start = u64::from(start_address);
+                let plan = if full {
+                    iter::once(start..start + bytes.len() as u64).collect()
+                } else {
+                    let sector_size = match sector_size {
+                        Some(size) => size,
+                        None => self.boot.sector_size(memory_id)?,
+                    };
+                    let seed = RandomState::new().hash_one(Instant::now());
+                    quick_verify::sample_plan(start_address, bytes.len(), sector_size, page_size, random_pages, seed)
+                };
+                let mut compared = 0;
+                let out_of_range = |_| CommunicationError::ParseError("file exceeds the address space".to_owned());
+                for region in plan {
+                    let address = u32::try_from(region.start).map_err(out_of_range)?;
+                    let byte_count = u32::try_from(region.end - region.start).map_err(out_of_range)?;
+                    let offset = (region.start - start) as usize;
+                    let expected = &bytes[offset..offset + byte_count as usize];
+                    let response = self.boot.read_memory(address, byte_count, memory_id)?;
+                    let diff = HexDiff {
+                        address,
+                        expected,
+                        actual: &response.bytes,
+                    };
+                    if let Some(offset) = diff.first_mismatch() {
+                        let mismatch = u64::from(address) + offset as u64;
+                        self.reporter.mismatch(mismatch, &diff);
+                        return Err(CommunicationError::ParseError(format!(
+                            "memory differs from the file at {mismatch:#010X}"
+                        )));
+                    }
+                    compared += u64::from(byte_count);
+                }
+                self.reporter.verified(compared, bytes.len() as u64);
+            }
+            Commands::BlankCheck {
+                start_address,
+                byte_count,
+                memory_id,
+            } => {
+                let programmed = self.boot.blank_check(start_address, byte_count, memory_id)?;
+                self.reporter.blank_check(byte_count, programmed);
+                if let Some(address) = programmed {
+                    return Err(CommunicationError::ParseError(format!(
+                        "memory is not erased at {address:#010X}"
+                    )));
+                }
+            }
+            Commands::Checksum {
+                start_address,
+                byte_count,
+                memory_id,
+                algo,
+            } => {
+                let checksum = self.boot.checksum_region(start_address, byte_count, memory_id, algo)?;
+                self.reporter.checksum(algo, &checksum);
+            }
+            Commands::Ping => {
+                let response = self.boot.ping_response().ok_or_else(|| {
+                    CommunicationError::ParseError(format!("{} does not ping the device", self.transport()))
+                })?;
+                self.reporter.ping(response);
+            }
+            Commands::LatencyTest { count } => {
+                let mut samples = Vec::with_capacity(count as usize);
+                for _ in 0..count {
+                    let start = Instant::now();
+                    self.boot.get_property(PropertyTagDiscriminants::CurrentVersion, 0)?;
+                    samples.push(start.elapsed());
+                }
+                if let Some(stats) = LatencyStats::from_samples(&samples) {
+                    self.reporter.latency(&self.transport(), &stats);
+                }
+            }
+            Commands::Benchmark {
+                byte_count,
+                address,
+                count,
+            } => {
+                let benchmark = benchmark::run(&mut self.boot, address, byte_count, count)?;
+                self.reporter.benchmark(&self.transport(), &benchmark);
+            }
+            Commands::Debug(DebugCommand::SendFrame { ref frame }) => {
+                let response = self.boot.device_mut().transact_frame(frame)?;
+                self.reporter.frame(&response);
+            }
+            Commands::ExportConfig { ref file, ref fuses } => {
+                let snapshot = ConfigSnapshot::capture(&mut self.boot, fuses)?;
+                let output = File::create(file).map_err(CommunicationError::FileError)?;
+                serde_json::to_writer_pretty(output, &snapshot.to_json())
+                    .map_err(|err| CommunicationError::IOError(err.into()))?;
+                self.reporter.config_exported(file, &snapshot);
+            }
+            Commands::ImportConfig { ref file, dry_run } => {
+                let content = fs::read_to_string(file).map_err(CommunicationError::FileError)?;
+                let document = serde_json::from_str(&content)
+                    .map_err(|err| CommunicationError::ParseError(format!("{file}: {err}")))?;
+                let snapshot = ConfigSnapshot::from_json(&document)?;
+                let changes = snapshot.diff(&mut self.boot)?;
+                self.reporter.config_changes(&changes);
+                if !dry_run {
+                    if let Some(change) = changes.iter().find(|change| !change.is_applicable()) {
+                        return Err(CommunicationError::ParseError(format!(
+                            "snapshot cannot be applied, {change}"
+                        )));
+                    }
+                    let status = config::apply(&mut self.boot, &changes)?;
+                    self.reporter.status(status, self.boot.last_outcome());
+                }
+            }
+            Commands::ListMemory => {
+                let memories = self.boot.list_memory()?;
+                self.reporter.memories(&memories);
             }
             Commands::LoadImage { ref file } => {
-                let mut file = File::open(file).map_err(CommunicationError::FileError)?;
-                let mut buffer = Vec::new();
-                file.read_to_end(&mut buffer).map_err(CommunicationError::FileError)?;
+                let segments = formats::read_segments(file, 0)?;
+                let (_, buffer) = formats::flatten(&segments, 0xFF)?;
+                let data_bytes: usize = segments.iter().map(|segment| segment.data.len()).sum();
+                if buffer.len() > data_bytes {
+                    self.reporter.warning(&Warning::GapsFilled {
+                        byte_count: (buffer.len() - data_bytes) as u32,
+                        fill: 0xFF,
+                    });
+                }
+                if let Some(report) = &mut self.report {
+                    report.sent_image(file.clone(), &buffer);
+                }
                 let status = self.boot.load_image(&buffer)?;
-                self.display_status(status);
+                self.reporter.status(status, self.boot.last_outcome());
+            }
+            Commands::Batch {
+                ref script,
+                continue_on_error,
+                no_coalesce,
+            } => self.run_batch(script, continue_on_error, no_coalesce)?,
+            Commands::Completions { .. }
+            | Commands::CompleteDevices { .. }
+            | Commands::Errors { .. }
+            | Commands::Describe { .. }
+            | Commands::ListPorts
+            | Commands::Config { .. } => {
+                unreachable!("commands without a device are handled before connecting")
             }
-        }
-
-        if self.args.secret {
-            println!("congratulations! you found the secret 🍨");
         }
 
         Ok(())
     }
 
-    fn display_memory_bytes(&self, response: &ReadMemoryResponse, byte_count: u32, use_hexdump: bool) {
-        if use_hexdump {
-            let cfg = HexConfig {
-                title: false,
-                group: 8,
-                width: 16,
-                ascii: true,
-                ..HexConfig::default()
-            };
-            println!("{:?}", response.bytes.hex_conf(cfg));
-        } else {
-            for byte_line in response.bytes.chunks(16) {
-                for byte in byte_line {
-                    print!("{byte:02X?} ");
+    fn run_batch(
+        &mut self,
+        script: &str,
+        continue_on_error: bool,
+        no_coalesce: bool,
+    ) -> Result<(), CommunicationError> {
+        let content = fs::read_to_string(script).map_err(CommunicationError::FileError)?;
+        let mut commands = batch::parse_batch_script(&content)?;
+        if !no_coalesce {
+            commands = batch::coalesce_writes(commands);
+        }
+        let total = commands.len();
+        let mut first_error = None;
+        let mut results = Vec::with_capacity(total);
+
+        for (index, batch_command) in commands.iter().enumerate() {
+            self.reporter
+                .line(&cformat!("<bold>[{}/{total}]</> {}", index + 1, batch_command.text));
+            let retries = self.boot.retries();
+            let (result, duration) = self.execute_recorded(&batch_command.text, &batch_command.command);
+            if let Err(err) = &result {
+                self.report_error(err);
+            }
+
+            let status = self.status_text(&result);
+            results.push(batch::BatchResult {
+                line: batch_command.line,
+                text: batch_command.text.clone(),
+                duration,
+                status,
+                passed: result.is_ok(),
+                retries: self.boot.retries() - retries,
+                output: self.reporter.take_document(),
+            });
+
+            if let Err(err) = result {
+                error!("command on line {} failed: {err}", batch_command.line);
+                first_error.get_or_insert(err);
+                if !continue_on_error {
+                    break;
                 }
-                println!();
             }
         }
-        self.display_memory(response, byte_count);
+
+        if self.reporter.is_json() {
+            self.reporter.insert("batch", batch::summary_json(&results, total));
+        } else if !self.args.silent {
+            batch::print_summary(&results, total);
+        }
+        first_error.map_or(Ok(()), Err)
     }
 
-    fn display_memory(&self, response: &ReadMemoryResponse, byte_count: u32) {
-        self.display_status_words(response.status, &response.response_words);
-        if !self.args.silent {
-            println!("Read {} of {byte_count} bytes.", response.bytes.len());
+    /// Description of the transport used to communicate with the device
+    fn transport(&self) -> String {
+        let device = &self.args.device;
+        if let (Some(port), Some(buspal)) = (&device.port, &self.args.buspal) {
+            format!("BusPal {port} {buspal:?}")
+        } else if let Some(port) = &device.port {
+            format!("UART {port}")
+        } else if let Some(usb) = &device.usb {
+            format!("USB {usb}")
+        } else if let Some(i2c) = &device.i2c {
+            format!("I2C {i2c}")
+        } else if let Some(can) = &device.can {
+            format!("CAN {can}")
+        } else if let Some(lpcusbsio) = &device.lpcusbsio {
+            format!("LPCUSBSIO {lpcusbsio:?}")
+        } else if let Some(tcp) = &device.tcp {
+            format!("TCP {tcp}")
+        } else if let Some(udp) = &device.udp {
+            format!("UDP {udp}")
+        } else if let Some(replay) = &device.replay {
+            format!("replay {}", replay.display())
+        } else {
+            "unknown".to_owned()
         }
     }
 
-    fn display_property(&self, response: &GetPropertyResponse) {
-        self.display_status_words(response.status, &response.response_words);
-        println!("{}", response.property);
+    /// Reads the written segments back, see [`McuBoot::verify_memory`]
+    fn verify_segments(&mut self, segments: &[Segment], memory_id: u32) -> Result<(), CommunicationError> {
+        let mut compared = 0;
+        for segment in segments {
+            let result = self.boot.verify_memory(segment.address, memory_id, &segment.data);
+            if let Err(CommunicationError::VerifyMismatch {
+                address,
+                window_address,
+                expected,
+                actual,
+            }) = &result
+            {
+                let diff = HexDiff {
+                    address: *window_address,
+                    expected,
+                    actual,
+                };
+                self.reporter.mismatch(u64::from(*address), &diff);
+            }
+            compared += result?;
+        }
+        let total = segments.iter().map(|segment| segment.data.len() as u64).sum();
+        self.reporter.verified(compared, total);
+        Ok(())
     }
 
-    fn display_status_words(&self, status: StatusCode, response_words: &[u32]) {
-        self.display_status(status);
-        self.display_words(response_words);
+    /// Reports the header of an SB file and checks that the device is able to process it
+    fn check_sb_file(&mut self, bytes: &[u8]) -> Result<(), CommunicationError> {
+        let header = SbHeader::parse(bytes)?;
+        self.reporter.sb_header(&header);
+        let version = sb::check_device(&mut self.boot, &header)?;
+        self.reporter.sb_supported(&header, version);
+        Ok(())
     }
 
-    fn display_words(&self, response_words: &[u32]) {
-        if !self.args.silent {
-            for (i, word) in response_words.iter().enumerate() {
-                let i = i + 1;
-                println!("Response word {i} = {word} ({word:#x})");
+    /// Executes a single command, recording it into the report if one is requested
+    fn execute_recorded(&mut self, text: &str, command: &Commands) -> (Result<(), CommunicationError>, Duration) {
+        // statuses of a previous command must not be reported for commands which send nothing
+        self.boot.take_last_outcome();
+        let start = Instant::now();
+        let result = self.execute_command(command);
+        let duration = start.elapsed();
+        for warning in self.boot.take_warnings() {
+            self.reporter.warning(&warning);
+        }
+        if self.report.is_some() {
+            let status = self.status_text(&result);
+            if let Some(report) = &mut self.report {
+                report.record(text, command, duration, status, result.is_ok());
             }
         }
+        (result, duration)
     }
 
-    fn display_status(&self, status: StatusCode) {
-        if !self.args.silent {
-            println!("Response status = {0} ({0:#x}) {1}.", u32::from(status), status);
+    /// Final status of a command, either the status reported by the device or the error
+    ///
+    /// Commands which received no response, like those sending nothing to the device, succeed with
+    /// [`StatusCode::Success`].
+    fn status_text(&self, result: &Result<(), CommunicationError>) -> String {
+        match result {
+            Ok(()) => self
+                .boot
+                .last_outcome()
+                .map_or_else(|| StatusCode::Success.to_string(), |outcome| outcome.status.to_string()),
+            Err(CommunicationError::UnexpectedStatus(status, _)) => status.to_string(),
+            Err(err) => err.to_string(),
         }
     }
 
-    fn display_trust_prov(&self, operation: &TrustProvOperation, response: &[u32]) {
-        if !self.args.silent {
-            println!("Output data size/value(s) is (are):");
-            match operation {
-                TrustProvOperation::OemGenMasterShare { .. } => println!(
-                    "\
-                    \tOEM Share size: {0} ({0:#02X})\n\
-                    \tOEM Master Share size: {1} ({1:#02X})\n\
-                    \tCust Cert Puk size: {2} ({2:#02X})",
-                    response[0], response[1], response[2]
-                ),
-                TrustProvOperation::OemSetMasterShare { .. } => {}
+    /// Records properties identifying the device into the report, unavailable properties are skipped
+    fn identify_device(&mut self) {
+        for tag in [
+            PropertyTagDiscriminants::CurrentVersion,
+            PropertyTagDiscriminants::TargetVersion,
+            PropertyTagDiscriminants::SystemDeviceId,
+            PropertyTagDiscriminants::UniqueDeviceId,
+        ] {
+            match self.boot.get_property(tag, 0) {
+                Ok(response) => {
+                    let text = response.property.to_string();
+                    if let (Some((name, value)), Some(report)) = (text.split_once(" = "), &mut self.report) {
+                        report.device_property(name, value);
+                    }
+                }
+                Err(err) => debug!("{tag:?} is not available for the report: {err}"),
             }
         }
     }
+
+    fn report_error(&mut self, err: &CommunicationError) {
+        let status = match err {
+            CommunicationError::UnexpectedStatus(status, _) => Some(*status),
+            _ => None,
+        };
+        self.reporter.error(&err.to_string(), status);
+    }
+}
+
+/// Writes a JSON sidecar next to the memory dump, so the dump can be interpreted later on
+fn write_metadata<T: Protocol>(
+    boot: &mut McuBoot<T>,
+    reporter: &mut Reporter,
+    file_name: &str,
+    start_address: u32,
+    memory_id: u32,
+    response: &ReadMemoryResponse,
+) -> Result<(), CommunicationError> {
+    let unique_id = match boot.get_property(PropertyTagDiscriminants::UniqueDeviceId, 0) {
+        Ok(GetPropertyResponse {
+            status: StatusCode::Success,
+            property: PropertyTag::UniqueDeviceId(id),
+            ..
+        }) => Some(id.to_string()),
+        Ok(GetPropertyResponse { status, .. }) => {
+            debug!("unique device ID is not available for metadata: {status}");
+            reporter.warning(&Warning::PropertySkipped(PropertyTagDiscriminants::UniqueDeviceId));
+            None
+        }
+        Err(err) => {
+            debug!("unique device ID is not available for metadata: {err}");
+            reporter.warning(&Warning::PropertySkipped(PropertyTagDiscriminants::UniqueDeviceId));
+            None
+        }
+    };
+    let metadata = serde_json::json!({
+        "start_address": start_address,
+        "length": response.response_words.first(),
+        "memory_id": memory_id,
+        "unique_device_id": unique_id,
+        "timestamp": jiff::Timestamp::now().to_string(),
+        "status": {
+            "code": u32::from(response.status),
+            "description": response.status.to_string(),
+        },
+    });
+    let file = File::create(format!("{file_name}.json")).map_err(CommunicationError::FileError)?;
+    serde_json::to_writer_pretty(file, &metadata).map_err(|err| CommunicationError::IOError(err.into()))?;
+    Ok(())
+}
+
+#[cfg(all(test, feature = "testing"))]
+mod tests {
+    use mboot::protocols::mock::{MockProtocol, VirtualDevice};
+
+    use super::*;
+
+    #[test]
+    fn fill_memory_on_virtual_device() {
+        let args = Args::parse_from(["rblhost", "--silent", "fill-memory", "0x20000000", "8", "0x11223344"]);
+        let mut blhost = Blhost::new(args, VirtualDevice::new());
+        blhost.execute().unwrap();
+        assert_eq!(
+            blhost.boot.device().memory(0x2000_0000, 8),
+            [0x44, 0x33, 0x22, 0x11, 0x44, 0x33, 0x22, 0x11]
+        );
+    }
+
+    #[test]
+    fn resume_offset_is_hinted_on_timeout() {
+        let bytes: Box<[u8]> = (0..12).collect();
+        let command = Commands::ReceiveSbFile {
+            bytes: bytes.clone(),
+            dry_run: false,
+            resume_from: Some(4),
+        };
+        for (error, resume_from) in [
+            (CommunicationError::Timeout, Some(serde_json::json!(8))),
+            (CommunicationError::InvalidCrc, None),
+        ] {
+            let device = MockProtocol::new([])
+                .expect_command(0x08, 1, &[8])
+                .respond_generic(StatusCode::Success, 0x08)
+                .expect_data(&bytes[4..8])
+                .fail(error);
+            let args = Args::parse_from(["rblhost", "--json", "--max-packet-size", "4", "reset"]);
+            let mut blhost = Blhost::new(args, device);
+
+            assert!(blhost.execute_command(&command).is_err());
+            let document = blhost.reporter.take_document().unwrap();
+            assert_eq!(document.get("resume_from"), resume_from.as_ref());
+        }
+    }
+
+    #[test]
+    fn single_device_is_selected() {
+        let matches = Args::command()
+            .try_get_matches_from(["rblhost", "-p", "COM3", "-u", "0x1fc9,0x0021", "reset"])
+            .unwrap();
+        let mut args = Args::from_arg_matches(&matches).unwrap();
+        assert_eq!(
+            args.device.select(&matches).unwrap_err().kind(),
+            ErrorKind::ArgumentConflict
+        );
+
+        let matches = Args::command()
+            .try_get_matches_from(["rblhost", "-p", "COM3", "reset"])
+            .unwrap();
+        let mut args = Args::from_arg_matches(&matches).unwrap();
+        args.device.select(&matches).unwrap();
+        assert_eq!(args.device.port.as_deref(), Some("COM3"));
+    }
 }
@@ -2,31 +2,51 @@
 //
 // SPDX-License-Identifier: BSD-3-Clause
 
-use color_print::cstr;
-use indicatif::{ProgressBar, ProgressStyle};
-use log::{info, trace};
+use std::{io::Write, thread, time::Duration};
+
+use formatters::{
+    HexDiff,
+    style::{RECEIVED, SENDING},
+};
+use log::{debug, info, trace, warn};
 use packets::{
     Packet, PacketParse,
     command::{CmdResponse, CommandHeader, CommandPacket},
     data_phase::DataPhasePacket,
+    ping::PingResponse,
 };
-use protocols::Protocol;
+use progress::{ProgressEvent, TransferDirection};
+use protocols::{Protocol, TransportStats};
+use scratch::ScratchAllocator;
 use tags::{
     ToAddress,
-    command::{CommandTag, CommandToParams, KeyProvOperation, TrustProvOperation},
+    command::{CommandTag, CommandTagDiscriminants, CommandToParams, KeyProvOperation, TrustProvOperation},
     command_flag::CommandFlag,
     command_response::CmdResponseTag,
-    property::{PropertyTag, PropertyTagDiscriminants},
+    property::{Family, PropertyTag, PropertyTagDiscriminants},
     status::StatusCode,
 };
+use tracing::{Span, debug_span, field};
+use warning::Warning;
 
 use crate::CommunicationError;
 
-mod formatters;
+pub mod abort;
+pub mod capabilities;
+pub mod checksum;
+pub mod formats;
+pub mod formatters;
+pub mod kw45;
 pub mod memory;
+pub mod ops;
 pub mod packets;
+pub mod progress;
 pub mod protocols;
+pub mod sb;
+pub mod scratch;
+pub mod session;
 pub mod tags;
+pub mod warning;
 
 /// Response structure for [`CommandTag::GetProperty`] command
 ///
@@ -70,6 +90,32 @@ pub enum KeyProvisioningResponse {
     },
 }
 
+/// Statuses reported by the device for the last completed command
+///
+/// Commands with a data phase receive an intermediate generic response in addition to the final
+/// one. The intermediate status sometimes carries the real cause of a failure, while the final
+/// status is only a generic fail.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CommandOutcome {
+    /// Status of the final response
+    pub status: StatusCode,
+    /// Status of the intermediate response, [`None`] for commands without a data phase
+    pub intermediate_status: Option<StatusCode>,
+}
+
+/// Options of data phase transfers
+///
+/// Some USB-serial adapters drop bytes when packets of the size advertised by the device are sent
+/// back to back, these options allow sending smaller packets with pauses between them.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct TransferOptions {
+    /// Size of data packets used instead of [`PropertyTag::MaxPacketSize`] reported by the device, zero
+    /// is treated as [`None`]
+    pub max_packet_size: Option<u32>,
+    /// Delay inserted between data packets
+    pub inter_packet_delay: Duration,
+}
+
 trait InvalidData<T> {
     /// Convert a type to [`Result`] of [`CommunicationError`].
     fn or_invalid(self) -> Result<T, CommunicationError>;
@@ -89,14 +135,45 @@ impl<T, E> InvalidData<T> for Result<T, E> {
 /// # Type Parameters
 ///
 /// * `T` - The underlying communication protocol (UART, USB, etc.)
+#[expect(
+    clippy::struct_excessive_bools,
+    reason = "the deprecated fields are kept for source compatibility"
+)]
 pub struct McuBoot<T>
 where
     T: Protocol,
 {
     device: T,
-    /// Enable/disable progress bar for data transfers
+    config: session::SessionConfig,
+    /// Has no effect, progress is reported to the receiver set by [`McuBoot::set_progress_callback`]
+    #[deprecated(note = "set a progress receiver with `McuBoot::set_progress_callback` instead")]
     pub progress_bar: bool,
+    /// Ignore the data phase flag of responses, key provisioning sets it in responses without data phase
+    #[deprecated(note = "key provisioning masks the data phase flag by itself")]
     pub mask_read_data_phase: bool,
+    /// Command currently in progress, reported with progress of its data phase
+    command_in_progress: Option<CommandTagDiscriminants>,
+    /// Intermediate status of the command currently in progress
+    intermediate_status: Option<StatusCode>,
+    last_outcome: Option<CommandOutcome>,
+    /// Requests sent again after a failure of the transport, see [`session::RetryPolicy`]
+    retries: u32,
+    /// Bytes of the last sent data phase accepted by the device
+    acked_bytes: u64,
+    /// The last sent data phase was aborted on request of the host, its final response is already read
+    host_abort: bool,
+    /// Warnings collected since they were last taken
+    warnings: Vec<Warning>,
+    /// Capabilities collected since the last reset
+    capabilities: Option<capabilities::DeviceCapabilities>,
+    /// Abort requests of data phases
+    abort: abort::AbortHandle,
+    /// Quirks of the configuration changed since they were applied to the device
+    quirks_pending: bool,
+    /// Tracing spans of the commands in progress, innermost last, closed once their final response is read
+    ///
+    /// Commands are nested when properties are queried while another command is being sent.
+    command_spans: Vec<Span>,
 }
 
 /// Result type for communication operations returning a value
@@ -104,6 +181,9 @@ pub type ResultComm<T> = Result<T, CommunicationError>;
 /// Result type for operations returning only a status code
 pub type ResultStatus = ResultComm<StatusCode>;
 
+/// Bytes read at once when the memory is checked, a difference is found without reading the rest
+const READ_BACK_CHUNK_SIZE: u32 = 0x1000;
+
 impl<T> McuBoot<T>
 where
     T: Protocol,
@@ -119,19 +199,192 @@ where
     /// A new [`McuBoot`] instance
     #[must_use]
     pub fn new(device: T) -> Self {
+        McuBoot::with_config(device, session::SessionConfig::default())
+    }
+
+    /// Creates a new [`McuBoot`] instance talking to the device as set by `config`
+    #[must_use]
+    pub fn with_config(mut device: T, config: session::SessionConfig) -> Self {
         info!(
             "Initialized MCU Boot with device identifier: {}",
             device.get_identifier()
         );
+        device.apply_quirks(&config.quirks);
         McuBoot {
             device,
+            config,
+            command_in_progress: None,
+            #[expect(deprecated, reason = "kept for source compatibility")]
             progress_bar: false,
+            #[expect(deprecated, reason = "kept for source compatibility")]
             mask_read_data_phase: false,
+            intermediate_status: None,
+            last_outcome: None,
+            retries: 0,
+            acked_bytes: 0,
+            host_abort: false,
+            warnings: Vec::new(),
+            capabilities: None,
+            abort: abort::AbortHandle::default(),
+            quirks_pending: false,
+            command_spans: Vec::new(),
         }
     }
 
+    /// Configuration of the session
+    #[must_use]
+    pub fn config(&self) -> &session::SessionConfig {
+        &self.config
+    }
+
+    /// Configuration of the session for changes
+    ///
+    /// The capabilities are requested again after the configuration is changed, as the family affects them. The
+    /// quirks are applied to the device before the next command is sent.
+    pub fn config_mut(&mut self) -> &mut session::SessionConfig {
+        self.capabilities = None;
+        self.quirks_pending = true;
+        &mut self.config
+    }
+
+    /// The underlying communication protocol
+    #[must_use]
+    pub fn device(&self) -> &T {
+        &self.device
+    }
+
+    /// The underlying communication protocol for sending frames directly, see [`Protocol::transact_frame`]
+    ///
+    /// Frames sent this way bypass the session, a command left unfinished breaks the following commands.
+    #[must_use]
+    pub fn device_mut(&mut self) -> &mut T {
+        &mut self.device
+    }
+
+    /// Response of the device to the ping sent when the connection was opened, see
+    /// [`Protocol::ping_response`]
+    #[must_use]
+    pub fn ping_response(&self) -> Option<PingResponse> {
+        self.device.ping_response()
+    }
+
+    /// Statistics of polling the device for ACKs, see [`Protocol::transport_stats`]
+    #[must_use]
+    pub fn transport_stats(&self) -> Option<TransportStats> {
+        self.device.transport_stats()
+    }
+
+    /// Consumes the [`McuBoot`] instance, returning the underlying communication protocol
+    #[must_use]
+    pub fn into_device(self) -> T {
+        self.device
+    }
+
+    /// Statuses of the last command which received a response
+    ///
+    /// # Returns
+    ///
+    /// [`CommandOutcome`] of the last response, [`None`] if no response was received yet
+    #[must_use]
+    pub fn last_outcome(&self) -> Option<CommandOutcome> {
+        self.last_outcome
+    }
+
+    /// Takes the statuses of the last command which received a response, see [`McuBoot::last_outcome`]
+    ///
+    /// Taken before a command, they tell afterwards whether that command received any response.
+    pub fn take_last_outcome(&mut self) -> Option<CommandOutcome> {
+        self.last_outcome.take()
+    }
+
+    /// Number of requests sent again since the session started, as set by [`session::RetryPolicy`]
+    #[must_use]
+    pub fn retries(&self) -> u32 {
+        self.retries
+    }
+
+    /// Takes [`Warning`]s collected by the commands since the last call
+    ///
+    /// Warnings are not cleared by new commands, so they should be taken after each command to tell
+    /// which command raised them.
+    pub fn take_warnings(&mut self) -> Vec<Warning> {
+        std::mem::take(&mut self.warnings)
+    }
+
+    /// Handle aborting data phases of this session from another thread, see [`abort::AbortHandle`]
+    #[must_use]
+    pub fn abort_handle(&self) -> abort::AbortHandle {
+        self.abort.clone()
+    }
+
+    /// Sets options of data phase transfers
+    ///
+    /// When [`TransferOptions::max_packet_size`] is set, the device is not queried for its max packet
+    /// size, so a value larger than the one supported by the device makes the transfers fail.
+    #[deprecated(note = "set `transfer` of `McuBoot::config_mut` instead")]
+    pub fn set_transfer_options(&mut self, options: TransferOptions) {
+        self.config.transfer = options;
+    }
+
+    /// Sets the device family, properties are requested and parsed according to its profile
+    ///
+    /// Some families report properties under codes documented for other properties, see [`Family`].
+    #[deprecated(note = "set `family` of `McuBoot::config_mut` instead")]
+    pub fn set_family(&mut self, family: Option<Family>) {
+        self.config_mut().family = family;
+    }
+
+    /// Device family of the session configuration
+    #[must_use]
+    pub fn family(&self) -> Option<Family> {
+        self.config.family
+    }
+
+    /// Capabilities of the device, requested on the first call
+    ///
+    /// The capabilities are kept until the device is reset, the family changes or the flash is unsecured.
+    ///
+    /// # Errors
+    ///
+    /// Any [`CommunicationError`] except [`CommunicationError::UnexpectedStatus`], which only means a
+    /// property is not available.
+    pub fn capabilities(&mut self) -> ResultComm<&capabilities::DeviceCapabilities> {
+        if self.capabilities.is_none() {
+            self.capabilities = Some(capabilities::DeviceCapabilities::collect(self)?);
+        }
+        Ok(self.capabilities.get_or_insert_default())
+    }
+
+    /// Number of bytes of the last sent data phase accepted by the device
+    ///
+    /// Over UART, each data packet is acknowledged by the device, so after a failed transfer this is the
+    /// offset at which [`McuBoot::receive_sb_file_resume`] can continue.
+    #[must_use]
+    pub fn acked_bytes(&self) -> u64 {
+        self.acked_bytes
+    }
+
+    /// Registers a callback receiving progress of data phase transfers
+    ///
+    /// Replaces the previously registered callback.
+    ///
+    /// # Arguments
+    ///
+    /// * `callback` - Function called with every [`ProgressEvent`]
+    pub fn set_progress_callback(&mut self, callback: impl FnMut(ProgressEvent) + Send + 'static) {
+        self.config.progress = Some(Box::new(callback));
+    }
+
+    /// Removes the progress callback, if any was registered
+    pub fn clear_progress_callback(&mut self) {
+        self.config.progress = None;
+    }
+
     /// Get a specific property value from the device
     ///
+    /// With a family set in the [`session::SessionConfig`], the property is requested by the code the family uses for
+    /// it and the response is parsed as the property the family reports under that code.
+    ///
     /// # Arguments
     ///
     /// * `tag` - The property tag to query
@@ -147,11 +400,44 @@ where
     /// - Communication with device fails
     /// - Invalid response is received
     /// - Property is not supported
+    ///
+    /// Failures of the transport are retried as set by [`session::RetryPolicy`].
     pub fn get_property(
         &mut self,
         tag: PropertyTagDiscriminants,
         memory_index: u32,
     ) -> ResultComm<GetPropertyResponse> {
+        let retry = self.config.retry;
+        let mut attempt = 0;
+        loop {
+            match self.get_property_once(tag, memory_index) {
+                Err(err) if attempt < retry.retries && session::RetryPolicy::is_retried(&err) => {
+                    attempt += 1;
+                    self.retries = self.retries.saturating_add(1);
+                    debug!(
+                        "Retrying get property {tag:?} ({attempt}/{}) after: {err}",
+                        retry.retries
+                    );
+                    thread::sleep(retry.delay);
+                }
+                result => return result,
+            }
+        }
+    }
+
+    /// Sends a single get property request, helper of [`McuBoot::get_property`]
+    fn get_property_once(
+        &mut self,
+        tag: PropertyTagDiscriminants,
+        memory_index: u32,
+    ) -> ResultComm<GetPropertyResponse> {
+        let (tag, property) = match self.config.family {
+            Some(family) => {
+                let tag = family.code_tag(tag);
+                (tag, family.property(tag))
+            }
+            None => (tag, tag),
+        };
         let command = CommandPacket::new_none_flag(CommandTag::GetProperty { tag, memory_index });
         self.send_command(&command)?;
 
@@ -160,7 +446,7 @@ where
         if let CmdResponseTag::GetProperty(val) = response.tag {
             Ok(GetPropertyResponse {
                 status: response.status,
-                property: PropertyTag::from_code(tag, &val),
+                property: PropertyTag::from_code(property, &val),
                 response_words: val,
             })
         } else {
@@ -183,6 +469,7 @@ where
     ///
     /// Returns [`CommunicationError`] if communication fails
     pub fn set_property(&mut self, tag: PropertyTagDiscriminants, value: u32) -> ResultStatus {
+        let tag = self.config.family.map_or(tag, |family| family.code_tag(tag));
         let command = CommandPacket::new_none_flag(CommandTag::SetProperty { tag, value });
         self.send_command(&command)?;
 
@@ -203,6 +490,7 @@ where
     ///
     /// Any [`CommunicationError`], almost all variants are possible.
     pub fn reset(&mut self) -> ResultStatus {
+        self.capabilities = None;
         let command = CommandPacket::new_none_flag(CommandTag::Reset);
         self.send_command(&command)?;
         let response = self.read_cmd_response()?;
@@ -259,6 +547,45 @@ where
         Ok(response.status)
     }
 
+    /// Writes an argument blob to RAM and executes the program at `start_address` with R0 pointing at it
+    ///
+    /// Flashloaders and similar programs take their arguments from a structure in RAM. The blob is
+    /// written at `args_address`, or into a scratch buffer when [`None`], which has to lie in the internal RAM
+    /// outside of the regions reserved by the bootloader.
+    ///
+    /// # Returns
+    ///
+    /// Status code of the execute command
+    ///
+    /// # Errors
+    ///
+    /// [`CommunicationError::ParseError`] if the blob does not fit into the free RAM, otherwise any
+    /// [`CommunicationError`] of the property queries, of writing the blob or of the execute command.
+    pub fn execute_with_args(
+        &mut self,
+        start_address: u32,
+        args: &[u8],
+        args_address: Option<u32>,
+        stackpointer: u32,
+    ) -> ResultStatus {
+        let size = CommunicationError::check_size::<u32>(args.len())?;
+        let mut scratch = ScratchAllocator::from_device(self)?;
+        let args_address = match args_address {
+            Some(address) if scratch.is_free(address, size) => address,
+            Some(address) => {
+                return Err(CommunicationError::ParseError(format!(
+                    "argument blob of {size} bytes at {address:#010X} is not within free RAM"
+                )));
+            }
+            None => scratch.allocate(size, 4).ok_or_else(|| {
+                CommunicationError::ParseError(format!("no free RAM for argument blob of {size} bytes"))
+            })?,
+        };
+        info!("writing argument blob of {size} bytes at {args_address:#010X}");
+        self.write_memory(args_address, 0, args)?;
+        self.execute(start_address, args_address, stackpointer)
+    }
+
     /// Fill memory region with a pattern
     ///
     /// # Arguments
@@ -316,6 +643,405 @@ where
         Ok(response.status)
     }
 
+    /// Write data to MCU memory and read it back to check it was written
+    ///
+    /// Host-enforced equivalent of [`PropertyTag::VerifyWrites`], see [`McuBoot::verify_memory`].
+    ///
+    /// # Returns
+    ///
+    /// Status code of the write
+    ///
+    /// # Errors
+    ///
+    /// Any [`CommunicationError`] of [`McuBoot::write_memory`] and [`McuBoot::verify_memory`].
+    pub fn write_memory_verified(&mut self, start_address: u32, memory_id: u32, bytes: &[u8]) -> ResultStatus {
+        let status = self.write_memory(start_address, memory_id, bytes)?;
+        self.verify_memory(start_address, memory_id, bytes)?;
+        Ok(status)
+    }
+
+    /// Read back memory at `start_address` and compare it with `bytes`
+    ///
+    /// The memory is read in chunks. Regions of the internal memory reserved by the bootloader are skipped, their
+    /// content changes as the bootloader runs. Chunks the device refuses to read as erased, with
+    /// [`StatusCode::MemoryBlankPageReadDisallowed`], are compared with erased content, so data missing there
+    /// fail the verification. Chunks the device refuses to read otherwise, e.g. of secured flash, are reported
+    /// as [`Warning::VerifySkipped`], the protocol has no command computing a checksum of the memory on the
+    /// device.
+    ///
+    /// # Returns
+    ///
+    /// Number of compared bytes
+    ///
+    /// # Errors
+    ///
+    /// [`CommunicationError::VerifyMismatch`] with the data around the first differing byte, any
+    /// [`CommunicationError`] of [`McuBoot::read_memory`] except [`CommunicationError::UnexpectedStatus`]
+    /// otherwise.
+    pub fn verify_memory(&mut self, start_address: u32, memory_id: u32, bytes: &[u8]) -> ResultComm<u64> {
+        let reserved = if memory_id == memory::mem_id::INTERNAL_MEMORY {
+            self.reserved_regions()?
+        } else {
+            Vec::new()
+        };
+        let start = u64::from(start_address);
+        let mut compared = 0;
+        for range in memory::without_reserved(start..start + bytes.len() as u64, &reserved) {
+            for chunk_start in range.clone().step_by(READ_BACK_CHUNK_SIZE as usize) {
+                let chunk_end = (chunk_start + u64::from(READ_BACK_CHUNK_SIZE)).min(range.end);
+                // both fit, the range lies within the address and length of the written bytes
+                let address = chunk_start as u32;
+                let byte_count = (chunk_end - chunk_start) as u32;
+                let expected = &bytes[(chunk_start - start) as usize..(chunk_end - start) as usize];
+                let actual = match self.read_memory(address, byte_count, memory_id) {
+                    Ok(response) if !response.status.is_memory_blank_page_read_disallowed() => response.bytes,
+                    Ok(_) => {
+                        // the device tells the chunk is erased instead of returning it
+                        if self.warnings.last() == Some(&Warning::BlankPageRead) {
+                            self.warnings.pop();
+                        }
+                        vec![0xFF; byte_count as usize].into()
+                    }
+                    Err(CommunicationError::UnexpectedStatus(..)) => {
+                        self.warnings.push(Warning::VerifySkipped {
+                            start_address: address,
+                            byte_count,
+                        });
+                        continue;
+                    }
+                    Err(err) => return Err(err),
+                };
+                let diff = HexDiff {
+                    address,
+                    expected,
+                    actual: &actual[..actual.len().min(expected.len())],
+                };
+                if let Some(mismatch) = CommunicationError::verify_mismatch(&diff) {
+                    return Err(mismatch);
+                }
+                compared += u64::from(byte_count);
+            }
+        }
+        Ok(compared)
+    }
+
+    /// Check that memory is erased, i.e. all its bytes read as `0xFF`
+    ///
+    /// The memory is read in chunks. Devices which refuse to read erased flash pages with
+    /// [`StatusCode::MemoryBlankPageReadDisallowed`] report erased chunks without returning their data.
+    ///
+    /// # Returns
+    ///
+    /// Address of the first byte which is not erased, [`None`] if the whole region is erased
+    ///
+    /// # Errors
+    ///
+    /// Any [`CommunicationError`] of [`McuBoot::read_memory`].
+    pub fn blank_check(&mut self, start_address: u32, byte_count: u32, memory_id: u32) -> ResultComm<Option<u32>> {
+        let start = u64::from(start_address);
+        let end = start + u64::from(byte_count);
+        for chunk_start in (start..end).step_by(READ_BACK_CHUNK_SIZE as usize) {
+            // both fit, the chunk lies within the checked region
+            let address = chunk_start as u32;
+            let byte_count = (end - chunk_start).min(u64::from(READ_BACK_CHUNK_SIZE)) as u32;
+            let response = self.read_memory(address, byte_count, memory_id)?;
+            if response.status.is_memory_blank_page_read_disallowed() {
+                // refusing to read the page is the answer here, not a problem
+                if self.warnings.last() == Some(&Warning::BlankPageRead) {
+                    self.warnings.pop();
+                }
+                continue;
+            }
+            if let Some(offset) = (0..byte_count as usize).find(|&i| response.bytes.get(i) != Some(&0xFF)) {
+                return Ok(Some(address + offset as u32));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Compute a checksum of MCU memory
+    ///
+    /// The memory is read in chunks which are fed to the hasher, so large memories are not kept on the host.
+    ///
+    /// # Returns
+    ///
+    /// The checksum, CRC-32 as big-endian bytes
+    ///
+    /// # Errors
+    ///
+    /// Any [`CommunicationError`] of [`McuBoot::read_memory`], [`CommunicationError::UnexpectedStatus`] if
+    /// the device refuses to return the content of a blank page and [`CommunicationError::InvalidData`]
+    /// if it returns less data than requested.
+    pub fn checksum_region(
+        &mut self,
+        start_address: u32,
+        byte_count: u32,
+        memory_id: u32,
+        algorithm: checksum::ChecksumAlgorithm,
+    ) -> ResultComm<Box<[u8]>> {
+        /// Bytes read at once, large enough to make the overhead of read commands negligible
+        const CHUNK_SIZE: u32 = 0x1_0000;
+
+        let mut hasher = algorithm.hasher();
+        let start = u64::from(start_address);
+        let end = start + u64::from(byte_count);
+        for chunk_start in (start..end).step_by(CHUNK_SIZE as usize) {
+            // both fit, the chunk lies within the region
+            let address = chunk_start as u32;
+            let byte_count = (end - chunk_start).min(u64::from(CHUNK_SIZE)) as u32;
+            let response = self.read_memory(address, byte_count, memory_id)?;
+            if response.status.is_memory_blank_page_read_disallowed() {
+                return Err(response.status.into());
+            }
+            if response.bytes.len() != byte_count as usize {
+                return Err(CommunicationError::InvalidData);
+            }
+            hasher.update(&response.bytes);
+        }
+        Ok(hasher.finalize())
+    }
+
+    /// Write image segments to MCU memory
+    ///
+    /// Each segment is written at its own address with a separate write memory command.
+    ///
+    /// # Arguments
+    ///
+    /// * `segments` - Segments of the image, typically parsed by [`formats::read_segments`]
+    /// * `memory_id` - Memory ID (0 for internal memory, see [`memory::mem_id`] for external)
+    ///
+    /// # Returns
+    ///
+    /// Status code of the last write
+    ///
+    /// # Errors
+    ///
+    /// Any [`CommunicationError`], almost all variants are possible. Writing stops at the first
+    /// failed segment.
+    pub fn write_image(&mut self, segments: &[formats::Segment], memory_id: u32) -> ResultStatus {
+        let mut status = StatusCode::Success;
+        for segment in segments {
+            info!(
+                "Writing {} bytes at {:#010X} (memory ID {memory_id})",
+                segment.data.len(),
+                segment.address
+            );
+            status = self.write_memory(segment.address, memory_id, &segment.data)?;
+        }
+        Ok(status)
+    }
+
+    /// Flash image segments to MCU memory
+    ///
+    /// High-level equivalent of erasing the covered flash region and writing all segments.
+    ///
+    /// # Arguments
+    ///
+    /// * `segments` - Segments of the image, typically parsed by [`formats::read_segments`]
+    /// * `erase` - Erase sectors covered by the segments before writing, the sector size of the memory is read
+    ///   by [`McuBoot::sector_size`]
+    /// * `memory_id` - Memory ID (0 for internal memory, see [`memory::mem_id`] for external)
+    ///
+    /// # Returns
+    ///
+    /// Status code of the last write
+    ///
+    /// # Errors
+    ///
+    /// Any [`CommunicationError`], almost all variants are possible. Flashing stops at the first
+    /// failed erase or write.
+    pub fn flash_image(&mut self, segments: &[formats::Segment], erase: bool, memory_id: u32) -> ResultStatus {
+        if erase {
+            let sector_size = self.sector_size(memory_id)?;
+            for (start_address, byte_count) in formats::sector_regions(segments, sector_size) {
+                info!("Erasing {byte_count} bytes at {start_address:#010X} (memory ID {memory_id})");
+                self.flash_erase_region(start_address, byte_count, memory_id)?;
+                let data_bytes = formats::covered_bytes(segments, start_address, byte_count);
+                if data_bytes < byte_count {
+                    self.warnings.push(Warning::ErasePadded {
+                        start_address,
+                        byte_count,
+                        data_bytes,
+                    });
+                }
+            }
+        }
+        self.write_image(segments, memory_id)
+    }
+
+    /// Size of erase sectors of the memory `memory_id`
+    ///
+    /// Internal flash reports it in [`PropertyTag::FlashSectorSize`], external memories in their
+    /// [`PropertyTag::ExternalMemoryAttributes`].
+    ///
+    /// # Errors
+    ///
+    /// [`CommunicationError::InvalidData`] if the device reports no sector size or zero, errors of the property
+    /// request otherwise.
+    pub fn sector_size(&mut self, memory_id: u32) -> ResultComm<u32> {
+        let size = if memory_id == memory::mem_id::INTERNAL_MEMORY {
+            match self
+                .get_property(PropertyTagDiscriminants::FlashSectorSize, 0)?
+                .property
+            {
+                PropertyTag::FlashSectorSize(size) => Some(size),
+                _ => None,
+            }
+        } else {
+            match self
+                .get_property(PropertyTagDiscriminants::ExternalMemoryAttributes, memory_id)?
+                .property
+            {
+                PropertyTag::ExternalMemoryAttributes(attributes) => attributes.sector_size(),
+                _ => None,
+            }
+        };
+        size.filter(|&size| size != 0).ok_or(CommunicationError::InvalidData)
+    }
+
+    /// List internal and configured external memories
+    ///
+    /// Internal flash and RAM are read from their properties, then
+    /// [`PropertyTag::ExternalMemoryAttributes`] is queried for every memory ID in
+    /// [`memory::mem_id::EXTERNAL`]. Memories the device rejects are not listed.
+    ///
+    /// # Returns
+    ///
+    /// List of the memories, internal ones first
+    ///
+    /// # Errors
+    ///
+    /// Any [`CommunicationError`] except [`CommunicationError::UnexpectedStatus`], which only means the
+    /// memory is not available.
+    pub fn list_memory(&mut self) -> ResultComm<Vec<memory::MemoryInfo>> {
+        let mut memories = Vec::new();
+
+        let flash_start = self.get_optional_property(PropertyTagDiscriminants::FlashStartAddress, 0)?;
+        let flash_size = self.get_optional_property(PropertyTagDiscriminants::FlashSize, 0)?;
+        if let (Some(PropertyTag::FlashStartAddress(start_address)), Some(PropertyTag::FlashSize(size))) =
+            (flash_start, flash_size)
+            && size != 0
+        {
+            let sector_size = match self.get_optional_property(PropertyTagDiscriminants::FlashSectorSize, 0)? {
+                Some(PropertyTag::FlashSectorSize(size)) => Some(size),
+                _ => None,
+            };
+            memories.push(memory::MemoryInfo::InternalFlash {
+                start_address,
+                size,
+                sector_size,
+            });
+        }
+
+        let ram_start = self.get_optional_property(PropertyTagDiscriminants::RAMStartAddress, 0)?;
+        let ram_size = self.get_optional_property(PropertyTagDiscriminants::RAMSize, 0)?;
+        if let (Some(PropertyTag::RAMStartAddress(start_address)), Some(PropertyTag::RAMSize(size))) =
+            (ram_start, ram_size)
+            && size != 0
+        {
+            memories.push(memory::MemoryInfo::InternalRam { start_address, size });
+        }
+
+        for (memory_id, name) in memory::mem_id::EXTERNAL {
+            if let Some(PropertyTag::ExternalMemoryAttributes(attributes)) =
+                self.get_optional_property(PropertyTagDiscriminants::ExternalMemoryAttributes, memory_id)?
+            {
+                memories.push(memory::MemoryInfo::External {
+                    memory_id,
+                    name,
+                    attributes,
+                });
+            }
+        }
+        Ok(memories)
+    }
+
+    /// Get all properties the device supports
+    ///
+    /// Every known property code is requested once with `memory_index`. With a family set, codes the family
+    /// reuses are parsed as the property it reports under them. Properties for which the device responds with
+    /// an error status, e.g. [`StatusCode::UnknownProperty`], are left out.
+    ///
+    /// # Errors
+    ///
+    /// Any [`CommunicationError`] except [`CommunicationError::UnexpectedStatus`], which only means the
+    /// property is not available.
+    pub fn get_all_properties(&mut self, memory_index: u32) -> ResultComm<Vec<GetPropertyResponse>> {
+        let mut responses = Vec::new();
+        for tag in (1..=u8::MAX).filter_map(|code| PropertyTagDiscriminants::try_from(code).ok()) {
+            let property = match self.config.family {
+                // the property is requested under the code the family reuses for it
+                Some(family) if family.code_tag(tag) != tag => continue,
+                Some(family) => family.property(tag),
+                None => tag,
+            };
+            match self.get_property(property, memory_index) {
+                Ok(response) => responses.push(response),
+                Err(CommunicationError::UnexpectedStatus(status, _)) => {
+                    debug!("property {property:?} with index {memory_index} is not available: {status}");
+                }
+                Err(err) => return Err(err),
+            }
+        }
+        Ok(responses)
+    }
+
+    /// (`start_address`, `end_address`) pairs of [`PropertyTag::ReservedRegions`], empty if the device does
+    /// not report them
+    pub(crate) fn reserved_regions(&mut self) -> ResultComm<Vec<(u32, u32)>> {
+        match self.get_optional_property(PropertyTagDiscriminants::ReservedRegions, 0)? {
+            Some(PropertyTag::ReservedRegions(regions)) => Ok(regions.regions().to_vec()),
+            Some(_) => Err(CommunicationError::InvalidData),
+            None => Ok(Vec::new()),
+        }
+    }
+
+    /// Fail if the range of memory overlaps a region reserved by the bootloader
+    ///
+    /// Intended as a check before writes, fills and erases, which the device rejects with a status not
+    /// telling why, or worse, accepts and corrupts its own data. Only the internal memory has reserved
+    /// regions, ranges of other memories and devices not reporting [`PropertyTag::ReservedRegions`] pass.
+    ///
+    /// # Errors
+    ///
+    /// [`CommunicationError::ReservedRegion`] with the first overlapped region, any [`CommunicationError`]
+    /// of the property request otherwise.
+    pub fn check_reserved(&mut self, start_address: u32, byte_count: u32, memory_id: u32) -> ResultComm<()> {
+        if memory_id != memory::mem_id::INTERNAL_MEMORY || byte_count == 0 {
+            return Ok(());
+        }
+        let end = u64::from(start_address) + u64::from(byte_count);
+        let overlapped = self
+            .reserved_regions()?
+            .into_iter()
+            .find(|&(start, last)| u64::from(start) < end && u64::from(last) >= u64::from(start_address));
+        match overlapped {
+            Some((start_address, end_address)) => Err(CommunicationError::ReservedRegion {
+                start_address,
+                end_address,
+            }),
+            None => Ok(()),
+        }
+    }
+
+    /// Get a property, which the device might not support
+    ///
+    /// Returns [`None`] if the device responds with an error status.
+    fn get_optional_property(
+        &mut self,
+        tag: PropertyTagDiscriminants,
+        memory_index: u32,
+    ) -> ResultComm<Option<PropertyTag>> {
+        match self.get_property(tag, memory_index) {
+            Ok(response) => Ok(Some(response.property)),
+            Err(CommunicationError::UnexpectedStatus(status, _)) => {
+                debug!("property {tag:?} with index {memory_index} is not available: {status}");
+                Ok(None)
+            }
+            Err(err) => Err(err),
+        }
+    }
+
     /// Erase all flash memory
     ///
     /// # Arguments
@@ -380,6 +1106,7 @@ where
     ///
     /// Any [`CommunicationError`], almost all variants are possible.
     pub fn flash_erase_all_unsecure(&mut self) -> ResultStatus {
+        self.capabilities = None;
         let command = CommandPacket::new_none_flag(CommandTag::FlashEraseAllUnsecure);
         self.send_command(&command)?;
         let response = self.read_cmd_response()?;
@@ -410,6 +1137,140 @@ where
         byte_count: u32,
         memory_id: u32,
     ) -> ResultComm<ReadMemoryResponse> {
+        let (status, bytes) = self.read_memory_data(start_address, byte_count, memory_id, None)?;
+        self.check_read_length(status, byte_count, bytes.len());
+        Ok(ReadMemoryResponse {
+            status,
+            response_words: Box::new([bytes.len() as u32]),
+            bytes,
+        })
+    }
+
+    /// Read data from MCU memory into `writer` as the data packets arrive
+    ///
+    /// Unlike [`McuBoot::read_memory`], the data are not collected in memory, so large memories are
+    /// dumped without holding the whole content and the first bytes are written right away.
+    ///
+    /// # Arguments
+    ///
+    /// * `start_address` - Start address to read from
+    /// * `byte_count` - Number of bytes to read
+    /// * `memory_id` - Memory ID (0 for internal memory)
+    /// * `writer` - Destination of the read data
+    ///
+    /// # Returns
+    ///
+    /// Response with the status, the only response word is the number of bytes written into `writer` and
+    /// `bytes` are empty
+    ///
+    /// # Errors
+    ///
+    /// Same as [`McuBoot::read_memory`], [`CommunicationError::FileError`] if writing into `writer` fails.
+    /// Bytes received before the failure have been written already.
+    pub fn read_memory_into(
+        &mut self,
+        start_address: u32,
+        byte_count: u32,
+        memory_id: u32,
+        writer: &mut dyn Write,
+    ) -> ResultComm<ReadMemoryResponse> {
+        let mut counter = ByteCounter {
+            inner: writer,
+            count: 0,
+        };
+        let (status, _) = self.read_memory_data(start_address, byte_count, memory_id, Some(&mut counter))?;
+        self.check_read_length(status, byte_count, counter.count);
+        Ok(ReadMemoryResponse {
+            status,
+            response_words: Box::new([counter.count as u32]),
+            bytes: Box::default(),
+        })
+    }
+
+    /// Read data from MCU memory with a separate read memory command for every `chunk_size` bytes
+    ///
+    /// Some ROMs limit the length of a single read memory command. The chunks are read one after another
+    /// and their data are joined, as if the whole memory was read by [`McuBoot::read_memory`]. Reading
+    /// stops at the first chunk failing or returning less data than requested.
+    ///
+    /// # Arguments
+    ///
+    /// * `start_address` - Start address to read from
+    /// * `byte_count` - Number of bytes to read
+    /// * `memory_id` - Memory ID (0 for internal memory)
+    /// * `chunk_size` - Maximum number of bytes read by a single command, zero reads everything at once
+    ///
+    /// # Returns
+    ///
+    /// Response containing the read data and the status of the last command
+    ///
+    /// # Errors
+    ///
+    /// Same as [`McuBoot::read_memory`].
+    pub fn read_memory_large(
+        &mut self,
+        start_address: u32,
+        byte_count: u32,
+        memory_id: u32,
+        chunk_size: u32,
+    ) -> ResultComm<ReadMemoryResponse> {
+        let mut bytes = Vec::new();
+        let response = self.read_memory_large_into(start_address, byte_count, memory_id, chunk_size, &mut bytes)?;
+        Ok(ReadMemoryResponse {
+            bytes: bytes.into(),
+            ..response
+        })
+    }
+
+    /// Read data from MCU memory into `writer`, with a separate read memory command for every `chunk_size` bytes
+    ///
+    /// Combines [`McuBoot::read_memory_large`] with [`McuBoot::read_memory_into`], the data of each chunk are
+    /// written as they arrive.
+    ///
+    /// # Returns
+    ///
+    /// Response with the status of the last command, the only response word is the total number of bytes
+    /// written into `writer` and `bytes` are empty
+    ///
+    /// # Errors
+    ///
+    /// Same as [`McuBoot::read_memory_into`].
+    pub fn read_memory_large_into(
+        &mut self,
+        start_address: u32,
+        byte_count: u32,
+        memory_id: u32,
+        chunk_size: u32,
+        writer: &mut dyn Write,
+    ) -> ResultComm<ReadMemoryResponse> {
+        let chunk_size = if chunk_size == 0 { byte_count } else { chunk_size };
+        let start = u64::from(start_address);
+        let end = start + u64::from(byte_count);
+        let mut chunk_start = start;
+        loop {
+            // both fit, the chunk lies within the region
+            let address = chunk_start as u32;
+            let count = (end - chunk_start).min(u64::from(chunk_size)) as u32;
+            let response = self.read_memory_into(address, count, memory_id, writer)?;
+            let received = response.response_words.first().copied().unwrap_or_default();
+            chunk_start += u64::from(received);
+            if chunk_start >= end || received < count || response.status != StatusCode::Success {
+                return Ok(ReadMemoryResponse {
+                    response_words: Box::new([(chunk_start - start) as u32]),
+                    ..response
+                });
+            }
+        }
+    }
+
+    /// Sends the read memory command and receives its data, into `sink` if given
+    fn read_memory_data(
+        &mut self,
+        start_address: u32,
+        byte_count: u32,
+        memory_id: u32,
+        sink: Option<&mut dyn Write>,
+    ) -> ResultComm<(StatusCode, Box<[u8]>)> {
         let command = CommandPacket::new_none_flag(CommandTag::ReadMemory {
             start_address,
             byte_count,
@@ -417,24 +1278,26 @@ where
         });
         self.send_command(&command)?;
 
-        // allowing one more status code when reading memory
-        let response = self.read_command()?;
-        let status = &response.status;
-        if !(status.is_success() || status.is_memory_blank_page_read_disallowed()) {
-            return Err((*status).into());
-        }
-
+        let response = self.read_cmd_response_into(sink)?;
         if let CmdResponseTag::ReadMemory(bytes) = response.tag {
-            Ok(ReadMemoryResponse {
-                status: response.status,
-                response_words: Box::new([bytes.len() as u32]),
-                bytes,
-            })
+            Ok((response.status, bytes))
         } else {
             Err(CommunicationError::InvalidPacketReceived)
         }
     }
 
+    /// Warns about reads returning less than `byte_count` bytes
+    fn check_read_length(&mut self, status: StatusCode, byte_count: u32, received: usize) {
+        if status.is_memory_blank_page_read_disallowed() {
+            self.warnings.push(Warning::BlankPageRead);
+        } else if received < byte_count as usize {
+            self.warnings.push(Warning::ShortRead {
+                requested: byte_count,
+                received: received as u32,
+            });
+        }
+    }
+
     /// Configure external memory
     ///
     /// # Arguments
@@ -469,14 +1332,46 @@ where
     /// # Note
     ///
     /// The SB file will be processed and executed by the bootloader.
-    /// Progress bar will be shown if enabled.
+    /// Progress is reported to the progress callback if registered.
     ///
     /// # Errors
     ///
     /// Any [`CommunicationError`], almost all variants are possible.
     pub fn receive_sb_file(&mut self, bytes: &[u8]) -> ResultStatus {
+        self.receive_sb_file_resume(bytes, 0)
+    }
+
+    /// Receive the rest of an SB file starting at `offset`
+    ///
+    /// Continues a transfer of [`McuBoot::receive_sb_file`] which failed, typically with
+    /// [`CommunicationError::Timeout`] on a slow link, instead of sending the whole file again. The
+    /// transfer failed at `offset` plus [`McuBoot::acked_bytes`] of the failed command.
+    ///
+    /// # Arguments
+    ///
+    /// * `bytes` - The whole SB file
+    /// * `offset` - Number of bytes already accepted by the device
+    ///
+    /// # Returns
+    ///
+    /// Status code indicating the result of the SB file processing
+    ///
+    /// # Errors
+    ///
+    /// [`CommunicationError::ParseError`] if `offset` is beyond the end of the file, otherwise any
+    /// [`CommunicationError`] of [`McuBoot::receive_sb_file`].
+    pub fn receive_sb_file_resume(&mut self, bytes: &[u8], offset: usize) -> ResultStatus {
+        let bytes = bytes.get(offset..).ok_or_else(|| {
+            CommunicationError::ParseError(format!(
+                "resume offset {offset} is beyond the end of the {} byte file",
+                bytes.len()
+            ))
+        })?;
         let command = CommandPacket::new_data_phase(CommandTag::ReceiveSBFile { bytes });
         match self.send_command(&command) {
+            // the final response was already read when the host aborted the transfer
+            Err(CommunicationError::Aborted) if self.host_abort => Err(CommunicationError::Aborted),
+            // the device aborted the data phase, the final response tells why
             Ok(()) | Err(CommunicationError::Aborted) => {
                 let response = self.read_cmd_response()?;
                 Ok(response.status)
@@ -544,7 +1439,7 @@ where
         operation: &KeyProvOperation,
     ) -> Result<KeyProvisioningResponse, CommunicationError> {
         let command = CommandPacket::new_none_flag(CommandTag::KeyProvisioning(operation));
-        if let KeyProvOperation::ReadKeyStore { .. } = operation {
+        if matches!(operation, KeyProvOperation::ReadKeyStore) {
             self.send_command(&command)?;
             let response = self.read_cmd_response()?;
             // Extract the data based on the response tag
@@ -560,14 +1455,50 @@ where
                 _ => Err(CommunicationError::InvalidPacketReceived),
             }
         } else {
-            self.mask_read_data_phase = true;
+            self.mask_data_phase(true);
             self.send_command(&command)?;
-            self.mask_read_data_phase = false;
+            self.mask_data_phase(false);
             let response = self.read_cmd_response()?;
             Ok(KeyProvisioningResponse::Status(response.status))
         }
     }
 
+    /// Writes the key store of the bootloader to the nonvolatile memory `memory_id`
+    ///
+    /// A key store which was not persisted correctly is otherwise noticed only after the next boot. With `verify`,
+    /// the key store is read before writing it, loaded back from the nonvolatile memory and read again. When the
+    /// two differ, [`Warning::KeyStoreMismatch`] is reported.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CommunicationError`] if communication fails or if the device rejects any of the operations,
+    /// including reading the key store when verifying.
+    pub fn write_key_nonvolatile(&mut self, memory_id: u32, verify: bool) -> ResultStatus {
+        let expected = if verify { Some(self.read_key_store()?) } else { None };
+        let status = match self.key_provisioning(&KeyProvOperation::WriteKeyNonvolatile { memory_id })? {
+            KeyProvisioningResponse::Status(status) | KeyProvisioningResponse::KeyStore { status, .. } => status,
+        };
+        if let Some(expected) = expected {
+            self.key_provisioning(&KeyProvOperation::ReadKeyNonvolatile { memory_id })?;
+            if self.read_key_store()? != expected {
+                self.warnings.push(Warning::KeyStoreMismatch { memory_id });
+            }
+        }
+        Ok(status)
+    }
+
+    /// Reads the key store from the bootloader
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CommunicationError`] if communication fails or if the device rejects the operation.
+    pub fn read_key_store(&mut self) -> ResultComm<Vec<u8>> {
+        match self.key_provisioning(&KeyProvOperation::ReadKeyStore)? {
+            KeyProvisioningResponse::KeyStore { bytes, .. } => Ok(bytes.into_vec()),
+            KeyProvisioningResponse::Status(_) => Err(CommunicationError::InvalidPacketReceived),
+        }
+    }
+
     /// Read from MCU flash program once region (eFuse/OTP)
     ///
     /// Reads a 32-bit value from the one-time programmable (OTP) memory region.
@@ -642,11 +1573,13 @@ where
         if verify && response.status.is_success() {
             // For verification, we read back the value and check if the bits we set are still set
             // Note: In OTP, we can only set bits from 0 to 1, not vice versa
-            match self.flash_read_once(index & ((1 << 24) - 1), count) {
+            let index = index & ((1 << 24) - 1);
+            match self.flash_read_once(index, count) {
                 Ok(read_value) => {
                     if read_value & data == data {
                         Ok(response.status)
                     } else {
+                        self.warnings.push(Warning::VerificationFailed { index });
                         // Custom status code for verification failure
                         Ok(StatusCode::OtpVerifyFail)
                     }
@@ -658,6 +1591,63 @@ where
         }
     }
 
+    /// Read a word from eFuse/OTP
+    ///
+    /// Shorthand for [`McuBoot::flash_read_once`] reading 4 bytes, like blhost `efuse-read-once`.
+    ///
+    /// # Arguments
+    ///
+    /// * `index` - Index of the eFuse/OTP word
+    ///
+    /// # Returns
+    ///
+    /// The read value
+    ///
+    /// # Errors
+    ///
+    /// Same as [`McuBoot::flash_read_once`].
+    pub fn efuse_read_once(&mut self, index: u32) -> ResultComm<u32> {
+        self.flash_read_once(index, 4)
+    }
+
+    /// Program a word into eFuse/OTP, optionally locking it
+    ///
+    /// Like blhost `efuse-program-once`, the lock is requested by setting a bit in the index word.
+    /// Which bit it is depends on the device family, most families use bit 24, some bit 31.
+    ///
+    /// # Arguments
+    ///
+    /// * `index` - Index of the eFuse/OTP word, without the lock bit
+    /// * `data` - Value to write
+    /// * `lock_bit` - Bit of the index word requesting the lock, [`None`] to keep the word unlocked
+    /// * `verify` - Read the word back and check that the written bits are set
+    ///
+    /// # Returns
+    ///
+    /// Status code, [`StatusCode::OtpVerifyFail`] if verification fails
+    ///
+    /// # Errors
+    ///
+    /// [`CommunicationError::ParseError`] if the lock bit is out of range or already set in `index`,
+    /// otherwise same as [`McuBoot::flash_program_once`].
+    pub fn efuse_program_once(&mut self, index: u32, data: u32, lock_bit: Option<u8>, verify: bool) -> ResultStatus {
+        let index = match lock_bit {
+            Some(bit) if bit >= 32 => {
+                return Err(CommunicationError::ParseError(format!(
+                    "lock bit {bit} is out of range"
+                )));
+            }
+            Some(bit) if index & (1 << bit) != 0 => {
+                return Err(CommunicationError::ParseError(format!(
+                    "index {index:#X} collides with lock bit {bit}"
+                )));
+            }
+            Some(bit) => index | (1 << bit),
+            None => index,
+        };
+        self.flash_program_once(index, 4, data, verify)
+    }
+
     /// Read fuse data
     ///
     /// Reads data from the device's fuse memory region. Fuses are one-time
@@ -691,10 +1681,6 @@ where
         });
         self.send_command(&command)?;
         let response = self.read_cmd_response()?;
-        let status = &response.status;
-        if !status.is_success() {
-            return Err((*status).into());
-        }
         match response.tag {
             CmdResponseTag::ReadMemory(bytes) => Ok(ReadMemoryResponse {
                 status: response.status,
@@ -770,16 +1756,42 @@ where
     ///
     /// # Returns
     ///
-    /// The command response if status indicates success
+    /// The command response if status indicates success, some commands accept other statuses as well, see
+    /// [`CommandTagDiscriminants::accepted_statuses`]
     ///
     /// # Errors
     ///
     /// Returns [`CommunicationError`] converted from the status code if the
     /// operation was not successful
     fn read_cmd_response(&mut self) -> ResultComm<CmdResponse> {
-        let response = self.read_command()?;
-        info!("{}: {response:02X?}", cstr!("<bold>Received"));
-        if response.status.is_success() {
+        self.read_cmd_response_into(None)
+    }
+
+    /// Read a command response, writing its data phase into `sink` instead of the response
+    ///
+    /// See [`McuBoot::read_cmd_response`].
+    fn read_cmd_response_into(&mut self, sink: Option<&mut dyn Write>) -> ResultComm<CmdResponse> {
+        // this is the final response, the span closes once dropped
+        let command_span = self.command_spans.pop().unwrap_or_else(Span::none);
+        let _entered = command_span.enter();
+        let response = self.read_response(sink);
+        match &response {
+            Ok(response) => command_span.record("status", field::display(response.status)),
+            Err(err) => command_span.record("error", field::display(err)),
+        };
+        response
+    }
+
+    /// Read a command response without closing the span of the command
+    ///
+    /// Used directly only for intermediate responses, see [`McuBoot::read_cmd_response`].
+    fn read_response(&mut self, sink: Option<&mut dyn Write>) -> ResultComm<CmdResponse> {
+        let response = self.read_command(sink)?;
+        info!("{RECEIVED}: {response:02X?}");
+        let accepted = self
+            .command_in_progress
+            .map_or(response.status.is_success(), |command| command.accepts(response.status));
+        if accepted {
             Ok(response)
         } else {
             Err(response.status.into())
@@ -815,47 +1827,181 @@ where
     ///    - Queries max packet size from device
     ///    - Reads intermediate response
     ///    - Splits data into chunks
-    ///    - Sends each chunk with optional progress tracking
+    ///    - Sends each chunk, reporting the progress to the progress callback
     fn send_command(&mut self, command: &CommandPacket) -> ResultComm<()> {
+        let span = debug_span!("command", tag = %command.tag, status = field::Empty, error = field::Empty);
+        let _entered = span.enter();
+        self.command_spans.push(span.clone());
+        let result = self.send_command_packets(command);
+        if let Err(err) = &result {
+            // no response is read after a failure, the command ends here
+            span.record("error", field::display(err));
+            self.command_spans.pop();
+        }
+        result
+    }
+
+    /// Send the packets of a command
+    ///
+    /// Helper of [`McuBoot::send_command`], which traces the command.
+    fn send_command_packets(&mut self, command: &CommandPacket) -> ResultComm<()> {
         let tag = &command.tag;
-        let (params, data_phase) = tag.to_params();
-        let packet = command.header.construct_frame(&params, tag.code());
-        info!("{}: {command:02X?}", cstr!("<bold>Sending"));
+        let (params, data_phase) = tag.to_params()?;
+        let payload = command.header.construct_payload(&params, tag.code())?;
+        if std::mem::take(&mut self.quirks_pending) {
+            self.device.apply_quirks(&self.config.quirks);
+        }
+        info!("{SENDING}: {command:02X?}");
+        self.intermediate_status = None;
 
         if let Some(data) = data_phase {
             info!("Sending data phase: {data:02X?}");
-            let max_packet_size: u32 = {
-                let response = self.get_property(PropertyTagDiscriminants::MaxPacketSize, 0)?;
-                match response.property {
-                    PropertyTag::MaxPacketSize(size) => size,
-                    _ => return Err(CommunicationError::InvalidData),
-                }
-            };
-            if !matches!(tag, CommandTag::NoCommand { .. }) {
-                self.device.write_packet_raw(&packet)?;
-                // this is the intermediate generic response
-                self.read_cmd_response()?;
+            // started is reported right away, querying the packet size and the intermediate response
+            // can take a while on slow links
+            let total = data.len() as u64;
+            let _data_phase = debug_span!("data_phase", direction = "send", bytes = total).entered();
+            self.report_progress(ProgressEvent::Started {
+                command: Some(tag.into()),
+                direction: TransferDirection::Send,
+                total,
+            });
+            let mut transferred = 0;
+            let result = self.send_data_phase(tag, &payload, data, &mut transferred);
+            self.abort.set_data_phase(false);
+            self.acked_bytes = transferred;
+            self.report_progress(match result {
+                Ok(()) => ProgressEvent::Finished { transferred },
+                Err(_) => ProgressEvent::Aborted { transferred },
+            });
+            result?;
+        } else {
+            self.command_in_progress = Some(tag.into());
+            self.with_timeout(self.config.timeouts.ack, |boot| {
+                boot.device.write_packet(CommandPacket::get_code(), &payload)
+            })?;
+        }
+        Ok(())
+    }
+
+    /// Run `f` with the protocol timeout changed to `timeout`, if any, and restore it afterwards
+    ///
+    /// The result of `f` is returned even if the timeout cannot be restored, the failure is only logged.
+    fn with_timeout<R>(
+        &mut self,
+        timeout: Option<Duration>,
+        f: impl FnOnce(&mut Self) -> ResultComm<R>,
+    ) -> ResultComm<R> {
+        let Some(timeout) = timeout else {
+            return f(self);
+        };
+        let previous = self.device.get_timeout();
+        if previous == timeout {
+            return f(self);
+        }
+        trace!("Changing timeout to {} ms", timeout.as_millis());
+        self.device.set_timeout(timeout)?;
+        let result = f(self);
+        if let Err(err) = self.device.set_timeout(previous) {
+            warn!("Timeout could not be restored to {} ms: {err}", previous.as_millis());
+        }
+        result
+    }
+
+    /// Send a command with a data phase
+    ///
+    /// Helper of [`McuBoot::send_command`], `transferred` is updated with every sent packet, so it is
+    /// valid even if the transfer fails.
+    fn send_data_phase(
+        &mut self,
+        tag: &CommandTag,
+        payload: &[u8],
+        data: &[u8],
+        transferred: &mut u64,
+    ) -> ResultComm<()> {
+        let max_packet_size: u32 = if let Some(size) = self.config.transfer.max_packet_size
+            && size > 0
+        {
+            debug!("Using max packet size {size} instead of the one reported by the device");
+            size
+        } else {
+            self.capabilities()?
+                .max_packet_size
+                .ok_or(CommunicationError::InvalidData)?
+        };
+        self.last_outcome = None;
+        self.host_abort = false;
+        self.command_in_progress = Some(tag.into());
+        if !matches!(tag, CommandTag::NoCommand { .. }) {
+            // this is the intermediate generic response
+            let status = self
+                .with_timeout(self.config.timeouts.ack, |boot| {
+                    boot.device.write_packet(CommandPacket::get_code(), payload)?;
+                    boot.read_response(None)
+                })?
+                .status;
+            debug!("Intermediate status: {status}");
+            self.intermediate_status = Some(status);
+        }
+        let total = data.len() as u64;
+        self.abort.set_data_phase(true);
+        for (index, bytes) in data
+            .chunks(
+                max_packet_size
+                    .try_into()
+                    .expect("pointer size of this platform is too small"),
+            )
+            .enumerate()
+        {
+            if index > 0 && !self.config.transfer.inter_packet_delay.is_zero() {
+                thread::sleep(self.config.transfer.inter_packet_delay);
             }
-            // Block for progress bar
-            {
-                let progress_bar = self.create_progress_bar(data.len() as u64, "Sending data");
-                for bytes in data.chunks(
-                    max_packet_size
-                        .try_into()
-                        .expect("pointer size of this platform is too small"),
-                ) {
-                    self.device.write_packet_concrete(DataPhasePacket::parse(bytes)?)?;
-                    if let Some(bar) = progress_bar.as_ref() {
-                        bar.inc(max_packet_size.into());
-                    }
-                }
+            if self.abort.take_request() {
+                return self.abort_sent_data_phase();
             }
-        } else {
-            self.device.write_packet_raw(&packet)?;
+            let packet = DataPhasePacket::parse(bytes)?;
+            self.with_timeout(self.config.timeouts.data, |boot| {
+                boot.device.write_packet_concrete(packet)
+            })?;
+            *transferred += bytes.len() as u64;
+            self.report_progress(ProgressEvent::Chunk {
+                transferred: *transferred,
+                total,
+            });
         }
         Ok(())
     }
 
+    /// Abort the data phase being sent with a zero-length data packet and read the final response
+    ///
+    /// # Errors
+    /// [`CommunicationError::Aborted`] once the device accepted the abort, errors of sending the packet
+    /// otherwise.
+    fn abort_sent_data_phase(&mut self) -> ResultComm<()> {
+        warn!("Aborting the data phase");
+        self.host_abort = true;
+        self.with_timeout(self.config.timeouts.data, |boot| {
+            boot.device.write_packet_concrete(DataPhasePacket { data: Vec::new() })
+        })?;
+        match self.read_cmd_response() {
+            Ok(response) => debug!("Final response of the aborted data phase: {response:02X?}"),
+            Err(err) => debug!("Final response of the aborted data phase: {err}"),
+        }
+        Err(CommunicationError::Aborted)
+    }
+
+    /// Read the final response of the data phase aborted by ACK abort after `transferred` bytes
+    ///
+    /// # Errors
+    /// [`CommunicationError::Aborted`], the final response is only logged.
+    fn abort_received_data_phase(&mut self, transferred: u64) -> ResultComm<CmdResponse> {
+        self.report_progress(ProgressEvent::Aborted { transferred });
+        let final_response = self.with_timeout(self.config.timeouts.ack, |boot| {
+            boot.device.read_packet_raw(CmdResponse::get_code())
+        });
+        debug!("Final response of the aborted data phase: {final_response:02X?}");
+        Err(CommunicationError::Aborted)
+    }
+
     /// Read a command response from the device
     ///
     /// Internal helper method that reads and parses command responses,
@@ -879,12 +2025,45 @@ where
     /// 1. Reads the initial response header
     /// 2. Extracts the data phase length
     /// 3. Reads data packets until complete
-    /// 4. Shows progress bar if enabled
+    /// 4. Reports the progress to the progress callback
     /// 5. Reads final status response
-    fn read_command(&mut self) -> ResultComm<CmdResponse> {
+    ///
+    /// With `sink`, packets of the data phase are written into it as they arrive and the response carries
+    /// no data.
+    fn read_command(&mut self, sink: Option<&mut dyn Write>) -> ResultComm<CmdResponse> {
+        let response = self.read_command_packets(sink);
+        self.abort.set_data_phase(false);
+        let response = response?;
+        self.last_outcome = Some(CommandOutcome {
+            status: response.status,
+            intermediate_status: self.intermediate_status.take(),
+        });
+        Ok(response)
+    }
+
+    /// Timeout of the first response to the command in progress, erase commands take longer
+    fn response_timeout(&self) -> Option<Duration> {
+        match self.command_in_progress {
+            Some(
+                CommandTagDiscriminants::FlashEraseAll
+                | CommandTagDiscriminants::FlashEraseRegion
+                | CommandTagDiscriminants::FlashEraseAllUnsecure,
+            ) => self.config.timeouts.erase,
+            _ => self.config.timeouts.ack,
+        }
+    }
+
+    /// Read all packets of a command response
+    ///
+    /// Helper of [`McuBoot::read_command`], which records the outcome of the response.
+    #[expect(deprecated, reason = "the field is deprecated for users only")]
+    fn read_command_packets(&mut self, mut sink: Option<&mut dyn Write>) -> ResultComm<CmdResponse> {
         trace!("Starting to read command");
-        let data = self.device.read_packet_raw(CmdResponse::get_code())?;
-        let params_slice = &data[8..];
+        let data = self.with_timeout(self.response_timeout(), |boot| {
+            boot.device.read_packet_raw(CmdResponse::get_code())
+        })?;
+        // header and status are always present
+        let params_slice = data.get(8..).ok_or(CommunicationError::InvalidData)?;
 
         // data[3] = param count
         if params_slice.len() % 4 != 0 && params_slice.len() != 4 * data[3] as usize {
@@ -915,30 +2094,69 @@ where
                 tag: CmdResponseTag::from_code(data[0], params_slice, None).ok_or(CommunicationError::InvalidData)?,
             }),
             CommandFlag::HasDataPhase => {
-                let length = u32::from_le_bytes(params_slice[0..4].try_into().or_invalid()?);
+                let length = params_slice
+                    .first_chunk()
+                    .map(|length| u32::from_le_bytes(*length))
+                    .ok_or(CommunicationError::InvalidData)?;
                 trace!("Data phase length: {length}");
+                let _data_phase = debug_span!("data_phase", direction = "receive", bytes = length).entered();
 
+                let total = u64::from(length);
+                self.report_progress(ProgressEvent::Started {
+                    command: self.command_in_progress,
+                    direction: TransferDirection::Receive,
+                    total,
+                });
                 let mut data_phase = Vec::new();
-                // Block for progress bar
-                {
-                    let progress_bar = self.create_progress_bar(length.into(), "Receiving data");
-                    while data_phase.len() != length as usize {
-                        trace!("Reading data phase packet");
-                        data_phase.extend(match self.device.read_packet_concrete::<DataPhasePacket>() {
-                            Ok(data) => {
-                                if let Some(bar) = progress_bar.as_ref() {
-                                    bar.inc(data.data.len() as u64);
-                                }
-                                data.data
+                let mut transferred = 0;
+                self.abort.set_data_phase(true);
+                while transferred != length as usize {
+                    trace!("Reading data phase packet");
+                    let abort = self.abort.take_request();
+                    let packet = self.with_timeout(self.config.timeouts.data, |boot| {
+                        if abort {
+                            warn!("Aborting the data phase");
+                            DataPhasePacket::parse(&boot.device.read_packet_abort(DataPhasePacket::get_code())?)
+                        } else {
+                            boot.device.read_packet_concrete::<DataPhasePacket>()
+                        }
+                    });
+                    match packet {
+                        Ok(data) if abort => {
+                            return self.abort_received_data_phase((transferred + data.data.len()) as u64);
+                        }
+                        Ok(data) => {
+                            transferred += data.data.len();
+                            match sink.as_mut() {
+                                Some(sink) => sink.write_all(&data.data).map_err(CommunicationError::FileError)?,
+                                None => data_phase.extend(data.data),
                             }
-                            Err(CommunicationError::Aborted) => break,
-                            Err(err) => return Err(err),
-                        });
+                        }
+                        Err(err) => {
+                            self.report_progress(ProgressEvent::Aborted {
+                                transferred: transferred as u64,
+                            });
+                            match err {
+                                CommunicationError::Aborted => break,
+                                err => return Err(err),
+                            }
+                        }
                     }
+                    self.report_progress(ProgressEvent::Chunk {
+                        transferred: transferred as u64,
+                        total,
+                    });
+                }
+                if transferred == length as usize {
+                    self.report_progress(ProgressEvent::Finished { transferred: total });
                 }
 
+                debug!("Intermediate status: {status}");
+                self.intermediate_status = Some(status);
                 trace!("Reading final response");
-                let final_response = self.device.read_packet_raw(CmdResponse::get_code())?;
+                let final_response = self.with_timeout(self.config.timeouts.ack, |boot| {
+                    boot.device.read_packet_raw(CmdResponse::get_code())
+                })?;
                 let status = parse_status(final_response[4..8].try_into().or_invalid()?)?;
 
                 Ok(CmdResponse {
@@ -954,44 +2172,38 @@ where
         }
     }
 
-    /// Create a progress bar for data transfers
-    ///
-    /// Internal helper method that creates a progress bar if progress tracking is enabled.
-    /// The progress bar displays the transfer status with binary size formatting.
-    ///
-    /// # Arguments
-    ///
-    /// * `len` - Total length of data to transfer in bytes
-    /// * `prefix` - Descriptive prefix for the progress bar
-    ///
-    /// # Returns
-    ///
-    /// Optional progress bar instance:
-    /// - Some(ProgressBar) if progress tracking is enabled
-    /// - None if progress tracking is disabled
-    ///
-    /// # Progress Bar Format
-    ///
-    /// The progress bar displays:
-    /// - Custom prefix text
-    /// - Visual progress indicator (40 characters wide)
-    /// - Current bytes transferred / total bytes
-    fn create_progress_bar(&self, len: u64, prefix: &'static str) -> Option<ProgressBar> {
-        if self.progress_bar {
-            let bar = ProgressBar::new(len);
-            bar.set_style(
-                ProgressStyle::with_template("{prefix} [{bar:40}] {binary_bytes:>}/{binary_total_bytes}")
-                    .unwrap()
-                    .progress_chars("##-"),
-            );
-            bar.set_prefix(prefix);
-            Some(bar)
-        } else {
-            None
+    /// Ignores the data phase flag of the following responses when `mask` is set
+    #[expect(deprecated, reason = "the field is deprecated for users only")]
+    fn mask_data_phase(&mut self, mask: bool) {
+        self.mask_read_data_phase = mask;
+    }
+
+    /// Passes the event to the progress callback, if any is registered
+    fn report_progress(&mut self, event: ProgressEvent) {
+        if let Some(callback) = self.config.progress.as_mut() {
+            callback(event);
         }
     }
 }
 
+/// Writer counting the bytes written into the inner writer
+struct ByteCounter<'a> {
+    inner: &'a mut dyn Write,
+    count: usize,
+}
+
+impl Write for ByteCounter<'_> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let written = self.inner.write(buf)?;
+        self.count += written;
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.inner.flush()
+    }
+}
+
 /// Parse status code from raw bytes
 ///
 /// Converts a 4-byte little-endian value into a [`StatusCode`] enum.
@@ -0,0 +1,57 @@
+// Copyright 2025 NXP
+//
+// SPDX-License-Identifier: BSD-3-Clause
+//! Aborting data phases
+//!
+//! A host which stops in the middle of a data phase leaves the bootloader waiting for the rest of the data,
+//! so the next command fails until the device is reset. [`AbortHandle`] lets another thread, like a signal
+//! handler, ask [`McuBoot`](crate::McuBoot) to abort the transfer the way the protocol defines: the host
+//! sends a zero-length data packet or answers a received packet with ACK abort, and then reads the final
+//! response of the command.
+
+use std::sync::{
+    Arc,
+    atomic::{AtomicBool, Ordering},
+};
+
+/// Handle requesting the abort of the data phase in progress
+///
+/// Obtained from [`McuBoot::abort_handle`](crate::McuBoot::abort_handle), clones refer to the same session.
+#[derive(Clone, Debug, Default)]
+pub struct AbortHandle {
+    inner: Arc<AbortState>,
+}
+
+#[derive(Debug, Default)]
+struct AbortState {
+    requested: AtomicBool,
+    in_data_phase: AtomicBool,
+}
+
+impl AbortHandle {
+    /// Requests the abort of the data phase in progress
+    ///
+    /// Returns whether a data phase is in progress, it is then aborted before its next packet and the
+    /// command fails with [`CommunicationError::Aborted`](crate::CommunicationError::Aborted). Without
+    /// a data phase in progress, the request is dropped and the caller may stop right away.
+    #[must_use = "without a data phase in progress, nothing is aborted"]
+    pub fn abort(&self) -> bool {
+        if self.inner.in_data_phase.load(Ordering::SeqCst) {
+            self.inner.requested.store(true, Ordering::SeqCst);
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Marks the start or the end of a data phase, a pending request is dropped either way
+    pub(super) fn set_data_phase(&self, in_data_phase: bool) {
+        self.inner.requested.store(false, Ordering::SeqCst);
+        self.inner.in_data_phase.store(in_data_phase, Ordering::SeqCst);
+    }
+
+    /// Takes the pending abort request
+    pub(super) fn take_request(&self) -> bool {
+        self.inner.requested.swap(false, Ordering::SeqCst)
+    }
+}
@@ -0,0 +1,99 @@
+// Copyright 2025 NXP
+//
+// SPDX-License-Identifier: BSD-3-Clause
+//! Capabilities of the connected device
+//!
+//! Pre-checks and embedders often need the same few properties, like the available commands or the max
+//! packet size. [`McuBoot::capabilities`](crate::McuBoot::capabilities) requests them once and keeps them
+//! in [`DeviceCapabilities`] until the device is reset.
+
+use log::debug;
+
+use super::{
+    McuBoot, ResultComm,
+    protocols::Protocol,
+    tags::{
+        command::CommandTagDiscriminants,
+        property::{PeripheryTag, PropertyTag, PropertyTagDiscriminants, Version},
+    },
+};
+use crate::CommunicationError;
+
+/// Properties describing what the device supports
+///
+/// Properties the device does not report are [`None`].
+#[derive(Clone, Debug, Default)]
+pub struct DeviceCapabilities {
+    /// Version of the bootloader
+    pub version: Option<Version>,
+    /// Commands the bootloader accepts
+    pub commands: Option<Box<[CommandTagDiscriminants]>>,
+    /// Peripherals the bootloader listens on
+    pub peripherals: Option<Box<[PeripheryTag]>>,
+    /// Size of data packets the bootloader accepts
+    pub max_packet_size: Option<u32>,
+    /// Whether the flash is secured, [`None`] if the device does not report it
+    pub secure: Option<bool>,
+}
+
+impl DeviceCapabilities {
+    /// Requests the properties from the device
+    ///
+    /// # Errors
+    ///
+    /// Any [`CommunicationError`] except [`CommunicationError::UnexpectedStatus`], which only means the
+    /// property is not available.
+    pub(super) fn collect<T: Protocol>(boot: &mut McuBoot<T>) -> ResultComm<Self> {
+        let mut capabilities = DeviceCapabilities::default();
+        for tag in [
+            PropertyTagDiscriminants::CurrentVersion,
+            PropertyTagDiscriminants::AvailableCommands,
+            PropertyTagDiscriminants::AvailablePeripherals,
+            PropertyTagDiscriminants::MaxPacketSize,
+            PropertyTagDiscriminants::FlashSecurityState,
+        ] {
+            let property = match boot.get_property(tag, 0) {
+                Ok(response) => response.property,
+                Err(CommunicationError::UnexpectedStatus(status, _)) => {
+                    debug!("property {tag:?} is not available: {status}");
+                    continue;
+                }
+                Err(err) => return Err(err),
+            };
+            match property {
+                PropertyTag::CurrentVersion(version) => capabilities.version = Some(version),
+                PropertyTag::AvailableCommands(commands) => capabilities.commands = Some(commands),
+                PropertyTag::AvailablePeripherals(peripherals) => capabilities.peripherals = Some(peripherals),
+                PropertyTag::MaxPacketSize(size) => capabilities.max_packet_size = Some(size),
+                PropertyTag::FlashSecurityState(state) => capabilities.secure = Some(!state.0),
+                // a family may report another property under the code
+                _ => {}
+            }
+        }
+        Ok(capabilities)
+    }
+
+    /// Whether the device accepts `command`
+    ///
+    /// Devices not reporting available commands are assumed to accept all of them.
+    #[must_use]
+    pub fn supports(&self, command: CommandTagDiscriminants) -> bool {
+        self.commands
+            .as_ref()
+            .is_none_or(|commands| commands.contains(&command))
+    }
+
+    /// Whether the device listens on `peripheral`, `false` if it does not report its peripherals
+    #[must_use]
+    pub fn has_peripheral(&self, peripheral: PeripheryTag) -> bool {
+        self.peripherals
+            .as_ref()
+            .is_some_and(|peripherals| peripherals.contains(&peripheral))
+    }
+
+    /// Whether the flash is secured, `false` if the device does not report it
+    #[must_use]
+    pub fn is_secure(&self) -> bool {
+        self.secure == Some(true)
+    }
+}
@@ -0,0 +1,76 @@
+// Copyright 2025 NXP
+//
+// SPDX-License-Identifier: BSD-3-Clause
+//! Checksums of the device memory
+//!
+//! [`McuBoot::checksum_region`](crate::McuBoot::checksum_region) feeds the memory read in chunks to a
+//! hasher, so the content of the memory can be compared with the programmed image without dumping it.
+
+use sha2::{Digest as _, Sha256};
+
+/// CRC-32 used by zlib, Ethernet and most checksum tools
+static CRC32: crc::Crc<u32> = crc::Crc::<u32>::new(&crc::CRC_32_ISO_HDLC);
+
+/// Algorithm computing the checksum of the memory
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, derive_more::Display)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+pub enum ChecksumAlgorithm {
+    /// CRC-32 (ISO-HDLC), as computed by zlib or `crc32` tools
+    #[default]
+    #[display("crc32")]
+    Crc32,
+    /// SHA-256
+    #[display("sha256")]
+    Sha256,
+}
+
+impl ChecksumAlgorithm {
+    /// Creates a hasher computing the checksum
+    pub(super) fn hasher(self) -> Hasher {
+        match self {
+            ChecksumAlgorithm::Crc32 => Hasher::Crc32(CRC32.digest()),
+            ChecksumAlgorithm::Sha256 => Hasher::Sha256(Sha256::new()),
+        }
+    }
+}
+
+/// State of a checksum computation
+pub(super) enum Hasher {
+    Crc32(crc::Digest<'static, u32>),
+    Sha256(Sha256),
+}
+
+impl Hasher {
+    /// Adds `bytes` to the checksum
+    pub(super) fn update(&mut self, bytes: &[u8]) {
+        match self {
+            Hasher::Crc32(digest) => digest.update(bytes),
+            Hasher::Sha256(digest) => digest.update(bytes),
+        }
+    }
+
+    /// Returns the checksum, CRC-32 as big-endian bytes so its hex digits read as the number
+    pub(super) fn finalize(self) -> Box<[u8]> {
+        match self {
+            Hasher::Crc32(digest) => digest.finalize().to_be_bytes().into(),
+            Hasher::Sha256(digest) => digest.finalize().to_vec().into(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn known_checksums() {
+        let checksum = |algorithm: ChecksumAlgorithm| {
+            let mut hasher = algorithm.hasher();
+            hasher.update(b"1234");
+            hasher.update(b"56789");
+            hasher.finalize()
+        };
+        assert_eq!(*checksum(ChecksumAlgorithm::Crc32), [0xCB, 0xF4, 0x39, 0x26]);
+        assert_eq!(checksum(ChecksumAlgorithm::Sha256)[..4], [0x15, 0xE2, 0xB0, 0xD3]);
+    }
+}
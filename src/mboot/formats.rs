@@ -0,0 +1,396 @@
+// Copyright 2025 NXP
+//
+// SPDX-License-Identifier: BSD-3-Clause
+//! Image File Formats
+//!
+//! This module parses firmware image files into memory segments, so images in formats carrying
+//! their own load addresses can be written to the device without converting them to flat binaries
+//! first. Segments read from the device are written as binary, Intel HEX or S-record files with
+//! [`write_segments`].
+//!
+//! # Supported Formats
+//! - Binary: raw bytes without any address information
+//! - Intel HEX: `.hex`, `.ihex` and `.ihx` files
+//! - Motorola S-record: `.srec`, `.s19`, `.s28`, `.s37` and `.mot` files
+//! - ELF: `.elf`, `.axf` and `.out` files, loadable segments are placed at their physical addresses
+//! - Addressed hex text: `.txt` files with `@address` lines followed by hex bytes, as produced by some
+//!   NXP tools
+
+use std::{fmt::Write, fs, io, path::Path};
+
+mod elf;
+mod ihex;
+mod srec;
+mod txt;
+
+pub use elf::parse_elf;
+pub use ihex::{parse_intel_hex, write_intel_hex};
+pub use srec::{parse_srec, write_srec};
+pub use txt::parse_txt;
+
+/// Error raised while parsing an image file
+#[derive(thiserror::Error, Debug)]
+pub enum FormatError {
+    /// Image file could not be read
+    #[error("error while reading image file")]
+    IOError(#[from] io::Error),
+
+    /// Record in a textual image file is malformed
+    #[error("invalid record on line {line}: {reason}")]
+    InvalidRecord {
+        /// Line number of the record, starting from 1
+        line: usize,
+        /// Description of the problem
+        reason: &'static str,
+    },
+
+    /// Checksum of a record does not match its content
+    #[error("invalid checksum on line {line}")]
+    InvalidChecksum {
+        /// Line number of the record, starting from 1
+        line: usize,
+    },
+
+    /// ELF file is malformed or not supported
+    #[error("invalid ELF file: {0}")]
+    InvalidElf(&'static str),
+
+    /// Segments cannot be written in the format
+    #[error("writing {0:?} files is not supported")]
+    UnsupportedOutput(ImageFormat),
+
+    /// Image file has no data to be joined into a single block
+    #[error("image file contains no data")]
+    Empty,
+
+    /// Segments are too far apart to be joined into a single block
+    #[error("segments span {span} bytes from address {start:#010X}, more than the limit of {MAX_SPAN} bytes")]
+    SpanTooLarge {
+        /// Address of the first segment
+        start: u32,
+        /// Bytes from the start of the first segment to the end of the last one
+        span: u64,
+    },
+}
+
+/// Bytes of data in records of written Intel HEX and S-record files
+const RECORD_SIZE: usize = 16;
+
+/// Largest block of data [`flatten`] joins segments into, larger than the memories of supported devices
+pub const MAX_SPAN: u64 = 256 * 1024 * 1024;
+
+/// Format of an image file
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+pub enum ImageFormat {
+    /// Raw binary data
+    Binary,
+    /// Intel HEX
+    #[cfg_attr(feature = "cli", value(name = "ihex"))]
+    IntelHex,
+    /// Motorola S-record
+    Srec,
+    /// Executable and Linkable Format
+    Elf,
+    /// Text with `@address` lines followed by hex bytes
+    AddressedText,
+}
+
+impl ImageFormat {
+    /// Detects the image format from the file extension
+    ///
+    /// Files with unknown or no extension are treated as [`ImageFormat::Binary`].
+    #[must_use]
+    pub fn from_path(path: &Path) -> Self {
+        let extension = path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .map(str::to_ascii_lowercase);
+        match extension.as_deref() {
+            Some("hex" | "ihex" | "ihx") => ImageFormat::IntelHex,
+            Some("srec" | "s19" | "s28" | "s37" | "mot") => ImageFormat::Srec,
+            Some("elf" | "axf" | "out") => ImageFormat::Elf,
+            Some("txt") => ImageFormat::AddressedText,
+            _ => ImageFormat::Binary,
+        }
+    }
+
+    /// Returns `true` if the format carries load addresses of its data
+    #[must_use]
+    pub fn has_addresses(self) -> bool {
+        !matches!(self, ImageFormat::Binary)
+    }
+}
+
+/// Continuous block of data located at a specific address
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Segment {
+    /// Address of the first byte
+    pub address: u32,
+    /// Data of the segment
+    pub data: Vec<u8>,
+}
+
+impl Segment {
+    /// Returns the address right after the last byte of this segment
+    #[must_use]
+    pub fn end_address(&self) -> u64 {
+        u64::from(self.address) + self.data.len() as u64
+    }
+}
+
+/// Parses image content in the specified format into segments
+///
+/// # Arguments
+/// * `content` - Content of the image file
+/// * `format` - Format of the content
+/// * `base_address` - Address of the data, used only for [`ImageFormat::Binary`]
+///
+/// # Errors
+/// Returns [`FormatError`] if the content is not valid for the given format.
+pub fn parse_segments(content: &[u8], format: ImageFormat, base_address: u32) -> Result<Vec<Segment>, FormatError> {
+    match format {
+        ImageFormat::Binary => Ok(vec![Segment {
+            address: base_address,
+            data: content.to_vec(),
+        }]),
+        ImageFormat::IntelHex => parse_intel_hex(&text_content(content)?),
+        ImageFormat::Srec => parse_srec(&text_content(content)?),
+        ImageFormat::Elf => parse_elf(content),
+        ImageFormat::AddressedText => parse_txt(&text_content(content)?),
+    }
+}
+
+/// Reads image file into segments, detecting the format from the file extension
+///
+/// # Arguments
+/// * `path` - Path to the image file
+/// * `base_address` - Address of the data for binary files
+///
+/// # Errors
+/// Returns [`FormatError`] if the file could not be read or parsed.
+pub fn read_segments(path: impl AsRef<Path>, base_address: u32) -> Result<Vec<Segment>, FormatError> {
+    let path = path.as_ref();
+    let content = fs::read(path)?;
+    parse_segments(&content, ImageFormat::from_path(path), base_address)
+}
+
+/// Writes segments in the specified format
+///
+/// Binary content is the segments joined by [`flatten`] with gaps filled with `0xFF`, as binary files
+/// cannot describe gaps.
+///
+/// # Errors
+/// [`FormatError::UnsupportedOutput`] for [`ImageFormat::Elf`] and [`ImageFormat::AddressedText`],
+/// [`FormatError::SpanTooLarge`] for binary files of segments too far apart.
+pub fn write_segments(segments: &[Segment], format: ImageFormat) -> Result<Vec<u8>, FormatError> {
+    match format {
+        ImageFormat::Binary => match flatten(segments, 0xFF) {
+            Ok((_, data)) => Ok(data),
+            Err(FormatError::Empty) => Ok(Vec::new()),
+            Err(error) => Err(error),
+        },
+        ImageFormat::IntelHex => Ok(write_intel_hex(segments).into_bytes()),
+        ImageFormat::Srec => Ok(write_srec(segments).into_bytes()),
+        ImageFormat::Elf | ImageFormat::AddressedText => Err(FormatError::UnsupportedOutput(format)),
+    }
+}
+
+/// Joins segments into a single continuous block of data
+///
+/// Gaps between the segments are filled with `fill` byte.
+///
+/// # Returns
+/// Address of the first byte and the joined data.
+///
+/// # Errors
+/// [`FormatError::Empty`] if there are no segments, [`FormatError::SpanTooLarge`] if the joined data would
+/// be larger than [`MAX_SPAN`].
+pub fn flatten(segments: &[Segment], fill: u8) -> Result<(u32, Vec<u8>), FormatError> {
+    let start = segments
+        .iter()
+        .map(|segment| segment.address)
+        .min()
+        .ok_or(FormatError::Empty)?;
+    let end = segments
+        .iter()
+        .map(Segment::end_address)
+        .max()
+        .ok_or(FormatError::Empty)?;
+    let span = end - u64::from(start);
+    if span > MAX_SPAN {
+        return Err(FormatError::SpanTooLarge { start, span });
+    }
+    let mut data = vec![fill; span as usize];
+    for segment in segments {
+        let offset = (segment.address - start) as usize;
+        data[offset..offset + segment.data.len()].copy_from_slice(&segment.data);
+    }
+    Ok((start, data))
+}
+
+/// Counts bytes of the segments inside the region of `byte_count` bytes at `start_address`
+///
+/// Segments are expected not to overlap, overlapping bytes are counted more than once.
+#[must_use]
+pub fn covered_bytes(segments: &[Segment], start_address: u32, byte_count: u32) -> u32 {
+    let start = u64::from(start_address);
+    let end = start + u64::from(byte_count);
+    let covered: u64 = segments
+        .iter()
+        .map(|segment| {
+            end.min(segment.end_address())
+                .saturating_sub(start.max(u64::from(segment.address)))
+        })
+        .sum();
+    covered.min(u64::from(byte_count)) as u32
+}
+
+/// Computes sector aligned regions covering all segments
+///
+/// Overlapping and adjacent regions are merged, so each sector is listed only once.
+///
+/// # Arguments
+/// * `segments` - Segments to cover
+/// * `sector_size` - Size of the erase sector in bytes, must not be zero
+///
+/// # Returns
+/// List of (`start_address`, `byte_count`) pairs sorted by address
+#[must_use]
+pub fn sector_regions(segments: &[Segment], sector_size: u32) -> Vec<(u32, u32)> {
+    let sector_size = u64::from(sector_size);
+    let mut ranges: Vec<(u64, u64)> = segments
+        .iter()
+        .filter(|segment| !segment.data.is_empty())
+        .map(|segment| {
+            let start = u64::from(segment.address) / sector_size * sector_size;
+            let end = segment.end_address().div_ceil(sector_size) * sector_size;
+            (start, end)
+        })
+        .collect();
+    ranges.sort_unstable();
+
+    let mut merged: Vec<(u64, u64)> = Vec::with_capacity(ranges.len());
+    for (start, end) in ranges {
+        match merged.last_mut() {
+            Some(last) if start <= last.1 => last.1 = last.1.max(end),
+            _ => merged.push((start, end)),
+        }
+    }
+    merged
+        .into_iter()
+        .map(|(start, end)| (start as u32, (end - start).min(u64::from(u32::MAX)) as u32))
+        .collect()
+}
+
+fn text_content(content: &[u8]) -> Result<String, FormatError> {
+    String::from_utf8(content.to_vec()).map_err(|_| FormatError::InvalidRecord {
+        line: 0,
+        reason: "file is not a text file",
+    })
+}
+
+/// Appends data to segments, extending the last segment if the data directly follow it
+fn push_data(segments: &mut Vec<Segment>, address: u32, data: &[u8]) {
+    match segments.last_mut() {
+        Some(last) if last.end_address() == u64::from(address) => last.data.extend_from_slice(data),
+        _ => segments.push(Segment {
+            address,
+            data: data.to_vec(),
+        }),
+    }
+}
+
+/// Appends bytes as upper case hexadecimal digits
+fn encode_hex(text: &mut String, bytes: &[u8]) {
+    for byte in bytes {
+        // writing into a String cannot fail
+        let _ = write!(text, "{byte:02X}");
+    }
+}
+
+/// Decodes hexadecimal record content into bytes
+fn decode_hex(line: usize, record: &str) -> Result<Vec<u8>, FormatError> {
+    if !record.len().is_multiple_of(2) {
+        return Err(FormatError::InvalidRecord {
+            line,
+            reason: "odd number of hex digits",
+        });
+    }
+    (0..record.len())
+        .step_by(2)
+        .map(|i| {
+            u8::from_str_radix(record.get(i..i + 2).unwrap_or_default(), 16).map_err(|_| FormatError::InvalidRecord {
+                line,
+                reason: "invalid hex digit",
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn flatten_fills_gaps() {
+        let segments = [
+            Segment {
+                address: 0x1004,
+                data: vec![3, 4],
+            },
+            Segment {
+                address: 0x1000,
+                data: vec![1, 2],
+            },
+        ];
+        assert_eq!(
+            flatten(&segments, 0xFF).unwrap(),
+            (0x1000, vec![1, 2, 0xFF, 0xFF, 3, 4])
+        );
+        assert!(matches!(flatten(&[], 0xFF), Err(FormatError::Empty)));
+    }
+
+    #[test]
+    fn flatten_rejects_distant_segments() {
+        let segments = [
+            Segment {
+                address: 0,
+                data: vec![1],
+            },
+            Segment {
+                address: 0xFFFF_0000,
+                data: vec![2],
+            },
+        ];
+        assert!(matches!(
+            flatten(&segments, 0xFF),
+            Err(FormatError::SpanTooLarge {
+                start: 0,
+                span: 0xFFFF_0001
+            })
+        ));
+    }
+
+    #[test]
+    fn sector_regions_are_aligned_and_merged() {
+        let segments = [
+            Segment {
+                address: 0x1010,
+                data: vec![0; 0x10],
+            },
+            Segment {
+                address: 0x1FF0,
+                data: vec![0; 0x20],
+            },
+            Segment {
+                address: 0x4000,
+                data: vec![0; 1],
+            },
+        ];
+        assert_eq!(sector_regions(&segments, 0x1000), [(0x1000, 0x2000), (0x4000, 0x1000)]);
+        assert_eq!(covered_bytes(&segments, 0x1000, 0x2000), 0x30);
+        assert_eq!(covered_bytes(&segments, 0x2000, 0x1000), 0x10);
+        assert_eq!(covered_bytes(&segments, 0x4000, 0x1000), 1);
+    }
+}
@@ -0,0 +1,222 @@
+// Copyright 2025 NXP
+//
+// SPDX-License-Identifier: BSD-3-Clause
+//! ELF loader
+//!
+//! Only the program headers are used, as they describe how the image is placed into memory.
+//! Loadable segments are placed at their physical (load) addresses, which differ from virtual
+//! addresses for data initialized from flash.
+
+use super::{FormatError, Segment};
+
+/// ELF magic number
+const ELF_MAGIC: &[u8; 4] = b"\x7FELF";
+/// Loadable program segment
+const PT_LOAD: u32 = 1;
+
+/// Reader of ELF fields respecting the class and byte order of the file
+struct ElfReader<'a> {
+    content: &'a [u8],
+    is_64bit: bool,
+    is_little_endian: bool,
+}
+
+impl ElfReader<'_> {
+    fn bytes<const N: usize>(&self, offset: u64) -> Result<[u8; N], FormatError> {
+        usize::try_from(offset)
+            .ok()
+            .and_then(|offset| self.content.get(offset..offset.checked_add(N)?))
+            .and_then(|bytes| bytes.try_into().ok())
+            .ok_or(FormatError::InvalidElf("file is truncated"))
+    }
+
+    fn u16(&self, offset: u64) -> Result<u16, FormatError> {
+        let bytes = self.bytes(offset)?;
+        Ok(if self.is_little_endian {
+            u16::from_le_bytes(bytes)
+        } else {
+            u16::from_be_bytes(bytes)
+        })
+    }
+
+    fn u32(&self, offset: u64) -> Result<u32, FormatError> {
+        let bytes = self.bytes(offset)?;
+        Ok(if self.is_little_endian {
+            u32::from_le_bytes(bytes)
+        } else {
+            u32::from_be_bytes(bytes)
+        })
+    }
+
+    /// Reads address or offset sized field, which is 4 bytes long in 32-bit and 8 bytes in 64-bit files
+    fn word(&self, offset: u64) -> Result<u64, FormatError> {
+        if !self.is_64bit {
+            return self.u32(offset).map(u64::from);
+        }
+        let bytes = self.bytes(offset)?;
+        Ok(if self.is_little_endian {
+            u64::from_le_bytes(bytes)
+        } else {
+            u64::from_be_bytes(bytes)
+        })
+    }
+}
+
+/// Parses ELF file content into segments
+///
+/// Every `PT_LOAD` program header with non-empty file content produces one segment located at its
+/// physical address. Memory-only parts of the segments (such as `.bss`) are not included.
+///
+/// # Errors
+/// Returns [`FormatError::InvalidElf`] if the file is not a valid ELF file or a segment does not fit
+/// into the 32-bit address space.
+pub fn parse_elf(content: &[u8]) -> Result<Vec<Segment>, FormatError> {
+    if content.len() < 16 || &content[..4] != ELF_MAGIC {
+        return Err(FormatError::InvalidElf("missing ELF magic number"));
+    }
+    let reader = ElfReader {
+        content,
+        is_64bit: match content[4] {
+            1 => false,
+            2 => true,
+            _ => return Err(FormatError::InvalidElf("unknown ELF class")),
+        },
+        is_little_endian: match content[5] {
+            1 => true,
+            2 => false,
+            _ => return Err(FormatError::InvalidElf("unknown byte order")),
+        },
+    };
+
+    let (phoff, phentsize, phnum) = if reader.is_64bit {
+        (reader.word(0x20)?, reader.u16(0x36)?, reader.u16(0x38)?)
+    } else {
+        (reader.word(0x1C)?, reader.u16(0x2A)?, reader.u16(0x2C)?)
+    };
+    // offsets of p_offset, p_paddr and p_filesz in the program header
+    let (offset_field, paddr_field, filesz_field) = if reader.is_64bit {
+        (0x08, 0x18, 0x20)
+    } else {
+        (0x04, 0x0C, 0x10)
+    };
+
+    let mut segments = Vec::new();
+    for index in 0..u64::from(phnum) {
+        let header = u64::from(phentsize)
+            .checked_mul(index)
+            .and_then(|offset| phoff.checked_add(offset))
+            .ok_or(FormatError::InvalidElf("program header is outside of the file"))?;
+        if reader.u32(header)? != PT_LOAD {
+            continue;
+        }
+        let offset = reader.word(field(header, offset_field)?)?;
+        let address = reader.word(field(header, paddr_field)?)?;
+        let size = reader.word(field(header, filesz_field)?)?;
+        if size == 0 {
+            continue;
+        }
+
+        let address = u32::try_from(address).map_err(|_| FormatError::InvalidElf("segment address exceeds 32 bits"))?;
+        if u64::from(address).checked_add(size).is_none_or(|end| end > 1 << 32) {
+            return Err(FormatError::InvalidElf("segment exceeds 32-bit address space"));
+        }
+        let data = usize::try_from(offset)
+            .ok()
+            .zip(usize::try_from(size).ok())
+            .and_then(|(offset, size)| content.get(offset..offset.checked_add(size)?))
+            .ok_or(FormatError::InvalidElf("segment data are outside of the file"))?;
+        segments.push(Segment {
+            address,
+            data: data.to_vec(),
+        });
+    }
+    segments.sort_by_key(|segment| segment.address);
+
+    Ok(segments)
+}
+
+/// Offset of the field at `offset` in the program header at `header`
+fn field(header: u64, offset: u64) -> Result<u64, FormatError> {
+    header
+        .checked_add(offset)
+        .ok_or(FormatError::InvalidElf("program header is outside of the file"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Builds a little-endian ELF32 file with the given (`p_type`, `p_paddr`, data) program headers
+    fn build_elf32(headers: &[(u32, u32, &[u8])]) -> Vec<u8> {
+        const EHDR_SIZE: usize = 0x34;
+        const PHDR_SIZE: usize = 0x20;
+        let mut elf = vec![0u8; EHDR_SIZE];
+        elf[..4].copy_from_slice(ELF_MAGIC);
+        elf[4] = 1;
+        elf[5] = 1;
+        elf[0x1C..0x20].copy_from_slice(&(EHDR_SIZE as u32).to_le_bytes());
+        elf[0x2A..0x2C].copy_from_slice(&(PHDR_SIZE as u16).to_le_bytes());
+        elf[0x2C..0x2E].copy_from_slice(&(headers.len() as u16).to_le_bytes());
+
+        let mut data_offset = EHDR_SIZE + PHDR_SIZE * headers.len();
+        let mut data = Vec::new();
+        for (p_type, paddr, content) in headers {
+            let mut phdr = vec![0u8; PHDR_SIZE];
+            phdr[..4].copy_from_slice(&p_type.to_le_bytes());
+            phdr[4..8].copy_from_slice(&(data_offset as u32).to_le_bytes());
+            phdr[8..12].copy_from_slice(&0x2000_0000u32.to_le_bytes());
+            phdr[12..16].copy_from_slice(&paddr.to_le_bytes());
+            phdr[16..20].copy_from_slice(&(content.len() as u32).to_le_bytes());
+            elf.extend(phdr);
+            data.extend_from_slice(content);
+            data_offset += content.len();
+        }
+        elf.extend(data);
+        elf
+    }
+
+    #[test]
+    fn load_segments_at_physical_address() {
+        let elf = build_elf32(&[(PT_LOAD, 0x1000, &[5, 6]), (4, 0, &[0xEE]), (PT_LOAD, 0x0, &[1, 2, 3])]);
+        assert_eq!(
+            parse_elf(&elf).unwrap(),
+            [
+                Segment {
+                    address: 0x0,
+                    data: vec![1, 2, 3]
+                },
+                Segment {
+                    address: 0x1000,
+                    data: vec![5, 6]
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn reject_truncated_file() {
+        let elf = build_elf32(&[(PT_LOAD, 0x1000, &[5, 6])]);
+        assert!(matches!(
+            parse_elf(&elf[..elf.len() - 1]),
+            Err(FormatError::InvalidElf(_))
+        ));
+    }
+
+    #[test]
+    fn reject_segment_size_overflowing_address() {
+        let mut elf = vec![0u8; 0x40 + 0x38];
+        elf[..4].copy_from_slice(ELF_MAGIC);
+        elf[4] = 2;
+        elf[5] = 1;
+        elf[0x20..0x28].copy_from_slice(&0x40u64.to_le_bytes());
+        elf[0x36..0x38].copy_from_slice(&0x38u16.to_le_bytes());
+        elf[0x38..0x3A].copy_from_slice(&1u16.to_le_bytes());
+        elf[0x40..0x44].copy_from_slice(&PT_LOAD.to_le_bytes());
+        elf[0x58..0x60].copy_from_slice(&0x1000u64.to_le_bytes());
+        elf[0x60..0x68].copy_from_slice(&u64::MAX.to_le_bytes());
+        assert!(matches!(
+            parse_elf(&elf),
+            Err(FormatError::InvalidElf("segment exceeds 32-bit address space"))
+        ));
+    }
+}
@@ -0,0 +1,181 @@
+// Copyright 2025 NXP
+//
+// SPDX-License-Identifier: BSD-3-Clause
+//! Intel HEX parser and writer
+
+use super::{FormatError, RECORD_SIZE, Segment, decode_hex, encode_hex, push_data};
+
+/// Data record
+const DATA: u8 = 0x00;
+/// End of file record
+const END_OF_FILE: u8 = 0x01;
+/// Extended segment address record, bits 4-19 of the address
+const EXTENDED_SEGMENT_ADDRESS: u8 = 0x02;
+/// Start segment address record, CS:IP of the entry point
+const START_SEGMENT_ADDRESS: u8 = 0x03;
+/// Extended linear address record, upper 16 bits of the address
+const EXTENDED_LINEAR_ADDRESS: u8 = 0x04;
+/// Start linear address record, entry point
+const START_LINEAR_ADDRESS: u8 = 0x05;
+
+/// Parses Intel HEX file content into segments
+///
+/// Consecutive data records are merged into a single segment. Start address records are ignored,
+/// parsing stops at the end of file record.
+///
+/// # Errors
+/// Returns [`FormatError`] if any record is malformed or has an invalid checksum.
+pub fn parse_intel_hex(text: &str) -> Result<Vec<Segment>, FormatError> {
+    let mut segments = Vec::new();
+    let mut base_address = 0u32;
+
+    for (index, line) in text.lines().enumerate() {
+        let line_number = index + 1;
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        let Some(record) = line.strip_prefix(':') else {
+            return Err(FormatError::InvalidRecord {
+                line: line_number,
+                reason: "record does not start with ':'",
+            });
+        };
+
+        let bytes = decode_hex(line_number, record)?;
+        if bytes.len() < 5 || bytes.len() != bytes[0] as usize + 5 {
+            return Err(FormatError::InvalidRecord {
+                line: line_number,
+                reason: "record length does not match its byte count",
+            });
+        }
+        if bytes.iter().fold(0u8, |acc, byte| acc.wrapping_add(*byte)) != 0 {
+            return Err(FormatError::InvalidChecksum { line: line_number });
+        }
+
+        let offset = u16::from_be_bytes([bytes[1], bytes[2]]);
+        let data = &bytes[4..bytes.len() - 1];
+        match bytes[3] {
+            DATA => push_data(&mut segments, base_address.wrapping_add(u32::from(offset)), data),
+            END_OF_FILE => break,
+            EXTENDED_SEGMENT_ADDRESS | EXTENDED_LINEAR_ADDRESS if data.len() == 2 => {
+                let value = u32::from(u16::from_be_bytes([data[0], data[1]]));
+                base_address = if bytes[3] == EXTENDED_SEGMENT_ADDRESS {
+                    value << 4
+                } else {
+                    value << 16
+                };
+            }
+            START_SEGMENT_ADDRESS | START_LINEAR_ADDRESS if data.len() == 4 => {}
+            _ => {
+                return Err(FormatError::InvalidRecord {
+                    line: line_number,
+                    reason: "unknown or malformed record type",
+                });
+            }
+        }
+    }
+
+    Ok(segments)
+}
+
+/// Formats segments as Intel HEX file content
+///
+/// Data records of up to 16 bytes never cross a 64 KiB boundary, an extended linear address record precedes
+/// them whenever the upper 16 bits of the address change. The content ends with the end of file record.
+#[must_use]
+pub fn write_intel_hex(segments: &[Segment]) -> String {
+    let mut text = String::new();
+    let mut upper_address = 0u16;
+    for segment in segments {
+        let mut address = segment.address;
+        let mut data = segment.data.as_slice();
+        while !data.is_empty() {
+            let upper = (address >> 16) as u16;
+            if upper != upper_address {
+                push_record(&mut text, 0, EXTENDED_LINEAR_ADDRESS, &upper.to_be_bytes());
+                upper_address = upper;
+            }
+            let to_boundary = 0x1_0000 - (address & 0xFFFF) as usize;
+            let (record, rest) = data.split_at(data.len().min(RECORD_SIZE).min(to_boundary));
+            push_record(&mut text, address as u16, DATA, record);
+            address = address.wrapping_add(record.len() as u32);
+            data = rest;
+        }
+    }
+    push_record(&mut text, 0, END_OF_FILE, &[]);
+    text
+}
+
+/// Appends a record with its byte count and checksum
+fn push_record(text: &mut String, offset: u16, record_type: u8, data: &[u8]) {
+    let mut bytes = vec![data.len() as u8];
+    bytes.extend(offset.to_be_bytes());
+    bytes.push(record_type);
+    bytes.extend(data);
+    bytes.push(
+        bytes
+            .iter()
+            .fold(0u8, |acc, byte| acc.wrapping_add(*byte))
+            .wrapping_neg(),
+    );
+    text.push(':');
+    encode_hex(text, &bytes);
+    text.push('\n');
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_linear_address_records() {
+        let text = "\
+            :020000040800F2\n\
+            :0400000001020304F2\n\
+            :0400040005060708DE\n\
+            :0400100009000000E3\n\
+            :04000005080001C12D\n\
+            :00000001FF\n";
+        let segments = parse_intel_hex(text).unwrap();
+        assert_eq!(
+            segments,
+            [
+                Segment {
+                    address: 0x0800_0000,
+                    data: vec![1, 2, 3, 4, 5, 6, 7, 8]
+                },
+                Segment {
+                    address: 0x0800_0010,
+                    data: vec![9, 0, 0, 0]
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn written_records_are_parsed_back() {
+        let segments = [
+            Segment {
+                address: 0x0800_FFF8,
+                data: (0..24).collect(),
+            },
+            Segment {
+                address: 0x2000_0000,
+                data: vec![0xAA, 0xBB],
+            },
+        ];
+        let text = write_intel_hex(&segments);
+        assert!(text.starts_with(":020000040800F2\n:08FFF800"));
+        assert!(text.ends_with(":00000001FF\n"));
+        assert_eq!(parse_intel_hex(&text).unwrap(), segments);
+    }
+
+    #[test]
+    fn reject_invalid_checksum() {
+        assert!(matches!(
+            parse_intel_hex(":0400000001020304F3\n"),
+            Err(FormatError::InvalidChecksum { line: 1 })
+        ));
+    }
+}
@@ -0,0 +1,160 @@
+// Copyright 2025 NXP
+//
+// SPDX-License-Identifier: BSD-3-Clause
+//! Motorola S-record parser and writer
+
+use super::{FormatError, RECORD_SIZE, Segment, decode_hex, encode_hex, push_data};
+
+/// Parses Motorola S-record file content into segments
+///
+/// Consecutive data records (S1, S2 and S3) are merged into a single segment. Header (S0),
+/// count (S5, S6) and termination (S7, S8, S9) records are ignored.
+///
+/// # Errors
+/// Returns [`FormatError`] if any record is malformed or has an invalid checksum.
+pub fn parse_srec(text: &str) -> Result<Vec<Segment>, FormatError> {
+    let mut segments = Vec::new();
+
+    for (index, line) in text.lines().enumerate() {
+        let line_number = index + 1;
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        let invalid = |reason| FormatError::InvalidRecord {
+            line: line_number,
+            reason,
+        };
+
+        let mut chars = line.chars();
+        if chars.next() != Some('S') {
+            return Err(invalid("record does not start with 'S'"));
+        }
+        let record_type = chars.next().ok_or(invalid("missing record type"))?;
+        let bytes = decode_hex(line_number, chars.as_str())?;
+        if bytes.is_empty() || bytes.len() != bytes[0] as usize + 1 {
+            return Err(invalid("record length does not match its byte count"));
+        }
+        if bytes.iter().fold(0u8, |acc, byte| acc.wrapping_add(*byte)) != 0xFF {
+            return Err(FormatError::InvalidChecksum { line: line_number });
+        }
+
+        let address_len = match record_type {
+            '1' => 2,
+            '2' => 3,
+            '3' => 4,
+            '0' | '5'..='9' => continue,
+            _ => return Err(invalid("unknown record type")),
+        };
+        if bytes.len() < address_len + 2 {
+            return Err(invalid("record is too short for its address"));
+        }
+        let address = bytes[1..=address_len]
+            .iter()
+            .fold(0u32, |acc, byte| (acc << 8) | u32::from(*byte));
+        push_data(&mut segments, address, &bytes[address_len + 1..bytes.len() - 1]);
+    }
+
+    Ok(segments)
+}
+
+/// Formats segments as Motorola S-record file content
+///
+/// Data records of up to 16 bytes use the shortest address field fitting all segments (S1, S2 or S3). They
+/// are preceded by an empty header record and followed by a count record, if the count fits into 16 bits,
+/// and by the matching termination record with address zero.
+#[must_use]
+pub fn write_srec(segments: &[Segment]) -> String {
+    let end_address = segments.iter().map(Segment::end_address).max().unwrap_or_default();
+    let (data_type, termination_type, address_len) = match end_address {
+        0..=0x1_0000 => ('1', '9', 2),
+        0x1_0001..=0x100_0000 => ('2', '8', 3),
+        _ => ('3', '7', 4),
+    };
+    let mut text = String::new();
+    push_record(&mut text, '0', &[0, 0], &[]);
+    let mut count = 0usize;
+    for segment in segments {
+        let mut address = segment.address;
+        for record in segment.data.chunks(RECORD_SIZE) {
+            push_record(&mut text, data_type, &address.to_be_bytes()[4 - address_len..], record);
+            address = address.wrapping_add(record.len() as u32);
+            count += 1;
+        }
+    }
+    if let Ok(count) = u16::try_from(count) {
+        push_record(&mut text, '5', &count.to_be_bytes(), &[]);
+    }
+    push_record(&mut text, termination_type, &[0; 4][4 - address_len..], &[]);
+    text
+}
+
+/// Appends a record with its byte count and checksum
+fn push_record(text: &mut String, record_type: char, address: &[u8], data: &[u8]) {
+    let mut bytes = vec![(address.len() + data.len() + 1) as u8];
+    bytes.extend(address);
+    bytes.extend(data);
+    bytes.push(!bytes.iter().fold(0u8, |acc, byte| acc.wrapping_add(*byte)));
+    text.push('S');
+    text.push(record_type);
+    encode_hex(text, &bytes);
+    text.push('\n');
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_data_records() {
+        let text = "\
+            S00600004844521B\n\
+            S107100001020304DE\n\
+            S107100405060708CA\n\
+            S30920000000AABBCCDDC8\n\
+            S9031000EC\n";
+        let segments = parse_srec(text).unwrap();
+        assert_eq!(
+            segments,
+            [
+                Segment {
+                    address: 0x1000,
+                    data: vec![1, 2, 3, 4, 5, 6, 7, 8]
+                },
+                Segment {
+                    address: 0x2000_0000,
+                    data: vec![0xAA, 0xBB, 0xCC, 0xDD]
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn written_records_are_parsed_back() {
+        let segments = [Segment {
+            address: 0x1000,
+            data: vec![1, 2, 3, 4, 5, 6, 7, 8],
+        }];
+        assert_eq!(
+            write_srec(&segments),
+            "S0030000FC\nS10B10000102030405060708C0\nS5030001FB\nS9030000FC\n"
+        );
+
+        let segments = [Segment {
+            address: 0x2000_0000,
+            data: (0..40).collect(),
+        }];
+        let text = write_srec(&segments);
+        assert!(text.contains("S3152000000000"));
+        assert!(text.ends_with("S70500000000FA\n"));
+        assert_eq!(parse_srec(&text).unwrap(), segments);
+    }
+
+    #[test]
+    fn reject_invalid_checksum() {
+        assert!(matches!(
+            parse_srec("S107100001020304DF\n"),
+            Err(FormatError::InvalidChecksum { line: 1 })
+        ));
+    }
+}
@@ -0,0 +1,116 @@
+// Copyright 2025 NXP
+//
+// SPDX-License-Identifier: BSD-3-Clause
+//! Addressed hex text parser
+//!
+//! The format consists of `@address` lines followed by lines of hex bytes, which are placed at
+//! consecutive addresses starting at the last address line:
+//!
+//! ```text
+//! @00001000
+//! 01 02 03 04 05 06 07 08
+//! @0x20000000
+//! AA BB CC DD
+//! q
+//! ```
+
+use super::{FormatError, Segment, decode_hex, push_data};
+
+/// Parses addressed hex text content into segments
+///
+/// Addresses are hexadecimal, optionally with `0x` prefix. Bytes are separated by whitespace, a group of
+/// hex digits without spaces is read as consecutive bytes. Empty lines and lines starting with `#` are
+/// ignored, a line with `q` ends the data.
+///
+/// # Errors
+/// Returns [`FormatError`] if an address or byte is malformed, or if data precede the first address.
+pub fn parse_txt(text: &str) -> Result<Vec<Segment>, FormatError> {
+    let mut segments = Vec::new();
+    let mut address: Option<u64> = None;
+
+    for (index, line) in text.lines().enumerate() {
+        let line_number = index + 1;
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let invalid = |reason| FormatError::InvalidRecord {
+            line: line_number,
+            reason,
+        };
+        if line.eq_ignore_ascii_case("q") {
+            break;
+        }
+
+        if let Some(value) = line.strip_prefix('@') {
+            let value = value.trim();
+            let value = value
+                .strip_prefix("0x")
+                .or_else(|| value.strip_prefix("0X"))
+                .unwrap_or(value);
+            let value = u32::from_str_radix(value, 16).map_err(|_| invalid("invalid address"))?;
+            address = Some(u64::from(value));
+            continue;
+        }
+
+        let current = address.ok_or(invalid("data before the first address line"))?;
+        let mut data = Vec::new();
+        for token in line.split_whitespace() {
+            data.extend(decode_hex(line_number, token)?);
+        }
+        let end = current + data.len() as u64;
+        if end > u64::from(u32::MAX) + 1 {
+            return Err(invalid("data exceed the 32-bit address space"));
+        }
+        push_data(&mut segments, current as u32, &data);
+        address = Some(end);
+    }
+
+    Ok(segments)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_addressed_data() {
+        let text = "\
+            @00001000\n\
+            01 02 03 04\n\
+            0506 07 08\n\
+            \n\
+            @0x20000000\n\
+            AA bb\n\
+            q\n\
+            @3000\n\
+            FF\n";
+        let segments = parse_txt(text).unwrap();
+        assert_eq!(
+            segments,
+            [
+                Segment {
+                    address: 0x1000,
+                    data: vec![1, 2, 3, 4, 5, 6, 7, 8]
+                },
+                Segment {
+                    address: 0x2000_0000,
+                    data: vec![0xAA, 0xBB]
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn reject_invalid_content() {
+        assert!(matches!(
+            parse_txt("01 02\n"),
+            Err(FormatError::InvalidRecord { line: 1, .. })
+        ));
+        assert!(matches!(
+            parse_txt("@1000\n01 0G\n"),
+            Err(FormatError::InvalidRecord { line: 2, .. })
+        ));
+        assert!(parse_txt("@xyz\n").is_err());
+    }
+}
@@ -1,11 +1,35 @@
 // Copyright 2025 NXP
 //
 // SPDX-License-Identifier: BSD-3-Clause
+//! Display Helpers
+//!
+//! Wrapper types implementing [`Display`] for values shown to the user, such as byte sizes and
+//! differences between memory contents.
+
 use std::fmt::{Debug, Display};
 
 use log::error;
 use num_traits::ToPrimitive;
 use number_prefix::NumberPrefix;
+use style::{HIGHLIGHT, RESET};
+
+/// ANSI styled labels of log messages and highlighting of values
+///
+/// Plain escape sequences are used, so the library does not depend on terminal styling crates.
+pub(crate) mod style {
+    /// Label of data received from the device
+    pub const RX: &str = "\x1b[91mRX\x1b[0m";
+    /// Label of data sent to the device
+    pub const TX: &str = "\x1b[92mTX\x1b[0m";
+    /// Label of a sent command
+    pub const SENDING: &str = "\x1b[1mSending\x1b[0m";
+    /// Label of a received response
+    pub const RECEIVED: &str = "\x1b[1mReceived\x1b[0m";
+    /// Start of a highlighted value
+    pub const HIGHLIGHT: &str = "\x1b[91m";
+    /// End of a highlighted value
+    pub const RESET: &str = "\x1b[0m";
+}
 
 /// Implements Display to print a number as bytes with IEC binary prefix.
 ///
@@ -42,3 +66,130 @@ impl Display for OnOffBool {
         f.write_str(if self.0 { "ON" } else { "OFF" })
     }
 }
+
+/// Implements Display, prints side-by-side hexdump of the first window where two buffers differ.
+///
+/// The window is [`HexDiff::WINDOW`] bytes long and starts at the row containing the first
+/// mismatch. Differing bytes are highlighted, bytes missing in one of the buffers are shown as `--`.
+pub struct HexDiff<'a> {
+    /// Address of the first byte of both buffers
+    pub address: u32,
+    /// Data which were expected
+    pub expected: &'a [u8],
+    /// Data which were actually read
+    pub actual: &'a [u8],
+}
+
+impl<'a> HexDiff<'a> {
+    /// Number of bytes displayed by the diff
+    pub const WINDOW: usize = 64;
+    const ROW: usize = 16;
+
+    /// Returns the diff of the displayed window only, [`None`] if the buffers are equal
+    ///
+    /// Keeps the difference of large buffers displayable after the buffers are dropped.
+    #[must_use]
+    pub fn window(&self) -> Option<HexDiff<'a>> {
+        let start = self.first_mismatch()? / Self::ROW * Self::ROW;
+        let cut = |bytes: &'a [u8]| &bytes[start.min(bytes.len())..(start + Self::WINDOW).min(bytes.len())];
+        Some(HexDiff {
+            // the mismatch lies within the buffers starting at the address
+            address: self.address.wrapping_add(start as u32),
+            expected: cut(self.expected),
+            actual: cut(self.actual),
+        })
+    }
+
+    /// Returns offset of the first differing byte, [`None`] if the buffers are equal
+    #[must_use]
+    pub fn first_mismatch(&self) -> Option<usize> {
+        let common = self.expected.len().min(self.actual.len());
+        (0..common)
+            .find(|&i| self.expected[i] != self.actual[i])
+            .or((self.expected.len() != self.actual.len()).then_some(common))
+    }
+
+    fn write_row(f: &mut std::fmt::Formatter<'_>, bytes: &[u8], other: &[u8], row: usize) -> std::fmt::Result {
+        for i in row..row + Self::ROW {
+            match (bytes.get(i), other.get(i)) {
+                (Some(byte), Some(other)) if byte == other => write!(f, "{byte:02X} ")?,
+                (Some(byte), _) => write!(f, "{HIGHLIGHT}{byte:02X}{RESET} ")?,
+                (None, _) => write!(f, "-- ")?,
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Display for HexDiff<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let Some(mismatch) = self.first_mismatch() else {
+            return writeln!(f, "no differences");
+        };
+        let start = mismatch / Self::ROW * Self::ROW;
+        let end = (start + Self::WINDOW).min(self.expected.len().max(self.actual.len()));
+
+        writeln!(f, "{:<10}  {:<48}| Actual", "Address", "Expected")?;
+        for row in (start..end).step_by(Self::ROW) {
+            write!(f, "{:#010X}  ", u64::from(self.address) + row as u64)?;
+            Self::write_row(f, self.expected, self.actual, row)?;
+            f.write_str("| ")?;
+            Self::write_row(f, self.actual, self.expected, row)?;
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::HexDiff;
+
+    #[test]
+    fn first_mismatch() {
+        let diff = |expected: &'static [u8], actual: &'static [u8]| HexDiff {
+            address: 0,
+            expected,
+            actual,
+        };
+        assert_eq!(diff(&[1, 2, 3], &[1, 2, 3]).first_mismatch(), None);
+        assert_eq!(diff(&[1, 2, 3], &[1, 0, 3]).first_mismatch(), Some(1));
+        assert_eq!(diff(&[1, 2, 3], &[1, 2]).first_mismatch(), Some(2));
+    }
+
+    #[test]
+    fn window() {
+        let expected: Vec<u8> = (0..=255).collect();
+        let mut actual = expected.clone();
+        actual[100] = 0;
+        let diff = HexDiff {
+            address: 0x1000,
+            expected: &expected,
+            actual: &actual,
+        };
+        let window = diff.window().unwrap();
+        assert_eq!(window.address, 0x1060);
+        assert_eq!(window.expected, &expected[0x60..0xA0]);
+        assert_eq!(window.actual, &actual[0x60..0xA0]);
+        assert_eq!(window.first_mismatch(), Some(4));
+        assert_eq!(window.to_string(), diff.to_string());
+
+        let short = HexDiff {
+            actual: &actual[..250],
+            ..diff
+        };
+        let window = short.window().unwrap();
+        assert_eq!(
+            (window.address, window.expected.len(), window.actual.len()),
+            (0x1060, 64, 64)
+        );
+        assert!(
+            HexDiff {
+                actual: &expected,
+                ..diff
+            }
+            .window()
+            .is_none()
+        );
+    }
+}
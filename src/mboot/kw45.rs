@@ -0,0 +1,104 @@
+// Copyright 2025 NXP
+//
+// SPDX-License-Identifier: BSD-3-Clause
+//! KW45/K32W1 Radio Firmware Loading
+//!
+//! The NBU (narrowband unit) firmware of KW45 and K32W1 devices is updated by sending a signed SB3.1
+//! file with [`McuBoot::receive_sb_file`]. [`load_nbu`] checks the file and the device before sending
+//! it with [`super::sb`], and [`status_hint`] explains the statuses the ROM reports when the update is rejected.
+
+use log::info;
+
+use super::{
+    McuBoot, ResultStatus,
+    protocols::Protocol,
+    sb::{self, SbHeader, SbKind},
+};
+use crate::{CommunicationError, tags::status::StatusCode};
+
+/// Checks that `bytes` contain an SB3.1 file
+///
+/// # Errors
+///
+/// Returns [`CommunicationError::ParseError`] if the file does not start with a valid SB3.1 header.
+pub fn check_sb3(bytes: &[u8]) -> Result<SbHeader, CommunicationError> {
+    match SbHeader::parse(bytes) {
+        Ok(header) if header.kind == SbKind::Sb3 => Ok(header),
+        Ok(header) => Err(CommunicationError::ParseError(format!(
+            "NBU firmware must be an SB3.1 file, found {header}"
+        ))),
+        Err(CommunicationError::ParseError(reason)) => Err(CommunicationError::ParseError(format!(
+            "NBU firmware must be an SB3.1 file, {reason}"
+        ))),
+        Err(err) => Err(err),
+    }
+}
+
+/// Loads NBU firmware from an SB3.1 file
+///
+/// Before sending the file, its header is checked with [`check_sb3`] and the device is checked with
+/// [`sb::check_device`].
+///
+/// # Arguments
+///
+/// * `boot` - Connected device
+/// * `sb_file` - Content of the SB3.1 file with the NBU firmware
+///
+/// # Returns
+///
+/// Status of the receive SB file command
+///
+/// # Errors
+///
+/// [`CommunicationError::ParseError`] if the file or the device is not suitable, otherwise any
+/// [`CommunicationError`] of [`McuBoot::receive_sb_file`]. Use [`status_hint`] to explain
+/// [`CommunicationError::UnexpectedStatus`].
+pub fn load_nbu<T: Protocol>(boot: &mut McuBoot<T>, sb_file: &[u8]) -> ResultStatus {
+    let header = check_sb3(sb_file)?;
+    sb::check_device(boot, &header)?;
+
+    info!("Sending NBU firmware, {} bytes", sb_file.len());
+    boot.receive_sb_file(sb_file)
+}
+
+/// Explains why the ROM rejected the NBU firmware
+///
+/// # Returns
+///
+/// Hint for statuses with a known cause when loading the NBU firmware, [`None`] otherwise
+#[must_use]
+pub fn status_hint(status: StatusCode) -> Option<&'static str> {
+    Some(match status {
+        StatusCode::RomldrSignature => "SB file is not signed by a key trusted by this device",
+        StatusCode::RomldrKeyNotFound | StatusCode::RomldrBadSbkek => {
+            "SB file is encrypted with a different key than the one provisioned in the device"
+        }
+        StatusCode::RomldrRollbackBlocked => "NBU firmware is older than the firmware already installed",
+        StatusCode::RomldrSectionLength | StatusCode::RomldrDataUnderrun | StatusCode::RomldrEofReached => {
+            "SB file is truncated or corrupted"
+        }
+        StatusCode::RomldrUnexpectedCommand | StatusCode::RomldrUnknownCommand => {
+            "SB file contains commands not supported by the device, is it built for KW45/K32W1?"
+        }
+        StatusCode::SecurityViolation => "lifecycle of the device does not allow loading NBU firmware",
+        StatusCode::MemoryRangeInvalid | StatusCode::MemoryWriteProtected => {
+            "SB file targets memory outside of the NBU flash"
+        }
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sb3_header() {
+        let mut header = [0; 60];
+        header[..8].copy_from_slice(b"sbv3\x01\x00\x03\x00");
+        assert!(check_sb3(&header).is_ok());
+        header[..8].copy_from_slice(b"sbv3\x00\x00\x02\x00");
+        assert!(check_sb3(&header).is_err());
+        assert!(check_sb3(b"STMP").is_err());
+    }
+}
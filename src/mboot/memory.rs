@@ -12,7 +12,7 @@
 //! This information is essential for understanding the target device's memory map and
 //! constraints when performing memory operations.
 
-use std::fmt::Display;
+use std::{fmt::Display, ops::Range};
 
 use super::formatters::BinaryBytesOne;
 
@@ -63,6 +63,112 @@ pub mod mem_id {
     pub const SD_CARD: u32 = 288;
     /// MMC/eMMC Memory Card
     pub const MMC_CARD: u32 = 289;
+
+    /// External memories with their names, attributes of these can be queried from the device
+    pub const EXTERNAL: [(u32, &str); 10] = [
+        (QUAD_SPI0, "QuadSPI NOR"),
+        (SEMC_NOR, "SEMC NOR"),
+        (FLEX_SPI_NOR, "FlexSPI NOR"),
+        (SPIFI_NOR, "SPIFI NOR"),
+        (SEMC_NAND, "SEMC NAND"),
+        (SPI_NAND, "SPI NAND"),
+        (SPI_NOR_EEPROM, "SPI NOR/EEPROM"),
+        (I2C_NOR_EEPROM, "I2C NOR/EEPROM"),
+        (SD_CARD, "SD Card"),
+        (MMC_CARD, "MMC Card"),
+    ];
+}
+
+/// Memory reported by the device, as listed by [`McuBoot::list_memory`][`super::McuBoot::list_memory`]
+#[derive(Clone, Copy, Debug)]
+pub enum MemoryInfo {
+    /// Internal flash memory
+    InternalFlash {
+        /// Start address of the flash
+        start_address: u32,
+        /// Size of the flash in bytes
+        size: u32,
+        /// Size of the erasable sector, if reported
+        sector_size: Option<u32>,
+    },
+    /// Internal RAM
+    InternalRam {
+        /// Start address of the RAM
+        start_address: u32,
+        /// Size of the RAM in bytes
+        size: u32,
+    },
+    /// Configured external memory
+    External {
+        /// Memory ID, one of [`mem_id::EXTERNAL`]
+        memory_id: u32,
+        /// Name of the memory
+        name: &'static str,
+        /// Attributes reported by the device
+        attributes: ExternalMemoryAttributes,
+    },
+}
+
+/// Width of the pattern repeated by the fill memory command
+///
+/// The command always carries a 32-bit pattern, narrower patterns are repeated to fill the word.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+pub enum PatternSize {
+    /// 8-bit pattern
+    #[cfg_attr(feature = "cli", value(name = "1"))]
+    Byte = 1,
+    /// 16-bit pattern
+    #[cfg_attr(feature = "cli", value(name = "2"))]
+    Short = 2,
+    /// 32-bit pattern
+    #[cfg_attr(feature = "cli", value(name = "4"))]
+    Word = 4,
+}
+
+impl PatternSize {
+    /// Repeats `pattern` to fill the 32-bit pattern of the command
+    ///
+    /// # Errors
+    /// Text describing the problem if `pattern` does not fit into the width.
+    ///
+    /// # Examples
+    /// ```
+    /// use mboot::memory::PatternSize;
+    ///
+    /// assert_eq!(PatternSize::Byte.expand(0xA5), Ok(0xA5A5_A5A5));
+    /// assert_eq!(PatternSize::Short.expand(0x1234), Ok(0x1234_1234));
+    /// assert!(PatternSize::Short.expand(0x1_0000).is_err());
+    /// ```
+    pub fn expand(self, pattern: u32) -> Result<u32, String> {
+        let too_wide = || format!("pattern {pattern:#X} does not fit into {} byte(s)", self as u8);
+        match self {
+            PatternSize::Byte => u8::try_from(pattern)
+                .map(|byte| u32::from_le_bytes([byte; 4]))
+                .map_err(|_| too_wide()),
+            PatternSize::Short => u16::try_from(pattern)
+                .map(|short| u32::from(short) * 0x1_0001)
+                .map_err(|_| too_wide()),
+            PatternSize::Word => Ok(pattern),
+        }
+    }
+
+    /// Checks that the filled region starts and ends on a multiple of the pattern width
+    ///
+    /// # Errors
+    /// Text describing the misaligned value.
+    pub fn check_alignment(self, start_address: u32, byte_count: u32) -> Result<(), String> {
+        let width = self as u32;
+        if !start_address.is_multiple_of(width) {
+            return Err(format!(
+                "start address {start_address:#X} is not aligned to {width} byte(s)"
+            ));
+        }
+        if !byte_count.is_multiple_of(width) {
+            return Err(format!("byte count {byte_count} is not a multiple of {width} byte(s)"));
+        }
+        Ok(())
+    }
 }
 
 /// Reserved memory regions information
@@ -111,10 +217,38 @@ impl ReservedRegions {
     /// Panics if the data array length is odd, as this indicates malformed data
     #[must_use]
     pub fn parse(data: &[u32]) -> Self {
-        assert!(data.len() % 2 == 0, "reserved regions value is not odd");
+        assert!(data.len().is_multiple_of(2), "reserved regions value is not odd");
         let regions = data.chunks(2).map(|region| (region[0], region[1])).collect();
         ReservedRegions { regions }
     }
+
+    /// (`start_address`, `end_address`) pairs of the regions, end addresses are inclusive
+    #[must_use]
+    pub fn regions(&self) -> &[(u32, u32)] {
+        &self.regions
+    }
+}
+
+/// Returns the parts of `range` outside of the `reserved` regions, sorted by address
+///
+/// `reserved` are (`start_address`, `end_address`) pairs with inclusive end addresses, as in
+/// [`ReservedRegions::regions`].
+pub(crate) fn without_reserved(range: Range<u64>, reserved: &[(u32, u32)]) -> Vec<Range<u64>> {
+    let mut ranges: Vec<_> = std::iter::once(range).filter(|range| !range.is_empty()).collect();
+    for &(start, end) in reserved {
+        let reserved = u64::from(start)..u64::from(end) + 1;
+        ranges = ranges
+            .into_iter()
+            .flat_map(|range| {
+                [
+                    range.start..range.end.min(reserved.start),
+                    range.start.max(reserved.end)..range.end,
+                ]
+            })
+            .filter(|range| !range.is_empty())
+            .collect();
+    }
+    ranges
 }
 
 /// External memory attributes information
@@ -193,6 +327,36 @@ impl ExternalMemoryAttributes {
             block_size,
         }
     }
+
+    /// Starting address of the memory
+    #[must_use]
+    pub fn start_address(&self) -> Option<u32> {
+        self.start_address
+    }
+
+    /// Total size of the memory, in bytes
+    #[must_use]
+    pub fn total_size(&self) -> Option<u64> {
+        self.total_size.map(|size| u64::from(size) * 1024)
+    }
+
+    /// Page size for programming operations
+    #[must_use]
+    pub fn page_size(&self) -> Option<u32> {
+        self.page_size
+    }
+
+    /// Sector size for erase operations
+    #[must_use]
+    pub fn sector_size(&self) -> Option<u32> {
+        self.sector_size
+    }
+
+    /// Block size for bulk operations
+    #[must_use]
+    pub fn block_size(&self) -> Option<u32> {
+        self.block_size
+    }
 }
 
 impl Display for ExternalMemoryAttributes {
@@ -0,0 +1,409 @@
+// Copyright 2025 NXP
+//
+// SPDX-License-Identifier: BSD-3-Clause
+//! Object Safe McuBoot Interface
+//!
+//! [`McuBoot`] is generic over its [`Protocol`], which makes it hard to store in applications choosing
+//! the protocol at runtime. [`McuBootOps`] exposes the same commands as an object safe trait, so
+//! `Box<dyn McuBootOps>` can be used instead, without generics or `enum_dispatch`.
+
+use std::io::Write;
+
+use super::{
+    CommandOutcome, GetPropertyResponse, KeyProvisioningResponse, McuBoot, ReadMemoryResponse, ResultComm,
+    ResultStatus, TransferOptions, capabilities::DeviceCapabilities, checksum::ChecksumAlgorithm, formats::Segment,
+    memory::MemoryInfo, progress::ProgressCallback, protocols::Protocol, session::SessionConfig, warning::Warning,
+};
+use crate::tags::{
+    command::{KeyProvOperation, TrustProvOperation},
+    property::PropertyTagDiscriminants,
+    status::StatusCode,
+};
+
+/// Commands of [`McuBoot`] usable through a trait object
+///
+/// Each method behaves exactly as the [`McuBoot`] method with the same name, refer to it for details.
+///
+/// # Example
+///
+/// ```no_run
+/// use mboot::{
+///     McuBoot, McuBootOps,
+///     protocols::{ProtocolOpen, uart::UARTProtocol},
+/// };
+///
+/// let mut boot: Box<dyn McuBootOps> = Box::new(McuBoot::new(UARTProtocol::open("/dev/ttyUSB0")?));
+/// boot.reset()?;
+/// # Ok::<(), mboot::CommunicationError>(())
+/// ```
+#[expect(clippy::missing_errors_doc, reason = "errors are documented on McuBoot methods")]
+pub trait McuBootOps {
+    /// See [`McuBoot::last_outcome`]
+    fn last_outcome(&self) -> Option<CommandOutcome>;
+    /// See [`McuBoot::take_last_outcome`]
+    fn take_last_outcome(&mut self) -> Option<CommandOutcome>;
+    /// See [`McuBoot::retries`]
+    fn retries(&self) -> u32;
+    /// See [`McuBoot::take_warnings`]
+    fn take_warnings(&mut self) -> Vec<Warning>;
+    /// See [`McuBoot::config`]
+    fn config(&self) -> &SessionConfig;
+    /// See [`McuBoot::config_mut`]
+    fn config_mut(&mut self) -> &mut SessionConfig;
+    /// See [`McuBoot::set_transfer_options`]
+    #[deprecated(note = "set `transfer` of `McuBootOps::config_mut` instead")]
+    fn set_transfer_options(&mut self, options: TransferOptions);
+    /// See [`McuBoot::acked_bytes`]
+    fn acked_bytes(&self) -> u64;
+    /// See [`McuBoot::set_progress_callback`]
+    fn set_progress_callback(&mut self, callback: ProgressCallback);
+    /// See [`McuBoot::clear_progress_callback`]
+    fn clear_progress_callback(&mut self);
+    /// See [`McuBoot::get_property`]
+    fn get_property(&mut self, tag: PropertyTagDiscriminants, memory_index: u32) -> ResultComm<GetPropertyResponse>;
+    /// See [`McuBoot::set_property`]
+    fn set_property(&mut self, tag: PropertyTagDiscriminants, value: u32) -> ResultStatus;
+    /// See [`McuBoot::reset`]
+    fn reset(&mut self) -> ResultStatus;
+    /// See [`McuBoot::call`]
+    fn call(&mut self, start_address: u32, argument: u32) -> ResultStatus;
+    /// See [`McuBoot::execute`]
+    fn execute(&mut self, start_address: u32, argument: u32, stackpointer: u32) -> ResultStatus;
+    /// See [`McuBoot::execute_with_args`]
+    fn execute_with_args(
+        &mut self,
+        start_address: u32,
+        args: &[u8],
+        args_address: Option<u32>,
+        stackpointer: u32,
+    ) -> ResultStatus;
+    /// See [`McuBoot::fill_memory`]
+    fn fill_memory(&mut self, start_address: u32, byte_count: u32, pattern: u32) -> ResultStatus;
+    /// See [`McuBoot::write_memory`]
+    fn write_memory(&mut self, start_address: u32, memory_id: u32, bytes: &[u8]) -> ResultStatus;
+    /// See [`McuBoot::write_memory_verified`]
+    fn write_memory_verified(&mut self, start_address: u32, memory_id: u32, bytes: &[u8]) -> ResultStatus;
+    /// See [`McuBoot::verify_memory`]
+    fn verify_memory(&mut self, start_address: u32, memory_id: u32, bytes: &[u8]) -> ResultComm<u64>;
+    /// See [`McuBoot::blank_check`]
+    fn blank_check(&mut self, start_address: u32, byte_count: u32, memory_id: u32) -> ResultComm<Option<u32>>;
+    /// See [`McuBoot::checksum_region`]
+    fn checksum_region(
+        &mut self,
+        start_address: u32,
+        byte_count: u32,
+        memory_id: u32,
+        algorithm: ChecksumAlgorithm,
+    ) -> ResultComm<Box<[u8]>>;
+    /// See [`McuBoot::check_reserved`]
+    fn check_reserved(&mut self, start_address: u32, byte_count: u32, memory_id: u32) -> ResultComm<()>;
+    /// See [`McuBoot::write_image`]
+    fn write_image(&mut self, segments: &[Segment], memory_id: u32) -> ResultStatus;
+    /// See [`McuBoot::flash_image`]
+    fn flash_image(&mut self, segments: &[Segment], erase: bool, memory_id: u32) -> ResultStatus;
+    /// See [`McuBoot::sector_size`]
+    fn sector_size(&mut self, memory_id: u32) -> ResultComm<u32>;
+    /// See [`McuBoot::list_memory`]
+    fn list_memory(&mut self) -> ResultComm<Vec<MemoryInfo>>;
+    /// See [`McuBoot::get_all_properties`]
+    fn get_all_properties(&mut self, memory_index: u32) -> ResultComm<Vec<GetPropertyResponse>>;
+    /// See [`McuBoot::capabilities`]
+    fn capabilities(&mut self) -> ResultComm<&DeviceCapabilities>;
+    /// See [`McuBoot::flash_erase_all`]
+    fn flash_erase_all(&mut self, memory_id: u32) -> ResultStatus;
+    /// See [`McuBoot::flash_erase_region`]
+    fn flash_erase_region(&mut self, start_address: u32, byte_count: u32, memory_id: u32) -> ResultStatus;
+    /// See [`McuBoot::flash_erase_all_unsecure`]
+    fn flash_erase_all_unsecure(&mut self) -> ResultStatus;
+    /// See [`McuBoot::read_memory`]
+    fn read_memory(&mut self, start_address: u32, byte_count: u32, memory_id: u32) -> ResultComm<ReadMemoryResponse>;
+    /// See [`McuBoot::read_memory_into`]
+    fn read_memory_into(
+        &mut self,
+        start_address: u32,
+        byte_count: u32,
+        memory_id: u32,
+        writer: &mut dyn Write,
+    ) -> ResultComm<ReadMemoryResponse>;
+    /// See [`McuBoot::read_memory_large`]
+    fn read_memory_large(
+        &mut self,
+        start_address: u32,
+        byte_count: u32,
+        memory_id: u32,
+        chunk_size: u32,
+    ) -> ResultComm<ReadMemoryResponse>;
+    /// See [`McuBoot::read_memory_large_into`]
+    fn read_memory_large_into(
+        &mut self,
+        start_address: u32,
+        byte_count: u32,
+        memory_id: u32,
+        chunk_size: u32,
+        writer: &mut dyn Write,
+    ) -> ResultComm<ReadMemoryResponse>;
+    /// See [`McuBoot::configure_memory`]
+    fn configure_memory(&mut self, memory_id: u32, address: u32) -> ResultStatus;
+    /// See [`McuBoot::receive_sb_file`]
+    fn receive_sb_file(&mut self, bytes: &[u8]) -> ResultStatus;
+    /// See [`McuBoot::receive_sb_file_resume`]
+    fn receive_sb_file_resume(&mut self, bytes: &[u8], offset: usize) -> ResultStatus;
+    /// See [`McuBoot::trust_provisioning`]
+    fn trust_provisioning(&mut self, operation: &TrustProvOperation) -> ResultComm<(StatusCode, Box<[u32]>)>;
+    /// See [`McuBoot::key_provisioning`]
+    fn key_provisioning(&mut self, operation: &KeyProvOperation) -> ResultComm<KeyProvisioningResponse>;
+    /// See [`McuBoot::write_key_nonvolatile`]
+    fn write_key_nonvolatile(&mut self, memory_id: u32, verify: bool) -> ResultStatus;
+    /// See [`McuBoot::read_key_store`]
+    fn read_key_store(&mut self) -> ResultComm<Vec<u8>>;
+    /// See [`McuBoot::flash_read_once`]
+    fn flash_read_once(&mut self, index: u32, count: u32) -> ResultComm<u32>;
+    /// See [`McuBoot::flash_program_once`]
+    fn flash_program_once(&mut self, index: u32, count: u32, data: u32, verify: bool) -> ResultStatus;
+    /// See [`McuBoot::efuse_read_once`]
+    fn efuse_read_once(&mut self, index: u32) -> ResultComm<u32>;
+    /// See [`McuBoot::efuse_program_once`]
+    fn efuse_program_once(&mut self, index: u32, data: u32, lock_bit: Option<u8>, verify: bool) -> ResultStatus;
+    /// See [`McuBoot::fuse_read`]
+    fn fuse_read(&mut self, start_address: u32, byte_count: u32, memory_id: u32) -> ResultComm<ReadMemoryResponse>;
+    /// See [`McuBoot::fuse_program`]
+    fn fuse_program(&mut self, start_address: u32, memory_id: u32, bytes: &[u8]) -> ResultStatus;
+    /// See [`McuBoot::load_image`]
+    fn load_image(&mut self, bytes: &[u8]) -> ResultStatus;
+}
+
+impl<T> McuBootOps for McuBoot<T>
+where
+    T: Protocol,
+{
+    fn last_outcome(&self) -> Option<CommandOutcome> {
+        McuBoot::last_outcome(self)
+    }
+
+    fn take_last_outcome(&mut self) -> Option<CommandOutcome> {
+        McuBoot::take_last_outcome(self)
+    }
+
+    fn retries(&self) -> u32 {
+        McuBoot::retries(self)
+    }
+
+    fn take_warnings(&mut self) -> Vec<Warning> {
+        McuBoot::take_warnings(self)
+    }
+
+    fn config(&self) -> &SessionConfig {
+        McuBoot::config(self)
+    }
+
+    fn config_mut(&mut self) -> &mut SessionConfig {
+        McuBoot::config_mut(self)
+    }
+
+    fn set_transfer_options(&mut self, options: TransferOptions) {
+        McuBoot::config_mut(self).transfer = options;
+    }
+
+    fn acked_bytes(&self) -> u64 {
+        McuBoot::acked_bytes(self)
+    }
+
+    fn set_progress_callback(&mut self, callback: ProgressCallback) {
+        McuBoot::set_progress_callback(self, callback);
+    }
+
+    fn clear_progress_callback(&mut self) {
+        McuBoot::clear_progress_callback(self);
+    }
+
+    fn get_property(&mut self, tag: PropertyTagDiscriminants, memory_index: u32) -> ResultComm<GetPropertyResponse> {
+        McuBoot::get_property(self, tag, memory_index)
+    }
+
+    fn set_property(&mut self, tag: PropertyTagDiscriminants, value: u32) -> ResultStatus {
+        McuBoot::set_property(self, tag, value)
+    }
+
+    fn reset(&mut self) -> ResultStatus {
+        McuBoot::reset(self)
+    }
+
+    fn call(&mut self, start_address: u32, argument: u32) -> ResultStatus {
+        McuBoot::call(self, start_address, argument)
+    }
+
+    fn execute(&mut self, start_address: u32, argument: u32, stackpointer: u32) -> ResultStatus {
+        McuBoot::execute(self, start_address, argument, stackpointer)
+    }
+
+    fn execute_with_args(
+        &mut self,
+        start_address: u32,
+        args: &[u8],
+        args_address: Option<u32>,
+        stackpointer: u32,
+    ) -> ResultStatus {
+        McuBoot::execute_with_args(self, start_address, args, args_address, stackpointer)
+    }
+
+    fn fill_memory(&mut self, start_address: u32, byte_count: u32, pattern: u32) -> ResultStatus {
+        McuBoot::fill_memory(self, start_address, byte_count, pattern)
+    }
+
+    fn write_memory(&mut self, start_address: u32, memory_id: u32, bytes: &[u8]) -> ResultStatus {
+        McuBoot::write_memory(self, start_address, memory_id, bytes)
+    }
+
+    fn write_memory_verified(&mut self, start_address: u32, memory_id: u32, bytes: &[u8]) -> ResultStatus {
+        McuBoot::write_memory_verified(self, start_address, memory_id, bytes)
+    }
+
+    fn verify_memory(&mut self, start_address: u32, memory_id: u32, bytes: &[u8]) -> ResultComm<u64> {
+        McuBoot::verify_memory(self, start_address, memory_id, bytes)
+    }
+
+    fn blank_check(&mut self, start_address: u32, byte_count: u32, memory_id: u32) -> ResultComm<Option<u32>> {
+        McuBoot::blank_check(self, start_address, byte_count, memory_id)
+    }
+
+    fn checksum_region(
+        &mut self,
+        start_address: u32,
+        byte_count: u32,
+        memory_id: u32,
+        algorithm: ChecksumAlgorithm,
+    ) -> ResultComm<Box<[u8]>> {
+        McuBoot::checksum_region(self, start_address, byte_count, memory_id, algorithm)
+    }
+
+    fn check_reserved(&mut self, start_address: u32, byte_count: u32, memory_id: u32) -> ResultComm<()> {
+        McuBoot::check_reserved(self, start_address, byte_count, memory_id)
+    }
+
+    fn write_image(&mut self, segments: &[Segment], memory_id: u32) -> ResultStatus {
+        McuBoot::write_image(self, segments, memory_id)
+    }
+
+    fn flash_image(&mut self, segments: &[Segment], erase: bool, memory_id: u32) -> ResultStatus {
+        McuBoot::flash_image(self, segments, erase, memory_id)
+    }
+
+    fn sector_size(&mut self, memory_id: u32) -> ResultComm<u32> {
+        McuBoot::sector_size(self, memory_id)
+    }
+
+    fn list_memory(&mut self) -> ResultComm<Vec<MemoryInfo>> {
+        McuBoot::list_memory(self)
+    }
+
+    fn get_all_properties(&mut self, memory_index: u32) -> ResultComm<Vec<GetPropertyResponse>> {
+        McuBoot::get_all_properties(self, memory_index)
+    }
+
+    fn capabilities(&mut self) -> ResultComm<&DeviceCapabilities> {
+        McuBoot::capabilities(self)
+    }
+
+    fn flash_erase_all(&mut self, memory_id: u32) -> ResultStatus {
+        McuBoot::flash_erase_all(self, memory_id)
+    }
+
+    fn flash_erase_region(&mut self, start_address: u32, byte_count: u32, memory_id: u32) -> ResultStatus {
+        McuBoot::flash_erase_region(self, start_address, byte_count, memory_id)
+    }
+
+    fn flash_erase_all_unsecure(&mut self) -> ResultStatus {
+        McuBoot::flash_erase_all_unsecure(self)
+    }
+
+    fn read_memory(&mut self, start_address: u32, byte_count: u32, memory_id: u32) -> ResultComm<ReadMemoryResponse> {
+        McuBoot::read_memory(self, start_address, byte_count, memory_id)
+    }
+
+    fn read_memory_into(
+        &mut self,
+        start_address: u32,
+        byte_count: u32,
+        memory_id: u32,
+        writer: &mut dyn Write,
+    ) -> ResultComm<ReadMemoryResponse> {
+        McuBoot::read_memory_into(self, start_address, byte_count, memory_id, writer)
+    }
+
+    fn read_memory_large(
+        &mut self,
+        start_address: u32,
+        byte_count: u32,
+        memory_id: u32,
+        chunk_size: u32,
+    ) -> ResultComm<ReadMemoryResponse> {
+        McuBoot::read_memory_large(self, start_address, byte_count, memory_id, chunk_size)
+    }
+
+    fn read_memory_large_into(
+        &mut self,
+        start_address: u32,
+        byte_count: u32,
+        memory_id: u32,
+        chunk_size: u32,
+        writer: &mut dyn Write,
+    ) -> ResultComm<ReadMemoryResponse> {
+        McuBoot::read_memory_large_into(self, start_address, byte_count, memory_id, chunk_size, writer)
+    }
+
+    fn configure_memory(&mut self, memory_id: u32, address: u32) -> ResultStatus {
+        McuBoot::configure_memory(self, memory_id, address)
+    }
+
+    fn receive_sb_file(&mut self, bytes: &[u8]) -> ResultStatus {
+        McuBoot::receive_sb_file(self, bytes)
+    }
+
+    fn receive_sb_file_resume(&mut self, bytes: &[u8], offset: usize) -> ResultStatus {
+        McuBoot::receive_sb_file_resume(self, bytes, offset)
+    }
+
+    fn trust_provisioning(&mut self, operation: &TrustProvOperation) -> ResultComm<(StatusCode, Box<[u32]>)> {
+        McuBoot::trust_provisioning(self, operation)
+    }
+
+    fn key_provisioning(&mut self, operation: &KeyProvOperation) -> ResultComm<KeyProvisioningResponse> {
+        McuBoot::key_provisioning(self, operation)
+    }
+
+    fn write_key_nonvolatile(&mut self, memory_id: u32, verify: bool) -> ResultStatus {
+        McuBoot::write_key_nonvolatile(self, memory_id, verify)
+    }
+
+    fn read_key_store(&mut self) -> ResultComm<Vec<u8>> {
+        McuBoot::read_key_store(self)
+    }
+
+    fn flash_read_once(&mut self, index: u32, count: u32) -> ResultComm<u32> {
+        McuBoot::flash_read_once(self, index, count)
+    }
+
+    fn flash_program_once(&mut self, index: u32, count: u32, data: u32, verify: bool) -> ResultStatus {
+        McuBoot::flash_program_once(self, index, count, data, verify)
+    }
+
+    fn efuse_read_once(&mut self, index: u32) -> ResultComm<u32> {
+        McuBoot::efuse_read_once(self, index)
+    }
+
+    fn efuse_program_once(&mut self, index: u32, data: u32, lock_bit: Option<u8>, verify: bool) -> ResultStatus {
+        McuBoot::efuse_program_once(self, index, data, lock_bit, verify)
+    }
+
+    fn fuse_read(&mut self, start_address: u32, byte_count: u32, memory_id: u32) -> ResultComm<ReadMemoryResponse> {
+        McuBoot::fuse_read(self, start_address, byte_count, memory_id)
+    }
+
+    fn fuse_program(&mut self, start_address: u32, memory_id: u32, bytes: &[u8]) -> ResultStatus {
+        McuBoot::fuse_program(self, start_address, memory_id, bytes)
+    }
+
+    fn load_image(&mut self, bytes: &[u8]) -> ResultStatus {
+        McuBoot::load_image(self, bytes)
+    }
+}
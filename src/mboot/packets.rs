@@ -15,7 +15,7 @@
 //! - CRC16 (2 bytes, little-endian)
 //! - Data payload (variable length)
 
-use super::ResultComm;
+use super::{ResultComm, protocols::CommunicationError};
 
 pub mod command;
 pub mod data_phase;
@@ -34,11 +34,19 @@ pub trait Packet {
 /// Trait for packet construction
 ///
 /// Implemented by packet types that can be serialized into bytes for transmission.
-/// The constructed packet includes the complete protocol header and payload.
-pub trait PacketConstruct {
-    /// Constructs the complete packet as bytes ready for transmission
-    #[must_use]
-    fn construct(&self) -> Vec<u8>;
+/// Protocols send the payload with their own framing, see
+/// [`Protocol::write_packet`](super::protocols::Protocol::write_packet).
+pub trait PacketConstruct: Packet {
+    /// Payload of the packet, without the protocol header
+    fn payload(&self) -> &[u8];
+
+    /// Constructs the complete packet with the standard framing, header followed by the payload
+    ///
+    /// # Errors
+    /// [`CommunicationError::PayloadTooLarge`] if the payload does not fit into the packet.
+    fn construct(&self) -> ResultComm<Vec<u8>> {
+        construct_header(Self::get_code(), self.payload())
+    }
 }
 
 /// Trait for packet parsing
@@ -78,6 +86,77 @@ const PING: u8 = 0xA6;
 /// Ping response packet identifier
 const PINGR: u8 = 0xA7;
 
+/// Framing of packets on byte-oriented transports (UART, I2C)
+///
+/// Packets are always constructed with the standard framing, which transports convert with
+/// [`Framing::encode`] for bootloader and bridge variants using a different start byte or omitting the CRC.
+/// Transports wrapping packets into their own reports, like USB-HID, ignore the framing.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Framing {
+    /// First byte of every frame, including ACK and ping frames
+    pub start_byte: u8,
+    /// Whether frames carry CRC16 of the header and payload after the length
+    pub crc: bool,
+}
+
+impl Framing {
+    /// Framing defined by the McuBoot protocol specification
+    pub const STANDARD: Framing = Framing {
+        start_byte: 0x5A,
+        crc: true,
+    };
+
+    /// Constructs a frame with this framing
+    ///
+    /// # Arguments
+    /// * `packet_code` - The packet type identifier (e.g., CMD, DATA, PING)
+    /// * `data` - The packet payload data
+    ///
+    /// # Errors
+    /// [`CommunicationError::PayloadTooLarge`] if `data` are longer than the 16-bit length field allows.
+    pub fn frame(self, packet_code: u8, data: &[u8]) -> ResultComm<Vec<u8>> {
+        let length = CommunicationError::check_size::<u16>(data.len())?.to_le_bytes();
+        let mut v = vec![self.start_byte, packet_code, length[0], length[1]];
+        v.extend(data);
+
+        if self.crc {
+            let crc = CRC_CHECK.checksum(&v).to_le_bytes();
+            v.insert(4, crc[0]);
+            v.insert(5, crc[1]);
+        }
+        Ok(v)
+    }
+
+    /// Converts a frame constructed with the standard framing to this framing
+    ///
+    /// Data shorter than the standard header are returned unchanged.
+    ///
+    /// # Errors
+    /// [`CommunicationError::PayloadTooLarge`] if the payload of `frame` does not fit into a frame.
+    pub fn encode(self, frame: &[u8]) -> ResultComm<Vec<u8>> {
+        match frame {
+            [_, packet_code, _, _, _, _, data @ ..] if self != Self::STANDARD => self.frame(*packet_code, data),
+            _ => Ok(frame.to_vec()),
+        }
+    }
+
+    /// Checks the received CRC, always passes when the framing has no CRC
+    ///
+    /// # Arguments
+    /// * `frame` - Header without the CRC followed by the payload
+    /// * `crc` - Received CRC, [`None`] when the framing has no CRC
+    #[must_use]
+    pub fn check_crc(self, frame: &[u8], crc: Option<u16>) -> bool {
+        crc.is_none_or(|crc| CRC_CHECK.checksum(frame) == crc)
+    }
+}
+
+impl Default for Framing {
+    fn default() -> Self {
+        Self::STANDARD
+    }
+}
+
 /// Constructs a complete McuBoot packet header with payload
 ///
 /// This function creates a properly formatted McuBoot packet by combining the
@@ -91,6 +170,9 @@ const PINGR: u8 = 0xA7;
 /// # Returns
 /// A [`Vec<u8>`] containing the complete packet with header and payload
 ///
+/// # Errors
+/// [`CommunicationError::PayloadTooLarge`] if `data` are longer than 64 KiB - 1.
+///
 /// # Packet Format
 /// The constructed packet follows this structure:
 /// - Start byte: 0x5A
@@ -98,17 +180,47 @@ const PINGR: u8 = 0xA7;
 /// - Length: 2 bytes (little-endian, length of data)
 /// - CRC16: 2 bytes (little-endian, calculated over header + data)
 /// - Data: variable length payload
-fn construct_header(packet_code: u8, data: Vec<u8>) -> Vec<u8> {
-    let length = data.len() as u16;
-    let length = length.to_le_bytes();
+pub(super) fn construct_header(packet_code: u8, data: &[u8]) -> ResultComm<Vec<u8>> {
+    Framing::STANDARD.frame(packet_code, data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CommunicationError, Framing};
 
-    let mut v = vec![0x5A, packet_code, length[0], length[1]];
-    v.extend(data);
+    #[test]
+    fn encode_framing() {
+        let frame = Framing::STANDARD.frame(0xA4, &[0x0B, 0, 0, 0]).unwrap();
+        assert_eq!(frame, [0x5A, 0xA4, 4, 0, 0x6F, 0x46, 0x0B, 0, 0, 0]);
+        assert_eq!(Framing::STANDARD.encode(&frame).unwrap(), frame);
 
-    let crc = CRC_CHECK.checksum(&v).to_le_bytes();
+        let no_crc = Framing {
+            crc: false,
+            ..Framing::STANDARD
+        };
+        assert_eq!(no_crc.encode(&frame).unwrap(), [0x5A, 0xA4, 4, 0, 0x0B, 0, 0, 0]);
+        assert!(no_crc.check_crc(&frame, None));
 
-    v.insert(4, crc[0]);
-    v.insert(5, crc[1]);
+        let start = Framing {
+            start_byte: 0x5B,
+            ..Framing::STANDARD
+        };
+        let encoded = start.encode(&frame).unwrap();
+        assert_eq!(encoded[0], 0x5B);
+        let crc = u16::from_le_bytes([encoded[4], encoded[5]]);
+        assert!(start.check_crc(&[&encoded[..4], &encoded[6..]].concat(), Some(crc)));
+        assert!(!start.check_crc(&[&frame[..4], &frame[6..]].concat(), Some(crc)));
+    }
 
-    v
+    #[test]
+    fn oversized_payload_is_rejected() {
+        assert_eq!(Framing::STANDARD.frame(0xA5, &vec![0; 0xFFFF]).unwrap().len(), 0x1_0005);
+        assert!(matches!(
+            Framing::STANDARD.frame(0xA5, &vec![0; 0x1_0000]),
+            Err(CommunicationError::PayloadTooLarge {
+                size: 0x1_0000,
+                limit: 0xFFFF
+            })
+        ));
+    }
 }
@@ -7,8 +7,12 @@
 //! McuBoot is NXP's bootloader protocol used for communication with target devices during firmware
 //! updates and debugging operations.
 
-use crate::mboot::tags::{
-    ToAddress, command::CommandTag, command_flag::CommandFlag, command_response::CmdResponseTag, status::StatusCode,
+use crate::mboot::{
+    ResultComm,
+    protocols::CommunicationError,
+    tags::{
+        ToAddress, command::CommandTag, command_flag::CommandFlag, command_response::CmdResponseTag, status::StatusCode,
+    },
 };
 
 use super::{Packet, construct_header};
@@ -48,9 +52,24 @@ impl CommandHeader {
     /// - Reserved byte (1 byte)
     /// - Parameter count (1 byte)
     /// - Parameters (4 bytes each, little-endian)
-    #[must_use]
-    pub fn construct_frame(&self, params: &[u32], command_code: u8) -> Vec<u8> {
-        let mut command_part = vec![command_code, self.flag.code(), self.reserved, params.len() as u8];
+    ///
+    /// # Errors
+    /// [`CommunicationError::PayloadTooLarge`] if there are more than 255 parameters.
+    pub fn construct_frame(&self, params: &[u32], command_code: u8) -> ResultComm<Vec<u8>> {
+        construct_header(super::CMD, &self.construct_payload(params, command_code)?)
+    }
+
+    /// Constructs the payload of a command packet, the frame of [`CommandHeader::construct_frame`] without the
+    /// protocol header
+    ///
+    /// Protocols send it with their own framing, see
+    /// [`Protocol::write_packet`](crate::mboot::protocols::Protocol::write_packet).
+    ///
+    /// # Errors
+    /// [`CommunicationError::PayloadTooLarge`] if there are more than 255 parameters.
+    pub fn construct_payload(&self, params: &[u32], command_code: u8) -> ResultComm<Vec<u8>> {
+        let param_count = CommunicationError::check_size(params.len())?;
+        let mut command_part = vec![command_code, self.flag.code(), self.reserved, param_count];
 
         // Pre-allocate space for parameters to avoid multiple allocations
         // Each u32 parameter takes 4 bytes when serialized
@@ -59,8 +78,7 @@ impl CommandHeader {
         // Convert u32 parameters to little-endian bytes and append to command
         // This matches the McuBoot protocol requirement for little-endian parameter encoding
         command_part.extend(params.iter().flat_map(|num| num.to_le_bytes()));
-
-        construct_header(super::CMD, command_part)
+        Ok(command_part)
     }
 }
 
@@ -154,6 +172,7 @@ impl Packet for CmdResponse {
 mod tests {
     use crate::mboot::{
         packets::command::{CommandHeader, CommandPacket},
+        protocols::CommunicationError,
         tags::{
             ToAddress,
             command::{CommandTag, CommandToParams},
@@ -178,7 +197,10 @@ mod tests {
             tag: PropertyTagDiscriminants::CurrentVersion,
             memory_index: 0,
         });
-        let bytes = cmd.header.construct_frame(&cmd.tag.to_params().0, cmd.tag.code());
+        let bytes = cmd
+            .header
+            .construct_frame(&cmd.tag.to_params().unwrap().0, cmd.tag.code())
+            .unwrap();
         assert_eq!(
             bytes,
             [
@@ -195,7 +217,10 @@ mod tests {
             data: 0x12345678,
         });
 
-        let bytes = cmd.header.construct_frame(&cmd.tag.to_params().0, cmd.tag.code());
+        let bytes = cmd
+            .header
+            .construct_frame(&cmd.tag.to_params().unwrap().0, cmd.tag.code())
+            .unwrap();
         assert_eq!(
             bytes,
             [
@@ -203,4 +228,14 @@ mod tests {
             ]
         );
     }
+
+    #[test]
+    fn test_command_too_many_params() {
+        let header = get_command(CommandTag::Reset).header;
+        assert!(header.construct_frame(&[0; 255], 0x0B).is_ok());
+        assert!(matches!(
+            header.construct_frame(&[0; 256], 0x0B),
+            Err(CommunicationError::PayloadTooLarge { size: 256, limit: 255 })
+        ));
+    }
 }
@@ -17,7 +17,7 @@ use crate::tags::{command::CommandTag, command_flag::CommandFlag};
 
 use crate::mboot::ResultComm;
 
-use super::{Packet, PacketConstruct, PacketParse, construct_header};
+use super::{Packet, PacketConstruct, PacketParse};
 
 /// Data phase packet identifier as defined by McuBoot protocol
 const DATA_PHASE_CODE: u8 = 0xA5;
@@ -48,21 +48,9 @@ impl Packet for DataPhasePacket {
 }
 
 impl PacketConstruct for DataPhasePacket {
-    /// Constructs a complete data phase packet ready for transmission
-    ///
-    /// This method wraps the data payload with the appropriate McuBoot protocol
-    /// header to create a properly formatted data phase packet. The header includes
-    /// the packet type, length, and CRC checksum as required by the protocol.
-    ///
-    /// # Returns
-    /// A [`Vec<u8>`] containing the complete data phase packet with protocol header:
-    /// - Start byte (0x5A)
-    /// - Packet code (0xA5)
-    /// - Length (2 bytes, little-endian)
-    /// - CRC16 (2 bytes, little-endian)
-    /// - Data payload (variable length)
-    fn construct(&self) -> Vec<u8> {
-        construct_header(DATA_PHASE_CODE, self.data.clone())
+    /// Returns the data carried by the packet
+    fn payload(&self) -> &[u8] {
+        &self.data
     }
 }
 
@@ -12,7 +12,9 @@
 //! This is the first communication performed when establishing a
 //! connection with a McuBoot-enabled device.
 
-use crate::{CommunicationError, mboot::ResultComm};
+use std::fmt;
+
+use crate::{CommunicationError, mboot::ResultComm, tags::property::Version};
 
 use super::{Packet, PacketParse};
 
@@ -28,6 +30,23 @@ pub struct PingResponse {
     pub options: u16,
 }
 
+impl PingResponse {
+    /// Version of the framing protocol, e.g. P1.3.0
+    ///
+    /// The version is sent as bugfix, minor, major and name bytes, [`PingResponse::version`] keeps them in
+    /// that order.
+    #[must_use]
+    pub fn protocol_version(&self) -> Version {
+        Version::parse(self.version.swap_bytes())
+    }
+}
+
+impl fmt::Display for PingResponse {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}, options {:#06X}", self.protocol_version(), self.options)
+    }
+}
+
 impl PacketParse for PingResponse {
     /// Parses raw bytes into a [`PingResponse`] packet
     ///
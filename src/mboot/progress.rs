@@ -0,0 +1,59 @@
+// Copyright 2025 NXP
+//
+// SPDX-License-Identifier: BSD-3-Clause
+//! Data Transfer Progress
+//!
+//! Progress of data phase transfers is reported through a callback registered with
+//! [`McuBoot::set_progress_callback`][`super::McuBoot::set_progress_callback`]. This lets any frontend,
+//! like a CLI progress bar or a GUI, display the transfer without the library depending on it.
+//!
+//! All commands with a data phase report their progress, be it memory and fuse reads and writes, SB files
+//! or images loaded without a command.
+
+use super::tags::command::CommandTagDiscriminants;
+
+/// Direction of the data phase transfer
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TransferDirection {
+    /// Data are sent from the host to the device
+    Send,
+    /// Data are received from the device
+    Receive,
+}
+
+/// Event reported during a data phase transfer
+///
+/// Each transfer starts with [`ProgressEvent::Started`], continues with a [`ProgressEvent::Chunk`] for
+/// every packet and ends either with [`ProgressEvent::Finished`] or [`ProgressEvent::Aborted`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ProgressEvent {
+    /// Transfer of `total` bytes started
+    Started {
+        /// Command transferring the data, [`None`] if it is not known
+        command: Option<CommandTagDiscriminants>,
+        /// Direction of the transfer
+        direction: TransferDirection,
+        /// Expected number of bytes
+        total: u64,
+    },
+    /// Packet was sent or received
+    Chunk {
+        /// Number of bytes transferred so far
+        transferred: u64,
+        /// Expected number of bytes
+        total: u64,
+    },
+    /// All data were transferred
+    Finished {
+        /// Number of bytes transferred
+        transferred: u64,
+    },
+    /// Transfer ended early, either by an error or by the device aborting the data phase
+    Aborted {
+        /// Number of bytes transferred before the transfer ended
+        transferred: u64,
+    },
+}
+
+/// Callback receiving [`ProgressEvent`]s
+pub type ProgressCallback = Box<dyn FnMut(ProgressEvent) + Send>;
@@ -15,7 +15,15 @@
 //! # Supported Protocols
 //! - UART: Serial communication over UART interfaces
 //! - USB: USB HID communication for direct device connection
-//! - I2C: I2C bus communication for embedded applications
+//! - I2C: I2C bus communication through the Linux I2C interface or a USB to I2C bridge
+//! - CAN: CAN bus through SocketCAN on Linux
+//! - BusPal: I2C or SPI bus behind a BusPal converter connected over UART
+//! - LPCUSBSIO: I2C or SPI port of an LPC-Link2 or MCU-Link debug probe
+//! - Network: UART of the device behind a remote serial bridge, over TCP or UDP
+//! - Record and replay: transcript of frames exchanged with any of the protocols above, replayed without the
+//!   device
+//! - Mock and virtual device: replayed scripts and an emulated bootloader for tests, enabled by the `testing`
+//!   feature
 
 #[cfg(feature = "python")]
 use pyo3::{PyErr, exceptions::PyValueError};
@@ -24,11 +32,24 @@ use std::time::Duration;
 
 use super::{
     ResultComm,
-    packets::{Packet, PacketConstruct, PacketParse},
-    tags::status::StatusCode,
+    formats::FormatError,
+    formatters::HexDiff,
+    packets::{Framing, Packet, PacketConstruct, PacketParse, command::CmdResponse, ping::PingResponse},
+    session::Quirks,
+    tags::status::{StatusCode, describe_status},
 };
 
+pub mod buspal;
+pub mod can;
+mod frame_sync;
 pub mod i2c;
+pub mod lpcusbsio;
+#[cfg(any(test, feature = "testing"))]
+pub mod mock;
+pub mod network;
+mod poll_schedule;
+pub mod record;
+mod repeated_log;
 pub mod uart;
 pub mod usb;
 
@@ -38,6 +59,7 @@ pub mod usb;
 /// communication with McuBoot devices, from low-level transport errors
 /// to protocol-level issues.
 #[derive(thiserror::Error, Debug)]
+#[non_exhaustive]
 pub enum CommunicationError {
     /// Error from the underlying serial port library
     #[error("error raised by UART library")]
@@ -76,7 +98,7 @@ pub enum CommunicationError {
     ParseError(String),
 
     /// Command returned an error status code
-    #[error("unexpected status code: {1} ({1:#X}) {0}")]
+    #[error("unexpected status code: {code} ({code:#X}) {text}", code = .1, text = describe_status(*.1))]
     UnexpectedStatus(StatusCode, u32),
 
     /// Communication was aborted by user or system
@@ -90,6 +112,73 @@ pub enum CommunicationError {
     /// Timeout occurred while waiting for response
     #[error("timeout occured while waiting for response")]
     Timeout,
+
+    /// Image file could not be parsed
+    #[error("error while parsing image file: {0}")]
+    ImageFormatError(#[from] FormatError),
+
+    /// Operation would change a memory region reserved by the bootloader
+    #[error("range overlaps the region {start_address:#010X}-{end_address:#010X} reserved by the bootloader")]
+    ReservedRegion {
+        /// Start address of the reserved region
+        start_address: u32,
+        /// Inclusive end address of the reserved region
+        end_address: u32,
+    },
+
+    /// Memory read back after writing differs from the written data
+    #[error("memory differs from the written data at {address:#010X}")]
+    VerifyMismatch {
+        /// Address of the first differing byte
+        address: u32,
+        /// Address of the first byte of `expected` and `actual`
+        window_address: u32,
+        /// Written data around the first difference, at most [`HexDiff::WINDOW`] bytes
+        expected: Box<[u8]>,
+        /// Data read back around the first difference
+        actual: Box<[u8]>,
+    },
+
+    /// Payload does not fit into the length field of a packet or command
+    #[error("payload of {size} bytes exceeds the limit of {limit} bytes")]
+    PayloadTooLarge {
+        /// Size of the payload
+        size: usize,
+        /// Largest size the length field can hold
+        limit: usize,
+    },
+}
+
+impl CommunicationError {
+    /// Creates [`CommunicationError::VerifyMismatch`] with the window of `diff` around the first difference,
+    /// [`None`] if the data do not differ
+    #[must_use]
+    pub fn verify_mismatch(diff: &HexDiff) -> Option<Self> {
+        let offset = diff.first_mismatch()?;
+        let window = diff.window()?;
+        Some(CommunicationError::VerifyMismatch {
+            // the mismatch lies within the data starting at the address
+            address: diff.address.wrapping_add(offset as u32),
+            window_address: window.address,
+            expected: window.expected.into(),
+            actual: window.actual.into(),
+        })
+    }
+
+    /// Converts `size` to the type of a length field, failing with [`CommunicationError::PayloadTooLarge`]
+    /// instead of truncating it
+    ///
+    /// # Errors
+    /// [`CommunicationError::PayloadTooLarge`] if `size` does not fit into `T`.
+    pub(crate) fn check_size<T>(size: usize) -> ResultComm<T>
+    where
+        T: TryFrom<usize> + Into<u64> + num_traits::Bounded,
+    {
+        T::try_from(size).map_err(|_| CommunicationError::PayloadTooLarge {
+            size,
+            limit: usize::try_from(T::max_value().into()).unwrap_or(usize::MAX),
+        })
+    }
 }
 
 impl From<StatusCode> for CommunicationError {
@@ -107,6 +196,25 @@ impl From<CommunicationError> for PyErr {
     }
 }
 
+/// Statistics of polling the device for ACKs, kept by transports which poll, like I2C
+///
+/// Slow targets answer many polls as busy, the statistics help to tune the timeout and polling interval.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct TransportStats {
+    /// ACKs waited for
+    pub acks: u64,
+    /// Reads of the bus while waiting for ACKs
+    pub polls: u64,
+    /// Polls answered with 0x00 by the busy device
+    pub busy: u64,
+    /// Polls answered with bytes which are neither busy nor an ACK frame
+    pub invalid: u64,
+    /// ACKs not received before the timeout
+    pub timeouts: u64,
+    /// Longest time spent waiting for an ACK
+    pub longest_wait: Duration,
+}
+
 /// Core protocol trait for McuBoot communication
 ///
 /// This trait defines the methods that all McuBoot protocol implementations
@@ -116,6 +224,18 @@ pub trait Protocol {
     /// Get the configured timeout duration for operations
     fn get_timeout(&self) -> Duration;
 
+    /// Change the timeout of the following operations
+    ///
+    /// [`McuBoot`](crate::McuBoot) changes it for phases of commands which have their own timeouts, see
+    /// [`PhaseTimeouts`](crate::session::PhaseTimeouts).
+    ///
+    /// # Errors
+    /// Any error of the underlying port if it cannot be reconfigured. The default implementation fails with
+    /// [`CommunicationError::UnsupportedPlatform`], for transports whose timeout cannot be changed.
+    fn set_timeout(&mut self, _timeout: Duration) -> ResultComm<()> {
+        Err(CommunicationError::UnsupportedPlatform)
+    }
+
     /// Get the polling interval for checking responses
     fn get_polling_interval(&self) -> Duration;
 
@@ -136,8 +256,11 @@ pub trait Protocol {
 
     /// Write raw packet data to the device
     ///
+    /// Packets of commands are written by [`Protocol::write_packet`], which does not construct the frame
+    /// first on transports with their own framing.
+    ///
     /// # Arguments
-    /// * `data` - Raw packet bytes to send
+    /// * `data` - Complete frame with the standard framing, converted by the transport if it uses another one
     ///
     /// # Returns
     /// A Result indicating success or error
@@ -146,6 +269,19 @@ pub trait Protocol {
     /// Any errors that occured while writing, from being unable to write to invalid CRC checksum.
     fn write_packet_raw(&mut self, data: &[u8]) -> ResultComm<()>;
 
+    /// Write a packet of type `packet_code` to the device, framed the way the transport sends packets
+    ///
+    /// Byte-oriented transports send the payload in a frame with their [`Framing`], others wrap it into
+    /// their own reports, like USB-HID. The default implementation writes a frame with the standard framing by
+    /// [`Protocol::write_packet_raw`].
+    ///
+    /// # Errors
+    /// Any errors that occured while writing, [`CommunicationError::PayloadTooLarge`] if the payload does not fit
+    /// into a packet.
+    fn write_packet(&mut self, packet_code: u8, payload: &[u8]) -> ResultComm<()> {
+        self.write_packet_raw(&Framing::STANDARD.frame(packet_code, payload)?)
+    }
+
     /// Read a raw packet with specific type code
     ///
     /// # Arguments
@@ -158,6 +294,60 @@ pub trait Protocol {
     /// Any errors that occured while reading, from being unable to read to invalid CRC checksum.
     fn read_packet_raw(&mut self, packet_code: u8) -> ResultComm<Vec<u8>>;
 
+    /// Response to the ping sent when the connection was opened
+    ///
+    /// [`None`] for transports which do not ping the device, like USB-HID.
+    fn ping_response(&self) -> Option<PingResponse> {
+        None
+    }
+
+    /// Statistics of polling for ACKs since the connection was opened
+    ///
+    /// [`None`] for transports which do not poll the device, like UART.
+    fn transport_stats(&self) -> Option<TransportStats> {
+        None
+    }
+
+    /// Apply workarounds of the session configuration, see [`Quirks`]
+    ///
+    /// Quirks which do not concern the transport are ignored, so does the default implementation with all of
+    /// them.
+    fn apply_quirks(&mut self, _quirks: &Quirks) {}
+
+    /// Read a raw packet like [`Protocol::read_packet_raw`], but answer it with ACK abort instead of ACK
+    ///
+    /// The device then stops sending its data phase and sends the final response. Transports without ACKs
+    /// cannot abort, they read the packet like [`Protocol::read_packet_raw`].
+    ///
+    /// # Errors
+    /// Any errors that occured while reading, from being unable to read to invalid CRC checksum.
+    fn read_packet_abort(&mut self, packet_code: u8) -> ResultComm<Vec<u8>> {
+        self.read_packet_raw(packet_code)
+    }
+
+    /// Write a complete frame as it is and return the frame the device responds with
+    ///
+    /// Meant for reproducing protocol issues, the frame is not checked, so frames with a wrong length or CRC
+    /// reach the device. It is given with the standard framing and converted by transports using another one.
+    /// A command frame is answered by a response frame, returned with its header and recomputed CRC, data
+    /// phases of the command are not read. Other frames, like data phase packets, have no response and an empty
+    /// frame is returned.
+    ///
+    /// # Errors
+    /// [`CommunicationError::InvalidHeader`] if `frame` has no packet type, errors of writing the frame and
+    /// reading the response otherwise.
+    fn transact_frame(&mut self, frame: &[u8]) -> ResultComm<Vec<u8>> {
+        let [_, packet_code, ..] = *frame else {
+            return Err(CommunicationError::InvalidHeader);
+        };
+        self.write_packet_raw(frame)?;
+        if packet_code != CmdResponse::get_code() {
+            return Ok(Vec::new());
+        }
+        let payload = self.read_packet_raw(packet_code)?;
+        Framing::STANDARD.frame(packet_code, &payload)
+    }
+
     /// Write a strongly-typed packet to the device
     ///
     /// This method handles packet construction and transmission for any type
@@ -175,7 +365,7 @@ pub trait Protocol {
     where
         T: PacketConstruct + Packet,
     {
-        self.write_packet_raw(&packet.construct())
+        self.write_packet(T::get_code(), packet.payload())
     }
 
     /// Read a strongly-typed packet from the device
@@ -244,6 +434,41 @@ pub trait ProtocolOpen: Protocol {
     {
         Self::open(identifier)
     }
+
+    /// Open a protocol connection with advanced options and non-standard framing
+    ///
+    /// # Arguments
+    /// * `identifier` - Connection identifier
+    /// * `baudrate` - Communication baudrate (protocol-specific)
+    /// * `timeout` - Operation timeout duration
+    /// * `polling_interval` - Response polling interval
+    /// * `framing` - Start byte and CRC usage of the frames
+    ///
+    /// # Returns
+    /// A Result containing the opened protocol instance or an error
+    ///
+    /// # Errors
+    /// Any error raised by the specific protocol library, mostly informing that the selected device does not exist.
+    ///
+    /// # Note
+    /// Default implementation ignores the framing and calls `open_with_options()`, it is used by transports
+    /// which do not send the frames as they are, like USB-HID.
+    #[expect(
+        unused_variables,
+        reason = "rust-analyzer would show the underscores for inlay hints"
+    )]
+    fn open_with_framing(
+        identifier: &str,
+        baudrate: u32,
+        timeout: Duration,
+        polling_interval: Duration,
+        framing: Framing,
+    ) -> ResultComm<Self>
+    where
+        Self: Sized,
+    {
+        Self::open_with_options(identifier, baudrate, timeout, polling_interval)
+    }
 }
 
 // Define a protocol enum that can be used instead of dyn Protocol
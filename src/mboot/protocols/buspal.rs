@@ -0,0 +1,379 @@
+// Copyright 2025 NXP
+//
+// SPDX-License-Identifier: BSD-3-Clause
+//! BusPal intermediary
+//!
+//! BusPal is a board acting as a USB-serial to I2C or SPI converter, controlled by the Bus Pirate binary
+//! protocol. The host switches it into the bit-bang mode, then into the I2C or SPI mode, configures the bus
+//! and tunnels the McuBoot frames with write-then-read commands. The frame exchange itself is the same as
+//! over a directly connected I2C bus.
+
+use std::{
+    io::{self, ErrorKind, Read, Write},
+    time::Duration,
+};
+
+use log::{debug, info, trace};
+use serialport::{ClearBuffer, SerialPort};
+
+use crate::{mboot::ResultComm, parse::parse_number};
+
+use super::{
+    CommunicationError, Protocol, TransportStats,
+    i2c::{DEFAULT_SLAVE, I2CProtocol},
+};
+use crate::mboot::{
+    packets::{Framing, ping::PingResponse},
+    session::Quirks,
+};
+
+/// Attempts to enter the bit-bang mode
+const RESET_COUNT: usize = 20;
+/// Timeout of responses during the mode switching
+const MODE_TIMEOUT: Duration = Duration::from_millis(10);
+/// Response to successful configuration commands
+const RESPONSE_OK: u8 = 0x01;
+/// Most bytes transferred by one write-then-read command
+const BULK_TRANSFER_MAX: usize = 4096;
+
+/// Commands of the bit-bang mode
+mod command {
+    pub const RESET: u8 = 0x00;
+    pub const SPI_MODE: u8 = 0x01;
+    pub const I2C_MODE: u8 = 0x02;
+    pub const SET_SPEED: u8 = 0x60;
+    pub const I2C_SET_ADDRESS: u8 = 0x70;
+    pub const SPI_CONFIG: u8 = 0x80;
+    pub const SPI_WRITE_THEN_READ: u8 = 0x04;
+    pub const I2C_WRITE_THEN_READ: u8 = 0x08;
+}
+
+/// Bus behind the BusPal and its configuration
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BusPalTarget {
+    /// I2C bus
+    I2c {
+        /// Slave address of the target
+        address: u8,
+        /// Bus clock in kHz
+        speed_khz: u32,
+    },
+    /// SPI bus
+    Spi {
+        /// Bus clock in kHz
+        speed_khz: u32,
+        /// Clock polarity, `true` for clock idle high
+        polarity: bool,
+        /// Clock phase, `true` for sampling on the second edge
+        phase: bool,
+        /// Bits are sent least significant first
+        lsb_first: bool,
+    },
+}
+
+impl BusPalTarget {
+    /// Parses the bus specification in blhost format
+    ///
+    /// The format is `i2c[,<address>[,<speed>]]` or `spi[,<speed>[,<polarity>[,<phase>[,lsb|msb]]]]`,
+    /// speed is in kHz. Defaults are address 0x10, 100 kHz, polarity 1, phase 1 and msb.
+    ///
+    /// # Errors
+    /// Text describing the invalid part of the specification.
+    pub fn parse(s: &str) -> Result<BusPalTarget, String> {
+        let mut parts = s.split(',').map(str::trim);
+        let bus = parts.next().unwrap_or_default();
+        let mut next_number = |default: u32| -> Result<u32, String> {
+            match parts.next() {
+                Some(value) if !value.is_empty() => parse_number(value),
+                _ => Ok(default),
+            }
+        };
+        let flag = |value: u32| match value {
+            0 => Ok(false),
+            1 => Ok(true),
+            _ => Err(format!("expected 0 or 1, got {value}")),
+        };
+
+        let target = if bus.eq_ignore_ascii_case("i2c") {
+            let address = next_number(DEFAULT_SLAVE.into())?;
+            BusPalTarget::I2c {
+                address: u8::try_from(address).map_err(|_| format!("invalid I2C address {address:#X}"))?,
+                speed_khz: next_number(100)?,
+            }
+        } else if bus.eq_ignore_ascii_case("spi") {
+            let speed_khz = next_number(100)?;
+            let polarity = flag(next_number(1)?)?;
+            let phase = flag(next_number(1)?)?;
+            let lsb_first = match parts.next() {
+                None | Some("" | "msb") => false,
+                Some("lsb") => true,
+                Some(order) => return Err(format!("invalid bit order '{order}', expected lsb or msb")),
+            };
+            BusPalTarget::Spi {
+                speed_khz,
+                polarity,
+                phase,
+                lsb_first,
+            }
+        } else {
+            return Err(format!("invalid BusPal bus '{bus}', expected i2c or spi"));
+        };
+
+        if parts.next().is_some() {
+            return Err("too many BusPal parameters".to_owned());
+        }
+        Ok(target)
+    }
+}
+
+/// Serial connection to the BusPal, switched to the I2C or SPI mode
+#[derive(Debug)]
+struct BusPalBus {
+    port: Box<dyn SerialPort>,
+    write_then_read: u8,
+}
+
+impl BusPalBus {
+    /// Switches the BusPal into the mode of `target` and configures the bus
+    fn open(mut port: Box<dyn SerialPort>, target: BusPalTarget) -> ResultComm<Self> {
+        let timeout = port.timeout();
+        port.set_timeout(MODE_TIMEOUT)?;
+        port.clear(ClearBuffer::Input)?;
+        let mut bus = BusPalBus {
+            port,
+            write_then_read: 0,
+        };
+
+        bus.enter_mode(command::RESET, b"BBIO1")?;
+        match target {
+            BusPalTarget::I2c { address, speed_khz } => {
+                bus.enter_mode(command::I2C_MODE, b"I2C1")?;
+                bus.configure(&[command::I2C_SET_ADDRESS, address])?;
+                bus.set_speed(speed_khz)?;
+                bus.write_then_read = command::I2C_WRITE_THEN_READ;
+            }
+            BusPalTarget::Spi {
+                speed_khz,
+                polarity,
+                phase,
+                lsb_first,
+            } => {
+                bus.enter_mode(command::SPI_MODE, b"SPI1")?;
+                bus.set_speed(speed_khz)?;
+                let config = command::SPI_CONFIG | u8::from(polarity) << 2 | u8::from(phase) << 1 | u8::from(lsb_first);
+                bus.configure(&[config])?;
+                bus.write_then_read = command::SPI_WRITE_THEN_READ;
+            }
+        }
+        bus.port.set_timeout(timeout)?;
+        Ok(bus)
+    }
+
+    /// Sends the mode command until the BusPal responds with `expected`
+    fn enter_mode(&mut self, mode: u8, expected: &[u8]) -> ResultComm<()> {
+        let mut response = vec![0; expected.len()];
+        for attempt in 1..=RESET_COUNT {
+            self.port.write_all(&[mode])?;
+            if self.port.read_exact(&mut response).is_ok() && response == expected {
+                trace!("BusPal entered mode {mode:#04X} in {attempt}. attempt");
+                return Ok(());
+            }
+        }
+        debug!("BusPal responded {response:02X?} to mode {mode:#04X}");
+        Err(CommunicationError::InvalidHeader)
+    }
+
+    fn set_speed(&mut self, speed_khz: u32) -> ResultComm<()> {
+        let mut command = vec![command::SET_SPEED];
+        command.extend(speed_khz.to_le_bytes());
+        self.configure(&command)
+    }
+
+    /// Sends a configuration command and checks its response
+    fn configure(&mut self, command: &[u8]) -> ResultComm<()> {
+        self.port.write_all(command)?;
+        self.check_response()?;
+        Ok(())
+    }
+
+    fn check_response(&mut self) -> io::Result<()> {
+        let mut response = [0u8];
+        self.port.read_exact(&mut response)?;
+        if response[0] == RESPONSE_OK {
+            Ok(())
+        } else {
+            Err(io::Error::new(
+                ErrorKind::InvalidData,
+                format!("BusPal responded {:#04X}", response[0]),
+            ))
+        }
+    }
+
+    /// Sends the write-then-read command header
+    fn start_transfer(&mut self, write: usize, read: usize) -> io::Result<()> {
+        let mut header = vec![self.write_then_read];
+        header.extend((write as u16).to_le_bytes());
+        header.extend((read as u16).to_le_bytes());
+        self.port.write_all(&header)
+    }
+}
+
+impl Read for BusPalBus {
+    /// Reads the whole buffer in one bus transaction
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let length = buf.len().min(BULK_TRANSFER_MAX);
+        self.start_transfer(0, length)?;
+        self.check_response()?;
+        self.port.read_exact(&mut buf[..length])?;
+        Ok(length)
+    }
+}
+
+impl Write for BusPalBus {
+    /// Writes the whole buffer in one bus transaction
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let length = buf.len().min(BULK_TRANSFER_MAX);
+        self.start_transfer(length, 0)?;
+        self.port.write_all(&buf[..length])?;
+        self.check_response()?;
+        Ok(length)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.port.flush()
+    }
+}
+
+/// McuBoot protocol tunnelled through BusPal
+#[derive(Debug)]
+pub struct BusPalProtocol {
+    inner: I2CProtocol,
+}
+
+impl BusPalProtocol {
+    /// Opens the BusPal on a serial port, configures the bus and pings the device behind it
+    ///
+    /// # Arguments
+    /// * `port` - Serial port of the BusPal, local or `rfc2217://<host>:<port>`
+    /// * `baudrate` - Baudrate of the serial port
+    /// * `target` - Bus behind the BusPal
+    /// * `timeout` - Operation timeout duration
+    /// * `polling_interval` - Response polling interval
+    /// * `framing` - Start byte and CRC usage of the frames
+    ///
+    /// # Errors
+    /// Any error of the serial port, [`CommunicationError::InvalidHeader`] if the BusPal does not switch to
+    /// the requested mode and errors of the ping.
+    pub fn open(
+        port: &str,
+        baudrate: u32,
+        target: BusPalTarget,
+        timeout: Duration,
+        polling_interval: Duration,
+        framing: Framing,
+    ) -> ResultComm<Self> {
+        let serial = super::uart::open_port(port, baudrate, timeout, super::uart::FlowControl::None)?;
+        let bus = BusPalBus::open(serial, target)?;
+        info!("Opened BusPal at {port} with {target:?}");
+
+        let address = match target {
+            BusPalTarget::I2c { address, .. } => address,
+            BusPalTarget::Spi { .. } => 0,
+        };
+        let inner = I2CProtocol::with_bus(
+            format!("{port} (BusPal)"),
+            Box::new(bus),
+            address,
+            timeout,
+            polling_interval,
+            framing,
+        )?;
+        Ok(BusPalProtocol { inner })
+    }
+}
+
+impl Protocol for BusPalProtocol {
+    fn get_timeout(&self) -> Duration {
+        self.inner.get_timeout()
+    }
+
+    fn set_timeout(&mut self, timeout: Duration) -> ResultComm<()> {
+        self.inner.set_timeout(timeout)
+    }
+
+    fn get_polling_interval(&self) -> Duration {
+        self.inner.get_polling_interval()
+    }
+
+    fn get_identifier(&self) -> &str {
+        self.inner.get_identifier()
+    }
+
+    fn ping_response(&self) -> Option<PingResponse> {
+        self.inner.ping_response()
+    }
+
+    fn apply_quirks(&mut self, quirks: &Quirks) {
+        self.inner.apply_quirks(quirks);
+    }
+
+    fn transport_stats(&self) -> Option<TransportStats> {
+        self.inner.transport_stats()
+    }
+
+    fn read(&mut self, bytes: usize) -> ResultComm<Vec<u8>> {
+        self.inner.read(bytes)
+    }
+
+    fn write_packet_raw(&mut self, data: &[u8]) -> ResultComm<()> {
+        self.inner.write_packet_raw(data)
+    }
+
+    fn write_packet(&mut self, packet_code: u8, payload: &[u8]) -> ResultComm<()> {
+        self.inner.write_packet(packet_code, payload)
+    }
+
+    fn read_packet_raw(&mut self, packet_code: u8) -> ResultComm<Vec<u8>> {
+        self.inner.read_packet_raw(packet_code)
+    }
+
+    fn read_packet_abort(&mut self, packet_code: u8) -> ResultComm<Vec<u8>> {
+        self.inner.read_packet_abort(packet_code)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::BusPalTarget;
+
+    #[test]
+    fn parse_target() {
+        assert_eq!(
+            BusPalTarget::parse("i2c").unwrap(),
+            BusPalTarget::I2c {
+                address: 0x10,
+                speed_khz: 100
+            }
+        );
+        assert_eq!(
+            BusPalTarget::parse("i2c,0x3A,400").unwrap(),
+            BusPalTarget::I2c {
+                address: 0x3A,
+                speed_khz: 400
+            }
+        );
+        assert_eq!(
+            BusPalTarget::parse("spi,1000,0,1,lsb").unwrap(),
+            BusPalTarget::Spi {
+                speed_khz: 1000,
+                polarity: false,
+                phase: true,
+                lsb_first: true
+            }
+        );
+        assert!(BusPalTarget::parse("can").is_err());
+        assert!(BusPalTarget::parse("spi,100,2").is_err());
+        assert!(BusPalTarget::parse("i2c,0x100").is_err());
+        assert!(BusPalTarget::parse("i2c,0x10,100,1").is_err());
+    }
+}
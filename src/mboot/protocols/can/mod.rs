@@ -0,0 +1,317 @@
+// Copyright 2025 NXP
+//
+// SPDX-License-Identifier: BSD-3-Clause
+//! CAN transport
+//!
+//! The bootloader streams the same bytes as over UART, split into CAN frames with up to 8 data bytes. The
+//! host sends frames with one identifier and receives frames with another, identifiers and the interface
+//! are selected by `<interface>[,<rx_id>,<tx_id>[,<bitrate>]]`, e.g. `can0,0x123,0x321`.
+//!
+//! [`CANProtocol`] exchanges the frames through a platform backend, SocketCAN is available on Linux.
+
+use std::{
+    collections::VecDeque,
+    fmt::Debug,
+    io::{self, Read, Write},
+    time::Duration,
+};
+
+use log::{info, warn};
+
+use super::{CommunicationError, Protocol, ProtocolOpen, i2c::I2CProtocol};
+use crate::{
+    mboot::{
+        ResultComm,
+        packets::{Framing, ping::PingResponse},
+        session::Quirks,
+    },
+    parse::parse_number,
+};
+
+mod socketcan;
+
+/// Identifier of frames sent by the device
+pub const DEFAULT_RX_ID: u32 = 0x123;
+/// Identifier of frames sent by the host
+pub const DEFAULT_TX_ID: u32 = 0x321;
+/// Bitrate the bootloader detects by default
+pub const DEFAULT_BITRATE: u32 = 1_000_000;
+/// Data bytes of a classic CAN frame
+const FRAME_DATA_SIZE: usize = 8;
+/// Largest extended frame identifier
+const MAX_ID: u32 = 0x1FFF_FFFF;
+
+/// Interface exchanging CAN frames
+pub(crate) trait CanBackend: Debug + Send {
+    /// Sends a frame with `id` and up to 8 bytes of `data`
+    fn send(&mut self, id: u32, data: &[u8]) -> io::Result<()>;
+
+    /// Waits for the next frame accepted by the receive filter and returns its data
+    ///
+    /// Fails with [`io::ErrorKind::TimedOut`] if no frame arrives in `timeout`.
+    fn receive(&mut self, timeout: Duration) -> io::Result<Vec<u8>>;
+}
+
+/// Interface and identifiers parsed from the identifier
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct CanTarget<'a> {
+    interface: &'a str,
+    rx_id: u32,
+    tx_id: u32,
+    bitrate: Option<u32>,
+}
+
+fn parse_identifier(identifier: &str) -> ResultComm<CanTarget<'_>> {
+    let invalid = |reason: String| CommunicationError::ParseError(format!("invalid CAN identifier: {reason}"));
+    let mut parts = identifier.split(',').map(str::trim);
+    let interface = parts
+        .next()
+        .filter(|interface| !interface.is_empty())
+        .ok_or_else(|| invalid("expected <interface>[,<rx_id>,<tx_id>[,<bitrate>]]".to_owned()))?;
+    let mut next_number = || parts.next().map(parse_number::<u32>).transpose().map_err(invalid);
+    let rx_id = next_number()?.unwrap_or(DEFAULT_RX_ID);
+    let tx_id = next_number()?.unwrap_or(DEFAULT_TX_ID);
+    let bitrate = next_number()?;
+    if parts.next().is_some() {
+        return Err(invalid("too many parameters".to_owned()));
+    }
+    if let Some(id) = [rx_id, tx_id].into_iter().find(|&id| id > MAX_ID) {
+        return Err(invalid(format!("identifier {id:#X} is out of range")));
+    }
+    Ok(CanTarget {
+        interface,
+        rx_id,
+        tx_id,
+        bitrate,
+    })
+}
+
+/// Byte stream carried by CAN frames
+#[derive(Debug)]
+struct CanStream {
+    backend: Box<dyn CanBackend>,
+    tx_id: u32,
+    timeout: Duration,
+    received: VecDeque<u8>,
+}
+
+impl Read for CanStream {
+    /// Returns bytes of already received frames, waiting for a frame only if there are none
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        while self.received.is_empty() {
+            self.received.extend(self.backend.receive(self.timeout)?);
+        }
+        let length = buf.len().min(self.received.len());
+        for (byte, received) in buf.iter_mut().zip(self.received.drain(..length)) {
+            *byte = received;
+        }
+        Ok(length)
+    }
+}
+
+impl Write for CanStream {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        for chunk in buf.chunks(FRAME_DATA_SIZE) {
+            self.backend.send(self.tx_id, chunk)?;
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// McuBoot protocol over a CAN bus
+#[derive(Debug)]
+pub struct CANProtocol {
+    inner: I2CProtocol,
+}
+
+impl ProtocolOpen for CANProtocol {
+    fn open(identifier: &str) -> ResultComm<Self> {
+        Self::open_with_options(identifier, 0, Duration::from_secs(5), Duration::from_millis(1))
+    }
+
+    fn open_with_options(
+        identifier: &str,
+        baudrate: u32,
+        timeout: Duration,
+        polling_interval: Duration,
+    ) -> ResultComm<Self> {
+        Self::open_with_framing(identifier, baudrate, timeout, polling_interval, Framing::default())
+    }
+
+    fn open_with_framing(
+        identifier: &str,
+        _baudrate: u32,
+        timeout: Duration,
+        polling_interval: Duration,
+        framing: Framing,
+    ) -> ResultComm<Self> {
+        let target = parse_identifier(identifier)?;
+        if let Some(bitrate) = target.bitrate.filter(|&bitrate| bitrate != DEFAULT_BITRATE) {
+            warn!(
+                "bitrate {bitrate} is not applied, bitrate of SocketCAN interfaces is set by the system, e.g. \
+                 'ip link set {} type can bitrate {bitrate}'",
+                target.interface
+            );
+        }
+        let backend = socketcan::SocketCan::open(target.interface, target.rx_id)?;
+        info!(
+            "Opened CAN interface {} receiving {:#X} and sending {:#X}",
+            target.interface, target.rx_id, target.tx_id
+        );
+
+        let stream = CanStream {
+            backend: Box::new(backend),
+            tx_id: target.tx_id,
+            timeout,
+            received: VecDeque::new(),
+        };
+        let inner = I2CProtocol::with_bus(
+            identifier.to_owned(),
+            Box::new(stream),
+            0,
+            timeout,
+            polling_interval,
+            framing,
+        )?;
+        Ok(CANProtocol { inner })
+    }
+}
+
+impl Protocol for CANProtocol {
+    fn get_timeout(&self) -> Duration {
+        self.inner.get_timeout()
+    }
+
+    fn set_timeout(&mut self, timeout: Duration) -> ResultComm<()> {
+        self.inner.set_timeout(timeout)
+    }
+
+    fn get_polling_interval(&self) -> Duration {
+        self.inner.get_polling_interval()
+    }
+
+    fn get_identifier(&self) -> &str {
+        self.inner.get_identifier()
+    }
+
+    fn ping_response(&self) -> Option<PingResponse> {
+        self.inner.ping_response()
+    }
+
+    fn apply_quirks(&mut self, quirks: &Quirks) {
+        self.inner.apply_quirks(quirks);
+    }
+
+    fn read(&mut self, bytes: usize) -> ResultComm<Vec<u8>> {
+        self.inner.read(bytes)
+    }
+
+    fn write_packet_raw(&mut self, data: &[u8]) -> ResultComm<()> {
+        self.inner.write_packet_raw(data)
+    }
+
+    fn write_packet(&mut self, packet_code: u8, payload: &[u8]) -> ResultComm<()> {
+        self.inner.write_packet(packet_code, payload)
+    }
+
+    fn read_packet_raw(&mut self, packet_code: u8) -> ResultComm<Vec<u8>> {
+        self.inner.read_packet_raw(packet_code)
+    }
+
+    fn read_packet_abort(&mut self, packet_code: u8) -> ResultComm<Vec<u8>> {
+        self.inner.read_packet_abort(packet_code)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        io::ErrorKind,
+        sync::{Arc, Mutex},
+    };
+
+    use super::*;
+
+    #[test]
+    fn identifiers() {
+        assert_eq!(
+            parse_identifier("can0").unwrap(),
+            CanTarget {
+                interface: "can0",
+                rx_id: DEFAULT_RX_ID,
+                tx_id: DEFAULT_TX_ID,
+                bitrate: None
+            }
+        );
+        assert_eq!(
+            parse_identifier("vcan1,0x100,0x200,500000").unwrap(),
+            CanTarget {
+                interface: "vcan1",
+                rx_id: 0x100,
+                tx_id: 0x200,
+                bitrate: Some(500_000)
+            }
+        );
+        assert!(parse_identifier("").is_err());
+        assert!(parse_identifier("can0,0x100,0x20000000").is_err());
+        assert!(parse_identifier("can0,x").is_err());
+        assert!(parse_identifier("can0,1,2,3,4").is_err());
+    }
+
+    type SentFrames = Arc<Mutex<Vec<(u32, Vec<u8>)>>>;
+
+    /// Backend recording sent frames and returning prepared ones
+    #[derive(Debug, Default)]
+    struct Loopback {
+        sent: SentFrames,
+        incoming: VecDeque<Vec<u8>>,
+    }
+
+    impl CanBackend for Loopback {
+        fn send(&mut self, id: u32, data: &[u8]) -> io::Result<()> {
+            self.sent.lock().unwrap().push((id, data.to_vec()));
+            Ok(())
+        }
+
+        fn receive(&mut self, _timeout: Duration) -> io::Result<Vec<u8>> {
+            self.incoming.pop_front().ok_or(ErrorKind::TimedOut.into())
+        }
+    }
+
+    #[test]
+    fn stream_splits_frames() {
+        let sent = SentFrames::default();
+        let backend = Loopback {
+            sent: Arc::clone(&sent),
+            incoming: VecDeque::from([vec![1, 2, 3], vec![4, 5]]),
+        };
+        let mut stream = CanStream {
+            backend: Box::new(backend),
+            tx_id: DEFAULT_TX_ID,
+            timeout: Duration::ZERO,
+            received: VecDeque::new(),
+        };
+
+        stream.write_all(&(0..10).collect::<Vec<u8>>()).unwrap();
+        assert_eq!(
+            *sent.lock().unwrap(),
+            [
+                (DEFAULT_TX_ID, vec![0, 1, 2, 3, 4, 5, 6, 7]),
+                (DEFAULT_TX_ID, vec![8, 9])
+            ]
+        );
+
+        let mut buf = [0; 4];
+        stream.read_exact(&mut buf).unwrap();
+        assert_eq!(buf, [1, 2, 3, 4]);
+        assert_eq!(stream.read(&mut buf).unwrap(), 1);
+        assert_eq!(stream.read(&mut buf).unwrap_err().kind(), ErrorKind::TimedOut);
+    }
+}
@@ -0,0 +1,150 @@
+// Copyright 2025 NXP
+//
+// SPDX-License-Identifier: BSD-3-Clause
+//! SocketCAN backend
+
+use crate::{CommunicationError, mboot::ResultComm};
+
+/// Raw CAN socket bound to one interface, receiving only frames with one identifier
+#[cfg(target_os = "linux")]
+#[derive(Debug)]
+pub(super) struct SocketCan {
+    socket: std::os::fd::OwnedFd,
+}
+
+#[cfg(target_os = "linux")]
+impl SocketCan {
+    /// Opens a raw socket on `interface` with a filter passing frames with `rx_id`
+    pub(super) fn open(interface: &str, rx_id: u32) -> ResultComm<Self> {
+        use std::{
+            ffi::CString,
+            io,
+            mem::{self, size_of},
+            os::fd::{AsRawFd, FromRawFd, OwnedFd},
+        };
+
+        let name = CString::new(interface)
+            .map_err(|_| CommunicationError::ParseError(format!("invalid CAN interface '{interface}'")))?;
+        let index = unsafe { libc::if_nametoindex(name.as_ptr()) };
+        if index == 0 {
+            return Err(io::Error::last_os_error().into());
+        }
+
+        let fd = unsafe { libc::socket(libc::PF_CAN, libc::SOCK_RAW, libc::CAN_RAW) };
+        if fd < 0 {
+            return Err(io::Error::last_os_error().into());
+        }
+        let socket = unsafe { OwnedFd::from_raw_fd(fd) };
+
+        let filter = libc::can_filter {
+            can_id: frame_id(rx_id),
+            can_mask: libc::CAN_EFF_FLAG | libc::CAN_RTR_FLAG | libc::CAN_EFF_MASK,
+        };
+        let result = unsafe {
+            libc::setsockopt(
+                socket.as_raw_fd(),
+                libc::SOL_CAN_RAW,
+                libc::CAN_RAW_FILTER,
+                (&raw const filter).cast(),
+                size_of::<libc::can_filter>() as libc::socklen_t,
+            )
+        };
+        if result < 0 {
+            return Err(io::Error::last_os_error().into());
+        }
+
+        let mut address: libc::sockaddr_can = unsafe { mem::zeroed() };
+        address.can_family = libc::AF_CAN as libc::sa_family_t;
+        address.can_ifindex = index.cast_signed();
+        let result = unsafe {
+            libc::bind(
+                socket.as_raw_fd(),
+                (&raw const address).cast(),
+                size_of::<libc::sockaddr_can>() as libc::socklen_t,
+            )
+        };
+        if result < 0 {
+            return Err(io::Error::last_os_error().into());
+        }
+        Ok(SocketCan { socket })
+    }
+}
+
+/// Identifier with the extended frame flag for identifiers not fitting into a standard frame
+#[cfg(target_os = "linux")]
+fn frame_id(id: u32) -> libc::canid_t {
+    if id > libc::CAN_SFF_MASK {
+        id | libc::CAN_EFF_FLAG
+    } else {
+        id
+    }
+}
+
+#[cfg(target_os = "linux")]
+impl super::CanBackend for SocketCan {
+    fn send(&mut self, id: u32, data: &[u8]) -> std::io::Result<()> {
+        use std::{io, mem, os::fd::AsRawFd};
+
+        let mut frame: libc::can_frame = unsafe { mem::zeroed() };
+        frame.can_id = frame_id(id);
+        frame.can_dlc = data.len() as u8;
+        frame.data[..data.len()].copy_from_slice(data);
+        let written = unsafe { libc::write(self.socket.as_raw_fd(), (&raw const frame).cast(), libc::CAN_MTU) };
+        if written < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(())
+    }
+
+    fn receive(&mut self, timeout: std::time::Duration) -> std::io::Result<Vec<u8>> {
+        use std::{
+            io::{self, ErrorKind},
+            mem,
+            os::fd::AsRawFd,
+        };
+
+        let mut poll = libc::pollfd {
+            fd: self.socket.as_raw_fd(),
+            events: libc::POLLIN,
+            revents: 0,
+        };
+        let timeout = libc::c_int::try_from(timeout.as_millis()).unwrap_or(libc::c_int::MAX);
+        let ready = unsafe { libc::poll(&raw mut poll, 1, timeout) };
+        match ready {
+            0 => return Err(ErrorKind::TimedOut.into()),
+            ..0 => return Err(io::Error::last_os_error()),
+            _ => {}
+        }
+
+        let mut frame: libc::can_frame = unsafe { mem::zeroed() };
+        let read = unsafe { libc::read(self.socket.as_raw_fd(), (&raw mut frame).cast(), libc::CAN_MTU) };
+        if read < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        let length = usize::from(frame.can_dlc).min(frame.data.len());
+        Ok(frame.data[..length].to_vec())
+    }
+}
+
+/// SocketCAN is available only on Linux
+#[cfg(not(target_os = "linux"))]
+#[derive(Debug)]
+pub(super) struct SocketCan;
+
+#[cfg(not(target_os = "linux"))]
+impl SocketCan {
+    pub(super) fn open(_interface: &str, _rx_id: u32) -> ResultComm<Self> {
+        Err(CommunicationError::UnsupportedPlatform)
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+impl super::CanBackend for SocketCan {
+    fn send(&mut self, _id: u32, _data: &[u8]) -> std::io::Result<()> {
+        unreachable!("SocketCAN cannot be opened on this platform")
+    }
+
+    fn receive(&mut self, _timeout: std::time::Duration) -> std::io::Result<Vec<u8>> {
+        unreachable!("SocketCAN cannot be opened on this platform")
+    }
+}
@@ -0,0 +1,192 @@
+// Copyright 2025 NXP
+//
+// SPDX-License-Identifier: BSD-3-Clause
+//! Synchronization on frames received over byte streams
+//!
+//! UART and I2C carry frames as a plain stream of bytes. After power cycle or reset, bootloaders since version
+//! 3.0 may send dummy data before the first frame, and a byte corrupted on a noisy link may look like a start
+//! byte or break the length of a frame. Readers of these transports skip bytes preceding the start byte and
+//! resynchronize on the next start byte when the CRC of a frame does not match. Frames which still do not match
+//! are answered with NACK, the device then sends them again.
+
+use log::{debug, trace, warn};
+
+use crate::mboot::{
+    ResultComm,
+    formatters::style::RX,
+    packets::{CRC_CHECK, Framing},
+    protocols::CommunicationError,
+};
+
+/// Bytes read while looking for the start byte before the read fails
+pub(super) const MAX_DUMMY_BYTES: usize = 50;
+
+/// Frames with a mismatched CRC skipped by resynchronizing on the next start byte before the read fails
+const MAX_RESYNC: usize = 3;
+
+/// Frames with a mismatched CRC answered with NACK, so the device sends them again, before the read fails
+pub(super) const MAX_RETRANSMISSIONS: usize = 3;
+
+/// Reads single bytes through `read` until `start_byte` is received
+///
+/// # Errors
+/// [`CommunicationError::InvalidHeader`] if none of [`MAX_DUMMY_BYTES`] bytes was the start byte, errors of
+/// `read` otherwise.
+pub(super) fn skip_to_start(start_byte: u8, read: &mut impl FnMut(usize) -> ResultComm<Vec<u8>>) -> ResultComm<()> {
+    let mut skipped = Vec::new();
+    while skipped.len() < MAX_DUMMY_BYTES {
+        let byte = read(1)?.first().copied().ok_or(CommunicationError::InvalidHeader)?;
+        if byte == start_byte {
+            if !skipped.is_empty() {
+                debug!(
+                    "{RX}: Skipped {} dummy bytes before the frame: {skipped:02X?}",
+                    skipped.len()
+                );
+            }
+            trace!("FRAME_START_BYTE received in {}. attempt.", skipped.len() + 1);
+            return Ok(());
+        }
+        skipped.push(byte);
+    }
+    Err(CommunicationError::InvalidHeader)
+}
+
+/// Receives a frame with `packet_code` through `read`, returns its header without the CRC followed by the payload
+///
+/// Bytes preceding the start byte are skipped. When the CRC does not match, the received bytes are scanned for
+/// the next start byte and the frame is read again from there.
+///
+/// # Errors
+/// [`CommunicationError::InvalidHeader`] if no start byte was received or the frame has another packet type,
+/// [`CommunicationError::InvalidCrc`] if no frame with a matching CRC was found, errors of `read` otherwise.
+pub(super) fn receive_frame(
+    framing: Framing,
+    packet_code: u8,
+    mut read: impl FnMut(usize) -> ResultComm<Vec<u8>>,
+) -> ResultComm<Vec<u8>> {
+    skip_to_start(framing.start_byte, &mut read)?;
+    let mut received = vec![framing.start_byte];
+    let mut resyncs = 0;
+    loop {
+        let frame = fill(&mut received, 2, &mut read).and_then(|()| {
+            if received[..2] == [framing.start_byte, packet_code] {
+                check_frame(framing, &mut received, &mut read)
+            } else if resyncs == 0 {
+                Err(CommunicationError::InvalidHeader)
+            } else {
+                Ok(None)
+            }
+        });
+        match frame {
+            Ok(Some(frame)) => return Ok(frame),
+            Ok(None) => {}
+            // a frame started by a corrupted byte may claim more bytes than the device sends
+            Err(_) if resyncs > 0 => return Err(CommunicationError::InvalidCrc),
+            Err(err) => return Err(err),
+        }
+
+        match received.iter().skip(1).position(|byte| *byte == framing.start_byte) {
+            Some(offset) if resyncs < MAX_RESYNC => {
+                resyncs += 1;
+                let skipped: Vec<u8> = received.drain(..=offset).collect();
+                debug!("{RX}: Resynchronizing, skipped {skipped:02X?}");
+            }
+            _ => return Err(CommunicationError::InvalidCrc),
+        }
+    }
+}
+
+/// Reads the rest of the frame started in `received`, [`None`] if its CRC does not match
+fn check_frame(
+    framing: Framing,
+    received: &mut Vec<u8>,
+    read: &mut impl FnMut(usize) -> ResultComm<Vec<u8>>,
+) -> ResultComm<Option<Vec<u8>>> {
+    let header_len = if framing.crc { 6 } else { 4 };
+    fill(received, 4, read)?;
+    let length = usize::from(u16::from_le_bytes([received[2], received[3]]));
+    fill(received, header_len + length, read)?;
+    let frame = [&received[..4], &received[header_len..header_len + length]].concat();
+    let crc = framing.crc.then(|| u16::from_le_bytes([received[4], received[5]]));
+    let computed = CRC_CHECK.checksum(&frame);
+    match crc {
+        Some(crc) if crc != computed => {
+            warn!("{RX}: CRC mismatch, received {crc:#06X}, computed {computed:#06X} over {frame:02X?}");
+            Ok(None)
+        }
+        _ => Ok(Some(frame)),
+    }
+}
+
+/// Reads through `read` until `received` has `length` bytes
+fn fill(received: &mut Vec<u8>, length: usize, read: &mut impl FnMut(usize) -> ResultComm<Vec<u8>>) -> ResultComm<()> {
+    if received.len() < length {
+        received.extend(read(length - received.len())?);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::VecDeque;
+
+    use super::*;
+    use crate::mboot::packets::construct_header;
+
+    /// Receives a command response frame from `bytes`, reading past them times out
+    fn receive(bytes: &[u8]) -> ResultComm<Vec<u8>> {
+        let mut bytes: VecDeque<u8> = bytes.iter().copied().collect();
+        receive_frame(Framing::STANDARD, 0xA4, |count| {
+            if bytes.len() < count {
+                return Err(CommunicationError::Timeout);
+            }
+            Ok(bytes.drain(..count).collect())
+        })
+    }
+
+    #[test]
+    fn frame_is_received() {
+        let frame = construct_header(0xA4, &[0xA0, 0, 0, 0]).unwrap();
+        assert_eq!(receive(&frame).unwrap(), [0x5A, 0xA4, 4, 0, 0xA0, 0, 0, 0]);
+        assert!(matches!(
+            receive(&construct_header(0xA5, &[1]).unwrap()),
+            Err(CommunicationError::InvalidHeader)
+        ));
+    }
+
+    #[test]
+    fn dummy_bytes_are_skipped() {
+        let frame = construct_header(0xA4, &[0xA0, 0, 0, 0]).unwrap();
+        let garbage = [0x00, 0xFF, 0x13];
+        assert_eq!(
+            receive(&[&garbage[..], &frame].concat()).unwrap(),
+            [0x5A, 0xA4, 4, 0, 0xA0, 0, 0, 0]
+        );
+
+        let garbage = [0xFF; MAX_DUMMY_BYTES];
+        assert!(matches!(
+            receive(&[&garbage[..], &frame].concat()),
+            Err(CommunicationError::InvalidHeader)
+        ));
+    }
+
+    #[test]
+    fn corrupted_start_is_resynchronized() {
+        let frame = construct_header(0xA4, &[0xA0, 0, 0, 0]).unwrap();
+        // noise looking like a start byte, its frame swallows the beginning of the real frame
+        let noisy = [&[0x5A, 0xA4, 2, 0, 0x12, 0x34][..], &frame].concat();
+        assert_eq!(receive(&noisy).unwrap(), [0x5A, 0xA4, 4, 0, 0xA0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn corrupted_payload_fails_with_crc_error() {
+        let mut frame = construct_header(0xA4, &[0xA0, 0x5A, 0, 0]).unwrap();
+        frame[6] = 0xA1;
+        assert!(matches!(receive(&frame), Err(CommunicationError::InvalidCrc)));
+
+        // the payload looks like the start of a frame longer than the received bytes
+        let mut frame = construct_header(0xA4, &[0xA0, 0x5A, 0xA4, 0x10, 0]).unwrap();
+        frame[6] = 0xA1;
+        assert!(matches!(receive(&frame), Err(CommunicationError::InvalidCrc)));
+    }
+}
@@ -0,0 +1,38 @@
+// Copyright 2025 NXP
+//
+// SPDX-License-Identifier: BSD-3-Clause
+//! Linux I2C device interface
+
+use std::fs::File;
+
+use crate::{CommunicationError, mboot::ResultComm};
+
+/// Opens the I2C bus device and sets the slave address
+///
+/// Note: This requires the i2c-dev kernel module to be loaded
+#[cfg(unix)]
+pub(super) fn open(path: &str, slave_address: u8) -> ResultComm<File> {
+    use std::{fs::OpenOptions, io, os::fd::AsRawFd};
+
+    let device = OpenOptions::new()
+        .read(true)
+        .write(true)
+        .open(path)
+        .map_err(CommunicationError::FileError)?;
+
+    // Set the slave address using ioctl
+    unsafe {
+        let i2c_slave = 0x0703; // I2C_SLAVE ioctl command
+        let result = libc::ioctl(device.as_raw_fd(), i2c_slave, libc::c_ulong::from(slave_address));
+        if result < 0 {
+            return Err(io::Error::last_os_error().into());
+        }
+    }
+    Ok(device)
+}
+
+/// I2C device interface is not available, only USB bridges can be used here
+#[cfg(not(unix))]
+pub(super) fn open(_path: &str, _slave_address: u8) -> ResultComm<File> {
+    Err(CommunicationError::UnsupportedPlatform)
+}
@@ -0,0 +1,204 @@
+// Copyright 2025 NXP
+//
+// SPDX-License-Identifier: BSD-3-Clause
+//! Microchip MCP2221A USB to I2C bridge
+//!
+//! The bridge is a USB-HID device controlled by 64-byte command reports, so it is usable on every
+//! platform supported by hidapi, including Windows where no I2C device interface is available.
+//! Transfers longer than one report are split into several reports of a single I2C transaction.
+
+use std::{
+    io::{self, ErrorKind, Read, Write},
+    thread,
+    time::{Duration, Instant},
+};
+
+use hidapi::{HidApi, HidDevice};
+use log::{debug, trace};
+
+use crate::{CommunicationError, mboot::ResultComm};
+
+const VID: u16 = 0x04D8;
+const PID: u16 = 0x00DD;
+
+/// Size of command and response reports
+const REPORT_SIZE: usize = 64;
+/// Data bytes carried by one report
+const CHUNK_SIZE: usize = 60;
+
+/// Command codes
+mod command {
+    pub const STATUS_SET_PARAMETERS: u8 = 0x10;
+    pub const I2C_WRITE: u8 = 0x90;
+    pub const I2C_READ: u8 = 0x91;
+    pub const I2C_GET_DATA: u8 = 0x40;
+}
+
+/// Status/set parameters: cancel the current I2C transfer
+const CANCEL_TRANSFER: u8 = 0x10;
+/// Status/set parameters: set the I2C clock divider
+const SET_SPEED: u8 = 0x20;
+/// Clock divider of the internal 12 MHz clock for 100 kHz
+const SPEED_DIVIDER: u8 = (12_000_000 / 100_000 - 3) as u8;
+/// Offset of the I2C engine state in the status response
+const STATE_OFFSET: usize = 8;
+/// Offset of the flags in the status response
+const FLAGS_OFFSET: usize = 20;
+/// Flag of the status response, the slave address was not acknowledged
+const ADDRESS_NACK: u8 = 0x40;
+/// Data length reported by I2C get data when the read failed
+const READ_ERROR: u8 = 0x7F;
+
+/// Longest time a single I2C transaction may take
+const TRANSACTION_TIMEOUT: Duration = Duration::from_millis(500);
+/// Delay between polls of the I2C engine
+const ENGINE_POLLING: Duration = Duration::from_millis(1);
+
+/// Opened MCP2221A bridge talking to one slave
+#[derive(Debug)]
+pub(super) struct Mcp2221 {
+    device: HidDevice,
+    slave_address: u8,
+}
+
+impl Mcp2221 {
+    /// Opens the first bridge, or the one with `serial`, and configures the I2C clock
+    pub(super) fn open(serial: Option<&str>, slave_address: u8) -> ResultComm<Self> {
+        let api =
+            HidApi::new().map_err(|e| CommunicationError::ParseError(format!("Failed to initialize HID API: {e}")))?;
+        let device = match serial {
+            Some(serial) => api.open_serial(VID, PID, serial),
+            None => api.open(VID, PID),
+        }
+        .map_err(|e| CommunicationError::ParseError(format!("Failed to open MCP2221A bridge: {e}")))?;
+
+        let mut bridge = Mcp2221 { device, slave_address };
+        // a transfer may be left unfinished by a previous session
+        let response = bridge.transact(&[
+            command::STATUS_SET_PARAMETERS,
+            0,
+            CANCEL_TRANSFER,
+            SET_SPEED,
+            SPEED_DIVIDER,
+        ])?;
+        debug!("MCP2221A opened, I2C state 0x{:02X}", response[STATE_OFFSET]);
+        Ok(bridge)
+    }
+
+    /// Sends a command report and reads its response
+    fn transact(&mut self, command: &[u8]) -> io::Result<[u8; REPORT_SIZE]> {
+        // the first byte is the report ID, which is not used by the bridge
+        let mut report = [0u8; REPORT_SIZE + 1];
+        report[1..=command.len()].copy_from_slice(command);
+        self.device.write(&report).map_err(io::Error::other)?;
+
+        let mut response = [0u8; REPORT_SIZE];
+        let timeout = TRANSACTION_TIMEOUT.as_millis() as i32;
+        let size = self
+            .device
+            .read_timeout(&mut response, timeout)
+            .map_err(io::Error::other)?;
+        if size == 0 {
+            return Err(ErrorKind::TimedOut.into());
+        }
+        if response[0] != command[0] {
+            return Err(io::Error::new(ErrorKind::InvalidData, "unexpected MCP2221A response"));
+        }
+        Ok(response)
+    }
+
+    /// Repeats `command` until the bridge accepts it
+    fn transact_until(
+        &mut self,
+        command: &[u8],
+        accepted: impl Fn(&[u8; REPORT_SIZE]) -> io::Result<bool>,
+    ) -> io::Result<[u8; REPORT_SIZE]> {
+        let start = Instant::now();
+        loop {
+            let response = self.transact(command)?;
+            if accepted(&response)? {
+                return Ok(response);
+            }
+            if start.elapsed() > TRANSACTION_TIMEOUT {
+                return Err(ErrorKind::TimedOut.into());
+            }
+            thread::sleep(ENGINE_POLLING);
+        }
+    }
+
+    fn i2c_write(&mut self, buf: &[u8]) -> io::Result<()> {
+        let length = transfer_length(buf.len())?;
+        for chunk in buf.chunks(CHUNK_SIZE) {
+            let mut report = vec![command::I2C_WRITE, length[0], length[1], self.slave_address << 1];
+            report.extend(chunk);
+            // the engine refuses the next chunk until it sends the previous one
+            self.transact_until(&report, |response| Ok(response[1] == 0))?;
+        }
+
+        // the write is finished when the engine is idle
+        self.transact_until(&[command::STATUS_SET_PARAMETERS], |status| {
+            if status[FLAGS_OFFSET] & ADDRESS_NACK != 0 {
+                return Err(io::Error::other("slave address was not acknowledged"));
+            }
+            Ok(status[STATE_OFFSET] == 0)
+        })?;
+        Ok(())
+    }
+
+    fn i2c_read(&mut self, buf: &mut [u8]) -> io::Result<()> {
+        let length = transfer_length(buf.len())?;
+        let response = self.transact(&[command::I2C_READ, length[0], length[1], self.slave_address << 1 | 1])?;
+        if response[1] != 0 {
+            return Err(io::Error::other("I2C read was not started"));
+        }
+
+        for chunk in buf.chunks_mut(CHUNK_SIZE) {
+            let response = self.transact_until(&[command::I2C_GET_DATA], |response| {
+                Ok(response[1] == 0 && response[3] != READ_ERROR && usize::from(response[3]) >= chunk.len())
+            })?;
+            chunk.copy_from_slice(&response[4..4 + chunk.len()]);
+        }
+        Ok(())
+    }
+
+    /// Brings the I2C engine to idle state after a failed transaction
+    fn cancel(&mut self) {
+        trace!("Cancelling MCP2221A I2C transfer");
+        if let Err(err) = self.transact(&[command::STATUS_SET_PARAMETERS, 0, CANCEL_TRANSFER]) {
+            debug!("Cancelling MCP2221A I2C transfer failed: {err}");
+        }
+    }
+}
+
+/// Length of an I2C transaction as sent to the bridge
+fn transfer_length(length: usize) -> io::Result<[u8; 2]> {
+    u16::try_from(length)
+        .map(u16::to_le_bytes)
+        .map_err(|_| io::Error::new(ErrorKind::InvalidInput, "I2C transfer is too long for MCP2221A"))
+}
+
+impl Read for Mcp2221 {
+    /// Reads the whole buffer in one I2C transaction
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        self.i2c_read(buf).inspect_err(|_| self.cancel())?;
+        Ok(buf.len())
+    }
+}
+
+impl Write for Mcp2221 {
+    /// Writes the whole buffer in one I2C transaction
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        self.i2c_write(buf).inspect_err(|_| self.cancel())?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
@@ -1,17 +1,100 @@
 // Copyright 2025 NXP
 //
 // SPDX-License-Identifier: BSD-3-Clause
+//! I2C transport
+//!
+//! Frames are exchanged either through the Linux I2C device interface (`/dev/i2c-X`), or through
+//! a USB to I2C bridge, which works on every platform:
+//! - `/dev/i2c-X[:0xYY]` - Linux I2C bus X
+//! - `mcp2221[:<serial>[:0xYY]]` - Microchip MCP2221A bridge, the first one or the one with the serial number
+//!
+//! `0xYY` is the slave address of the target, [`DEFAULT_SLAVE`] if not specified.
 
-#[cfg(unix)]
-mod i2c_unix;
+use std::{
+    fmt::Debug,
+    io::{Read, Write},
+};
 
-#[cfg(unix)]
-pub use i2c_unix::I2CProtocol;
+use crate::{CommunicationError, mboot::ResultComm, parse::parse_number};
 
-#[cfg(windows)]
-mod i2c_windows;
-#[cfg(windows)]
-pub use i2c_windows::I2CProtocol;
+mod dev;
+mod mcp2221;
+mod protocol;
+
+pub use protocol::I2CProtocol;
 
-#[allow(dead_code, reason = "not used on Windows")]
 pub const DEFAULT_SLAVE: u8 = 0x10;
+
+/// Byte transport to the I2C slave, every read and write is a single I2C transaction
+pub(crate) trait I2cBus: Read + Write + Debug + Send {}
+
+impl<T> I2cBus for T where T: Read + Write + Debug + Send {}
+
+/// I2C bus selected by the identifier
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum I2cTarget<'a> {
+    /// Path of the Linux I2C device
+    Device(&'a str),
+    /// MCP2221A bridge, optionally selected by its serial number
+    Mcp2221 { serial: Option<&'a str> },
+}
+
+/// Parses the I2C identifier into the bus and the slave address
+fn parse_identifier(identifier: &str) -> ResultComm<(I2cTarget<'_>, u8)> {
+    let parse_address = |address: Option<&str>| match address {
+        Some(address) => parse_number(address).map_err(CommunicationError::ParseError),
+        None => Ok(DEFAULT_SLAVE),
+    };
+
+    let mut parts = identifier.split(':');
+    let first = parts.next().unwrap_or_default();
+    let (target, address) = if first.eq_ignore_ascii_case("mcp2221") {
+        let serial = parts.next().filter(|serial| !serial.is_empty());
+        (I2cTarget::Mcp2221 { serial }, parts.next())
+    } else {
+        (I2cTarget::Device(first), parts.next())
+    };
+
+    if parts.next().is_some() {
+        return Err(CommunicationError::ParseError(format!(
+            "invalid I2C identifier '{identifier}', expected /dev/i2c-X[:0xYY] or mcp2221[:<serial>[:0xYY]]"
+        )));
+    }
+    Ok((target, parse_address(address)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn identifiers() {
+        assert_eq!(
+            parse_identifier("/dev/i2c-1").unwrap(),
+            (I2cTarget::Device("/dev/i2c-1"), DEFAULT_SLAVE)
+        );
+        assert_eq!(
+            parse_identifier("/dev/i2c-1:0x3A").unwrap(),
+            (I2cTarget::Device("/dev/i2c-1"), 0x3A)
+        );
+        assert_eq!(
+            parse_identifier("mcp2221").unwrap(),
+            (I2cTarget::Mcp2221 { serial: None }, DEFAULT_SLAVE)
+        );
+        assert_eq!(
+            parse_identifier("MCP2221::0x3A").unwrap(),
+            (I2cTarget::Mcp2221 { serial: None }, 0x3A)
+        );
+        assert_eq!(
+            parse_identifier("mcp2221:0001234567:0x11").unwrap(),
+            (
+                I2cTarget::Mcp2221 {
+                    serial: Some("0001234567")
+                },
+                0x11
+            )
+        );
+        assert!(parse_identifier("/dev/i2c-1:0x3A:1").is_err());
+        assert!(parse_identifier("/dev/i2c-1:xyz").is_err());
+    }
+}
@@ -0,0 +1,559 @@
+// Copyright 2025 NXP
+//
+// SPDX-License-Identifier: BSD-3-Clause
+
+use std::{
+    io::{self, Read, Write},
+    time::{Duration, Instant},
+};
+
+use crate::mboot::formatters::style::{RX, TX};
+use log::{Level, debug, error, info, trace, warn};
+
+use super::{I2cBus, I2cTarget, mcp2221::Mcp2221, parse_identifier};
+use crate::mboot::{
+    ResultComm,
+    packets::{
+        Framing, Packet, PacketParse,
+        ping::{Ping, PingResponse},
+    },
+    protocols::{
+        ACK, ACK_ABORT, NACK, Protocol, ProtocolOpen, TransportStats, frame_sync, poll_schedule::PollSchedule,
+        repeated_log::RepeatedLog,
+    },
+    session::Quirks,
+};
+
+use crate::CommunicationError;
+
+#[derive(Debug)]
+pub struct I2CProtocol {
+    interface: String,
+    device: Box<dyn I2cBus>,
+    slave_address: u8,
+    timeout: Duration,
+    polling_interval: Duration,
+    framing: Framing,
+    /// Response to the last ping
+    ping_response: Option<PingResponse>,
+    /// Statistics of polling for ACKs
+    stats: TransportStats,
+}
+
+impl ProtocolOpen for I2CProtocol {
+    fn open(identifier: &str) -> ResultComm<Self> {
+        Self::open_with_options(identifier, 0, Duration::from_secs(5), Duration::from_millis(1))
+    }
+
+    fn open_with_options(
+        identifier: &str,
+        baudrate: u32,
+        timeout: Duration,
+        polling_interval: Duration,
+    ) -> ResultComm<Self> {
+        Self::open_with_framing(identifier, baudrate, timeout, polling_interval, Framing::default())
+    }
+
+    fn open_with_framing(
+        identifier: &str,
+        _baudrate: u32,
+        timeout: Duration,
+        polling_interval: Duration,
+        framing: Framing,
+    ) -> ResultComm<Self> {
+        let (target, slave_address) = parse_identifier(identifier)?;
+        let device: Box<dyn I2cBus> = match target {
+            I2cTarget::Device(path) => Box::new(super::dev::open(path, slave_address)?),
+            I2cTarget::Mcp2221 { serial } => Box::new(Mcp2221::open(serial, slave_address)?),
+        };
+
+        info!(
+            "Opened I2C device {} with slave address 0x{:02X} with {}ms timeout",
+            identifier,
+            slave_address,
+            timeout.as_millis()
+        );
+        Self::with_bus(
+            identifier.to_owned(),
+            device,
+            slave_address,
+            timeout,
+            polling_interval,
+            framing,
+        )
+    }
+}
+
+impl I2CProtocol {
+    /// Creates the protocol on top of an opened bus and pings the device
+    ///
+    /// Besides I2C buses, it is used for other polled buses with the same frame exchange, such as SPI
+    /// tunnelled through BusPal.
+    pub(crate) fn with_bus(
+        interface: String,
+        device: Box<dyn I2cBus>,
+        slave_address: u8,
+        timeout: Duration,
+        polling_interval: Duration,
+        framing: Framing,
+    ) -> ResultComm<Self> {
+        let mut device = I2CProtocol {
+            interface,
+            device,
+            slave_address,
+            timeout,
+            polling_interval,
+            framing,
+            ping_response: None,
+            stats: TransportStats::default(),
+        };
+
+        // Test connection with ping
+        device.ping()?;
+
+        Ok(device)
+    }
+}
+
+impl Protocol for I2CProtocol {
+    fn get_timeout(&self) -> Duration {
+        self.timeout
+    }
+
+    fn set_timeout(&mut self, timeout: Duration) -> ResultComm<()> {
+        self.timeout = timeout;
+        Ok(())
+    }
+
+    fn get_polling_interval(&self) -> Duration {
+        self.polling_interval
+    }
+
+    fn get_identifier(&self) -> &str {
+        &self.interface
+    }
+
+    fn ping_response(&self) -> Option<PingResponse> {
+        self.ping_response
+    }
+
+    fn apply_quirks(&mut self, quirks: &Quirks) {
+        if let Some(framing) = quirks.framing {
+            self.framing = framing;
+        }
+    }
+
+    fn transport_stats(&self) -> Option<TransportStats> {
+        Some(self.stats)
+    }
+
+    fn read(&mut self, bytes: usize) -> ResultComm<Vec<u8>> {
+        let mut buf = vec![0u8; bytes];
+        self.read_static(&mut buf)?;
+        Ok(buf)
+    }
+
+    fn write_packet_raw(&mut self, data: &[u8]) -> ResultComm<()> {
+        self.write(&self.framing.encode(data)?)?;
+        self.read_ack()?;
+        Ok(())
+    }
+
+    fn write_packet(&mut self, packet_code: u8, payload: &[u8]) -> ResultComm<()> {
+        self.write(&self.framing.frame(packet_code, payload)?)?;
+        self.read_ack()?;
+        Ok(())
+    }
+
+    fn read_packet_raw(&mut self, packet_code: u8) -> ResultComm<Vec<u8>> {
+        self.read_frame(packet_code, ACK)
+    }
+
+    fn read_packet_abort(&mut self, packet_code: u8) -> ResultComm<Vec<u8>> {
+        self.read_frame(packet_code, ACK_ABORT)
+    }
+}
+
+impl I2CProtocol {
+    /// Reads a frame and answers it with `ack`, [`ACK`] or [`ACK_ABORT`]
+    ///
+    /// A frame with a mismatched CRC is answered with [`NACK`] and received again, up to
+    /// [`frame_sync::MAX_RETRANSMISSIONS`] times.
+    fn read_frame(&mut self, packet_code: u8, ack: u8) -> ResultComm<Vec<u8>> {
+        let framing = self.framing;
+        let mut retransmissions = 0;
+        let frame = loop {
+            let frame = frame_sync::receive_frame(framing, packet_code, |bytes| self.read(bytes));
+            if matches!(frame, Err(CommunicationError::InvalidCrc)) && retransmissions < frame_sync::MAX_RETRANSMISSIONS
+            {
+                retransmissions += 1;
+                warn!("{RX}: Requesting retransmission {retransmissions} of the frame");
+                self.send_ack(NACK)?;
+                continue;
+            }
+            if matches!(frame, Ok(_) | Err(CommunicationError::InvalidCrc)) {
+                self.send_ack(ack)?;
+            }
+            break frame?;
+        };
+
+        if frame.len() == 4 {
+            error!("{RX}: Data aborted by sender!");
+            return Err(CommunicationError::Aborted);
+        }
+
+        Ok(frame[4..].to_vec())
+    }
+    fn read_static(&mut self, buf: &mut [u8]) -> Result<(), io::Error> {
+        self.device.read_exact(buf)?;
+        debug!("{RX}: {buf:02X?}");
+        Ok(())
+    }
+
+    fn write(&mut self, buf: &[u8]) -> Result<(), io::Error> {
+        debug!("{TX}: {buf:02X?}");
+        self.device.write_all(buf)
+    }
+
+    fn ping(&mut self) -> ResultComm<PingResponse> {
+        trace!("Pinging device with slave address 0x{:02X}", self.slave_address);
+        self.write(&[self.framing.start_byte, Ping::get_code()])?;
+
+        // After power cycle, MBoot v3.0+ may respond with leading dummy data
+        frame_sync::skip_to_start(self.framing.start_byte, &mut |bytes| self.read(bytes))?;
+
+        // Read frame type (should be PingResponse code)
+        let mut frame_type = [0u8; 1];
+        self.device.read_exact(&mut frame_type)?;
+
+        if frame_type[0] != PingResponse::get_code() {
+            return Err(CommunicationError::InvalidHeader);
+        }
+
+        // Read the rest of the response, 6 bytes and 2 bytes of CRC if the framing has it
+        let mut response_data = [0u8; 8];
+        let response_data = &mut response_data[..if self.framing.crc { 8 } else { 6 }];
+        self.device.read_exact(response_data)?;
+
+        // Combine all parts for CRC check and debug output
+        let mut buf = vec![self.framing.start_byte, frame_type[0]];
+        buf.extend_from_slice(response_data);
+
+        debug!("{RX}: {buf:02X?}");
+
+        let crc = match buf.get(8..) {
+            Some(crc) if self.framing.crc => Some(u16::from_le_bytes(
+                crc.try_into().or(Err(CommunicationError::InvalidHeader))?,
+            )),
+            _ => None,
+        };
+
+        if !self.framing.check_crc(&buf[..8], crc) {
+            return Err(CommunicationError::InvalidCrc);
+        }
+
+        let res = PingResponse::parse(&buf)?;
+        self.ping_response = Some(res);
+        Ok(res)
+    }
+
+    fn send_ack(&mut self, ack: u8) -> Result<(), std::io::Error> {
+        trace!(
+            "Sending {}",
+            match ack {
+                ACK_ABORT => "ACK abort",
+                NACK => "NACK",
+                _ => "ACK",
+            }
+        );
+        self.write(&[self.framing.start_byte, ack])
+    }
+
+    fn read_ack(&mut self) -> ResultComm<()> {
+        let start = Instant::now();
+        let result = self.poll_ack();
+        self.stats.acks += 1;
+        if matches!(result, Err(CommunicationError::Timeout)) {
+            self.stats.timeouts += 1;
+        }
+        self.stats.longest_wait = self.stats.longest_wait.max(start.elapsed());
+        result
+    }
+
+    fn poll_ack(&mut self) -> ResultComm<()> {
+        let timeout = self.get_timeout();
+        let polling_interval = self.get_polling_interval();
+        let mut schedule = PollSchedule::new(timeout, polling_interval);
+        let mut buf = [0u8; 2];
+
+        trace!(
+            "Reading ACK with timeout {}ms and polling interval {}ms",
+            timeout.as_millis(),
+            polling_interval.as_millis()
+        );
+
+        // busy frames are read on every poll, log them only once
+        let mut polling_log = RepeatedLog::new("ACK polling", Level::Trace);
+
+        while schedule.wait() {
+            self.stats.polls += 1;
+            if self.device.read_exact(&mut buf).is_ok() {
+                // If we get 0x00, it means the device is busy, so we should continue polling
+                if buf[0] == 0x00 {
+                    self.stats.busy += 1;
+                    polling_log.log(|| "Device busy (received 0x00), continuing to poll".to_owned());
+                    continue;
+                }
+
+                // Check for the frame start marker
+                if buf[0] != self.framing.start_byte {
+                    self.stats.invalid += 1;
+                    polling_log.log(|| format!("Invalid frame start marker: 0x{:02X}, continuing to poll", buf[0]));
+                    continue;
+                }
+
+                debug!("{RX}: {buf:02X?}");
+
+                return match buf[1] {
+                    ACK => Ok(()),
+                    NACK => Err(CommunicationError::NACKSent),
+                    ACK_ABORT => Err(CommunicationError::Aborted),
+                    _ => {
+                        self.stats.invalid += 1;
+                        polling_log.log(|| format!("Invalid ACK code: 0x{:02X}, continuing to poll", buf[1]));
+                        continue;
+                    }
+                };
+            }
+        }
+
+        Err(CommunicationError::Timeout)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::VecDeque,
+        sync::{Arc, Mutex},
+    };
+
+    use proptest::prelude::*;
+
+    use super::*;
+    use crate::McuBoot;
+    use crate::mboot::packets::{CRC_CHECK, PacketConstruct, command::CommandHeader, data_phase::DataPhasePacket};
+    use crate::tags::{ToAddress, command_flag::CommandFlag, status::StatusCode};
+
+    /// Bus returning queued bytes and recording written ones
+    #[derive(Clone, Debug, Default)]
+    struct Loopback(Arc<Mutex<(VecDeque<u8>, Vec<u8>)>>);
+
+    impl Loopback {
+        fn push(&self, bytes: &[u8]) {
+            self.0.lock().unwrap().0.extend(bytes);
+        }
+
+        fn take_written(&self) -> Vec<u8> {
+            std::mem::take(&mut self.0.lock().unwrap().1)
+        }
+    }
+
+    impl Read for Loopback {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            let received = &mut self.0.lock().unwrap().0;
+            let length = buf.len().min(received.len());
+            for (byte, received) in buf.iter_mut().zip(received.drain(..length)) {
+                *byte = received;
+            }
+            Ok(length)
+        }
+    }
+
+    impl Write for Loopback {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().1.extend(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    fn open(framing: Framing) -> (I2CProtocol, Loopback) {
+        let bus = Loopback::default();
+        let protocol = I2CProtocol {
+            interface: "loopback".to_owned(),
+            device: Box::new(bus.clone()),
+            slave_address: 0x10,
+            timeout: Duration::from_millis(100),
+            polling_interval: Duration::ZERO,
+            framing,
+            ping_response: None,
+            stats: TransportStats::default(),
+        };
+        (protocol, bus)
+    }
+
+    fn framing() -> impl Strategy<Value = Framing> {
+        // zero is read as busy while polling for ACK, so it cannot start frames
+        (1..=u8::MAX, any::<bool>()).prop_map(|(start_byte, crc)| Framing { start_byte, crc })
+    }
+
+    proptest! {
+        #[test]
+        fn command_frames_round_trip(
+            framing in framing(),
+            has_data_phase: bool,
+            code: u8,
+            reserved: u8,
+            params in prop::collection::vec(any::<u32>(), 0..8),
+        ) {
+            let flag = if has_data_phase { CommandFlag::HasDataPhase } else { CommandFlag::NoData };
+            let frame = CommandHeader { flag, reserved }.construct_frame(&params, code)?;
+            prop_assert_eq!(usize::from(u16::from_le_bytes([frame[2], frame[3]])), 4 + params.len() * 4);
+
+            let (mut protocol, bus) = open(framing);
+            bus.push(&framing.encode(&frame)?);
+            let payload = protocol.read_packet_raw(0xA4)?;
+            prop_assert_eq!(&payload[..4], &[code, flag.code(), reserved, params.len() as u8]);
+            let words: Vec<u32> = payload[4..]
+                .chunks_exact(4)
+                .map(|word| u32::from_le_bytes(word.try_into().unwrap()))
+                .collect();
+            prop_assert_eq!(words, params);
+            prop_assert_eq!(bus.take_written(), [framing.start_byte, ACK]);
+        }
+
+        #[test]
+        fn data_frames_round_trip(framing in framing(), data in prop::collection::vec(any::<u8>(), 1..1024)) {
+            let (mut protocol, bus) = open(framing);
+            let frame = DataPhasePacket { data: data.clone() }.construct()?;
+
+            bus.push(&[framing.start_byte, ACK]);
+            protocol.write_packet_raw(&frame)?;
+            let written = bus.take_written();
+            prop_assert_eq!(&written, &framing.frame(0xA5, &data)?);
+
+            bus.push(&written);
+            prop_assert_eq!(protocol.read_packet_raw(0xA5)?, data);
+        }
+
+        #[test]
+        fn corrupted_frames_fail_crc(data in prop::collection::vec(any::<u8>(), 1..256), index: usize, bit in 0u8..8) {
+            let (mut protocol, bus) = open(Framing::STANDARD);
+            let mut frame = DataPhasePacket { data }.construct()?;
+            // corrupt the CRC or the payload, a corrupted length desynchronizes the stream instead
+            let index = 4 + index % (frame.len() - 4);
+            frame[index] ^= 1 << bit;
+
+            bus.push(&frame);
+            prop_assert!(protocol.read_packet_raw(0xA5).is_err());
+            prop_assert_eq!(&bus.take_written()[..2], &[0x5A, NACK]);
+        }
+    }
+
+    #[test]
+    fn frame_edge_lengths() {
+        let (mut protocol, bus) = open(Framing::STANDARD);
+        bus.push(&Framing::STANDARD.frame(0xA5, &[]).unwrap());
+        assert!(matches!(
+            protocol.read_packet_raw(0xA5),
+            Err(CommunicationError::Aborted)
+        ));
+
+        let data = vec![0x5A; 0xFFFF];
+        let frame = DataPhasePacket { data: data.clone() }.construct().unwrap();
+        assert_eq!(frame[2..4], [0xFF, 0xFF]);
+        bus.push(&frame);
+        assert_eq!(protocol.read_packet_raw(0xA5).unwrap(), data);
+
+        bus.push(&frame[..100]);
+        assert!(protocol.read_packet_raw(0xA5).is_err());
+    }
+
+    #[test]
+    fn corrupted_frame_is_received_again() {
+        let (mut protocol, bus) = open(Framing::STANDARD);
+        let frame = Framing::STANDARD.frame(0xA4, &[0xA0, 0, 0, 0]).unwrap();
+        let mut corrupted = frame.clone();
+        corrupted[6] ^= 1;
+        bus.push(&corrupted);
+        bus.push(&frame);
+        assert_eq!(protocol.read_packet_raw(0xA4).unwrap(), [0xA0, 0, 0, 0]);
+        assert_eq!(bus.take_written(), [0x5A, NACK, 0x5A, ACK]);
+
+        for _ in 0..=frame_sync::MAX_RETRANSMISSIONS {
+            bus.push(&corrupted);
+        }
+        assert!(matches!(
+            protocol.read_packet_raw(0xA4),
+            Err(CommunicationError::InvalidCrc)
+        ));
+        assert_eq!(bus.take_written(), [0x5A, NACK, 0x5A, NACK, 0x5A, NACK, 0x5A, ACK]);
+    }
+
+    #[test]
+    fn dummy_bytes_before_response_are_skipped() {
+        let (mut protocol, bus) = open(Framing::STANDARD);
+        bus.push(&[0x00, 0xFF, 0x00]);
+        bus.push(&Framing::STANDARD.frame(0xA4, &[0xA0, 0, 0, 0]).unwrap());
+        assert_eq!(protocol.read_packet_raw(0xA4).unwrap(), [0xA0, 0, 0, 0]);
+        assert_eq!(bus.take_written(), [0x5A, ACK]);
+    }
+
+    #[test]
+    fn ack_polls_are_counted() {
+        let (mut protocol, bus) = open(Framing::STANDARD);
+        let frame = DataPhasePacket { data: vec![1] }.construct().unwrap();
+        bus.push(&[0x00, 0x00, 0x00, 0x00, 0x12, 0x34, 0x5A, ACK]);
+        protocol.write_packet_raw(&frame).unwrap();
+        assert!(matches!(
+            protocol.write_packet_raw(&frame),
+            Err(CommunicationError::Timeout)
+        ));
+
+        let stats = protocol.transport_stats().unwrap();
+        assert_eq!((stats.acks, stats.busy, stats.invalid, stats.timeouts), (2, 2, 1, 1));
+        assert!(stats.polls > 4);
+        assert!(stats.longest_wait >= protocol.get_timeout());
+    }
+
+    #[test]
+    fn ping_response_is_kept() {
+        let (mut protocol, bus) = open(Framing::STANDARD);
+        assert_eq!(protocol.ping_response(), None);
+        let mut response = vec![0x5A, 0xA7, 0x00, 0x03, 0x01, 0x50, 0x01, 0x00];
+        response.extend(CRC_CHECK.checksum(&response).to_le_bytes());
+        bus.push(&response);
+        protocol.ping().unwrap();
+
+        let response = protocol.ping_response().unwrap();
+        assert_eq!(response.options, 1);
+        assert_eq!(response.protocol_version().to_string(), "P1.3.0");
+    }
+
+    #[test]
+    fn framing_quirk_is_applied_before_command() {
+        let (protocol, bus) = open(Framing::STANDARD);
+        let mut boot = McuBoot::new(protocol);
+        let framing = Framing {
+            start_byte: 0x5B,
+            crc: false,
+        };
+        boot.config_mut().quirks.framing = Some(framing);
+
+        bus.push(&[0x5B, ACK]);
+        bus.push(
+            &framing
+                .frame(0xA4, &[0xA0, 0, 0, 2, 0, 0, 0, 0, 0x0B, 0, 0, 0])
+                .unwrap(),
+        );
+        assert_eq!(boot.reset().unwrap(), StatusCode::Success);
+        assert_eq!(
+            bus.take_written(),
+            [framing.frame(0xA4, &[0x0B, 0, 0, 0]).unwrap(), vec![0x5B, ACK]].concat()
+        );
+    }
+}
@@ -0,0 +1,494 @@
+// Copyright 2025 NXP
+//
+// SPDX-License-Identifier: BSD-3-Clause
+//! LPCUSBSIO bridge
+//!
+//! LPC-Link2 and MCU-Link debug probes expose an USB-HID serial I/O bridge (LPCUSBSIO) with I2C and SPI
+//! ports. Every request is a single HID report with a header identifying the port and the transaction,
+//! the probe answers with a report carrying the same transaction ID. The McuBoot frames are exchanged
+//! over the selected port the same way as over a directly connected I2C bus.
+
+use std::{
+    io::{self, ErrorKind, Read, Write},
+    time::Duration,
+};
+
+use hidapi::{HidApi, HidDevice};
+use log::{debug, info, trace};
+
+use super::{
+    CommunicationError, Protocol, TransportStats,
+    i2c::{DEFAULT_SLAVE, I2CProtocol},
+};
+use crate::{
+    mboot::{
+        ResultComm,
+        packets::{Framing, ping::PingResponse},
+        session::Quirks,
+    },
+    parse::parse_number,
+};
+
+const VID: u16 = 0x1FC9;
+/// Product IDs of the probes and sizes of their HID reports
+const PROBES: [(u16, usize); 2] = [(0x0090, 64), (0x0143, 1024)];
+
+/// Size of the request and response header
+const HEADER_SIZE: usize = 6;
+/// Size of the I2C transfer parameters following the header
+const I2C_PARAMS_SIZE: usize = 4;
+/// Size of the SPI transfer parameters following the header
+const SPI_PARAMS_SIZE: usize = 6;
+/// Longest time the probe may take to answer a request
+const RESPONSE_TIMEOUT: Duration = Duration::from_millis(500);
+
+/// Request codes
+mod request {
+    pub const I2C_INIT_PORT: u8 = 0x01;
+    pub const I2C_DEVICE_WRITE: u8 = 0x03;
+    pub const I2C_DEVICE_READ: u8 = 0x04;
+    pub const SPI_INIT_PORT: u8 = 0x11;
+    pub const SPI_DEVICE_XFER: u8 = 0x13;
+}
+
+/// Response code of a successful request
+const RESPONSE_OK: u8 = 0x00;
+
+/// Options of I2C transfers
+mod i2c_option {
+    pub const START: u8 = 0x01;
+    pub const STOP: u8 = 0x02;
+    pub const BREAK_ON_NACK: u8 = 0x04;
+    pub const NACK_LAST_BYTE: u8 = 0x08;
+}
+
+/// Port of the bridge and its configuration
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LpcUsbSioTarget {
+    /// I2C port
+    I2c {
+        /// Index of the I2C port of the probe
+        port: u8,
+        /// Slave address of the target
+        address: u8,
+        /// Bus clock in kHz
+        speed_khz: u32,
+    },
+    /// SPI port
+    Spi {
+        /// Index of the SPI port of the probe
+        port: u8,
+        /// GPIO port of the slave select pin
+        ssel_port: u8,
+        /// GPIO pin of the slave select
+        ssel_pin: u8,
+        /// Bus clock in kHz
+        speed_khz: u32,
+        /// Clock polarity, `true` for clock idle high
+        polarity: bool,
+        /// Clock phase, `true` for sampling on the second edge
+        phase: bool,
+    },
+}
+
+impl LpcUsbSioTarget {
+    /// Parses the port specification in blhost format
+    ///
+    /// The format is `i2c[<index>][,<address>[,<speed>]]` or
+    /// `spi[<index>][,<ssel port>,<ssel pin>[,<speed>[,<polarity>[,<phase>]]]]`, speed is in kHz. Defaults are
+    /// port 0, address 0x10 and 100 kHz for I2C, slave select `P0_15`, 1000 kHz, polarity 1 and phase 1 for SPI.
+    ///
+    /// # Errors
+    /// Text describing the invalid part of the specification.
+    pub fn parse(s: &str) -> Result<LpcUsbSioTarget, String> {
+        let mut parts = s.split(',').map(str::trim);
+        let first = parts.next().unwrap_or_default().to_ascii_lowercase();
+        let port_index = |index: &str| match index {
+            "" => Ok(0),
+            index => parse_number::<u8>(index),
+        };
+        let mut next_number = |default: u32| -> Result<u32, String> {
+            match parts.next() {
+                Some(value) if !value.is_empty() => parse_number(value),
+                _ => Ok(default),
+            }
+        };
+        let byte = |value: u32| u8::try_from(value).map_err(|_| format!("value {value} does not fit into a byte"));
+        let flag = |value: u32| match value {
+            0 => Ok(false),
+            1 => Ok(true),
+            _ => Err(format!("expected 0 or 1, got {value}")),
+        };
+
+        let target = if let Some(index) = first.strip_prefix("i2c") {
+            LpcUsbSioTarget::I2c {
+                port: port_index(index)?,
+                address: byte(next_number(DEFAULT_SLAVE.into())?)?,
+                speed_khz: next_number(100)?,
+            }
+        } else if let Some(index) = first.strip_prefix("spi") {
+            LpcUsbSioTarget::Spi {
+                port: port_index(index)?,
+                ssel_port: byte(next_number(0)?)?,
+                ssel_pin: byte(next_number(15)?)?,
+                speed_khz: next_number(1000)?,
+                polarity: flag(next_number(1)?)?,
+                phase: flag(next_number(1)?)?,
+            }
+        } else {
+            return Err(format!("invalid LPCUSBSIO port '{first}', expected i2c or spi"));
+        };
+
+        if parts.next().is_some() {
+            return Err("too many LPCUSBSIO parameters".to_owned());
+        }
+        Ok(target)
+    }
+}
+
+/// Opened probe talking to one I2C or SPI slave
+#[derive(Debug)]
+struct LpcUsbSioBus {
+    device: HidDevice,
+    report_size: usize,
+    target: LpcUsbSioTarget,
+    transaction: u8,
+}
+
+impl LpcUsbSioBus {
+    /// Opens the first connected probe and initializes the port of `target`
+    fn open(target: LpcUsbSioTarget) -> ResultComm<Self> {
+        let api =
+            HidApi::new().map_err(|e| CommunicationError::ParseError(format!("Failed to initialize HID API: {e}")))?;
+        let (info, report_size) = api
+            .device_list()
+            .find_map(|info| {
+                PROBES
+                    .iter()
+                    .find(|(pid, _)| info.vendor_id() == VID && info.product_id() == *pid)
+                    .map(|&(_, report_size)| (info, report_size))
+            })
+            .ok_or_else(|| CommunicationError::ParseError("No LPCUSBSIO probe is connected".to_owned()))?;
+        let device = info
+            .open_device(&api)
+            .map_err(|e| CommunicationError::ParseError(format!("Failed to open LPCUSBSIO probe: {e}")))?;
+        debug!(
+            "Opened LPCUSBSIO probe {:04X}:{:04X} with {report_size}-byte reports",
+            info.vendor_id(),
+            info.product_id()
+        );
+
+        let mut bus = LpcUsbSioBus {
+            device,
+            report_size,
+            target,
+            transaction: 0,
+        };
+        match target {
+            LpcUsbSioTarget::I2c { speed_khz, .. } => {
+                let mut params = speed_khz.saturating_mul(1000).to_le_bytes().to_vec();
+                params.extend(0u32.to_le_bytes());
+                bus.transact(request::I2C_INIT_PORT, &params)?;
+            }
+            LpcUsbSioTarget::Spi {
+                speed_khz,
+                polarity,
+                phase,
+                ..
+            } => {
+                let mut params = speed_khz.saturating_mul(1000).to_le_bytes().to_vec();
+                // 8-bit frames, clock phase in bit 6 and polarity in bit 7
+                let options = 0x07 | u32::from(phase) << 6 | u32::from(polarity) << 7;
+                params.extend(options.to_le_bytes());
+                bus.transact(request::SPI_INIT_PORT, &params)?;
+            }
+        }
+        Ok(bus)
+    }
+
+    fn port(&self) -> u8 {
+        match self.target {
+            LpcUsbSioTarget::I2c { port, .. } | LpcUsbSioTarget::Spi { port, .. } => port,
+        }
+    }
+
+    /// Payload bytes carried by one report after the header and the transfer parameters
+    fn chunk_size(&self) -> usize {
+        let params = match self.target {
+            LpcUsbSioTarget::I2c { .. } => I2C_PARAMS_SIZE,
+            LpcUsbSioTarget::Spi { .. } => SPI_PARAMS_SIZE,
+        };
+        self.report_size - HEADER_SIZE - params
+    }
+
+    /// Sends a request report and returns the payload of its response
+    fn transact(&mut self, request: u8, payload: &[u8]) -> io::Result<Vec<u8>> {
+        self.transaction = self.transaction.wrapping_add(1);
+        let length = u16::try_from(HEADER_SIZE + payload.len()).expect("payload fits into a report");
+
+        // the first byte is the report ID, which is not used by the probe
+        let mut report = vec![0u8; self.report_size + 1];
+        report[1..3].copy_from_slice(&length.to_le_bytes());
+        // session ID at offset 4 stays zero
+        report[3] = self.transaction;
+        report[5] = request;
+        report[6] = self.port();
+        report[1 + HEADER_SIZE..1 + HEADER_SIZE + payload.len()].copy_from_slice(payload);
+        trace!("LPCUSBSIO request {request:#04X} with {} bytes", payload.len());
+        self.device.write(&report).map_err(io::Error::other)?;
+
+        let mut response = vec![0u8; self.report_size];
+        let timeout = RESPONSE_TIMEOUT.as_millis() as i32;
+        loop {
+            let size = self
+                .device
+                .read_timeout(&mut response, timeout)
+                .map_err(io::Error::other)?;
+            if size < HEADER_SIZE {
+                return Err(ErrorKind::TimedOut.into());
+            }
+            // responses to requests of a previous session are skipped
+            if response[2] == self.transaction {
+                break;
+            }
+        }
+        if response[4] != RESPONSE_OK {
+            return Err(io::Error::other(format!(
+                "LPCUSBSIO request {request:#04X} failed with {:#04X}",
+                response[4]
+            )));
+        }
+        let length = usize::from(u16::from_le_bytes([response[0], response[1]])).clamp(HEADER_SIZE, response.len());
+        Ok(response[HEADER_SIZE..length].to_vec())
+    }
+
+    fn i2c_write(&mut self, address: u8, buf: &[u8]) -> io::Result<()> {
+        let chunks = buf.chunks(self.chunk_size()).count();
+        for (index, chunk) in buf.chunks(self.chunk_size()).enumerate() {
+            let mut options = i2c_option::BREAK_ON_NACK;
+            if index == 0 {
+                options |= i2c_option::START;
+            }
+            if index == chunks - 1 {
+                options |= i2c_option::STOP;
+            }
+            let mut payload = (chunk.len() as u16).to_le_bytes().to_vec();
+            payload.extend([options, address]);
+            payload.extend(chunk);
+            self.transact(request::I2C_DEVICE_WRITE, &payload)?;
+        }
+        Ok(())
+    }
+
+    fn i2c_read(&mut self, address: u8, buf: &mut [u8]) -> io::Result<()> {
+        let chunk_size = self.chunk_size();
+        let chunks = buf.len().div_ceil(chunk_size);
+        for (index, chunk) in buf.chunks_mut(chunk_size).enumerate() {
+            let mut options = 0;
+            if index == 0 {
+                options |= i2c_option::START;
+            }
+            if index == chunks - 1 {
+                options |= i2c_option::STOP | i2c_option::NACK_LAST_BYTE;
+            }
+            let mut payload = (chunk.len() as u16).to_le_bytes().to_vec();
+            payload.extend([options, address]);
+            let response = self.transact(request::I2C_DEVICE_READ, &payload)?;
+            let data = response
+                .get(..chunk.len())
+                .ok_or_else(|| io::Error::new(ErrorKind::UnexpectedEof, "LPCUSBSIO returned less data"))?;
+            chunk.copy_from_slice(data);
+        }
+        Ok(())
+    }
+
+    /// Exchanges `buf` with the slave, received bytes replace the sent ones
+    fn spi_transfer(&mut self, ssel_port: u8, ssel_pin: u8, buf: &mut [u8]) -> io::Result<()> {
+        let chunk_size = self.chunk_size();
+        for chunk in buf.chunks_mut(chunk_size) {
+            let mut payload = (chunk.len() as u16).to_le_bytes().to_vec();
+            payload.extend((chunk.len() as u16).to_le_bytes());
+            payload.extend([ssel_port, ssel_pin]);
+            payload.extend(&*chunk);
+            let response = self.transact(request::SPI_DEVICE_XFER, &payload)?;
+            let data = response
+                .get(..chunk.len())
+                .ok_or_else(|| io::Error::new(ErrorKind::UnexpectedEof, "LPCUSBSIO returned less data"))?;
+            chunk.copy_from_slice(data);
+        }
+        Ok(())
+    }
+}
+
+impl Read for LpcUsbSioBus {
+    /// Reads the whole buffer in one bus transaction
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        match self.target {
+            LpcUsbSioTarget::I2c { address, .. } => self.i2c_read(address, buf)?,
+            LpcUsbSioTarget::Spi {
+                ssel_port, ssel_pin, ..
+            } => {
+                // the slave needs clock to send data, dummy bytes are sent meanwhile
+                buf.fill(0);
+                self.spi_transfer(ssel_port, ssel_pin, buf)?;
+            }
+        }
+        Ok(buf.len())
+    }
+}
+
+impl Write for LpcUsbSioBus {
+    /// Writes the whole buffer in one bus transaction
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        match self.target {
+            LpcUsbSioTarget::I2c { address, .. } => self.i2c_write(address, buf)?,
+            LpcUsbSioTarget::Spi {
+                ssel_port, ssel_pin, ..
+            } => self.spi_transfer(ssel_port, ssel_pin, &mut buf.to_vec())?,
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// McuBoot protocol over the LPCUSBSIO bridge of a debug probe
+#[derive(Debug)]
+pub struct LpcUsbSioProtocol {
+    inner: I2CProtocol,
+}
+
+impl LpcUsbSioProtocol {
+    /// Opens the first connected probe, initializes its port and pings the device behind it
+    ///
+    /// # Arguments
+    /// * `target` - Port of the probe and its configuration
+    /// * `timeout` - Operation timeout duration
+    /// * `polling_interval` - Response polling interval
+    /// * `framing` - Start byte and CRC usage of the frames
+    ///
+    /// # Errors
+    /// [`CommunicationError::ParseError`] if no probe can be opened, errors of the port initialization and
+    /// of the ping.
+    pub fn open(
+        target: LpcUsbSioTarget,
+        timeout: Duration,
+        polling_interval: Duration,
+        framing: Framing,
+    ) -> ResultComm<Self> {
+        let bus = LpcUsbSioBus::open(target)?;
+        info!("Opened LPCUSBSIO probe with {target:?}");
+
+        let address = match target {
+            LpcUsbSioTarget::I2c { address, .. } => address,
+            LpcUsbSioTarget::Spi { .. } => 0,
+        };
+        let inner = I2CProtocol::with_bus(
+            format!("LPCUSBSIO {target:?}"),
+            Box::new(bus),
+            address,
+            timeout,
+            polling_interval,
+            framing,
+        )?;
+        Ok(LpcUsbSioProtocol { inner })
+    }
+}
+
+impl Protocol for LpcUsbSioProtocol {
+    fn get_timeout(&self) -> Duration {
+        self.inner.get_timeout()
+    }
+
+    fn set_timeout(&mut self, timeout: Duration) -> ResultComm<()> {
+        self.inner.set_timeout(timeout)
+    }
+
+    fn get_polling_interval(&self) -> Duration {
+        self.inner.get_polling_interval()
+    }
+
+    fn get_identifier(&self) -> &str {
+        self.inner.get_identifier()
+    }
+
+    fn ping_response(&self) -> Option<PingResponse> {
+        self.inner.ping_response()
+    }
+
+    fn apply_quirks(&mut self, quirks: &Quirks) {
+        self.inner.apply_quirks(quirks);
+    }
+
+    fn transport_stats(&self) -> Option<TransportStats> {
+        self.inner.transport_stats()
+    }
+
+    fn read(&mut self, bytes: usize) -> ResultComm<Vec<u8>> {
+        self.inner.read(bytes)
+    }
+
+    fn write_packet_raw(&mut self, data: &[u8]) -> ResultComm<()> {
+        self.inner.write_packet_raw(data)
+    }
+
+    fn write_packet(&mut self, packet_code: u8, payload: &[u8]) -> ResultComm<()> {
+        self.inner.write_packet(packet_code, payload)
+    }
+
+    fn read_packet_raw(&mut self, packet_code: u8) -> ResultComm<Vec<u8>> {
+        self.inner.read_packet_raw(packet_code)
+    }
+
+    fn read_packet_abort(&mut self, packet_code: u8) -> ResultComm<Vec<u8>> {
+        self.inner.read_packet_abort(packet_code)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::LpcUsbSioTarget;
+
+    #[test]
+    fn parse_target() {
+        assert_eq!(
+            LpcUsbSioTarget::parse("i2c").unwrap(),
+            LpcUsbSioTarget::I2c {
+                port: 0,
+                address: 0x10,
+                speed_khz: 100
+            }
+        );
+        assert_eq!(
+            LpcUsbSioTarget::parse("i2c1,0x3A,400").unwrap(),
+            LpcUsbSioTarget::I2c {
+                port: 1,
+                address: 0x3A,
+                speed_khz: 400
+            }
+        );
+        assert_eq!(
+            LpcUsbSioTarget::parse("spi,1,3,2000,0,0").unwrap(),
+            LpcUsbSioTarget::Spi {
+                port: 0,
+                ssel_port: 1,
+                ssel_pin: 3,
+                speed_khz: 2000,
+                polarity: false,
+                phase: false
+            }
+        );
+        assert!(LpcUsbSioTarget::parse("uart").is_err());
+        assert!(LpcUsbSioTarget::parse("i2cx").is_err());
+        assert!(LpcUsbSioTarget::parse("spi,0,15,1000,2").is_err());
+        assert!(LpcUsbSioTarget::parse("i2c,0x10,100,1").is_err());
+    }
+}
@@ -0,0 +1,687 @@
+// Copyright 2025 NXP
+//
+// SPDX-License-Identifier: BSD-3-Clause
+//! Protocols for testing without hardware
+//!
+//! Enabled by the `testing` feature, the module provides two [`Protocol`] implementations:
+//! - [`MockProtocol`] replays a script of expected frames and prepared responses, which is useful for
+//!   checking the exact packets produced by a command.
+//! - [`VirtualDevice`] emulates the bootloader packet state machine with memory, properties and one-time
+//!   programmable words kept in RAM, which is useful for testing whole command flows.
+//!
+//! ```
+//! use mboot::{McuBoot, protocols::mock::VirtualDevice};
+//!
+//! let mut boot = McuBoot::new(VirtualDevice::new());
+//! boot.write_memory(0x2000_0000, 0, &[1, 2, 3, 4]).unwrap();
+//! assert_eq!(boot.read_memory(0x2000_0000, 4, 0).unwrap().bytes.as_ref(), [1, 2, 3, 4]);
+//! ```
+
+use std::{
+    collections::{HashMap, VecDeque},
+    time::Duration,
+};
+
+use crate::mboot::{
+    ResultComm,
+    packets::{CRC_CHECK, construct_header},
+    tags::{property::PropertyTagDiscriminants as Property, status::StatusCode},
+};
+
+use super::{CommunicationError, Protocol};
+
+/// Command packet code, also used by responses
+pub const CMD: u8 = 0xA4;
+/// Data phase packet code
+pub const DATA: u8 = 0xA5;
+
+/// Response tags
+const GENERIC_RESPONSE: u8 = 0xA0;
+const READ_MEMORY_RESPONSE: u8 = 0xA3;
+const GET_PROPERTY_RESPONSE: u8 = 0xA7;
+const FLASH_READ_ONCE_RESPONSE: u8 = 0xAF;
+
+/// Response flag announcing a data phase
+const HAS_DATA_PHASE: u8 = 0x01;
+
+// command tags handled by the virtual device
+const FLASH_ERASE_ALL: u8 = 0x01;
+const FLASH_ERASE_REGION: u8 = 0x02;
+const READ_MEMORY: u8 = 0x03;
+const WRITE_MEMORY: u8 = 0x04;
+const FILL_MEMORY: u8 = 0x05;
+const GET_PROPERTY: u8 = 0x07;
+const RECEIVE_SB_FILE: u8 = 0x08;
+const EXECUTE: u8 = 0x09;
+const CALL: u8 = 0x0A;
+const RESET: u8 = 0x0B;
+const SET_PROPERTY: u8 = 0x0C;
+const FLASH_PROGRAM_ONCE: u8 = 0x0E;
+const FLASH_READ_ONCE: u8 = 0x0F;
+const CONFIGURE_MEMORY: u8 = 0x11;
+const FUSE_PROGRAM: u8 = 0x14;
+const FUSE_READ: u8 = 0x17;
+
+/// Commands reported in the `AvailableCommands` property of [`VirtualDevice`]
+const SUPPORTED_COMMANDS: [u8; 16] = [
+    FLASH_ERASE_ALL,
+    FLASH_ERASE_REGION,
+    READ_MEMORY,
+    WRITE_MEMORY,
+    FILL_MEMORY,
+    GET_PROPERTY,
+    RECEIVE_SB_FILE,
+    EXECUTE,
+    CALL,
+    RESET,
+    SET_PROPERTY,
+    FLASH_PROGRAM_ONCE,
+    FLASH_READ_ONCE,
+    CONFIGURE_MEMORY,
+    FUSE_PROGRAM,
+    FUSE_READ,
+];
+
+/// Properties which can be changed by the set-property command
+const WRITABLE_PROPERTIES: [Property; 7] = [
+    Property::VerifyWrites,
+    Property::FlashReadMargin,
+    Property::IrqNotifierPin,
+    Property::PFRKeystoreUpdateOpt,
+    Property::ByteWriteTimeoutMs,
+    Property::FuseProgramVoltage,
+    Property::VerifyErase,
+];
+
+/// Constructs a complete frame, header with CRC followed by the payload
+///
+/// # Panics
+/// If the payload is longer than 64 KiB - 1.
+#[must_use]
+pub fn frame(packet_code: u8, payload: &[u8]) -> Vec<u8> {
+    construct_header(packet_code, payload).expect("payload of mocked frame fits into a frame")
+}
+
+/// Constructs the payload of a command or response packet
+///
+/// # Arguments
+/// * `tag` - Command or response tag
+/// * `flags` - Packet flags, `1` if a data phase follows
+/// * `params` - Parameters, for responses the first one is the status
+#[must_use]
+pub fn command_payload(tag: u8, flags: u8, params: &[u32]) -> Vec<u8> {
+    let mut payload = vec![tag, flags, 0, params.len() as u8];
+    payload.extend(params.iter().flat_map(|param| param.to_le_bytes()));
+    payload
+}
+
+/// Constructs the payload of a generic response to the command with `command_tag`
+#[must_use]
+pub fn generic_response(status: StatusCode, command_tag: u8) -> Vec<u8> {
+    command_payload(GENERIC_RESPONSE, 0, &[status.into(), command_tag.into()])
+}
+
+/// One step of a [`MockProtocol`] script
+#[derive(Debug)]
+pub enum Exchange {
+    /// The host is expected to write exactly this frame
+    Write(Vec<u8>),
+    /// The device responds with a packet, returned by [`Protocol::read_packet_raw`]
+    Read {
+        /// Expected packet code
+        packet_code: u8,
+        /// Payload of the packet without the header
+        payload: Vec<u8>,
+    },
+    /// Raw bytes returned by [`Protocol::read`]
+    Raw(Vec<u8>),
+    /// The next read or write fails with this error
+    Error(CommunicationError),
+}
+
+/// Protocol replaying a scripted exchange
+///
+/// Every write is compared with the next [`Exchange::Write`] of the script and every read returns the
+/// next [`Exchange::Read`]. A write which does not match the script panics, as it is a failure of the
+/// test. Reading past the end of the script returns [`CommunicationError::Timeout`], like a silent device.
+#[derive(Debug)]
+pub struct MockProtocol {
+    identifier: String,
+    script: VecDeque<Exchange>,
+    timeout: Duration,
+    polling_interval: Duration,
+}
+
+impl MockProtocol {
+    /// Creates a protocol replaying `script`
+    #[must_use]
+    pub fn new(script: impl IntoIterator<Item = Exchange>) -> Self {
+        MockProtocol {
+            identifier: "mock".to_owned(),
+            script: script.into_iter().collect(),
+            timeout: Duration::from_secs(5),
+            polling_interval: Duration::from_millis(1),
+        }
+    }
+
+    /// Appends an expected command frame to the script
+    #[must_use]
+    pub fn expect_command(mut self, tag: u8, flags: u8, params: &[u32]) -> Self {
+        self.script
+            .push_back(Exchange::Write(frame(CMD, &command_payload(tag, flags, params))));
+        self
+    }
+
+    /// Appends an expected data phase frame to the script
+    #[must_use]
+    pub fn expect_data(mut self, data: &[u8]) -> Self {
+        self.script.push_back(Exchange::Write(frame(DATA, data)));
+        self
+    }
+
+    /// Appends a response packet to the script
+    #[must_use]
+    pub fn respond(mut self, packet_code: u8, payload: Vec<u8>) -> Self {
+        self.script.push_back(Exchange::Read { packet_code, payload });
+        self
+    }
+
+    /// Appends a generic response to the command with `command_tag` to the script
+    #[must_use]
+    pub fn respond_generic(self, status: StatusCode, command_tag: u8) -> Self {
+        self.respond(CMD, generic_response(status, command_tag))
+    }
+
+    /// Appends an error to the script
+    #[must_use]
+    pub fn fail(mut self, error: CommunicationError) -> Self {
+        self.script.push_back(Exchange::Error(error));
+        self
+    }
+
+    /// Number of script steps which have not been replayed yet
+    #[must_use]
+    pub fn remaining(&self) -> usize {
+        self.script.len()
+    }
+
+    /// Checks that the whole script was replayed
+    ///
+    /// # Panics
+    /// Panics if any step of the script is left.
+    pub fn assert_finished(&self) {
+        assert!(self.script.is_empty(), "script was not finished: {:02X?}", self.script);
+    }
+}
+
+impl Protocol for MockProtocol {
+    fn get_timeout(&self) -> Duration {
+        self.timeout
+    }
+
+    fn set_timeout(&mut self, timeout: Duration) -> ResultComm<()> {
+        self.timeout = timeout;
+        Ok(())
+    }
+
+    fn get_polling_interval(&self) -> Duration {
+        self.polling_interval
+    }
+
+    fn get_identifier(&self) -> &str {
+        &self.identifier
+    }
+
+    fn read(&mut self, bytes: usize) -> ResultComm<Vec<u8>> {
+        match self.script.pop_front() {
+            Some(Exchange::Raw(data)) => {
+                assert_eq!(data.len(), bytes, "read {bytes} raw bytes, script has {data:02X?}");
+                Ok(data)
+            }
+            Some(Exchange::Error(error)) => Err(error),
+            Some(step) => panic!("read {bytes} raw bytes, script expects {step:02X?}"),
+            None => Err(CommunicationError::Timeout),
+        }
+    }
+
+    fn write_packet_raw(&mut self, data: &[u8]) -> ResultComm<()> {
+        match self.script.pop_front() {
+            Some(Exchange::Write(expected)) => {
+                assert_eq!(data, expected, "written frame does not match the script");
+                Ok(())
+            }
+            Some(Exchange::Error(error)) => Err(error),
+            step => panic!("wrote {data:02X?}, script expects {step:02X?}"),
+        }
+    }
+
+    fn read_packet_raw(&mut self, packet_code: u8) -> ResultComm<Vec<u8>> {
+        match self.script.pop_front() {
+            Some(Exchange::Read {
+                packet_code: code,
+                payload,
+            }) => {
+                if code == packet_code {
+                    Ok(payload)
+                } else {
+                    Err(CommunicationError::InvalidPacketReceived)
+                }
+            }
+            Some(Exchange::Error(error)) => Err(error),
+            Some(step) => panic!("read packet {packet_code:02X}, script expects {step:02X?}"),
+            None => Err(CommunicationError::Timeout),
+        }
+    }
+}
+
+/// Memory region of the virtual device
+#[derive(Clone, Copy, Debug)]
+struct Region {
+    start: u32,
+    size: u32,
+}
+
+impl Region {
+    fn contains(self, address: u32, count: u32) -> bool {
+        let end = u64::from(address) + u64::from(count);
+        address >= self.start && end <= u64::from(self.start) + u64::from(self.size)
+    }
+}
+
+/// Data phase expected from the host
+#[derive(Debug)]
+struct DataSink {
+    command_tag: u8,
+    address: u32,
+    remaining: usize,
+    received: Vec<u8>,
+}
+
+/// Emulated bootloader
+///
+/// The device answers command frames like a real bootloader, so it can be used with
+/// [`McuBoot`](crate::McuBoot) in tests of whole command flows. Flash and RAM regions are taken from
+/// the `FlashStartAddress`, `FlashSize`, `RAMStartAddress` and `RAMSize` properties, unwritten memory
+/// reads as `0xFF`. Commands which are not emulated are answered with [`StatusCode::UnknownCommand`].
+#[derive(Debug)]
+pub struct VirtualDevice {
+    identifier: String,
+    timeout: Duration,
+    properties: HashMap<u8, Vec<u32>>,
+    memory: HashMap<u32, u8>,
+    otp: HashMap<u32, u32>,
+    failures: HashMap<u8, StatusCode>,
+    responses: VecDeque<(u8, Vec<u8>)>,
+    sink: Option<DataSink>,
+    streamed: Vec<u8>,
+    commands: Vec<(u8, Vec<u32>)>,
+}
+
+impl Default for VirtualDevice {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl VirtualDevice {
+    /// Creates a device with K3.1.0 bootloader, 512 KiB flash at 0 and 256 KiB RAM at `0x2000_0000`
+    #[must_use]
+    pub fn new() -> Self {
+        let available_commands = SUPPORTED_COMMANDS.iter().fold(0u32, |mask, tag| mask | 1 << (tag - 1));
+        let properties = [
+            (Property::CurrentVersion, vec![0x4B03_0100]),
+            (Property::AvailablePeripherals, vec![0x11]),
+            (Property::FlashStartAddress, vec![0]),
+            (Property::FlashSize, vec![0x8_0000]),
+            (Property::FlashSectorSize, vec![0x2000]),
+            (Property::FlashBlockCount, vec![1]),
+            (Property::AvailableCommands, vec![available_commands]),
+            (Property::VerifyWrites, vec![1]),
+            (Property::MaxPacketSize, vec![32]),
+            (Property::RAMStartAddress, vec![0x2000_0000]),
+            (Property::RAMSize, vec![0x4_0000]),
+            (Property::SystemDeviceId, vec![0x0123_4567]),
+            (
+                Property::UniqueDeviceId,
+                vec![0x0102_0304, 0x0506_0708, 0x090A_0B0C, 0x0D0E_0F10],
+            ),
+            (Property::TargetVersion, vec![0x5401_0000]),
+        ]
+        .into_iter()
+        .map(|(tag, values)| (u8::from(tag), values))
+        .collect();
+        VirtualDevice {
+            identifier: "virtual".to_owned(),
+            timeout: Duration::from_secs(5),
+            properties,
+            memory: HashMap::new(),
+            otp: HashMap::new(),
+            failures: HashMap::new(),
+            responses: VecDeque::new(),
+            sink: None,
+            streamed: Vec::new(),
+            commands: Vec::new(),
+        }
+    }
+
+    /// Sets the values of a property, replacing the default ones
+    #[must_use]
+    pub fn with_property(mut self, tag: Property, values: &[u32]) -> Self {
+        self.properties.insert(tag.into(), values.to_vec());
+        self
+    }
+
+    /// Stores `data` into the memory at `address`
+    #[must_use]
+    pub fn with_memory(mut self, address: u32, data: &[u8]) -> Self {
+        self.store(address, data);
+        self
+    }
+
+    /// Makes every following command with `command_tag` fail with `status`
+    #[must_use]
+    pub fn with_failure(mut self, command_tag: u8, status: StatusCode) -> Self {
+        self.failures.insert(command_tag, status);
+        self
+    }
+
+    /// Reads `count` bytes of the memory at `address`
+    #[must_use]
+    pub fn memory(&self, address: u32, count: usize) -> Vec<u8> {
+        (address..)
+            .take(count)
+            .map(|address| self.memory.get(&address).copied().unwrap_or(0xFF))
+            .collect()
+    }
+
+    /// Current values of a property
+    #[must_use]
+    pub fn property(&self, tag: Property) -> Option<&[u32]> {
+        self.properties.get(&tag.into()).map(Vec::as_slice)
+    }
+
+    /// Word programmed at the OTP or fuse `index`
+    #[must_use]
+    pub fn otp(&self, index: u32) -> Option<u32> {
+        self.otp.get(&index).copied()
+    }
+
+    /// Data received by receive-sb-file and load-image
+    #[must_use]
+    pub fn streamed_data(&self) -> &[u8] {
+        &self.streamed
+    }
+
+    /// Tags and parameters of all received commands
+    #[must_use]
+    pub fn commands(&self) -> &[(u8, Vec<u32>)] {
+        &self.commands
+    }
+
+    fn store(&mut self, address: u32, data: &[u8]) {
+        for (address, byte) in (address..).zip(data) {
+            self.memory.insert(address, *byte);
+        }
+    }
+
+    fn region(&self, start: Property, size: Property) -> Option<Region> {
+        Some(Region {
+            start: *self.property(start)?.first()?,
+            size: *self.property(size)?.first()?,
+        })
+    }
+
+    fn flash(&self) -> Option<Region> {
+        self.region(Property::FlashStartAddress, Property::FlashSize)
+    }
+
+    /// Checks that the range lies in flash or RAM
+    fn check_range(&self, address: u32, count: u32) -> Result<(), StatusCode> {
+        [self.flash(), self.region(Property::RAMStartAddress, Property::RAMSize)]
+            .into_iter()
+            .flatten()
+            .any(|region| region.contains(address, count))
+            .then_some(())
+            .ok_or(StatusCode::MemoryRangeInvalid)
+    }
+
+    fn max_packet_size(&self) -> usize {
+        self.property(Property::MaxPacketSize)
+            .and_then(|values| values.first())
+            .map_or(32, |&size| size as usize)
+    }
+
+    fn respond(&mut self, tag: u8, flags: u8, params: &[u32]) {
+        self.responses.push_back((CMD, command_payload(tag, flags, params)));
+    }
+
+    fn respond_generic(&mut self, status: StatusCode, command_tag: u8) {
+        self.responses.push_back((CMD, generic_response(status, command_tag)));
+    }
+
+    /// Handles a command packet
+    fn command(&mut self, tag: u8, params: &[u32]) {
+        self.commands.push((tag, params.to_vec()));
+        if let Some(&status) = self.failures.get(&tag) {
+            self.respond_generic(status, tag);
+            return;
+        }
+        let param = |index: usize| params.get(index).copied().unwrap_or_default();
+        let result = match tag {
+            FLASH_ERASE_ALL => self.flash().map_or(Err(StatusCode::MemoryRangeInvalid), |flash| {
+                self.erase(flash.start, flash.size);
+                Ok(())
+            }),
+            FLASH_ERASE_REGION => self.flash_erase(param(0), param(1)),
+            READ_MEMORY => match self.check_range(param(0), param(1)) {
+                Ok(()) => {
+                    let data = self.memory(param(0), param(1) as usize);
+                    self.send_data(tag, &data);
+                    return;
+                }
+                Err(status) => Err(status),
+            },
+            WRITE_MEMORY | FUSE_PROGRAM => self.start_data_phase(tag, param(0), param(1)),
+            RECEIVE_SB_FILE => self.start_data_phase(tag, 0, param(0)),
+            FILL_MEMORY => self.check_range(param(0), param(1)).map(|()| {
+                let pattern = param(2).to_le_bytes();
+                let data: Vec<u8> = pattern.into_iter().cycle().take(param(1) as usize).collect();
+                self.store(param(0), &data);
+            }),
+            GET_PROPERTY => {
+                match self.properties.get(&(param(0) as u8)) {
+                    Some(values) => {
+                        let params: Vec<u32> = [StatusCode::Success.into()].into_iter().chain(values.clone()).collect();
+                        self.respond(GET_PROPERTY_RESPONSE, 0, &params);
+                    }
+                    None => self.respond(GET_PROPERTY_RESPONSE, 0, &[StatusCode::UnknownProperty.into()]),
+                }
+                return;
+            }
+            SET_PROPERTY => {
+                let property = param(0) as u8;
+                let writable = WRITABLE_PROPERTIES.iter().any(|&tag| u8::from(tag) == property);
+                match self.properties.get_mut(&property) {
+                    Some(values) if writable => {
+                        *values = vec![param(1)];
+                        Ok(())
+                    }
+                    Some(_) => Err(StatusCode::ReadOnlyProperty),
+                    None => Err(StatusCode::UnknownProperty),
+                }
+            }
+            FLASH_PROGRAM_ONCE => {
+                self.otp.insert(param(0), param(2));
+                Ok(())
+            }
+            FLASH_READ_ONCE => {
+                let value = self.otp(param(0)).unwrap_or_default();
+                self.respond(FLASH_READ_ONCE_RESPONSE, 0, &[StatusCode::Success.into(), 4, value]);
+                return;
+            }
+            FUSE_READ => {
+                let data: Vec<u8> = (param(0)..)
+                    .take(param(1).div_ceil(4) as usize)
+                    .flat_map(|index| self.otp(index).unwrap_or_default().to_le_bytes())
+                    .take(param(1) as usize)
+                    .collect();
+                self.send_data(tag, &data);
+                return;
+            }
+            EXECUTE | CALL | RESET | CONFIGURE_MEMORY => Ok(()),
+            _ => Err(StatusCode::UnknownCommand),
+        };
+        self.respond_generic(result.err().unwrap_or(StatusCode::Success), tag);
+    }
+
+    fn erase(&mut self, address: u32, count: u32) {
+        let range = u64::from(address)..u64::from(address) + u64::from(count);
+        self.memory.retain(|&address, _| !range.contains(&u64::from(address)));
+    }
+
+    fn flash_erase(&mut self, address: u32, count: u32) -> Result<(), StatusCode> {
+        match self.flash() {
+            Some(flash) if flash.contains(address, count) => {
+                self.erase(address, count);
+                Ok(())
+            }
+            _ => Err(StatusCode::MemoryRangeInvalid),
+        }
+    }
+
+    /// Prepares for a data phase, the final response is sent after all data are received
+    fn start_data_phase(&mut self, command_tag: u8, address: u32, count: u32) -> Result<(), StatusCode> {
+        if command_tag == WRITE_MEMORY {
+            self.check_range(address, count)?;
+        }
+        self.sink = Some(DataSink {
+            command_tag,
+            address,
+            remaining: count as usize,
+            received: Vec::new(),
+        });
+        Ok(())
+    }
+
+    /// Sends the read memory response followed by the data phase and the final response
+    fn send_data(&mut self, command_tag: u8, data: &[u8]) {
+        self.respond(
+            READ_MEMORY_RESPONSE,
+            HAS_DATA_PHASE,
+            &[StatusCode::Success.into(), data.len() as u32],
+        );
+        for chunk in data.chunks(self.max_packet_size()) {
+            self.responses.push_back((DATA, chunk.to_vec()));
+        }
+        self.respond_generic(StatusCode::Success, command_tag);
+    }
+
+    /// Handles a data phase packet
+    fn data(&mut self, data: &[u8]) {
+        let Some(sink) = &mut self.sink else {
+            // data without a command are a boot image sent by load-image
+            self.streamed.extend(data);
+            return;
+        };
+        if data.is_empty() {
+            // zero-length packet aborts the data phase
+            let command_tag = sink.command_tag;
+            self.sink = None;
+            self.respond_generic(StatusCode::AbortDataPhase, command_tag);
+            return;
+        }
+        let count = data.len().min(sink.remaining);
+        sink.received.extend(&data[..count]);
+        sink.remaining -= count;
+        if sink.remaining > 0 {
+            return;
+        }
+        let Some(sink) = self.sink.take() else {
+            return;
+        };
+        match sink.command_tag {
+            WRITE_MEMORY => self.store(sink.address, &sink.received),
+            FUSE_PROGRAM => {
+                for (index, word) in (sink.address..).zip(sink.received.chunks(4)) {
+                    let mut bytes = [0; 4];
+                    bytes[..word.len()].copy_from_slice(word);
+                    self.otp.insert(index, u32::from_le_bytes(bytes));
+                }
+            }
+            _ => self.streamed.extend(sink.received),
+        }
+        self.respond_generic(StatusCode::Success, sink.command_tag);
+    }
+}
+
+impl Protocol for VirtualDevice {
+    fn get_timeout(&self) -> Duration {
+        self.timeout
+    }
+
+    fn set_timeout(&mut self, timeout: Duration) -> ResultComm<()> {
+        self.timeout = timeout;
+        Ok(())
+    }
+
+    fn get_polling_interval(&self) -> Duration {
+        Duration::ZERO
+    }
+
+    fn get_identifier(&self) -> &str {
+        &self.identifier
+    }
+
+    fn read(&mut self, _bytes: usize) -> ResultComm<Vec<u8>> {
+        Err(CommunicationError::Timeout)
+    }
+
+    /// Processes a frame, frames with invalid header or CRC are rejected with [`CommunicationError::NACKSent`]
+    fn write_packet_raw(&mut self, data: &[u8]) -> ResultComm<()> {
+        let [0x5A, code, len_low, len_high, crc_low, crc_high, payload @ ..] = data else {
+            return Err(CommunicationError::NACKSent);
+        };
+        let mut header = vec![0x5A, *code, *len_low, *len_high];
+        header.extend(payload);
+        if usize::from(u16::from_le_bytes([*len_low, *len_high])) != payload.len()
+            || CRC_CHECK.checksum(&header) != u16::from_le_bytes([*crc_low, *crc_high])
+        {
+            return Err(CommunicationError::NACKSent);
+        }
+
+        match (*code, payload) {
+            (CMD, [tag, _, _, count, params @ ..]) if params.len() == usize::from(*count) * 4 => {
+                let params: Vec<u32> = params
+                    .chunks_exact(4)
+                    .map(|bytes| u32::from_le_bytes(bytes.try_into().expect("chunks have 4 bytes")))
+                    .collect();
+                self.command(*tag, &params);
+            }
+            (DATA, _) => self.data(payload),
+            _ => return Err(CommunicationError::NACKSent),
+        }
+        Ok(())
+    }
+
+    fn read_packet_raw(&mut self, packet_code: u8) -> ResultComm<Vec<u8>> {
+        match self.responses.pop_front() {
+            Some((code, payload)) if code == packet_code => Ok(payload),
+            Some(_) => Err(CommunicationError::InvalidPacketReceived),
+            None => Err(CommunicationError::Timeout),
+        }
+    }
+    /// Drops the rest of the data phase, the final response reports it was aborted
+    fn read_packet_abort(&mut self, packet_code: u8) -> ResultComm<Vec<u8>> {
+        let packet = self.read_packet_raw(packet_code)?;
+        while self.responses.front().is_some_and(|(code, _)| *code == DATA) {
+            self.responses.pop_front();
+        }
+        // the command tag is the second parameter of the final generic response
+        if let Some((CMD, payload)) = self.responses.front()
+            && let Some(&command_tag) = payload.get(8)
+        {
+            self.responses[0] = (CMD, generic_response(StatusCode::AbortDataPhase, command_tag));
+        }
+        Ok(packet)
+    }
+}
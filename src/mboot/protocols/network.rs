@@ -0,0 +1,298 @@
+// Copyright 2025 NXP
+//
+// SPDX-License-Identifier: BSD-3-Clause
+//! Network transport
+//!
+//! Remote serial bridges, such as ser2net in raw mode or a board farm, forward a TCP connection or UDP
+//! datagrams to the UART of the device, so the bootloader receives the same byte stream as over a local serial
+//! port. The bridge is selected by `[tcp://|udp://]<host>:<port>`, TCP is used without the scheme.
+
+use std::{
+    collections::VecDeque,
+    io::{self, Read, Write},
+    net::{SocketAddr, TcpStream, ToSocketAddrs, UdpSocket},
+    time::Duration,
+};
+
+use log::{debug, info};
+
+use super::{CommunicationError, Protocol, ProtocolOpen, TransportStats, i2c::I2CProtocol};
+use crate::mboot::{
+    ResultComm,
+    packets::{Framing, ping::PingResponse},
+    session::Quirks,
+};
+
+/// Largest UDP datagram
+const MAX_DATAGRAM_SIZE: usize = 65_507;
+
+/// Transport protocol selected by the identifier scheme
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Transport {
+    Tcp,
+    Udp,
+}
+
+/// Splits the identifier into the transport and the `<host>:<port>` address
+fn parse_identifier(identifier: &str) -> ResultComm<(Transport, &str)> {
+    let (transport, address) = if let Some(address) = identifier.strip_prefix("tcp://") {
+        (Transport::Tcp, address)
+    } else if let Some(address) = identifier.strip_prefix("udp://") {
+        (Transport::Udp, address)
+    } else {
+        (Transport::Tcp, identifier)
+    };
+    match address.rsplit_once(':') {
+        Some((host, port)) if !host.is_empty() && port.parse::<u16>().is_ok() => Ok((transport, address)),
+        _ => Err(CommunicationError::ParseError(format!(
+            "invalid network identifier '{identifier}', expected [tcp://|udp://]<host>:<port>"
+        ))),
+    }
+}
+
+/// Byte stream to the remote bridge
+#[derive(Debug)]
+enum NetworkStream {
+    Tcp(TcpStream),
+    Udp {
+        socket: UdpSocket,
+        /// Bytes of received datagrams not read yet
+        received: VecDeque<u8>,
+    },
+}
+
+impl NetworkStream {
+    /// Connects to the first address `address` resolves to, which accepts the connection
+    fn connect(transport: Transport, address: &str, timeout: Duration) -> io::Result<Self> {
+        // zero timeouts are rejected by the sockets, they mean no timeout here
+        let timeout = Some(timeout).filter(|timeout| !timeout.is_zero());
+        let mut last_error = io::Error::new(io::ErrorKind::NotFound, format!("{address} did not resolve"));
+        for remote in address.to_socket_addrs()? {
+            let stream = match transport {
+                Transport::Tcp => Self::connect_tcp(remote, timeout),
+                Transport::Udp => Self::connect_udp(remote),
+            };
+            match stream {
+                Ok(stream) => {
+                    stream.set_timeout(timeout)?;
+                    return Ok(stream);
+                }
+                Err(err) => {
+                    debug!("connecting to {remote} failed: {err}");
+                    last_error = err;
+                }
+            }
+        }
+        Err(last_error)
+    }
+
+    fn connect_tcp(remote: SocketAddr, timeout: Option<Duration>) -> io::Result<Self> {
+        let stream = match timeout {
+            Some(timeout) => TcpStream::connect_timeout(&remote, timeout)?,
+            None => TcpStream::connect(remote)?,
+        };
+        // frames are short and every one of them waits for an ACK
+        stream.set_nodelay(true)?;
+        Ok(NetworkStream::Tcp(stream))
+    }
+
+    fn connect_udp(remote: SocketAddr) -> io::Result<Self> {
+        let local: SocketAddr = if remote.is_ipv4() {
+            ([0, 0, 0, 0], 0).into()
+        } else {
+            ([0u16; 8], 0).into()
+        };
+        let socket = UdpSocket::bind(local)?;
+        socket.connect(remote)?;
+        Ok(NetworkStream::Udp {
+            socket,
+            received: VecDeque::new(),
+        })
+    }
+
+    fn set_timeout(&self, timeout: Option<Duration>) -> io::Result<()> {
+        match self {
+            NetworkStream::Tcp(stream) => {
+                stream.set_read_timeout(timeout)?;
+                stream.set_write_timeout(timeout)
+            }
+            NetworkStream::Udp { socket, .. } => socket.set_read_timeout(timeout),
+        }
+    }
+}
+
+impl Read for NetworkStream {
+    /// Reads from the TCP stream, or returns bytes of already received datagrams, waiting for a datagram
+    /// only if there are none
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match self {
+            NetworkStream::Tcp(stream) => stream.read(buf),
+            NetworkStream::Udp { socket, received } => {
+                if buf.is_empty() {
+                    return Ok(0);
+                }
+                while received.is_empty() {
+                    let mut datagram = vec![0; MAX_DATAGRAM_SIZE];
+                    let length = socket.recv(&mut datagram)?;
+                    received.extend(&datagram[..length]);
+                }
+                let length = buf.len().min(received.len());
+                for (byte, received) in buf.iter_mut().zip(received.drain(..length)) {
+                    *byte = received;
+                }
+                Ok(length)
+            }
+        }
+    }
+}
+
+impl Write for NetworkStream {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            NetworkStream::Tcp(stream) => stream.write(buf),
+            NetworkStream::Udp { socket, .. } => socket.send(&buf[..buf.len().min(MAX_DATAGRAM_SIZE)]),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            NetworkStream::Tcp(stream) => stream.flush(),
+            NetworkStream::Udp { .. } => Ok(()),
+        }
+    }
+}
+
+/// McuBoot protocol over a TCP connection or UDP datagrams to a remote serial bridge
+#[derive(Debug)]
+pub struct NetworkProtocol {
+    inner: I2CProtocol,
+}
+
+impl ProtocolOpen for NetworkProtocol {
+    fn open(identifier: &str) -> ResultComm<Self> {
+        Self::open_with_options(identifier, 0, Duration::from_secs(5), Duration::from_millis(1))
+    }
+
+    fn open_with_options(
+        identifier: &str,
+        baudrate: u32,
+        timeout: Duration,
+        polling_interval: Duration,
+    ) -> ResultComm<Self> {
+        Self::open_with_framing(identifier, baudrate, timeout, polling_interval, Framing::default())
+    }
+
+    /// The baudrate of the device UART is configured on the bridge, `baudrate` is ignored
+    fn open_with_framing(
+        identifier: &str,
+        _baudrate: u32,
+        timeout: Duration,
+        polling_interval: Duration,
+        framing: Framing,
+    ) -> ResultComm<Self> {
+        let (transport, address) = parse_identifier(identifier)?;
+        let stream = NetworkStream::connect(transport, address, timeout)?;
+        info!("Connected to {address} over {transport:?}");
+
+        let inner = I2CProtocol::with_bus(
+            identifier.to_owned(),
+            Box::new(stream),
+            0,
+            timeout,
+            polling_interval,
+            framing,
+        )?;
+        Ok(NetworkProtocol { inner })
+    }
+}
+
+impl Protocol for NetworkProtocol {
+    fn get_timeout(&self) -> Duration {
+        self.inner.get_timeout()
+    }
+
+    fn set_timeout(&mut self, timeout: Duration) -> ResultComm<()> {
+        self.inner.set_timeout(timeout)
+    }
+
+    fn get_polling_interval(&self) -> Duration {
+        self.inner.get_polling_interval()
+    }
+
+    fn get_identifier(&self) -> &str {
+        self.inner.get_identifier()
+    }
+
+    fn ping_response(&self) -> Option<PingResponse> {
+        self.inner.ping_response()
+    }
+
+    fn apply_quirks(&mut self, quirks: &Quirks) {
+        self.inner.apply_quirks(quirks);
+    }
+
+    fn transport_stats(&self) -> Option<TransportStats> {
+        self.inner.transport_stats()
+    }
+
+    fn read(&mut self, bytes: usize) -> ResultComm<Vec<u8>> {
+        self.inner.read(bytes)
+    }
+
+    fn write_packet_raw(&mut self, data: &[u8]) -> ResultComm<()> {
+        self.inner.write_packet_raw(data)
+    }
+
+    fn write_packet(&mut self, packet_code: u8, payload: &[u8]) -> ResultComm<()> {
+        self.inner.write_packet(packet_code, payload)
+    }
+
+    fn read_packet_raw(&mut self, packet_code: u8) -> ResultComm<Vec<u8>> {
+        self.inner.read_packet_raw(packet_code)
+    }
+
+    fn read_packet_abort(&mut self, packet_code: u8) -> ResultComm<Vec<u8>> {
+        self.inner.read_packet_abort(packet_code)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn identifiers() {
+        assert_eq!(
+            parse_identifier("192.168.1.50:5555").unwrap(),
+            (Transport::Tcp, "192.168.1.50:5555")
+        );
+        assert_eq!(
+            parse_identifier("tcp://lab-farm:4001").unwrap(),
+            (Transport::Tcp, "lab-farm:4001")
+        );
+        assert_eq!(parse_identifier("udp://[::1]:7").unwrap(), (Transport::Udp, "[::1]:7"));
+        assert!(parse_identifier("192.168.1.50").is_err());
+        assert!(parse_identifier(":5555").is_err());
+        assert!(parse_identifier("host:70000").is_err());
+    }
+
+    #[test]
+    fn udp_datagrams_are_buffered() {
+        let bridge = UdpSocket::bind("127.0.0.1:0").unwrap();
+        let address = bridge.local_addr().unwrap().to_string();
+        let mut stream = NetworkStream::connect(Transport::Udp, &address, Duration::from_secs(1)).unwrap();
+
+        stream.write_all(&[0x5A, 0xA6]).unwrap();
+        let mut datagram = [0; 16];
+        let (length, host) = bridge.recv_from(&mut datagram).unwrap();
+        assert_eq!(datagram[..length], [0x5A, 0xA6]);
+
+        bridge.send_to(&[1, 2, 3], host).unwrap();
+        bridge.send_to(&[4], host).unwrap();
+        let mut buf = [0; 2];
+        stream.read_exact(&mut buf).unwrap();
+        assert_eq!(buf, [1, 2]);
+        stream.read_exact(&mut buf).unwrap();
+        assert_eq!(buf, [3, 4]);
+    }
+}
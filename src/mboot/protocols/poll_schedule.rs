@@ -0,0 +1,87 @@
+// Copyright 2025 NXP
+//
+// SPDX-License-Identifier: BSD-3-Clause
+//! Sleep schedule of polling loops
+//!
+//! Waiting for ACK may take minutes during long erase operations. Polling with a fixed short sleep
+//! keeps the CPU busy all that time, while a fixed long sleep delays every quick response. [`PollSchedule`]
+//! polls right away and then sleeps exponentially longer, up to the polling interval, until the timeout
+//! elapses.
+
+use std::{
+    thread,
+    time::{Duration, Instant},
+};
+
+/// Exponential backoff of a polling loop capped by the polling interval
+#[derive(Debug)]
+pub struct PollSchedule {
+    start: Instant,
+    timeout: Duration,
+    polling_interval: Duration,
+    /// Sleep before the next poll, [`None`] before the first poll
+    sleep: Option<Duration>,
+}
+
+impl PollSchedule {
+    /// Sleep before the second poll
+    const FIRST_SLEEP: Duration = Duration::from_micros(50);
+
+    /// Starts the timeout of the polling loop
+    pub fn new(timeout: Duration, polling_interval: Duration) -> Self {
+        PollSchedule {
+            start: Instant::now(),
+            timeout,
+            polling_interval,
+            sleep: None,
+        }
+    }
+
+    /// Returns how long to sleep before the next poll, [`None`] once the timeout elapsed
+    fn next_sleep(&mut self) -> Option<Duration> {
+        let remaining = self
+            .timeout
+            .checked_sub(self.start.elapsed())
+            .filter(|rem| !rem.is_zero())?;
+        let sleep = match self.sleep {
+            None => Duration::ZERO,
+            Some(sleep) => sleep.min(remaining),
+        };
+        let next = self.sleep.map_or(Self::FIRST_SLEEP, |sleep| sleep.saturating_mul(2));
+        self.sleep = Some(next.min(self.polling_interval));
+        Some(sleep)
+    }
+
+    /// Sleeps before the next poll, returns `false` once the timeout elapsed
+    pub fn wait(&mut self) -> bool {
+        match self.next_sleep() {
+            Some(sleep) => {
+                if !sleep.is_zero() {
+                    thread::sleep(sleep);
+                }
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::PollSchedule;
+
+    #[test]
+    fn backoff_is_capped() {
+        let mut schedule = PollSchedule::new(Duration::from_secs(60), Duration::from_micros(300));
+        let sleeps: Vec<_> = (0..6).map_while(|_| schedule.next_sleep()).collect();
+        assert_eq!(sleeps, [0, 50, 100, 200, 300, 300].map(Duration::from_micros));
+
+        let mut schedule = PollSchedule::new(Duration::from_secs(60), Duration::ZERO);
+        assert!((0..3).all(|_| schedule.next_sleep() == Some(Duration::ZERO)));
+
+        let mut schedule = PollSchedule::new(Duration::ZERO, Duration::from_millis(1));
+        assert!(!schedule.wait());
+    }
+}
@@ -4,11 +4,13 @@
 use crate::mboot::Packet;
 use crate::mboot::PacketParse;
 use crate::mboot::ResultComm;
+use crate::mboot::packets::ping::PingResponse;
+use crate::mboot::session::Quirks;
 use crate::protocols::Duration;
 use crate::protocols::PacketConstruct;
 use enum_dispatch::enum_dispatch;
 
-use super::{Protocol, i2c::I2CProtocol, uart::UARTProtocol, usb::USBProtocol};
+use super::{Protocol, TransportStats, i2c::I2CProtocol, uart::UARTProtocol, usb::USBProtocol};
 
 /// Unified protocol implementation enum
 ///
@@ -0,0 +1,616 @@
+// Copyright 2025 NXP
+//
+// SPDX-License-Identifier: BSD-3-Clause
+//! Session Recording and Replay
+//!
+//! [`RecordingProtocol`] wraps any [`Protocol`] and captures every sent and received frame with its
+//! timestamp into a [`Transcript`]. The transcript is saved as a compact binary file, or as JSON when the
+//! file name ends with `.json` and the `cli` feature is enabled. [`ReplayProtocol`] feeds a saved transcript
+//! back to [`McuBoot`](crate::McuBoot), so a session recorded in the field can be reproduced offline, without
+//! the device.
+//!
+//! ```
+//! # #[cfg(feature = "testing")] {
+//! use mboot::{
+//!     McuBoot,
+//!     protocols::{
+//!         mock::VirtualDevice,
+//!         record::{RecordingProtocol, ReplayProtocol},
+//!     },
+//! };
+//!
+//! let mut boot = McuBoot::new(RecordingProtocol::new(VirtualDevice::new()));
+//! boot.write_memory(0x2000_0000, 0, &[1, 2, 3, 4]).unwrap();
+//! let transcript = boot.into_device().into_transcript();
+//!
+//! let mut replay = McuBoot::new(ReplayProtocol::new(transcript));
+//! replay.write_memory(0x2000_0000, 0, &[1, 2, 3, 4]).unwrap();
+//! # }
+//! ```
+
+use std::{
+    collections::VecDeque,
+    fs,
+    io::{self, Read},
+    path::Path,
+    time::{Duration, Instant},
+};
+
+use log::debug;
+
+use crate::mboot::{
+    ResultComm,
+    packets::{Framing, ping::PingResponse},
+    session::Quirks,
+};
+
+use super::{CommunicationError, Protocol, TransportStats};
+
+/// Identifies binary transcripts
+const MAGIC: &[u8; 4] = b"MBTR";
+/// Version of the binary transcript format
+const VERSION: u8 = 1;
+
+/// Direction of a recorded frame
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Direction {
+    /// Frame was sent by the host
+    Sent,
+    /// Frame was received from the device
+    Received,
+}
+
+/// Frame exchanged with the device
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Frame {
+    /// Time since the recording started
+    pub timestamp: Duration,
+    /// Whether the frame was sent or received
+    pub direction: Direction,
+    /// Packet code of a received packet, [`None`] for sent frames and raw reads
+    pub packet_code: Option<u8>,
+    /// Complete frame for sent frames, payload for received packets and bytes of raw reads
+    pub data: Vec<u8>,
+    /// Error returned by the protocol instead of the frame
+    pub error: Option<String>,
+}
+
+/// Frames of a recorded session
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Transcript {
+    /// Identifier of the recorded device
+    pub identifier: String,
+    /// Frames in the order they were exchanged
+    pub frames: Vec<Frame>,
+}
+
+impl Transcript {
+    /// Saves the transcript, as JSON if the file name ends with `.json`, in the binary format otherwise
+    ///
+    /// # Errors
+    /// [`CommunicationError::FileError`] if the file cannot be written and [`CommunicationError::ParseError`]
+    /// for JSON without the `cli` feature.
+    pub fn save(&self, path: impl AsRef<Path>) -> ResultComm<()> {
+        let path = path.as_ref();
+        let content = if is_json(path) {
+            self.to_json()?.into_bytes()
+        } else {
+            self.to_bytes()
+        };
+        fs::write(path, content).map_err(CommunicationError::FileError)
+    }
+
+    /// Loads a transcript saved by [`Transcript::save`]
+    ///
+    /// # Errors
+    /// [`CommunicationError::FileError`] if the file cannot be read and [`CommunicationError::ParseError`]
+    /// if it is not a valid transcript.
+    pub fn load(path: impl AsRef<Path>) -> ResultComm<Self> {
+        let path = path.as_ref();
+        let content = fs::read(path).map_err(CommunicationError::FileError)?;
+        if is_json(path) {
+            Self::from_json(&String::from_utf8_lossy(&content))
+        } else {
+            Self::from_bytes(&content)
+        }
+    }
+
+    /// Encodes the transcript in the binary format
+    ///
+    /// The format starts with `MBTR`, version byte and the length prefixed identifier. Each frame follows as
+    /// timestamp in microseconds (u64), direction (0 sent, 1 received), packet code or `0xFF` if there is
+    /// none, presence of the packet code and error (bits 0 and 1), length prefixed data (u32) and the length
+    /// prefixed error message (u16) if there is one. All numbers are little-endian.
+    #[must_use]
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = MAGIC.to_vec();
+        bytes.push(VERSION);
+        push_string(&mut bytes, &self.identifier);
+        for frame in &self.frames {
+            let micros = u64::try_from(frame.timestamp.as_micros()).unwrap_or(u64::MAX);
+            bytes.extend(micros.to_le_bytes());
+            bytes.push(u8::from(frame.direction == Direction::Received));
+            bytes.push(frame.packet_code.unwrap_or(0xFF));
+            bytes.push(u8::from(frame.packet_code.is_some()) | u8::from(frame.error.is_some()) << 1);
+            bytes.extend((frame.data.len() as u32).to_le_bytes());
+            bytes.extend(&frame.data);
+            if let Some(error) = &frame.error {
+                push_string(&mut bytes, error);
+            }
+        }
+        bytes
+    }
+
+    /// Decodes a transcript encoded by [`Transcript::to_bytes`]
+    ///
+    /// # Errors
+    /// [`CommunicationError::ParseError`] if the data are not a valid transcript.
+    pub fn from_bytes(mut bytes: &[u8]) -> ResultComm<Self> {
+        let invalid = |_| CommunicationError::ParseError("transcript is truncated or invalid".to_owned());
+        let mut header = [0; 5];
+        bytes.read_exact(&mut header).map_err(invalid)?;
+        if header[..4] != *MAGIC || header[4] != VERSION {
+            return Err(CommunicationError::ParseError(
+                "file is not a transcript of a supported version".to_owned(),
+            ));
+        }
+        let identifier = read_string(&mut bytes).map_err(invalid)?;
+        let mut frames = Vec::new();
+        while !bytes.is_empty() {
+            let mut header = [0; 15];
+            bytes.read_exact(&mut header).map_err(invalid)?;
+            let [
+                t0,
+                t1,
+                t2,
+                t3,
+                t4,
+                t5,
+                t6,
+                t7,
+                direction,
+                packet_code,
+                flags,
+                l0,
+                l1,
+                l2,
+                l3,
+            ] = header;
+            let mut data = vec![0; u32::from_le_bytes([l0, l1, l2, l3]) as usize];
+            bytes.read_exact(&mut data).map_err(invalid)?;
+            let error = if flags & 2 == 0 {
+                None
+            } else {
+                Some(read_string(&mut bytes).map_err(invalid)?)
+            };
+            frames.push(Frame {
+                timestamp: Duration::from_micros(u64::from_le_bytes([t0, t1, t2, t3, t4, t5, t6, t7])),
+                direction: if direction == 0 {
+                    Direction::Sent
+                } else {
+                    Direction::Received
+                },
+                packet_code: (flags & 1 != 0).then_some(packet_code),
+                data,
+                error,
+            });
+        }
+        Ok(Transcript { identifier, frames })
+    }
+
+    /// Encodes the transcript as JSON, data of the frames are hex strings
+    ///
+    /// # Errors
+    /// [`CommunicationError::ParseError`] without the `cli` feature.
+    #[cfg(feature = "cli")]
+    pub fn to_json(&self) -> ResultComm<String> {
+        let frames: Vec<_> = self
+            .frames
+            .iter()
+            .map(|frame| {
+                serde_json::json!({
+                    "timestamp_us": u64::try_from(frame.timestamp.as_micros()).unwrap_or(u64::MAX),
+                    "direction": match frame.direction {
+                        Direction::Sent => "sent",
+                        Direction::Received => "received",
+                    },
+                    "packet_code": frame.packet_code,
+                    "data": hex_string(&frame.data),
+                    "error": frame.error,
+                })
+            })
+            .collect();
+        let document = serde_json::json!({"identifier": self.identifier, "frames": frames});
+        Ok(format!("{document:#}\n"))
+    }
+
+    /// Encodes the transcript as JSON, data of the frames are hex strings
+    ///
+    /// # Errors
+    /// [`CommunicationError::ParseError`] without the `cli` feature.
+    #[cfg(not(feature = "cli"))]
+    pub fn to_json(&self) -> ResultComm<String> {
+        Err(json_unsupported())
+    }
+
+    /// Decodes a transcript encoded by [`Transcript::to_json`]
+    ///
+    /// # Errors
+    /// [`CommunicationError::ParseError`] if the text is not a valid transcript or without the `cli` feature.
+    #[cfg(feature = "cli")]
+    pub fn from_json(text: &str) -> ResultComm<Self> {
+        use serde_json::Value;
+
+        let invalid = |reason: &str| CommunicationError::ParseError(format!("invalid JSON transcript: {reason}"));
+        let document: Value = serde_json::from_str(text).map_err(|err| invalid(&err.to_string()))?;
+        let identifier = document["identifier"].as_str().unwrap_or_default().to_owned();
+        let frames = document["frames"]
+            .as_array()
+            .ok_or_else(|| invalid("frames are missing"))?
+            .iter()
+            .map(|frame| {
+                let direction = match frame["direction"].as_str() {
+                    Some("sent") => Direction::Sent,
+                    Some("received") => Direction::Received,
+                    _ => return Err(invalid("unknown direction")),
+                };
+                let packet_code = match &frame["packet_code"] {
+                    Value::Null => None,
+                    code => Some(
+                        code.as_u64()
+                            .and_then(|code| u8::try_from(code).ok())
+                            .ok_or_else(|| invalid("packet code is not a byte"))?,
+                    ),
+                };
+                let data = frame["data"]
+                    .as_str()
+                    .and_then(parse_hex)
+                    .ok_or_else(|| invalid("data are not a hex string"))?;
+                Ok(Frame {
+                    timestamp: Duration::from_micros(frame["timestamp_us"].as_u64().unwrap_or_default()),
+                    direction,
+                    packet_code,
+                    data,
+                    error: frame["error"].as_str().map(str::to_owned),
+                })
+            })
+            .collect::<ResultComm<_>>()?;
+        Ok(Transcript { identifier, frames })
+    }
+
+    /// Decodes a transcript encoded by [`Transcript::to_json`]
+    ///
+    /// # Errors
+    /// [`CommunicationError::ParseError`] if the text is not a valid transcript or without the `cli` feature.
+    #[cfg(not(feature = "cli"))]
+    pub fn from_json(_text: &str) -> ResultComm<Self> {
+        Err(json_unsupported())
+    }
+}
+
+fn is_json(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|extension| extension.eq_ignore_ascii_case("json"))
+}
+
+#[cfg(not(feature = "cli"))]
+fn json_unsupported() -> CommunicationError {
+    CommunicationError::ParseError("JSON transcripts require the cli feature".to_owned())
+}
+
+fn push_string(bytes: &mut Vec<u8>, text: &str) {
+    let text = &text.as_bytes()[..text.len().min(usize::from(u16::MAX))];
+    bytes.extend((text.len() as u16).to_le_bytes());
+    bytes.extend(text);
+}
+
+fn read_string(bytes: &mut &[u8]) -> io::Result<String> {
+    let mut length = [0; 2];
+    bytes.read_exact(&mut length)?;
+    let mut text = vec![0; usize::from(u16::from_le_bytes(length))];
+    bytes.read_exact(&mut text)?;
+    Ok(String::from_utf8_lossy(&text).into_owned())
+}
+
+#[cfg(feature = "cli")]
+fn hex_string(bytes: &[u8]) -> String {
+    use std::fmt::Write;
+
+    bytes
+        .iter()
+        .fold(String::with_capacity(bytes.len() * 2), |mut text, byte| {
+            let _ = write!(text, "{byte:02x}");
+            text
+        })
+}
+
+#[cfg(feature = "cli")]
+fn parse_hex(text: &str) -> Option<Vec<u8>> {
+    if !text.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..text.len())
+        .step_by(2)
+        .map(|index| u8::from_str_radix(text.get(index..index + 2)?, 16).ok())
+        .collect()
+}
+
+/// Protocol recording all frames exchanged through the wrapped protocol
+#[derive(Debug)]
+pub struct RecordingProtocol<T> {
+    inner: T,
+    start: Instant,
+    transcript: Transcript,
+}
+
+impl<T: Protocol> RecordingProtocol<T> {
+    /// Starts recording frames of `inner`
+    #[must_use]
+    pub fn new(inner: T) -> Self {
+        let transcript = Transcript {
+            identifier: inner.get_identifier().to_owned(),
+            frames: Vec::new(),
+        };
+        RecordingProtocol {
+            inner,
+            start: Instant::now(),
+            transcript,
+        }
+    }
+
+    /// Frames recorded so far
+    #[must_use]
+    pub fn transcript(&self) -> &Transcript {
+        &self.transcript
+    }
+
+    /// Stops recording, returning the recorded frames
+    #[must_use]
+    pub fn into_transcript(self) -> Transcript {
+        self.transcript
+    }
+
+    fn record<R>(
+        &mut self,
+        direction: Direction,
+        packet_code: Option<u8>,
+        result: ResultComm<R>,
+        data: impl FnOnce(&R) -> Vec<u8>,
+    ) -> ResultComm<R> {
+        let (data, error) = match &result {
+            Ok(value) => (data(value), None),
+            Err(err) => (Vec::new(), Some(err.to_string())),
+        };
+        self.transcript.frames.push(Frame {
+            timestamp: self.start.elapsed(),
+            direction,
+            packet_code,
+            data,
+            error,
+        });
+        result
+    }
+}
+
+impl<T: Protocol> Protocol for RecordingProtocol<T> {
+    fn get_timeout(&self) -> Duration {
+        self.inner.get_timeout()
+    }
+
+    fn set_timeout(&mut self, timeout: Duration) -> ResultComm<()> {
+        self.inner.set_timeout(timeout)
+    }
+
+    fn get_polling_interval(&self) -> Duration {
+        self.inner.get_polling_interval()
+    }
+
+    fn get_identifier(&self) -> &str {
+        self.inner.get_identifier()
+    }
+
+    fn ping_response(&self) -> Option<PingResponse> {
+        self.inner.ping_response()
+    }
+
+    fn transport_stats(&self) -> Option<TransportStats> {
+        self.inner.transport_stats()
+    }
+
+    fn apply_quirks(&mut self, quirks: &Quirks) {
+        self.inner.apply_quirks(quirks);
+    }
+
+    fn read(&mut self, bytes: usize) -> ResultComm<Vec<u8>> {
+        let result = self.inner.read(bytes);
+        self.record(Direction::Received, None, result, Clone::clone)
+    }
+
+    fn write_packet_raw(&mut self, data: &[u8]) -> ResultComm<()> {
+        let result = self.inner.write_packet_raw(data);
+        self.record(Direction::Sent, None, result, |()| data.to_vec())
+    }
+
+    /// Records the packet as a frame with the standard framing, like the frames written by
+    /// [`Protocol::write_packet_raw`]
+    fn write_packet(&mut self, packet_code: u8, payload: &[u8]) -> ResultComm<()> {
+        let frame = Framing::STANDARD.frame(packet_code, payload)?;
+        let result = self.inner.write_packet(packet_code, payload);
+        self.record(Direction::Sent, None, result, |()| frame)
+    }
+
+    fn read_packet_raw(&mut self, packet_code: u8) -> ResultComm<Vec<u8>> {
+        let result = self.inner.read_packet_raw(packet_code);
+        self.record(Direction::Received, Some(packet_code), result, Clone::clone)
+    }
+
+    fn read_packet_abort(&mut self, packet_code: u8) -> ResultComm<Vec<u8>> {
+        let result = self.inner.read_packet_abort(packet_code);
+        self.record(Direction::Received, Some(packet_code), result, Clone::clone)
+    }
+}
+
+/// Protocol answering with frames of a [`Transcript`] instead of a device
+///
+/// Sent frames are compared with the recorded ones, so a replay diverging from the recorded session fails
+/// with [`CommunicationError::ParseError`] instead of returning unrelated responses. Recorded errors are
+/// returned again, reading past the end of the transcript returns [`CommunicationError::Timeout`].
+#[derive(Debug)]
+pub struct ReplayProtocol {
+    identifier: String,
+    frames: VecDeque<Frame>,
+    replayed: usize,
+}
+
+impl ReplayProtocol {
+    /// Creates a protocol replaying `transcript`
+    #[must_use]
+    pub fn new(transcript: Transcript) -> Self {
+        ReplayProtocol {
+            identifier: format!("replay of {}", transcript.identifier),
+            frames: transcript.frames.into(),
+            replayed: 0,
+        }
+    }
+
+    /// Number of frames which have not been replayed yet
+    #[must_use]
+    pub fn remaining(&self) -> usize {
+        self.frames.len()
+    }
+
+    /// Takes the next frame, checking it has the expected direction and packet code
+    fn next(&mut self, direction: Direction, packet_code: Option<u8>) -> ResultComm<Frame> {
+        let Some(frame) = self.frames.pop_front() else {
+            return Err(CommunicationError::Timeout);
+        };
+        self.replayed += 1;
+        if frame.direction != direction || frame.packet_code != packet_code {
+            return Err(self.diverged(&format!(
+                "{direction:?} frame with packet code {packet_code:02X?} requested, recorded {:?} frame with {:02X?}",
+                frame.direction, frame.packet_code
+            )));
+        }
+        debug!("replaying frame {}: {:02X?}", self.replayed, frame.data);
+        match frame.error {
+            Some(error) => Err(recorded_error(&error)),
+            None => Ok(frame),
+        }
+    }
+
+    fn diverged(&self, reason: &str) -> CommunicationError {
+        CommunicationError::ParseError(format!("replay diverged at frame {}, {reason}", self.replayed))
+    }
+}
+
+/// Converts a recorded error message back to the error, unknown messages become [`CommunicationError::IOError`]
+fn recorded_error(message: &str) -> CommunicationError {
+    [
+        CommunicationError::Timeout,
+        CommunicationError::Aborted,
+        CommunicationError::NACKSent,
+        CommunicationError::InvalidCrc,
+        CommunicationError::InvalidHeader,
+        CommunicationError::InvalidData,
+        CommunicationError::InvalidPacketReceived,
+    ]
+    .into_iter()
+    .find(|error| error.to_string() == message)
+    .unwrap_or_else(|| CommunicationError::IOError(io::Error::other(message.to_owned())))
+}
+
+impl Protocol for ReplayProtocol {
+    fn get_timeout(&self) -> Duration {
+        Duration::ZERO
+    }
+
+    /// Replayed responses are available right away, there is nothing to wait for
+    fn set_timeout(&mut self, _timeout: Duration) -> ResultComm<()> {
+        Ok(())
+    }
+
+    fn get_polling_interval(&self) -> Duration {
+        Duration::ZERO
+    }
+
+    fn get_identifier(&self) -> &str {
+        &self.identifier
+    }
+
+    fn read(&mut self, bytes: usize) -> ResultComm<Vec<u8>> {
+        let frame = self.next(Direction::Received, None)?;
+        if frame.data.len() != bytes {
+            return Err(self.diverged(&format!("{bytes} bytes read, recorded {}", frame.data.len())));
+        }
+        Ok(frame.data)
+    }
+
+    fn write_packet_raw(&mut self, data: &[u8]) -> ResultComm<()> {
+        let frame = self.next(Direction::Sent, None)?;
+        if frame.data != data {
+            return Err(self.diverged(&format!("sent {data:02X?}, recorded {:02X?}", frame.data)));
+        }
+        Ok(())
+    }
+
+    fn read_packet_raw(&mut self, packet_code: u8) -> ResultComm<Vec<u8>> {
+        Ok(self.next(Direction::Received, Some(packet_code))?.data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn transcript() -> Transcript {
+        Transcript {
+            identifier: "/dev/ttyACM0".to_owned(),
+            frames: vec![
+                Frame {
+                    timestamp: Duration::from_micros(12),
+                    direction: Direction::Sent,
+                    packet_code: None,
+                    data: vec![0x5A, 0xA4, 4, 0, 0x6F, 0x46, 0x0B, 0, 0, 0],
+                    error: None,
+                },
+                Frame {
+                    timestamp: Duration::from_millis(3),
+                    direction: Direction::Received,
+                    packet_code: Some(0xA4),
+                    data: Vec::new(),
+                    error: Some(CommunicationError::Aborted.to_string()),
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn binary_round_trip() {
+        let transcript = transcript();
+        let bytes = transcript.to_bytes();
+        assert_eq!(bytes[..5], *b"MBTR\x01");
+        assert_eq!(Transcript::from_bytes(&bytes).unwrap(), transcript);
+        assert!(Transcript::from_bytes(&bytes[..bytes.len() - 1]).is_err());
+        assert!(Transcript::from_bytes(b"MBTR\x02").is_err());
+    }
+
+    #[cfg(feature = "cli")]
+    #[test]
+    fn json_round_trip() {
+        let transcript = transcript();
+        let json = transcript.to_json().unwrap();
+        assert!(json.contains("\"data\": \"5aa404006f460b000000\""));
+        assert_eq!(Transcript::from_json(&json).unwrap(), transcript);
+    }
+
+    #[test]
+    fn replay_checks_sent_frames() {
+        let mut replay = ReplayProtocol::new(transcript());
+        assert!(matches!(
+            replay.write_packet_raw(&[0x5A, 0xA4]),
+            Err(CommunicationError::ParseError(_))
+        ));
+        assert!(matches!(replay.read_packet_raw(0xA4), Err(CommunicationError::Aborted)));
+        assert!(matches!(replay.read_packet_raw(0xA4), Err(CommunicationError::Timeout)));
+    }
+}
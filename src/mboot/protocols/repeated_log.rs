@@ -0,0 +1,90 @@
+// Copyright 2025 NXP
+//
+// SPDX-License-Identifier: BSD-3-Clause
+//! De-duplication of repeated log messages
+//!
+//! Polling loops, such as waiting for ACK on I2C, may log the same message hundreds of times while
+//! the device is busy. [`RepeatedLog`] logs only the first occurrence of a message, followed by a
+//! single "last message repeated N times" line once a different message arrives, so real anomalies
+//! stay visible in the trace output.
+
+use log::{Level, debug, log, log_enabled};
+
+/// Logger collapsing consecutive identical messages
+///
+/// Pending repetitions are flushed and a summary is written to debug output when dropped.
+#[derive(Debug)]
+pub struct RepeatedLog {
+    /// Short description of the polling loop used in the summary
+    context: &'static str,
+    level: Level,
+    last: Option<String>,
+    repeated: usize,
+    /// Messages not logged, either repeated ones or all of them while the level is disabled
+    suppressed: usize,
+}
+
+impl RepeatedLog {
+    /// Creates a new de-duplicating logger for the given polling loop
+    pub fn new(context: &'static str, level: Level) -> Self {
+        RepeatedLog {
+            context,
+            level,
+            last: None,
+            repeated: 0,
+            suppressed: 0,
+        }
+    }
+
+    /// Logs the message, unless it is the same as the previous one
+    ///
+    /// While the level is disabled, the message is neither formatted nor logged, but it is still counted into the
+    /// summary.
+    pub fn log(&mut self, message: impl FnOnce() -> String) {
+        if !log_enabled!(self.level) {
+            self.suppressed += 1;
+            return;
+        }
+        let message = message();
+        if self.last.as_ref() == Some(&message) {
+            self.repeated += 1;
+            self.suppressed += 1;
+            return;
+        }
+        self.flush();
+        log!(self.level, "{message}");
+        self.last = Some(message);
+    }
+
+    fn flush(&mut self) {
+        if self.repeated > 0 {
+            log!(self.level, "last message repeated {} times", self.repeated);
+            self.repeated = 0;
+        }
+    }
+}
+
+impl Drop for RepeatedLog {
+    fn drop(&mut self) {
+        self.flush();
+        if self.suppressed > 0 {
+            debug!("{}: {} messages suppressed", self.context, self.suppressed);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn disabled_messages_are_counted() {
+        // no logger is installed in tests, so every level is disabled
+        let mut log = RepeatedLog::new("test", Level::Trace);
+        for _ in 0..3 {
+            log.log(|| unreachable!("disabled messages are not formatted"));
+        }
+        assert_eq!(log.suppressed, 3);
+        assert_eq!(log.last, None);
+    }
+}
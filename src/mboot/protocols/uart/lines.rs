@@ -0,0 +1,153 @@
+// Copyright 2025 NXP
+//
+// SPDX-License-Identifier: BSD-3-Clause
+//! Handshaking and modem control lines of the serial port
+//!
+//! Many boards wire DTR and RTS of their USB-serial adapter to the reset and ISP pins of the MCU, so the
+//! bootloader is entered by driving these lines before the first ping. [`SerialOptions`] selects the flow
+//! control, the levels of the lines and a sequence of [`LineStep`]s applied right after the port is opened.
+
+use std::{fmt, thread, time::Duration};
+
+use log::debug;
+use serialport::SerialPort;
+
+use crate::{mboot::ResultComm, parse::parse_number};
+
+/// Flow control of the serial port
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, derive_more::Display)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+pub enum FlowControl {
+    /// No flow control
+    #[default]
+    #[display("none")]
+    None,
+    /// XON/XOFF characters
+    #[display("software")]
+    Software,
+    /// RTS/CTS lines
+    #[display("hardware")]
+    Hardware,
+}
+
+impl From<FlowControl> for serialport::FlowControl {
+    fn from(flow_control: FlowControl) -> Self {
+        match flow_control {
+            FlowControl::None => serialport::FlowControl::None,
+            FlowControl::Software => serialport::FlowControl::Software,
+            FlowControl::Hardware => serialport::FlowControl::Hardware,
+        }
+    }
+}
+
+/// Modem control line driven by the host
+#[derive(Clone, Copy, Debug, PartialEq, Eq, derive_more::Display)]
+pub enum ControlLine {
+    /// Data Terminal Ready
+    #[display("dtr")]
+    Dtr,
+    /// Request To Send
+    #[display("rts")]
+    Rts,
+}
+
+/// Step of the sequence driving the control lines before the first ping
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LineStep {
+    /// Asserts the line for the duration, then returns it to its level set by [`SerialOptions`]
+    Pulse(ControlLine, Duration),
+    /// Waits for the duration, e.g. for the bootloader to start after a reset pulse
+    Wait(Duration),
+}
+
+impl LineStep {
+    /// Parses `dtr:<duration>`, `rts:<duration>` or `wait:<duration>`
+    ///
+    /// The duration is in milliseconds with an optional `ms` suffix, or in seconds with the `s` suffix.
+    ///
+    /// # Errors
+    /// Text describing the invalid step.
+    ///
+    /// # Examples
+    /// ```
+    /// use std::time::Duration;
+    ///
+    /// use mboot::protocols::uart::{ControlLine, LineStep};
+    ///
+    /// assert_eq!(
+    ///     LineStep::parse("dtr:100ms"),
+    ///     Ok(LineStep::Pulse(ControlLine::Dtr, Duration::from_millis(100)))
+    /// );
+    /// assert_eq!(LineStep::parse("wait:1s"), Ok(LineStep::Wait(Duration::from_secs(1))));
+    /// assert!(LineStep::parse("cts:10").is_err());
+    /// ```
+    pub fn parse(s: &str) -> Result<LineStep, String> {
+        let (name, duration) = s
+            .split_once(':')
+            .ok_or_else(|| format!("'{s}' is not in format <dtr|rts|wait>:<duration>"))?;
+        let duration = if let Some(ms) = duration.strip_suffix("ms") {
+            Duration::from_millis(parse_number(ms)?)
+        } else if let Some(secs) = duration.strip_suffix('s') {
+            Duration::from_secs(parse_number(secs)?)
+        } else {
+            Duration::from_millis(parse_number(duration)?)
+        };
+        match name.to_ascii_lowercase().as_str() {
+            "dtr" => Ok(LineStep::Pulse(ControlLine::Dtr, duration)),
+            "rts" => Ok(LineStep::Pulse(ControlLine::Rts, duration)),
+            "wait" => Ok(LineStep::Wait(duration)),
+            _ => Err(format!("unknown line '{name}', expected dtr, rts or wait")),
+        }
+    }
+}
+
+impl fmt::Display for LineStep {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LineStep::Pulse(line, duration) => write!(f, "{line}:{}ms", duration.as_millis()),
+            LineStep::Wait(duration) => write!(f, "wait:{}ms", duration.as_millis()),
+        }
+    }
+}
+
+/// Options of the serial port beyond the baudrate
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SerialOptions {
+    /// Flow control of the port
+    pub flow_control: FlowControl,
+    /// Level DTR is set to after opening, [`None`] keeps the level set by the driver
+    pub dtr: Option<bool>,
+    /// Level RTS is set to after opening, [`None`] keeps the level set by the driver
+    pub rts: Option<bool>,
+    /// Steps applied after the levels are set, before the device is pinged
+    pub boot_sequence: Vec<LineStep>,
+}
+
+impl SerialOptions {
+    /// Sets the levels of the lines and runs the boot sequence
+    pub(super) fn drive_lines(&self, port: &mut dyn SerialPort) -> ResultComm<()> {
+        if let Some(level) = self.dtr {
+            port.write_data_terminal_ready(level)?;
+        }
+        if let Some(level) = self.rts {
+            port.write_request_to_send(level)?;
+        }
+        for step in &self.boot_sequence {
+            debug!("Boot sequence step {step}");
+            match *step {
+                LineStep::Pulse(ControlLine::Dtr, duration) => {
+                    port.write_data_terminal_ready(true)?;
+                    thread::sleep(duration);
+                    port.write_data_terminal_ready(self.dtr.unwrap_or_default())?;
+                }
+                LineStep::Pulse(ControlLine::Rts, duration) => {
+                    port.write_request_to_send(true)?;
+                    thread::sleep(duration);
+                    port.write_request_to_send(self.rts.unwrap_or_default())?;
+                }
+                LineStep::Wait(duration) => thread::sleep(duration),
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,413 @@
+// Copyright 2025 NXP
+//
+// SPDX-License-Identifier: BSD-3-Clause
+//! UART transport
+//!
+//! The identifier is the name of a local serial port, e.g. `COM3` or `/dev/ttyUSB0`, or
+//! `rfc2217://<host>:<port>` for a port exported by a terminal server or serial bridge over RFC 2217.
+//!
+//! Flow control and the modem control lines, often wired to the reset and ISP pins of the board, are set
+//! with [`SerialOptions`] passed to [`UARTProtocol::open_with_serial_options`]. When the baudrate of the
+//! device is not known, [`UARTProtocol::autodetect_baud`] pings it at several baudrates. Ports available on
+//! the host are listed by [`list_ports`].
+
+use std::{io, time::Duration};
+
+use crate::mboot::formatters::style::{RX, TX};
+use log::{debug, error, info, trace, warn};
+use serialport::ClearBuffer;
+
+use crate::mboot::{
+    ResultComm,
+    packets::{
+        Framing, Packet, PacketParse,
+        ping::{Ping, PingResponse},
+    },
+    protocols::{ACK, ACK_ABORT, NACK, frame_sync, poll_schedule::PollSchedule},
+    session::Quirks,
+};
+
+use super::{CommunicationError, Protocol, ProtocolOpen};
+
+mod lines;
+mod ports;
+mod rfc2217;
+
+pub use lines::{ControlLine, FlowControl, LineStep, SerialOptions};
+pub use ports::{PortInfo, UsbPortInfo, list_ports};
+
+/// Baudrates tried by [`UARTProtocol::autodetect_baud`] when no list is given, fastest first
+pub const FALLBACK_BAUDRATES: [u32; 4] = [115_200, 57_600, 19_200, 9_600];
+
+/// Opens a local serial port, or a remote one if the identifier starts with `rfc2217://`
+pub(crate) fn open_port(
+    identifier: &str,
+    baudrate: u32,
+    timeout: Duration,
+    flow_control: FlowControl,
+) -> ResultComm<Box<dyn serialport::SerialPort>> {
+    match identifier.strip_prefix("rfc2217://") {
+        Some(address) => {
+            let mut port = rfc2217::Rfc2217Port::open(address, baudrate, timeout)?;
+            if flow_control != FlowControl::None {
+                serialport::SerialPort::set_flow_control(&mut port, flow_control.into())?;
+            }
+            Ok(Box::new(port))
+        }
+        None => Ok(serialport::new(identifier, baudrate)
+            .timeout(timeout)
+            .flow_control(flow_control.into())
+            .open()?),
+    }
+}
+
+#[derive(Debug)]
+pub struct UARTProtocol {
+    interface: String,
+    port: Box<dyn serialport::SerialPort>,
+    polling_interval: Duration,
+    framing: Framing,
+    /// Response to the last ping
+    ping_response: Option<PingResponse>,
+}
+
+impl ProtocolOpen for UARTProtocol {
+    fn open(identifier: &str) -> ResultComm<Self> {
+        Self::open_with_options(identifier, 57600, Duration::from_secs(5), Duration::from_millis(1))
+    }
+
+    fn open_with_options(
+        identifier: &str,
+        baudrate: u32,
+        timeout: Duration,
+        polling_interval: Duration,
+    ) -> ResultComm<Self> {
+        Self::open_with_framing(identifier, baudrate, timeout, polling_interval, Framing::default())
+    }
+
+    fn open_with_framing(
+        identifier: &str,
+        baudrate: u32,
+        timeout: Duration,
+        polling_interval: Duration,
+        framing: Framing,
+    ) -> ResultComm<Self> {
+        Self::open_with_serial_options(
+            identifier,
+            baudrate,
+            timeout,
+            polling_interval,
+            framing,
+            &SerialOptions::default(),
+        )
+    }
+}
+
+impl UARTProtocol {
+    /// Opens the port like [`ProtocolOpen::open_with_framing`], with flow control and control lines set
+    /// by `options`
+    ///
+    /// The lines are driven and the boot sequence is run before the device is pinged, so a sequence
+    /// resetting the board into the bootloader is followed by a successful ping.
+    ///
+    /// # Errors
+    /// Errors of opening the port or driving its lines, [`CommunicationError`] of the ping otherwise.
+    pub fn open_with_serial_options(
+        identifier: &str,
+        baudrate: u32,
+        timeout: Duration,
+        polling_interval: Duration,
+        framing: Framing,
+        options: &SerialOptions,
+    ) -> ResultComm<Self> {
+        let mut device = Self::open_unpinged(identifier, baudrate, timeout, polling_interval, framing, options)?;
+        device.ping()?;
+        Ok(device)
+    }
+
+    /// Opens the port like [`UARTProtocol::open_with_serial_options`], pinging the device at each of
+    /// `baudrates` until it responds
+    ///
+    /// The lines are driven and the boot sequence is run once, before the first ping. Each baudrate the
+    /// device does not respond at takes `timeout`, so a short timeout speeds up the detection. An empty
+    /// list tries [`FALLBACK_BAUDRATES`]. The detected baudrate is returned by [`UARTProtocol::baudrate`].
+    ///
+    /// # Errors
+    /// Errors of opening the port or driving its lines, [`CommunicationError`] of the ping at the last
+    /// baudrate if the device did not respond at any of them.
+    pub fn autodetect_baud(
+        identifier: &str,
+        baudrates: &[u32],
+        timeout: Duration,
+        polling_interval: Duration,
+        framing: Framing,
+        options: &SerialOptions,
+    ) -> ResultComm<Self> {
+        let baudrates = if baudrates.is_empty() {
+            &FALLBACK_BAUDRATES
+        } else {
+            baudrates
+        };
+        let mut device = Self::open_unpinged(identifier, baudrates[0], timeout, polling_interval, framing, options)?;
+        let mut last_error = CommunicationError::Timeout;
+        for &baudrate in baudrates {
+            device.port.set_baud_rate(baudrate)?;
+            // bytes received at the previous baudrate are garbage
+            device.port.clear(ClearBuffer::Input)?;
+            match device.ping() {
+                Ok(_) => {
+                    info!("Device responded at {baudrate} baud");
+                    return Ok(device);
+                }
+                Err(err) => {
+                    debug!("No response at {baudrate} baud: {err}");
+                    last_error = err;
+                }
+            }
+        }
+        Err(last_error)
+    }
+
+    /// Current baudrate of the port
+    ///
+    /// # Errors
+    /// Error of the serial port library if the baudrate cannot be read.
+    pub fn baudrate(&self) -> ResultComm<u32> {
+        Ok(self.port.baud_rate()?)
+    }
+
+    /// Opens the port and drives its lines without pinging the device
+    fn open_unpinged(
+        identifier: &str,
+        baudrate: u32,
+        timeout: Duration,
+        polling_interval: Duration,
+        framing: Framing,
+        options: &SerialOptions,
+    ) -> ResultComm<Self> {
+        let mut s = open_port(identifier, baudrate, timeout, options.flow_control)?;
+        options.drive_lines(s.as_mut())?;
+
+        let device = UARTProtocol {
+            interface: identifier.to_owned(),
+            port: s,
+            polling_interval,
+            framing,
+            ping_response: None,
+        };
+
+        info!(
+            "Opened UART device {} at {} baud with {}ms timeout and {} flow control",
+            device.interface,
+            baudrate,
+            timeout.as_millis(),
+            options.flow_control
+        );
+        Ok(device)
+    }
+}
+
+impl Protocol for UARTProtocol {
+    fn get_polling_interval(&self) -> Duration {
+        self.polling_interval
+    }
+
+    fn get_timeout(&self) -> Duration {
+        self.port.timeout()
+    }
+
+    fn set_timeout(&mut self, timeout: Duration) -> ResultComm<()> {
+        Ok(self.port.set_timeout(timeout)?)
+    }
+
+    fn get_identifier(&self) -> &str {
+        &self.interface
+    }
+
+    fn ping_response(&self) -> Option<PingResponse> {
+        self.ping_response
+    }
+
+    fn apply_quirks(&mut self, quirks: &Quirks) {
+        if let Some(framing) = quirks.framing {
+            self.framing = framing;
+        }
+    }
+
+    fn read(&mut self, bytes: usize) -> ResultComm<Vec<u8>> {
+        let mut buf = vec![0u8; bytes];
+        // ngl it's really cool that this is just provided by std::io trait
+        self.read_static(&mut buf)?;
+        Ok(buf)
+    }
+
+    fn write_packet_raw(&mut self, data: &[u8]) -> ResultComm<()> {
+        self.write(&self.framing.encode(data)?)?;
+        self.read_ack()?;
+        Ok(())
+    }
+
+    fn write_packet(&mut self, packet_code: u8, payload: &[u8]) -> ResultComm<()> {
+        self.write(&self.framing.frame(packet_code, payload)?)?;
+        self.read_ack()?;
+        Ok(())
+    }
+
+    fn read_packet_raw(&mut self, packet_code: u8) -> ResultComm<Vec<u8>> {
+        self.read_frame(packet_code, ACK)
+    }
+
+    fn read_packet_abort(&mut self, packet_code: u8) -> ResultComm<Vec<u8>> {
+        self.read_frame(packet_code, ACK_ABORT)
+    }
+}
+
+impl UARTProtocol {
+    /// Reads a frame and answers it with `ack`, [`ACK`] or [`ACK_ABORT`]
+    ///
+    /// A frame with a mismatched CRC is answered with [`NACK`] and received again, up to
+    /// [`frame_sync::MAX_RETRANSMISSIONS`] times.
+    fn read_frame(&mut self, packet_code: u8, ack: u8) -> ResultComm<Vec<u8>> {
+        let framing = self.framing;
+        let mut retransmissions = 0;
+        let frame = loop {
+            let frame = frame_sync::receive_frame(framing, packet_code, |bytes| self.read(bytes));
+            if matches!(frame, Err(CommunicationError::InvalidCrc)) && retransmissions < frame_sync::MAX_RETRANSMISSIONS
+            {
+                retransmissions += 1;
+                warn!("{RX}: Requesting retransmission {retransmissions} of the frame");
+                self.send_ack(NACK)?;
+                continue;
+            }
+            if matches!(frame, Ok(_) | Err(CommunicationError::InvalidCrc)) {
+                self.send_ack(ack)?;
+            }
+            break frame?;
+        };
+
+        if frame.len() == 4 {
+            error!("{RX}: Data aborted by sender!");
+            return Err(CommunicationError::Aborted);
+        }
+
+        Ok(frame[4..].to_vec())
+    }
+    fn read_static(&mut self, buf: &mut [u8]) -> Result<(), io::Error> {
+        self.port.read_exact(buf)?;
+        debug!("{RX}: {buf:02X?}");
+        Ok(())
+    }
+
+    fn write(&mut self, buf: &[u8]) -> Result<(), io::Error> {
+        debug!("{TX}: {buf:02X?}");
+        self.port.write_all(buf)
+    }
+
+    fn ping(&mut self) -> ResultComm<PingResponse> {
+        trace!("Pinging device");
+        self.write(&[self.framing.start_byte, Ping::get_code()])?;
+
+        // After power cycle, MBoot v3.0+ may respond with leading dummy data
+        frame_sync::skip_to_start(self.framing.start_byte, &mut |bytes| self.read(bytes))?;
+
+        // Read frame type (should be PingResponse code)
+        let mut frame_type = [0u8; 1];
+        self.port.read_exact(&mut frame_type)?;
+
+        if frame_type[0] != PingResponse::get_code() {
+            return Err(CommunicationError::InvalidHeader);
+        }
+
+        // Read the rest of the response, 6 bytes and 2 bytes of CRC if the framing has it
+        let mut response_data = [0u8; 8];
+        let response_data = &mut response_data[..if self.framing.crc { 8 } else { 6 }];
+        self.port.read_exact(response_data)?;
+
+        // Combine all parts for CRC check and debug output
+        let mut buf = vec![self.framing.start_byte, frame_type[0]];
+        buf.extend_from_slice(response_data);
+
+        debug!("{RX}: {buf:02X?}");
+
+        let crc = match buf.get(8..) {
+            Some(crc) if self.framing.crc => Some(u16::from_le_bytes(
+                crc.try_into().or(Err(CommunicationError::InvalidHeader))?,
+            )),
+            _ => None,
+        };
+
+        if !self.framing.check_crc(&buf[..8], crc) {
+            return Err(CommunicationError::InvalidCrc);
+        }
+
+        let res = PingResponse::parse(&buf)?;
+        self.ping_response = Some(res);
+        Ok(res)
+    }
+
+    fn send_ack(&mut self, ack: u8) -> Result<(), std::io::Error> {
+        trace!(
+            "Sending {}",
+            match ack {
+                ACK_ABORT => "ACK abort",
+                NACK => "NACK",
+                _ => "ACK",
+            }
+        );
+        self.write(&[self.framing.start_byte, ack])
+    }
+
+    fn read_ack(&mut self) -> ResultComm<()> {
+        let timeout = self.get_timeout();
+        let polling_interval = self.get_polling_interval();
+        let mut schedule = PollSchedule::new(timeout, polling_interval);
+        let mut buf = [0u8; 2];
+
+        trace!(
+            "Reading ACK with timeout {}ms and polling interval {}ms",
+            timeout.as_millis(),
+            polling_interval.as_millis()
+        );
+
+        while schedule.wait() {
+            if self.read_static(&mut buf).is_ok() {
+                if buf[0] != self.framing.start_byte {
+                    return Err(CommunicationError::InvalidHeader);
+                }
+
+                return match buf[1] {
+                    ACK => Ok(()),
+                    NACK => Err(CommunicationError::NACKSent),
+                    ACK_ABORT => Err(CommunicationError::Aborted),
+                    _ => Err(CommunicationError::InvalidHeader),
+                };
+            }
+        }
+
+        Err(CommunicationError::Timeout)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::mboot::{packets::ping::PingResponse, protocols::ProtocolOpen};
+
+    use super::UARTProtocol;
+
+    const DEVICE: &str = "COM3";
+    fn open_connection() -> UARTProtocol {
+        UARTProtocol::open(DEVICE).unwrap()
+    }
+
+    #[test]
+    #[ignore = "Requires hardware connection to board"]
+    fn test_board_ping() {
+        let mut port = open_connection();
+        let expected = PingResponse {
+            version: 0x00030150,
+            options: 0x0000,
+        };
+        let res = port.ping().unwrap();
+        assert_eq!(res, expected);
+    }
+}
@@ -0,0 +1,108 @@
+// Copyright 2025 NXP
+//
+// SPDX-License-Identifier: BSD-3-Clause
+//! Serial ports available on the host
+//!
+//! A board often shows up as one of several `/dev/ttyACM*` or `COM*` ports. [`list_ports`] returns them with
+//! the USB descriptors of their bridges, so the port of the board can be told apart by its VID and PID or
+//! by [`PortInfo::is_likely_nxp`].
+
+use serialport::SerialPortType;
+
+use crate::mboot::ResultComm;
+
+/// USB vendor IDs of bridges found on NXP boards: NXP (MCU-Link, LPC-Link2), Freescale and Arm (`DAPLink` on
+/// FRDM boards)
+const NXP_VIDS: [u16; 3] = [0x1FC9, 0x15A2, 0x0D28];
+
+/// USB descriptors of a USB-serial port
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UsbPortInfo {
+    /// Vendor ID
+    pub vid: u16,
+    /// Product ID
+    pub pid: u16,
+    /// Serial number, if reported
+    pub serial_number: Option<String>,
+    /// Manufacturer, if reported
+    pub manufacturer: Option<String>,
+    /// Product name, if reported
+    pub product: Option<String>,
+}
+
+/// Serial port available on the host
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PortInfo {
+    /// Name of the port used as the identifier, e.g. `COM3` or `/dev/ttyACM0`
+    pub name: String,
+    /// Descriptors of USB-serial ports, [`None`] for other ports
+    pub usb: Option<UsbPortInfo>,
+}
+
+impl PortInfo {
+    /// Whether the port likely belongs to an NXP board, judged by the vendor ID and manufacturer of its bridge
+    #[must_use]
+    pub fn is_likely_nxp(&self) -> bool {
+        self.usb.as_ref().is_some_and(|usb| {
+            NXP_VIDS.contains(&usb.vid)
+                || usb
+                    .manufacturer
+                    .as_ref()
+                    .is_some_and(|manufacturer| manufacturer.to_ascii_uppercase().contains("NXP"))
+        })
+    }
+}
+
+/// Lists serial ports available on the host, sorted by name
+///
+/// # Errors
+/// Error of the serial port library if the ports cannot be enumerated.
+pub fn list_ports() -> ResultComm<Vec<PortInfo>> {
+    let mut ports: Vec<PortInfo> = serialport::available_ports()?
+        .into_iter()
+        .map(|port| PortInfo {
+            name: port.port_name,
+            usb: match port.port_type {
+                SerialPortType::UsbPort(usb) => Some(UsbPortInfo {
+                    vid: usb.vid,
+                    pid: usb.pid,
+                    serial_number: usb.serial_number,
+                    manufacturer: usb.manufacturer,
+                    product: usb.product,
+                }),
+                _ => None,
+            },
+        })
+        .collect();
+    ports.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(ports)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn nxp_ports_are_recognized() {
+        let port = |vid, manufacturer: &str| PortInfo {
+            name: "/dev/ttyACM0".to_owned(),
+            usb: Some(UsbPortInfo {
+                vid,
+                pid: 0x0143,
+                serial_number: None,
+                manufacturer: Some(manufacturer.to_owned()),
+                product: None,
+            }),
+        };
+        assert!(port(0x1FC9, "").is_likely_nxp());
+        assert!(port(0x1366, "NXP Semiconductors").is_likely_nxp());
+        assert!(!port(0x0403, "FTDI").is_likely_nxp());
+        assert!(
+            !PortInfo {
+                name: "/dev/ttyS0".to_owned(),
+                usb: None
+            }
+            .is_likely_nxp()
+        );
+    }
+}
@@ -0,0 +1,537 @@
+// Copyright 2025 NXP
+//
+// SPDX-License-Identifier: BSD-3-Clause
+//! RFC 2217 serial port
+//!
+//! Terminal servers and serial bridges like ser2net export serial ports over telnet with the Com Port
+//! Control option described in RFC 2217, which lets the client set the baudrate, line settings and modem
+//! lines over the connection. [`Rfc2217Port`] implements [`SerialPort`] on top of such a connection, so it is
+//! used exactly as a local port.
+
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    io::{self, Read, Write},
+    net::{TcpStream, ToSocketAddrs},
+    sync::{Mutex, MutexGuard},
+    time::{Duration, Instant},
+};
+
+use log::{debug, info, trace};
+use serialport::{ClearBuffer, DataBits, Error, ErrorKind, FlowControl, Parity, SerialPort, StopBits};
+
+use crate::{CommunicationError, mboot::ResultComm};
+
+/// Telnet commands and options
+mod telnet {
+    pub const IAC: u8 = 255;
+    pub const DONT: u8 = 254;
+    pub const DO: u8 = 253;
+    pub const WONT: u8 = 252;
+    pub const WILL: u8 = 251;
+    pub const SB: u8 = 250;
+    pub const SE: u8 = 240;
+
+    pub const BINARY: u8 = 0;
+    pub const SUPPRESS_GO_AHEAD: u8 = 3;
+    pub const COM_PORT_OPTION: u8 = 44;
+}
+
+/// Commands of the Com Port Control option
+mod command {
+    pub const SET_BAUDRATE: u8 = 1;
+    pub const SET_DATASIZE: u8 = 2;
+    pub const SET_PARITY: u8 = 3;
+    pub const SET_STOPSIZE: u8 = 4;
+    pub const SET_CONTROL: u8 = 5;
+    pub const NOTIFY_MODEMSTATE: u8 = 7;
+    pub const PURGE_DATA: u8 = 12;
+    /// The server responds to a command with its code increased by this offset
+    pub const SERVER_OFFSET: u8 = 100;
+}
+
+/// Values of [`command::SET_CONTROL`]
+mod control {
+    pub const NO_FLOW_CONTROL: u8 = 1;
+    pub const SOFTWARE_FLOW_CONTROL: u8 = 2;
+    pub const HARDWARE_FLOW_CONTROL: u8 = 3;
+    pub const BREAK_ON: u8 = 5;
+    pub const BREAK_OFF: u8 = 6;
+    pub const DTR_ON: u8 = 8;
+    pub const DTR_OFF: u8 = 9;
+    pub const RTS_ON: u8 = 11;
+    pub const RTS_OFF: u8 = 12;
+}
+
+/// Bits of [`command::NOTIFY_MODEMSTATE`]
+mod modem_state {
+    pub const CTS: u8 = 0x10;
+    pub const DSR: u8 = 0x20;
+    pub const RING_INDICATOR: u8 = 0x40;
+    pub const CARRIER_DETECT: u8 = 0x80;
+}
+
+/// Options the client supports in the respective direction
+const CLIENT_OPTIONS: [u8; 3] = [telnet::BINARY, telnet::SUPPRESS_GO_AHEAD, telnet::COM_PORT_OPTION];
+const SERVER_OPTIONS: [u8; 2] = [telnet::BINARY, telnet::SUPPRESS_GO_AHEAD];
+
+/// Position of the telnet parser in the received stream
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+enum ParserState {
+    #[default]
+    Data,
+    Iac,
+    Negotiation(u8),
+    Subnegotiation,
+    SubnegotiationIac,
+}
+
+/// Received data and telnet state of the connection
+#[derive(Debug, Default)]
+struct Connection {
+    state: ParserState,
+    /// Serial data not read yet
+    data: VecDeque<u8>,
+    subnegotiation: Vec<u8>,
+    /// Last value of each Com Port Control response, keyed by the server command code
+    responses: HashMap<u8, Vec<u8>>,
+    /// Negotiations sent to the server, answers to them are not answered again
+    negotiated: HashSet<[u8; 2]>,
+}
+
+impl Connection {
+    /// Negotiation requesting binary transfer without go-ahead and the Com Port Control option
+    fn initial_negotiation(&mut self) -> Vec<u8> {
+        let requests = [
+            [telnet::WILL, telnet::BINARY],
+            [telnet::DO, telnet::BINARY],
+            [telnet::WILL, telnet::SUPPRESS_GO_AHEAD],
+            [telnet::DO, telnet::SUPPRESS_GO_AHEAD],
+            [telnet::WILL, telnet::COM_PORT_OPTION],
+        ];
+        self.negotiated.extend(requests);
+        requests
+            .iter()
+            .flat_map(|request| [telnet::IAC, request[0], request[1]])
+            .collect()
+    }
+
+    /// Processes received bytes, returns bytes which have to be sent back to the server
+    fn receive(&mut self, bytes: &[u8]) -> Vec<u8> {
+        let mut reply = Vec::new();
+        for &byte in bytes {
+            self.state = match (self.state, byte) {
+                (ParserState::Data, telnet::IAC) => ParserState::Iac,
+                (ParserState::Data, _) | (ParserState::Iac, telnet::IAC) => {
+                    self.data.push_back(byte);
+                    ParserState::Data
+                }
+                (ParserState::Iac, telnet::SB) => {
+                    self.subnegotiation.clear();
+                    ParserState::Subnegotiation
+                }
+                (ParserState::Iac, telnet::WILL | telnet::WONT | telnet::DO | telnet::DONT) => {
+                    ParserState::Negotiation(byte)
+                }
+                (ParserState::Iac, _) => {
+                    trace!("ignoring telnet command {byte}");
+                    ParserState::Data
+                }
+                (ParserState::Negotiation(verb), _) => {
+                    reply.extend(self.negotiate(verb, byte).into_iter().flatten());
+                    ParserState::Data
+                }
+                (ParserState::Subnegotiation, telnet::IAC) => ParserState::SubnegotiationIac,
+                (ParserState::Subnegotiation, _) | (ParserState::SubnegotiationIac, telnet::IAC) => {
+                    self.subnegotiation.push(byte);
+                    ParserState::Subnegotiation
+                }
+                (ParserState::SubnegotiationIac, telnet::SE) => {
+                    if let [telnet::COM_PORT_OPTION, code, value @ ..] = self.subnegotiation.as_slice() {
+                        trace!("RFC 2217 response {code}: {value:02X?}");
+                        self.responses.insert(*code, value.to_vec());
+                    }
+                    ParserState::Data
+                }
+                (ParserState::SubnegotiationIac, _) => ParserState::Data,
+            };
+        }
+        reply
+    }
+
+    /// Answers an option negotiation of the server, unless it answers our own request
+    fn negotiate(&mut self, verb: u8, option: u8) -> Option<[u8; 3]> {
+        let answer = match verb {
+            telnet::WILL if SERVER_OPTIONS.contains(&option) => telnet::DO,
+            telnet::WILL | telnet::WONT => telnet::DONT,
+            telnet::DO if CLIENT_OPTIONS.contains(&option) => telnet::WILL,
+            _ => telnet::WONT,
+        };
+        self.negotiated
+            .insert([answer, option])
+            .then_some([telnet::IAC, answer, option])
+    }
+}
+
+/// Line settings of the port
+#[derive(Clone, Copy, Debug)]
+struct Settings {
+    baud_rate: u32,
+    data_bits: DataBits,
+    parity: Parity,
+    stop_bits: StopBits,
+    flow_control: FlowControl,
+}
+
+/// Serial port exported by an RFC 2217 server
+#[derive(Debug)]
+pub(super) struct Rfc2217Port {
+    address: String,
+    stream: TcpStream,
+    timeout: Duration,
+    settings: Settings,
+    connection: Mutex<Connection>,
+}
+
+impl Rfc2217Port {
+    /// Connects to the server at `<host>:<port>` and configures the port to `baudrate` and 8N1
+    ///
+    /// # Errors
+    /// Any I/O error of the connection, or [`CommunicationError::SerialPortError`] if the server does not
+    /// confirm the settings within `timeout`.
+    pub(super) fn open(address: &str, baudrate: u32, timeout: Duration) -> ResultComm<Self> {
+        let timeout = timeout.max(Duration::from_millis(1));
+        let mut last_error = io::Error::new(io::ErrorKind::NotFound, format!("{address} did not resolve"));
+        let mut stream = None;
+        for remote in address.to_socket_addrs()? {
+            match TcpStream::connect_timeout(&remote, timeout) {
+                Ok(connected) => {
+                    stream = Some(connected);
+                    break;
+                }
+                Err(err) => {
+                    debug!("connecting to {remote} failed: {err}");
+                    last_error = err;
+                }
+            }
+        }
+        let stream = stream.ok_or(CommunicationError::IOError(last_error))?;
+        stream.set_nodelay(true)?;
+        stream.set_write_timeout(Some(timeout))?;
+
+        let mut port = Rfc2217Port {
+            address: address.to_owned(),
+            stream,
+            timeout,
+            settings: Settings {
+                baud_rate: baudrate,
+                data_bits: DataBits::Eight,
+                parity: Parity::None,
+                stop_bits: StopBits::One,
+                flow_control: FlowControl::None,
+            },
+            connection: Mutex::new(Connection::default()),
+        };
+        let negotiation = port.lock().initial_negotiation();
+        port.send(&negotiation)?;
+        port.set_baud_rate(baudrate)?;
+        port.set_data_bits(DataBits::Eight)?;
+        port.set_parity(Parity::None)?;
+        port.set_stop_bits(StopBits::One)?;
+        port.set_flow_control(FlowControl::None)?;
+        info!("Opened RFC 2217 port {address} at {baudrate} baud");
+        Ok(port)
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Connection> {
+        self.connection
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+
+    fn send(&self, bytes: &[u8]) -> io::Result<()> {
+        (&self.stream).write_all(bytes)
+    }
+
+    /// Reads whatever the server sent within `timeout` and processes it
+    fn fill(&self, connection: &mut Connection, timeout: Duration) -> io::Result<()> {
+        self.stream
+            .set_read_timeout(Some(timeout.max(Duration::from_millis(1))))?;
+        let mut buf = [0u8; 4096];
+        let length = (&self.stream).read(&mut buf)?;
+        if length == 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                format!("{} closed the connection", self.address),
+            ));
+        }
+        let reply = connection.receive(&buf[..length]);
+        if !reply.is_empty() {
+            self.send(&reply)?;
+        }
+        Ok(())
+    }
+
+    /// Processes all data the server already sent, without waiting
+    fn poll(&self, connection: &mut Connection) -> io::Result<()> {
+        self.stream.set_nonblocking(true)?;
+        let result = loop {
+            match self.fill(connection, self.timeout) {
+                Ok(()) => {}
+                Err(err) if err.kind() == io::ErrorKind::WouldBlock => break Ok(()),
+                Err(err) => break Err(err),
+            }
+        };
+        self.stream.set_nonblocking(false)?;
+        result
+    }
+
+    /// Sends a Com Port Control command and waits for its response
+    fn command(&self, code: u8, value: &[u8]) -> serialport::Result<Vec<u8>> {
+        let mut connection = self.lock();
+        let response_code = code + command::SERVER_OFFSET;
+        connection.responses.remove(&response_code);
+
+        let mut request = vec![telnet::IAC, telnet::SB, telnet::COM_PORT_OPTION, code];
+        for &byte in value {
+            request.push(byte);
+            if byte == telnet::IAC {
+                request.push(byte);
+            }
+        }
+        request.extend([telnet::IAC, telnet::SE]);
+        self.send(&request)?;
+
+        let start = Instant::now();
+        loop {
+            if let Some(response) = connection.responses.remove(&response_code) {
+                return Ok(response);
+            }
+            let remaining = self.timeout.saturating_sub(start.elapsed());
+            match self.fill(&mut connection, remaining) {
+                Err(err) if matches!(err.kind(), io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut) => {
+                    return Err(Error::new(
+                        ErrorKind::Io(io::ErrorKind::TimedOut),
+                        format!(
+                            "{} did not confirm RFC 2217 command {code}, it might not be an RFC 2217 server",
+                            self.address
+                        ),
+                    ));
+                }
+                result => result?,
+            }
+        }
+    }
+
+    fn set_control(&self, value: u8) -> serialport::Result<()> {
+        self.command(command::SET_CONTROL, &[value])?;
+        Ok(())
+    }
+
+    /// Reads a line from the last modem state notification of the server
+    fn modem_line(&self, line: u8) -> serialport::Result<bool> {
+        let mut connection = self.lock();
+        self.poll(&mut connection)?;
+        let state = connection
+            .responses
+            .get(&(command::NOTIFY_MODEMSTATE + command::SERVER_OFFSET))
+            .and_then(|value| value.first().copied())
+            .unwrap_or_default();
+        Ok(state & line != 0)
+    }
+}
+
+impl Read for Rfc2217Port {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        let mut connection = self.lock();
+        while connection.data.is_empty() {
+            self.fill(&mut connection, self.timeout)?;
+        }
+        let length = buf.len().min(connection.data.len());
+        for (byte, received) in buf.iter_mut().zip(connection.data.drain(..length)) {
+            *byte = received;
+        }
+        Ok(length)
+    }
+}
+
+impl Write for Rfc2217Port {
+    /// Writes the data, escaping bytes which are telnet commands
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let mut escaped = Vec::with_capacity(buf.len());
+        for &byte in buf {
+            escaped.push(byte);
+            if byte == telnet::IAC {
+                escaped.push(byte);
+            }
+        }
+        self.send(&escaped)?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        (&self.stream).flush()
+    }
+}
+
+impl SerialPort for Rfc2217Port {
+    fn name(&self) -> Option<String> {
+        Some(format!("rfc2217://{}", self.address))
+    }
+
+    fn baud_rate(&self) -> serialport::Result<u32> {
+        Ok(self.settings.baud_rate)
+    }
+
+    fn data_bits(&self) -> serialport::Result<DataBits> {
+        Ok(self.settings.data_bits)
+    }
+
+    fn flow_control(&self) -> serialport::Result<FlowControl> {
+        Ok(self.settings.flow_control)
+    }
+
+    fn parity(&self) -> serialport::Result<Parity> {
+        Ok(self.settings.parity)
+    }
+
+    fn stop_bits(&self) -> serialport::Result<StopBits> {
+        Ok(self.settings.stop_bits)
+    }
+
+    fn timeout(&self) -> Duration {
+        self.timeout
+    }
+
+    fn set_baud_rate(&mut self, baud_rate: u32) -> serialport::Result<()> {
+        self.command(command::SET_BAUDRATE, &baud_rate.to_be_bytes())?;
+        self.settings.baud_rate = baud_rate;
+        Ok(())
+    }
+
+    fn set_data_bits(&mut self, data_bits: DataBits) -> serialport::Result<()> {
+        self.command(command::SET_DATASIZE, &[data_bits.into()])?;
+        self.settings.data_bits = data_bits;
+        Ok(())
+    }
+
+    fn set_flow_control(&mut self, flow_control: FlowControl) -> serialport::Result<()> {
+        self.set_control(match flow_control {
+            FlowControl::None => control::NO_FLOW_CONTROL,
+            FlowControl::Software => control::SOFTWARE_FLOW_CONTROL,
+            FlowControl::Hardware => control::HARDWARE_FLOW_CONTROL,
+        })?;
+        self.settings.flow_control = flow_control;
+        Ok(())
+    }
+
+    fn set_parity(&mut self, parity: Parity) -> serialport::Result<()> {
+        let value = match parity {
+            Parity::None => 1,
+            Parity::Odd => 2,
+            Parity::Even => 3,
+        };
+        self.command(command::SET_PARITY, &[value])?;
+        self.settings.parity = parity;
+        Ok(())
+    }
+
+    fn set_stop_bits(&mut self, stop_bits: StopBits) -> serialport::Result<()> {
+        self.command(command::SET_STOPSIZE, &[stop_bits.into()])?;
+        self.settings.stop_bits = stop_bits;
+        Ok(())
+    }
+
+    fn set_timeout(&mut self, timeout: Duration) -> serialport::Result<()> {
+        self.timeout = timeout.max(Duration::from_millis(1));
+        Ok(())
+    }
+
+    fn write_request_to_send(&mut self, level: bool) -> serialport::Result<()> {
+        self.set_control(if level { control::RTS_ON } else { control::RTS_OFF })
+    }
+
+    fn write_data_terminal_ready(&mut self, level: bool) -> serialport::Result<()> {
+        self.set_control(if level { control::DTR_ON } else { control::DTR_OFF })
+    }
+
+    fn read_clear_to_send(&mut self) -> serialport::Result<bool> {
+        self.modem_line(modem_state::CTS)
+    }
+
+    fn read_data_set_ready(&mut self) -> serialport::Result<bool> {
+        self.modem_line(modem_state::DSR)
+    }
+
+    fn read_ring_indicator(&mut self) -> serialport::Result<bool> {
+        self.modem_line(modem_state::RING_INDICATOR)
+    }
+
+    fn read_carrier_detect(&mut self) -> serialport::Result<bool> {
+        self.modem_line(modem_state::CARRIER_DETECT)
+    }
+
+    fn bytes_to_read(&self) -> serialport::Result<u32> {
+        let mut connection = self.lock();
+        self.poll(&mut connection)?;
+        Ok(u32::try_from(connection.data.len()).unwrap_or(u32::MAX))
+    }
+
+    fn bytes_to_write(&self) -> serialport::Result<u32> {
+        Ok(0)
+    }
+
+    /// Drops received data and asks the server to purge its buffers
+    fn clear(&self, buffer_to_clear: ClearBuffer) -> serialport::Result<()> {
+        let value = match buffer_to_clear {
+            ClearBuffer::Input => 1,
+            ClearBuffer::Output => 2,
+            ClearBuffer::All => 3,
+        };
+        self.command(command::PURGE_DATA, &[value])?;
+        if !matches!(buffer_to_clear, ClearBuffer::Output) {
+            let mut connection = self.lock();
+            self.poll(&mut connection)?;
+            connection.data.clear();
+        }
+        Ok(())
+    }
+
+    fn try_clone(&self) -> serialport::Result<Box<dyn SerialPort>> {
+        Err(Error::new(ErrorKind::Unknown, "RFC 2217 ports cannot be cloned"))
+    }
+
+    fn set_break(&self) -> serialport::Result<()> {
+        self.set_control(control::BREAK_ON)
+    }
+
+    fn clear_break(&self) -> serialport::Result<()> {
+        self.set_control(control::BREAK_OFF)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn telnet_stream() {
+        let mut connection = Connection::default();
+        let initial = connection.initial_negotiation();
+        assert_eq!(initial.len(), 15);
+
+        // escaped 0xFF data, server acknowledging our request and asking for an unsupported option
+        let reply = connection.receive(&[0x5A, 0xFF, 0xFF, 0xFF, 0xFB, 0x00, 0xFF, 0xFD, 0x18, 0xA7]);
+        assert_eq!(connection.data, [0x5A, 0xFF, 0xA7]);
+        assert_eq!(reply, [telnet::IAC, telnet::WONT, 0x18]);
+
+        // baudrate response split across reads, with an escaped byte in the value
+        let reply = connection.receive(&[0xFF, 0xFA, 44, 101, 0x00, 0x01]);
+        assert!(reply.is_empty());
+        connection.receive(&[0xFF, 0xFF, 0x00, 0xFF, 0xF0, 0x01]);
+        assert_eq!(connection.responses[&101], [0x00, 0x01, 0xFF, 0x00]);
+        assert_eq!(connection.data, [0x5A, 0xFF, 0xA7, 0x01]);
+    }
+}
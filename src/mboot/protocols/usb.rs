@@ -2,15 +2,15 @@
 //
 // SPDX-License-Identifier: BSD-3-Clause
 
-use std::{io, time::Duration};
+use std::{collections::BTreeMap, ffi::CString, io, time::Duration};
 
-use crate::mboot::ResultComm;
-use color_print::cstr;
-use hidapi::{HidApi, HidDevice};
-use log::{debug, info};
+use crate::mboot::formatters::style::{RX, TX};
+use crate::mboot::{ResultComm, session::Quirks};
+use hidapi::{HidApi, HidDevice, MAX_REPORT_DESCRIPTOR_SIZE};
+use log::{debug, info, trace};
 use std::fmt::Debug;
 
-use super::{CommunicationError, Protocol, ProtocolOpen};
+use super::{CommunicationError, Protocol, ProtocolOpen, poll_schedule::PollSchedule};
 
 /// Report IDs for USB-HID protocol as per NXP documentation
 mod report {
@@ -27,12 +27,93 @@ mod report {
 /// Maximum packet size for USB transfers
 const MAX_PACKET_SIZE: usize = 1024;
 
+/// Handling of the length field of received HID reports
+///
+/// Bootloaders differ in what can be trusted when a report is received, some pad reports to the full
+/// report size with stale bytes, others report a wrong length field. The default suits all known devices, others
+/// are handled by [`USBProtocol::set_length_quirk`] or [`Quirks::usb_length`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum UsbLengthQuirk {
+    /// Payload is cut to the length field of the report, bytes beyond it are stale padding
+    #[default]
+    TrustLength,
+    /// Payload is the whole report after its header, the length field is ignored
+    TrustReportSize,
+}
+
+impl UsbLengthQuirk {
+    /// Parses quirk name, either `trust-length` or `trust-report-size`
+    ///
+    /// # Errors
+    /// Text listing the valid names if `s` is not one of them.
+    pub fn parse(s: &str) -> Result<UsbLengthQuirk, String> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "trust-length" => Ok(UsbLengthQuirk::TrustLength),
+            "trust-report-size" => Ok(UsbLengthQuirk::TrustReportSize),
+            _ => Err(format!(
+                "unknown USB quirk {s}, expected trust-length or trust-report-size"
+            )),
+        }
+    }
+}
+
+/// Sizes in bytes of the output reports declared by a HID report descriptor, by report ID
+///
+/// Only the items determining the sizes are interpreted, the report size times the report count of each output
+/// item is added to the current report ID. The sizes exclude the report ID.
+#[must_use]
+pub fn output_report_sizes(descriptor: &[u8]) -> BTreeMap<u8, usize> {
+    /// Global items in effect for the following main items
+    #[derive(Clone, Copy, Default)]
+    struct Globals {
+        id: u8,
+        /// Bits of each field
+        size: usize,
+        count: usize,
+    }
+
+    let mut bits: BTreeMap<u8, usize> = BTreeMap::new();
+    let mut globals = Globals::default();
+    let mut stack = Vec::new();
+    let mut items = descriptor;
+    while let [prefix, rest @ ..] = items {
+        // long items carry their data size in the next byte, none of them is interpreted
+        let (tag, length) = match *prefix {
+            0xFE => (None, rest.first().map_or(0, |size| usize::from(*size) + 2)),
+            _ => (Some(prefix & 0xFC), [0, 1, 2, 4][usize::from(prefix & 0x03)]),
+        };
+        let Some(data) = rest.get(..length) else {
+            break;
+        };
+        let value = data.iter().rev().fold(0, |value, byte| value << 8 | usize::from(*byte));
+        match tag {
+            // report size, report count and report ID
+            Some(0x74) => globals.size = value,
+            Some(0x94) => globals.count = value,
+            Some(0x84) => globals.id = value as u8,
+            // push and pop of the global items
+            Some(0xA4) => stack.push(globals),
+            Some(0xB4) => globals = stack.pop().unwrap_or_default(),
+            // output
+            Some(0x90) => *bits.entry(globals.id).or_default() += globals.size * globals.count,
+            _ => {}
+        }
+        items = &rest[length..];
+    }
+    bits.into_iter()
+        .map(|(report_id, bits)| (report_id, bits.div_ceil(8)))
+        .collect()
+}
+
 #[derive(Debug)]
 pub struct USBProtocol {
     interface: String,
     device: HidDevice,
     timeout_ms: i32,
     polling_interval: Duration,
+    length_quirk: UsbLengthQuirk,
+    /// Sizes of output reports by report ID, shorter reports are padded with zeros
+    report_sizes: BTreeMap<u8, usize>,
 }
 
 impl ProtocolOpen for USBProtocol {
@@ -46,36 +127,189 @@ impl ProtocolOpen for USBProtocol {
         timeout: Duration,
         polling_interval: Duration,
     ) -> ResultComm<Self> {
-        // Parse the identifier which can be in format "vid:pid" or a path
-        let (vid, pid) = parse_usb_identifier(identifier)?;
+        // Parse the identifier which can be in format "vid:pid[,serial=XYZ]" or "path=<hid path>"
+        let selector = parse_usb_identifier(identifier)?;
+        Self::open_selector(identifier, &selector, timeout, polling_interval)
+    }
+}
 
+/// Connected USB-HID device, as listed by [`scan_devices`]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UsbDevice {
+    /// USB vendor ID
+    pub vid: u16,
+    /// USB product ID
+    pub pid: u16,
+    /// Serial number string, if the device reports one
+    pub serial: Option<String>,
+    /// Product string, if the device reports one
+    pub product: Option<String>,
+    /// Platform-specific HID path
+    pub path: String,
+}
+
+impl UsbDevice {
+    /// Identifier selecting exactly this device when passed to [`USBProtocol::open`]
+    #[must_use]
+    pub fn identifier(&self) -> String {
+        format!("path={}", self.path)
+    }
+}
+
+/// Lists connected USB-HID devices, optionally only those with the given VID and PID
+///
+/// Devices are sorted by their path, each path is listed once, although HID API may report it for multiple usages.
+///
+/// # Errors
+/// Returns [`CommunicationError::ParseError`] if the HID API could not be initialized.
+pub fn scan_devices(vid: Option<u16>, pid: Option<u16>) -> ResultComm<Vec<UsbDevice>> {
+    let api =
+        HidApi::new().map_err(|e| CommunicationError::ParseError(format!("Failed to initialize HID API: {e}")))?;
+    let mut devices: Vec<UsbDevice> = api
+        .device_list()
+        .filter(|device| vid.is_none_or(|vid| vid == device.vendor_id()))
+        .filter(|device| pid.is_none_or(|pid| pid == device.product_id()))
+        .map(|device| UsbDevice {
+            vid: device.vendor_id(),
+            pid: device.product_id(),
+            serial: device
+                .serial_number()
+                .filter(|serial| !serial.is_empty())
+                .map(str::to_owned),
+            product: device.product_string().map(str::to_owned),
+            path: device.path().to_string_lossy().into_owned(),
+        })
+        .collect();
+    devices.sort_by(|a, b| a.path.cmp(&b.path));
+    devices.dedup_by(|a, b| a.path == b.path);
+    Ok(devices)
+}
+
+/// Selects which USB-HID device should be opened
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum UsbSelector {
+    /// First device matching VID and PID, optionally narrowed down by its serial number
+    VidPid { vid: u16, pid: u16, serial: Option<String> },
+    /// Device with the given platform-specific HID path
+    Path(String),
+}
+
+impl USBProtocol {
+    /// Opens USB-HID device with specified VID, PID and serial number
+    ///
+    /// Useful when multiple identical boards are connected to the host, as the plain VID and PID
+    /// pair always selects the first enumerated device. Default timeout of 5 seconds and polling
+    /// interval of 1 ms are used.
+    ///
+    /// # Arguments
+    /// * `vid` - USB vendor ID
+    /// * `pid` - USB product ID
+    /// * `serial` - Serial number string reported by the device
+    ///
+    /// # Errors
+    /// Returns [`CommunicationError::ParseError`] if the HID API could not be initialized or no
+    /// device with the given serial number was found.
+    pub fn open_by_serial(vid: u16, pid: u16, serial: &str) -> ResultComm<Self> {
+        let selector = UsbSelector::VidPid {
+            vid,
+            pid,
+            serial: Some(serial.to_owned()),
+        };
+        Self::open_selector(
+            &format!("{vid:#06X},{pid:#06X},serial={serial}"),
+            &selector,
+            Duration::from_secs(5),
+            Duration::from_millis(1),
+        )
+    }
+
+    fn open_selector(
+        identifier: &str,
+        selector: &UsbSelector,
+        timeout: Duration,
+        polling_interval: Duration,
+    ) -> ResultComm<Self> {
         // Initialize HidApi
         let api =
             HidApi::new().map_err(|e| CommunicationError::ParseError(format!("Failed to initialize HID API: {e}")))?;
 
         // Find and open the device
-        let device = api
-            .open(vid, pid)
-            .map_err(|e| CommunicationError::ParseError(format!("Failed to open USB device: {e}")))?;
+        let device = match selector {
+            UsbSelector::VidPid {
+                vid,
+                pid,
+                serial: Some(serial),
+            } => api.open_serial(*vid, *pid, serial),
+            UsbSelector::VidPid { vid, pid, serial: None } => api.open(*vid, *pid),
+            UsbSelector::Path(path) => {
+                let path = CString::new(path.as_str())
+                    .map_err(|_| CommunicationError::ParseError(format!("Invalid HID path: {path}")))?;
+                api.open_path(&path)
+            }
+        }
+        .map_err(|e| CommunicationError::ParseError(format!("Failed to open USB device: {e}")))?;
 
         // Convert timeout to i32, clamping if necessary
         let timeout_ms = timeout.as_millis().try_into().unwrap_or(i32::MAX);
+        let report_sizes = detect_report_sizes(&device);
 
         let usb_protocol = USBProtocol {
             interface: identifier.to_owned(),
             device,
             timeout_ms,
             polling_interval,
+            length_quirk: UsbLengthQuirk::default(),
+            report_sizes,
         };
 
         info!(
-            "Opened USB-HID device {} with {}ms timeout",
+            "Opened USB-HID device {} with {}ms timeout, {:?}, output report sizes {:?}",
             usb_protocol.interface,
-            timeout.as_millis()
+            timeout.as_millis(),
+            usb_protocol.length_quirk,
+            usb_protocol.report_sizes
         );
 
         Ok(usb_protocol)
     }
+
+    /// Overrides the handling of the length field of received reports, [`UsbLengthQuirk::TrustLength`] by default
+    pub fn set_length_quirk(&mut self, quirk: UsbLengthQuirk) {
+        self.length_quirk = quirk;
+    }
+
+    /// Returns the handling of the length field of received reports
+    #[must_use]
+    pub fn length_quirk(&self) -> UsbLengthQuirk {
+        self.length_quirk
+    }
+
+    /// Overrides the sizes of output reports by report ID, detected from the report descriptor
+    ///
+    /// Reports shorter than their size are padded with zeros, reports without a size are sent as they are.
+    pub fn set_report_sizes(&mut self, sizes: BTreeMap<u8, usize>) {
+        self.report_sizes = sizes;
+    }
+
+    /// Returns the sizes of output reports by report ID, excluding the report ID
+    #[must_use]
+    pub fn report_sizes(&self) -> &BTreeMap<u8, usize> {
+        &self.report_sizes
+    }
+}
+
+/// Reads the output report sizes from the report descriptor
+///
+/// Without the descriptor no sizes are known, so the reports are sent without padding.
+fn detect_report_sizes(device: &HidDevice) -> BTreeMap<u8, usize> {
+    let mut descriptor = vec![0; MAX_REPORT_DESCRIPTOR_SIZE];
+    match device.get_report_descriptor(&mut descriptor) {
+        Ok(length) => output_report_sizes(&descriptor[..length]),
+        Err(err) => {
+            debug!("Report descriptor cannot be read, reports are not padded: {err}");
+            BTreeMap::new()
+        }
+    }
 }
 
 impl Protocol for USBProtocol {
@@ -87,106 +321,179 @@ impl Protocol for USBProtocol {
         Duration::from_millis(self.timeout_ms.try_into().expect("negative timeout in USB"))
     }
 
+    fn set_timeout(&mut self, timeout: Duration) -> ResultComm<()> {
+        self.timeout_ms = timeout.as_millis().try_into().unwrap_or(i32::MAX);
+        Ok(())
+    }
+
     fn get_identifier(&self) -> &str {
         &self.interface
     }
 
+    fn apply_quirks(&mut self, quirks: &Quirks) {
+        if let Some(quirk) = quirks.usb_length {
+            self.length_quirk = quirk;
+        }
+    }
+
     fn read(&mut self, bytes: usize) -> ResultComm<Vec<u8>> {
         let mut buf = vec![0u8; bytes];
         self.read_usb(&mut buf)?;
         Ok(buf)
     }
+    /// Strips the UART framing of the frame and sends its packet in a HID report
     fn write_packet_raw(&mut self, data: &[u8]) -> ResultComm<()> {
-        // For USB-HID, we need to extract the command data from the UART framing
-        // UART frame format: [5A, cmd_type, len_lsb, len_msb, crc_lsb, crc_msb, ...data...]
-        if data.len() < 6 || data[0] != 0x5A {
-            return Err(CommunicationError::InvalidHeader);
-        }
-
-        let cmd_type = data[1];
-        let data_len = u16::from_le_bytes([data[2], data[3]]) as usize;
+        let (packet_code, payload) = unframe(data)?;
+        self.write_packet(packet_code, payload)
+    }
 
-        if data.len() < 6 + data_len {
-            return Err(CommunicationError::InvalidData);
+    fn write_packet(&mut self, packet_code: u8, payload: &[u8]) -> ResultComm<()> {
+        let mut report = encode_report(packet_code, payload)?;
+        if let Some(&size) = self.report_sizes.get(&report[0]) {
+            // the report ID precedes the report
+            if report.len() < size + 1 {
+                report.resize(size + 1, 0);
+            }
         }
-
-        // Extract the command data (without UART framing and CRC)
-        // Skip the UART header (4 bytes) and CRC (2 bytes)
-        let cmd_data = &data[6..6 + data_len];
-
-        // Determine report ID based on packet type
-        let report_id = match cmd_type {
-            0xA4 => report::CMD_OUT,  // Command packet
-            0xA5 => report::DATA_OUT, // Data packet
-            _ => return Err(CommunicationError::InvalidHeader),
-        };
-
-        // Create a generic HID report
-        let mut report = vec![0u8; 4 + cmd_data.len()]; // 4 bytes for header + data
-
-        // Set report header
-        report[0] = report_id;
-        report[1] = 0x00; // Padding (should be 0)
-        report[2] = (cmd_data.len() & 0xFF) as u8;
-        report[3] = ((cmd_data.len() >> 8) & 0xFF) as u8;
-
-        // Copy command data
-        report[4..4 + cmd_data.len()].copy_from_slice(cmd_data);
-
-        // Write the report
         self.write_usb(&report)?;
-
         Ok(())
     }
-    //
 
+    /// Polls for reports until the packet is complete, a packet longer than one report continues in the payload
+    /// of the following reports
     fn read_packet_raw(&mut self, _: u8) -> ResultComm<Vec<u8>> {
-        // Read the initial response
-        let mut report = vec![0u8; MAX_PACKET_SIZE];
-        let size = self
-            .device
-            .read_timeout(&mut report, self.timeout_ms)
-            .map_err(|e| CommunicationError::IOError(io::Error::other(e.to_string())))?;
+        let schedule = PollSchedule::new(self.get_timeout(), self.polling_interval);
+        let device = &self.device;
+        receive_packet(schedule, self.length_quirk, |report| {
+            device
+                .read_timeout(report, 0)
+                .map_err(|e| CommunicationError::IOError(io::Error::other(e.to_string())))
+        })
+    }
+}
 
-        debug!("{}: Read {} bytes: {:02X?}", cstr!("<r!>RX"), size, &report[..size]);
+/// Polls reports by `read` until they make a whole packet, returns its payload
+///
+/// # Errors
+/// [`CommunicationError::Timeout`] if the packet is not complete before `schedule` times out, even if some of its
+/// reports arrived, errors of `read` and of decoding the packet otherwise.
+fn receive_packet(
+    mut schedule: PollSchedule,
+    quirk: UsbLengthQuirk,
+    mut read: impl FnMut(&mut [u8]) -> ResultComm<usize>,
+) -> ResultComm<Vec<u8>> {
+    let mut report = vec![0u8; MAX_PACKET_SIZE];
+    let mut received = Vec::new();
+    while schedule.wait() {
+        let size = read(&mut report)?;
+        if size == 0 {
+            continue;
+        }
+        debug!("{RX}: Read {} bytes: {:02X?}", size, &report[..size]);
 
-        if size < 4 {
-            return Err(CommunicationError::InvalidHeader);
+        append_report(&mut received, &report[..size]);
+        let missing = missing_bytes(&received, quirk);
+        if missing == 0 {
+            let response = decode_report(&received, quirk)?;
+            debug!("Constructed response: {response:02X?}");
+            return Ok(response);
         }
+        trace!("Waiting for {missing} more bytes of the packet in the next report");
+    }
 
-        // Extract report ID and packet length
-        let report_id = report[0];
-        let packet_length = u16::from_le_bytes([report[2], report[3]]) as usize;
+    if !received.is_empty() {
+        // a partial payload is not returned, the packet would be silently cut short
+        debug!(
+            "Packet is incomplete after timeout, {} bytes missing",
+            missing_bytes(&received, quirk)
+        );
+    }
+    Err(CommunicationError::Timeout)
+}
 
-        if packet_length == 0 {
-            // error!(cstr!("<r!>RX</>: Data aborted by sender!"));
-            return Err(CommunicationError::Aborted);
-        }
+/// Appends a received report to the `received` ones, continuing reports add only the payload after their header
+fn append_report(received: &mut Vec<u8>, report: &[u8]) {
+    if received.is_empty() {
+        received.extend_from_slice(report);
+    } else {
+        received.extend_from_slice(report.get(4..).unwrap_or_default());
+    }
+}
 
-        // Check if this is a command response (report ID 0x03)
-        if report_id == report::CMD_IN {
-            // For other command responses, extract the payload
-            let mut response = Vec::new();
+/// Bytes of a response or data packet missing after the `received` reports, according to its length field
+fn missing_bytes(received: &[u8], quirk: UsbLengthQuirk) -> usize {
+    match (received, quirk) {
+        ([report::CMD_IN | report::DATA_IN, _, low, high, data @ ..], UsbLengthQuirk::TrustLength) => {
+            usize::from(u16::from_le_bytes([*low, *high])).saturating_sub(data.len())
+        }
+        _ => 0,
+    }
+}
 
-            // Extract the command tag and other fields
-            response.extend_from_slice(&report[4..4 + packet_length]);
+/// Splits a frame with the UART framing into its packet type and payload
+///
+/// # Errors
+/// [`CommunicationError::InvalidHeader`] if the frame does not start with the UART header,
+/// [`CommunicationError::InvalidData`] if it is shorter than its length field.
+fn unframe(frame: &[u8]) -> ResultComm<(u8, &[u8])> {
+    // UART frame format: [5A, cmd_type, len_lsb, len_msb, crc_lsb, crc_msb, ...data...]
+    let [0x5A, packet_code, low, high, _, _, data @ ..] = frame else {
+        return Err(CommunicationError::InvalidHeader);
+    };
+    let payload = data
+        .get(..usize::from(u16::from_le_bytes([*low, *high])))
+        .ok_or(CommunicationError::InvalidData)?;
+    Ok((*packet_code, payload))
+}
 
-            debug!("Constructed response: {response:02X?}");
+/// Wraps the payload of a command or data packet into a HID report
+///
+/// # Errors
+/// [`CommunicationError::InvalidHeader`] if the packet is not a command or data packet,
+/// [`CommunicationError::InvalidData`] if the payload does not fit the length field.
+fn encode_report(packet_code: u8, payload: &[u8]) -> ResultComm<Vec<u8>> {
+    let report_id = match packet_code {
+        0xA4 => report::CMD_OUT,  // Command packet
+        0xA5 => report::DATA_OUT, // Data packet
+        _ => return Err(CommunicationError::InvalidHeader),
+    };
+
+    // Report header: ID, padding (should be 0) and length of the data
+    let length = CommunicationError::check_size::<u16>(payload.len())?.to_le_bytes();
+    let mut report = vec![report_id, 0x00, length[0], length[1]];
+    report.extend_from_slice(payload);
+    Ok(report)
+}
 
-            return Ok(response);
-        } else if report_id == report::DATA_IN {
-            // Data packet - extract the data portion
-            if size >= 4 + packet_length {
-                return Ok(report[4..4 + packet_length].to_vec());
-            }
-        }
+/// Extracts the payload of a received HID report
+///
+/// With [`UsbLengthQuirk::TrustReportSize`], the length field is ignored and the whole data is returned.
+///
+/// # Errors
+/// [`CommunicationError::InvalidHeader`] if the report is shorter than its header,
+/// [`CommunicationError::Aborted`] for reports without data and [`CommunicationError::InvalidData`] if a
+/// response is shorter than its length field.
+fn decode_report(report: &[u8], quirk: UsbLengthQuirk) -> ResultComm<Vec<u8>> {
+    let [report_id, _, low, high, data @ ..] = report else {
+        return Err(CommunicationError::InvalidHeader);
+    };
+    let packet_length = match quirk {
+        UsbLengthQuirk::TrustLength => u16::from_le_bytes([*low, *high]) as usize,
+        UsbLengthQuirk::TrustReportSize => data.len(),
+    };
+
+    if packet_length == 0 {
+        return Err(CommunicationError::Aborted);
+    }
 
-        // For other packet types, just return the data portion
-        if size > 4 {
-            Ok(report[4..size].to_vec())
-        } else {
-            Ok(Vec::new())
-        }
+    match *report_id {
+        report::CMD_IN => data
+            .get(..packet_length)
+            .map(<[u8]>::to_vec)
+            .ok_or(CommunicationError::InvalidData),
+        // Data packet - extract the data portion, for other packet types just return it whole
+        report::DATA_IN if data.len() >= packet_length => Ok(data[..packet_length].to_vec()),
+        _ => Ok(data.to_vec()),
     }
 }
 
@@ -194,14 +501,14 @@ impl USBProtocol {
     fn read_usb(&mut self, buf: &mut [u8]) -> Result<(), io::Error> {
         match self.device.read(buf) {
             Ok(size) => {
-                debug!("{}: Read {} bytes: {:02X?}", cstr!("<r!>RX"), size, &buf[..size]);
+                debug!("{RX}: Read {} bytes: {:02X?}", size, &buf[..size]);
                 Ok(())
             }
             Err(e) => Err(io::Error::other(e.to_string())),
         }
     }
     fn write_usb(&self, buf: &[u8]) -> Result<(), io::Error> {
-        debug!("{}: {:02X?}", cstr!("<g!>TX"), buf);
+        debug!("{TX}: {buf:02X?}");
 
         match self.device.write(buf) {
             Ok(written) => {
@@ -237,7 +544,21 @@ impl USBProtocol {
 
 // Helper functions
 
-fn parse_usb_identifier(identifier: &str) -> ResultComm<(u16, u16)> {
+fn parse_usb_identifier(identifier: &str) -> ResultComm<UsbSelector> {
+    if let Some(path) = identifier.strip_prefix("path=") {
+        if path.is_empty() {
+            return Err(CommunicationError::ParseError("Empty HID path".to_owned()));
+        }
+        return Ok(UsbSelector::Path(path.to_owned()));
+    }
+
+    // Serial number selector is always the last part of the identifier
+    let (identifier, serial) = match identifier.split_once(",serial=") {
+        Some((_, "")) => return Err(CommunicationError::ParseError("Empty USB serial number".to_owned())),
+        Some((ids, serial)) => (ids, Some(serial.to_owned())),
+        None => (identifier, None),
+    };
+
     // Check if the identifier contains a separator (either ':' or ',')
     if let Some(pos) = identifier.find([':', ',']) {
         let vid_str = &identifier[..pos];
@@ -249,14 +570,14 @@ fn parse_usb_identifier(identifier: &str) -> ResultComm<(u16, u16)> {
         let pid = parse_number_string(pid_str)
             .map_err(|_| CommunicationError::ParseError(format!("Invalid PID: {pid_str}")))?;
 
-        Ok((vid, pid))
+        Ok(UsbSelector::VidPid { vid, pid, serial })
     } else {
         // Try to parse as a single value (VID only)
         let vid = parse_number_string(identifier)
             .map_err(|_| CommunicationError::ParseError(format!("Invalid USB identifier: {identifier}")))?;
 
         // Use 0 as default PID, which will match any device with the specified VID
-        Ok((vid, 0))
+        Ok(UsbSelector::VidPid { vid, pid: 0, serial })
     }
 }
 
@@ -278,3 +599,194 @@ fn parse_number_string(s: &str) -> Result<u16, std::num::ParseIntError> {
         trimmed.parse::<u16>().or_else(|_| u16::from_str_radix(trimmed, 16))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::*;
+    use crate::mboot::packets::{
+        Framing, Packet,
+        command::{CommandHeader, CommandPacket},
+        data_phase::DataPhasePacket,
+        ping::Ping,
+    };
+    use crate::tags::command_flag::CommandFlag;
+
+    proptest! {
+        #[test]
+        fn command_reports_round_trip(code: u8, reserved: u8, params in prop::collection::vec(any::<u32>(), 0..8)) {
+            let header = CommandHeader {
+                flag: CommandFlag::NoData,
+                reserved,
+            };
+            let payload = header.construct_payload(&params, code)?;
+            let mut report = encode_report(CommandPacket::get_code(), &payload)?;
+            prop_assert_eq!(report[0], report::CMD_OUT);
+            prop_assert_eq!(usize::from(u16::from_le_bytes([report[2], report[3]])), 4 + params.len() * 4);
+
+            report[0] = report::CMD_IN;
+            prop_assert_eq!(decode_report(&report, UsbLengthQuirk::TrustLength)?, payload);
+        }
+
+        #[test]
+        fn data_reports_round_trip(data in prop::collection::vec(any::<u8>(), 1..MAX_PACKET_SIZE - 4)) {
+            let mut report = encode_report(DataPhasePacket::get_code(), &data)?;
+            prop_assert_eq!(report[0], report::DATA_OUT);
+
+            report[0] = report::DATA_IN;
+            prop_assert_eq!(decode_report(&report, UsbLengthQuirk::TrustLength)?, data);
+        }
+
+        #[test]
+        fn truncated_reports_are_rejected(data in prop::collection::vec(any::<u8>(), 1..64), cut in 1usize..64) {
+            let mut report = encode_report(DataPhasePacket::get_code(), &data)?;
+            report[0] = report::CMD_IN;
+            report.truncate(report.len().saturating_sub(cut).max(4));
+            prop_assert!(decode_report(&report, UsbLengthQuirk::TrustLength).is_err());
+            prop_assert!(decode_report(&report[..report.len().min(3)], UsbLengthQuirk::TrustLength).is_err());
+        }
+    }
+
+    #[test]
+    fn frames_are_unframed() {
+        let data: Vec<u8> = (0..10).collect();
+        let frame = Framing::STANDARD.frame(DataPhasePacket::get_code(), &data).unwrap();
+        assert_eq!(unframe(&frame).unwrap(), (DataPhasePacket::get_code(), &data[..]));
+        assert!(matches!(unframe(&frame[..8]), Err(CommunicationError::InvalidData)));
+        assert!(matches!(unframe(&frame[..5]), Err(CommunicationError::InvalidHeader)));
+        assert!(matches!(unframe(&frame[1..]), Err(CommunicationError::InvalidHeader)));
+    }
+
+    #[test]
+    fn report_edge_lengths() {
+        let empty = encode_report(DataPhasePacket::get_code(), &[]).unwrap();
+        assert_eq!(empty, [report::DATA_OUT, 0, 0, 0]);
+        assert!(matches!(
+            decode_report(&empty, UsbLengthQuirk::TrustLength),
+            Err(CommunicationError::Aborted)
+        ));
+
+        let data = vec![0xA5; 0xFFFF];
+        let mut report = encode_report(DataPhasePacket::get_code(), &data).unwrap();
+        assert_eq!(report[2..4], [0xFF, 0xFF]);
+        report[0] = report::DATA_IN;
+        assert_eq!(decode_report(&report, UsbLengthQuirk::TrustLength).unwrap(), data);
+
+        assert!(matches!(
+            encode_report(Ping::get_code(), &[]),
+            Err(CommunicationError::InvalidHeader)
+        ));
+    }
+
+    #[test]
+    fn split_packets_are_reassembled() {
+        let data: Vec<u8> = (0..100).collect();
+        let mut report = encode_report(DataPhasePacket::get_code(), &data).unwrap();
+        report[0] = report::DATA_IN;
+        let (first, rest) = report.split_at(64);
+        assert_eq!(missing_bytes(first, UsbLengthQuirk::TrustLength), 40);
+        assert_eq!(missing_bytes(first, UsbLengthQuirk::TrustReportSize), 0);
+
+        let mut received = Vec::new();
+        append_report(&mut received, first);
+        append_report(&mut received, &[&[report::DATA_IN, 0, 40, 0][..], rest].concat());
+        assert_eq!(missing_bytes(&received, UsbLengthQuirk::TrustLength), 0);
+        assert_eq!(decode_report(&received, UsbLengthQuirk::TrustLength).unwrap(), data);
+        assert_eq!(missing_bytes(&[report::CMD_IN, 0], UsbLengthQuirk::TrustLength), 0);
+    }
+
+    #[test]
+    fn split_packet_times_out() {
+        let data: Vec<u8> = (0..100).collect();
+        let mut report = encode_report(DataPhasePacket::get_code(), &data).unwrap();
+        report[0] = report::DATA_IN;
+        let mut reports = vec![report[..64].to_vec()];
+        let read = |buffer: &mut [u8]| {
+            let report = reports.pop().unwrap_or_default();
+            buffer[..report.len()].copy_from_slice(&report);
+            Ok(report.len())
+        };
+        let schedule = PollSchedule::new(Duration::from_millis(20), Duration::from_millis(1));
+        assert!(matches!(
+            receive_packet(schedule, UsbLengthQuirk::TrustLength, read),
+            Err(CommunicationError::Timeout)
+        ));
+    }
+
+    #[test]
+    fn report_sizes_from_descriptor() {
+        #[rustfmt::skip]
+        let descriptor = [
+            0x06, 0x00, 0xFF, // usage page (vendor defined)
+            0x09, 0x01, // usage
+            0xA1, 0x01, // collection (application)
+            0x15, 0x00, 0x26, 0xFF, 0x00, // logical minimum and maximum
+            0x75, 0x08, // report size (8 bits)
+            0x85, 0x01, 0x95, 0x20, 0x09, 0x01, 0x91, 0x02, // report 1, 32 output bytes
+            0xA4, // push
+            0x85, 0x02, 0x96, 0xFC, 0x03, 0x09, 0x01, 0x91, 0x02, // report 2, 1020 output bytes
+            0xB4, // pop
+            0x85, 0x03, 0x09, 0x01, 0x81, 0x02, // report 3, 32 input bytes
+            0xFE, 0x02, 0x10, 0xAA, 0xBB, // long item
+            0x85, 0x04, 0x75, 0x01, 0x95, 0x0C, 0x91, 0x02, // report 4, 12 output bits
+            0xC0, // end collection
+        ];
+        let sizes = output_report_sizes(&descriptor);
+        assert_eq!(sizes.into_iter().collect::<Vec<_>>(), [(1, 32), (2, 1020), (4, 2)]);
+        assert!(output_report_sizes(&descriptor[..20]).is_empty());
+    }
+
+    #[test]
+    fn length_quirks() {
+        // response padded with stale bytes beyond its length field
+        let padded = [report::CMD_IN, 0, 2, 0, 0xA0, 0x00, 0xEE, 0xEE];
+        assert_eq!(
+            decode_report(&padded, UsbLengthQuirk::TrustLength).unwrap(),
+            [0xA0, 0x00]
+        );
+        // response with a wrong length field
+        let lying = [report::CMD_IN, 0, 8, 0, 0xA0, 0x00, 0x01, 0x02];
+        assert!(decode_report(&lying, UsbLengthQuirk::TrustLength).is_err());
+        assert_eq!(
+            decode_report(&lying, UsbLengthQuirk::TrustReportSize).unwrap(),
+            [0xA0, 0x00, 0x01, 0x02]
+        );
+        assert!(matches!(
+            decode_report(&[report::CMD_IN, 0, 4, 0], UsbLengthQuirk::TrustReportSize),
+            Err(CommunicationError::Aborted)
+        ));
+
+        assert_eq!(
+            UsbLengthQuirk::parse("Trust-Report-Size"),
+            Ok(UsbLengthQuirk::TrustReportSize)
+        );
+        assert!(UsbLengthQuirk::parse("trust-nothing").is_err());
+    }
+
+    #[test]
+    fn usb_identifier_selectors() {
+        assert_eq!(
+            parse_usb_identifier("0x1FC9,0x0021").unwrap(),
+            UsbSelector::VidPid {
+                vid: 0x1FC9,
+                pid: 0x21,
+                serial: None
+            }
+        );
+        assert_eq!(
+            parse_usb_identifier("0x1FC9:0x0021,serial=ABC123").unwrap(),
+            UsbSelector::VidPid {
+                vid: 0x1FC9,
+                pid: 0x21,
+                serial: Some("ABC123".to_owned())
+            }
+        );
+        assert_eq!(
+            parse_usb_identifier("path=/dev/hidraw3").unwrap(),
+            UsbSelector::Path("/dev/hidraw3".to_owned())
+        );
+        assert!(parse_usb_identifier("0x1FC9,0x0021,serial=").is_err());
+        assert!(parse_usb_identifier("path=").is_err());
+    }
+}
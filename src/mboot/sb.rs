@@ -0,0 +1,333 @@
+// Copyright 2025 NXP
+//
+// SPDX-License-Identifier: BSD-3-Clause
+//! Secure Binary Files
+//!
+//! SB files are sent to the device with [`McuBoot::receive_sb_file`] and processed by the bootloader.
+//! Sending a file built for a different family can leave the device in an unusable state, so the header
+//! of the file is parsed by [`SbHeader::parse`] and checked against the connected device by
+//! [`check_device`] before the file is streamed.
+//!
+//! # Supported Formats
+//! - SB2.0 and SB2.1: `STMP` signature, used by bootloaders up to version 3.0
+//! - SB3.1: `sbv3` magic, used by bootloaders since version 3.1
+
+use std::fmt::Display;
+
+use log::info;
+
+use super::{McuBoot, ResultComm, protocols::Protocol};
+use crate::{
+    CommunicationError,
+    tags::{command::CommandTagDiscriminants, property::Version},
+};
+
+/// Signature at offset 20 of SB1 and SB2 files
+const SB2_SIGNATURE: &[u8; 4] = b"STMP";
+/// Second signature at offset 52 of SB2 files
+const SB2_SIGNATURE2: &[u8; 4] = b"sgtl";
+/// Size of the SB2 image header
+const SB2_HEADER_SIZE: usize = 96;
+/// Size of SB2 cipher blocks in bytes
+const SB2_BLOCK_SIZE: u32 = 16;
+/// Flag of signed SB2.1 files
+const SB2_FLAG_SIGNED: u32 = 0x8;
+/// Magic at the start of SB3 files
+const SB3_MAGIC: &[u8; 4] = b"sbv3";
+/// Size of the SB3.1 header up to and including the description
+const SB3_HEADER_SIZE: usize = 60;
+
+/// Format of the SB file
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SbKind {
+    /// SB2.0 or SB2.1
+    Sb2,
+    /// SB3.1
+    Sb3,
+}
+
+/// Encryption of the SB file content
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SbEncryption {
+    /// SB2 sections are encrypted, the key is wrapped in `key_count` key dictionary entries
+    KeyDictionary {
+        /// Number of key dictionary entries
+        key_count: u16,
+    },
+    /// SB2 file without key dictionary, sections are in plain text
+    Plain,
+    /// SB3.1 blocks are encrypted by keys derived in the device from the SB key derivation key
+    DerivedKey,
+}
+
+impl Display for SbEncryption {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SbEncryption::KeyDictionary { key_count } => write!(f, "encrypted, {key_count} key blob(s)"),
+            SbEncryption::Plain => write!(f, "not encrypted"),
+            SbEncryption::DerivedKey => write!(f, "encrypted, key derived in the device"),
+        }
+    }
+}
+
+/// Header of an SB file
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SbHeader {
+    /// Format of the file
+    pub kind: SbKind,
+    /// Major format version
+    pub major: u16,
+    /// Minor format version
+    pub minor: u16,
+    /// Flags of the image
+    pub flags: u32,
+    /// Number of blocks in the image
+    pub block_count: u32,
+    /// Size of one block in bytes
+    pub block_size: u32,
+    /// Timestamp of the image creation, in microseconds since 2000-01-01
+    pub timestamp: u64,
+    /// Encryption of the file content
+    pub encryption: SbEncryption,
+    /// Firmware version used for rollback protection, SB3.1 only
+    pub firmware_version: Option<u32>,
+    /// Image type, SB3.1 only
+    pub image_type: Option<u32>,
+    /// Description of the image, SB3.1 only
+    pub description: Option<String>,
+}
+
+impl SbHeader {
+    /// Parses the header at the start of an SB file
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CommunicationError::ParseError`] if the file is not an SB2.x or SB3.1 file.
+    pub fn parse(bytes: &[u8]) -> Result<Self, CommunicationError> {
+        if bytes.starts_with(SB3_MAGIC) {
+            Self::parse_sb3(bytes)
+        } else if bytes.get(20..24) == Some(SB2_SIGNATURE) {
+            Self::parse_sb2(bytes)
+        } else {
+            Err(CommunicationError::ParseError(
+                "not an SB file, neither 'sbv3' magic nor 'STMP' signature was found".to_owned(),
+            ))
+        }
+    }
+
+    fn parse_sb2(bytes: &[u8]) -> Result<Self, CommunicationError> {
+        if bytes.len() < SB2_HEADER_SIZE {
+            return Err(CommunicationError::ParseError(
+                "SB file is shorter than its header".to_owned(),
+            ));
+        }
+        let major = u16::from(bytes[24]);
+        let minor = u16::from(bytes[25]);
+        if major != 2 {
+            return Err(CommunicationError::ParseError(format!(
+                "SB{major}.{minor} files are not supported"
+            )));
+        }
+        if &bytes[52..56] != SB2_SIGNATURE2 {
+            return Err(CommunicationError::ParseError(
+                "SB2 file is missing the 'sgtl' signature".to_owned(),
+            ));
+        }
+        let key_count = u16_at(bytes, 40);
+        Ok(SbHeader {
+            kind: SbKind::Sb2,
+            major,
+            minor,
+            flags: u32::from(u16_at(bytes, 26)),
+            block_count: u32_at(bytes, 28),
+            block_size: SB2_BLOCK_SIZE,
+            timestamp: u64_at(bytes, 56),
+            encryption: if key_count > 0 {
+                SbEncryption::KeyDictionary { key_count }
+            } else {
+                SbEncryption::Plain
+            },
+            firmware_version: None,
+            image_type: None,
+            description: None,
+        })
+    }
+
+    fn parse_sb3(bytes: &[u8]) -> Result<Self, CommunicationError> {
+        if bytes.len() < SB3_HEADER_SIZE {
+            return Err(CommunicationError::ParseError(
+                "SB file is shorter than its header".to_owned(),
+            ));
+        }
+        let minor = u16_at(bytes, 4);
+        let major = u16_at(bytes, 6);
+        if (major, minor) != (3, 1) {
+            return Err(CommunicationError::ParseError(format!(
+                "SB{major}.{minor} files are not supported"
+            )));
+        }
+        let description = &bytes[44..SB3_HEADER_SIZE];
+        let description = description.split(|byte| *byte == 0).next().unwrap_or(description);
+        Ok(SbHeader {
+            kind: SbKind::Sb3,
+            major,
+            minor,
+            flags: u32_at(bytes, 8),
+            block_count: u32_at(bytes, 12),
+            block_size: u32_at(bytes, 16),
+            timestamp: u64_at(bytes, 20),
+            encryption: SbEncryption::DerivedKey,
+            firmware_version: Some(u32_at(bytes, 28)),
+            image_type: Some(u32_at(bytes, 36)),
+            description: Some(String::from_utf8_lossy(description).into_owned()),
+        })
+    }
+
+    /// Returns `true` if the file is signed
+    ///
+    /// SB3.1 files are always signed, SB2 files only if they were built as SB2.1 with a certificate.
+    #[must_use]
+    pub fn is_signed(&self) -> bool {
+        match self.kind {
+            SbKind::Sb2 => self.flags & SB2_FLAG_SIGNED != 0,
+            SbKind::Sb3 => true,
+        }
+    }
+
+    /// Returns `true` if a bootloader of `version` is able to process the file
+    ///
+    /// SB2 files are processed by bootloaders up to version 3.0, which were replaced by bootloaders
+    /// processing SB3.1 files since version 3.1.
+    #[must_use]
+    pub fn supported_by(&self, version: Version) -> bool {
+        let version = (version.major, version.minor);
+        match self.kind {
+            SbKind::Sb2 => version < (3, 1),
+            SbKind::Sb3 => version >= (3, 1),
+        }
+    }
+}
+
+impl Display for SbHeader {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "SB{}.{}", self.major, self.minor)
+    }
+}
+
+/// Checks that the connected device is able to process the SB file
+///
+/// The bootloader version in [`McuBoot::capabilities`] must support the format of the file and the
+/// device must report [`CommandTagDiscriminants::ReceiveSBFile`] among its available commands.
+///
+/// # Returns
+///
+/// Version of the bootloader
+///
+/// # Errors
+///
+/// [`CommunicationError::ParseError`] if the device cannot process the file, any [`CommunicationError`]
+/// of [`McuBoot::capabilities`] otherwise.
+pub fn check_device<T: Protocol>(boot: &mut McuBoot<T>, header: &SbHeader) -> ResultComm<Version> {
+    let capabilities = boot.capabilities()?;
+    let Some(version) = capabilities.version else {
+        return Err(CommunicationError::InvalidData);
+    };
+    info!("Bootloader version: {version}");
+    if !header.supported_by(version) {
+        return Err(CommunicationError::ParseError(format!(
+            "{header} file is not supported by bootloader {version}, is it built for this device family?"
+        )));
+    }
+
+    if capabilities.commands.is_some() && capabilities.supports(CommandTagDiscriminants::ReceiveSBFile) {
+        Ok(version)
+    } else {
+        Err(CommunicationError::ParseError(
+            "device does not support receive SB file command, is it running the ROM bootloader?".to_owned(),
+        ))
+    }
+}
+
+fn u16_at(bytes: &[u8], offset: usize) -> u16 {
+    u16::from_le_bytes([bytes[offset], bytes[offset + 1]])
+}
+
+fn u32_at(bytes: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes(bytes[offset..offset + 4].try_into().unwrap())
+}
+
+fn u64_at(bytes: &[u8], offset: usize) -> u64 {
+    u64::from_le_bytes(bytes[offset..offset + 8].try_into().unwrap())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sb2_header() -> Vec<u8> {
+        let mut bytes = vec![0; SB2_HEADER_SIZE];
+        bytes[20..24].copy_from_slice(SB2_SIGNATURE);
+        bytes[24] = 2;
+        bytes[25] = 1;
+        bytes[26] = 0x8;
+        bytes[28..32].copy_from_slice(&100u32.to_le_bytes());
+        bytes[40] = 1;
+        bytes[52..56].copy_from_slice(SB2_SIGNATURE2);
+        bytes
+    }
+
+    fn sb3_header() -> Vec<u8> {
+        let mut bytes = vec![0; SB3_HEADER_SIZE];
+        bytes[..4].copy_from_slice(SB3_MAGIC);
+        bytes[4] = 1;
+        bytes[6] = 3;
+        bytes[12..16].copy_from_slice(&5u32.to_le_bytes());
+        bytes[16..20].copy_from_slice(&292u32.to_le_bytes());
+        bytes[28..32].copy_from_slice(&7u32.to_le_bytes());
+        bytes[36..40].copy_from_slice(&6u32.to_le_bytes());
+        bytes[44..48].copy_from_slice(b"test");
+        bytes
+    }
+
+    #[test]
+    fn parse_sb2() {
+        let header = SbHeader::parse(&sb2_header()).unwrap();
+        assert_eq!(header.kind, SbKind::Sb2);
+        assert_eq!((header.major, header.minor), (2, 1));
+        assert_eq!(header.block_count, 100);
+        assert_eq!(header.encryption, SbEncryption::KeyDictionary { key_count: 1 });
+        assert!(header.is_signed());
+
+        let mut sb1 = sb2_header();
+        sb1[24] = 1;
+        assert!(SbHeader::parse(&sb1).is_err());
+        assert!(SbHeader::parse(&sb2_header()[..50]).is_err());
+    }
+
+    #[test]
+    fn parse_sb3() {
+        let header = SbHeader::parse(&sb3_header()).unwrap();
+        assert_eq!(header.kind, SbKind::Sb3);
+        assert_eq!(header.to_string(), "SB3.1");
+        assert_eq!((header.block_count, header.block_size), (5, 292));
+        assert_eq!(header.firmware_version, Some(7));
+        assert_eq!(header.description.as_deref(), Some("test"));
+        assert!(SbHeader::parse(b"garbage").is_err());
+    }
+
+    #[test]
+    fn bootloader_version() {
+        let version = |minor| Version {
+            mark: 'K',
+            major: 3,
+            minor,
+            fixation: 0,
+        };
+        let sb2 = SbHeader::parse(&sb2_header()).unwrap();
+        let sb3 = SbHeader::parse(&sb3_header()).unwrap();
+        assert!(sb2.supported_by(version(0)));
+        assert!(!sb2.supported_by(version(1)));
+        assert!(!sb3.supported_by(version(0)));
+        assert!(sb3.supported_by(version(2)));
+    }
+}
@@ -0,0 +1,172 @@
+// Copyright 2025 NXP
+//
+// SPDX-License-Identifier: BSD-3-Clause
+//! Scratch Buffers in RAM
+//!
+//! Some operations need a temporary buffer in the RAM of the device, e.g. for a memory configuration block
+//! or for trust provisioning inputs and outputs. [`ScratchAllocator`] takes the internal RAM reported by the
+//! device, removes regions reserved by the bootloader and hands out non-overlapping chunks of the rest, so
+//! independent operations of one session do not overwrite each other's buffers or the bootloader itself.
+
+use std::ops::Range;
+
+use log::debug;
+
+use super::{
+    McuBoot, ResultComm, memory,
+    protocols::{CommunicationError, Protocol},
+    tags::property::{PropertyTag, PropertyTagDiscriminants},
+};
+
+/// Allocator of scratch buffers in the internal RAM of the device
+///
+/// Allocations are never freed, the allocator lives as long as the session which uses the buffers.
+///
+/// # Example
+///
+/// ```
+/// use mboot::scratch::ScratchAllocator;
+///
+/// // 64 KiB of RAM with the first 8 KiB used by the bootloader
+/// let mut scratch = ScratchAllocator::new(0x2000_0000, 0x1_0000, &[(0x2000_0000, 0x2000_1FFF)]);
+/// assert_eq!(scratch.allocate(0x100, 4), Some(0x2000_2000));
+/// assert_eq!(scratch.allocate(0x100, 0x1000), Some(0x2000_3000));
+/// assert_eq!(scratch.allocate(0x10_0000, 4), None);
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ScratchAllocator {
+    /// Free address ranges, sorted by address and not overlapping
+    free: Vec<Range<u64>>,
+}
+
+impl ScratchAllocator {
+    /// Creates an allocator for RAM at `ram_start` with `ram_size` bytes
+    ///
+    /// # Arguments
+    /// * `ram_start` - Start address of the RAM
+    /// * `ram_size` - Size of the RAM in bytes
+    /// * `reserved` - (`start_address`, `end_address`) pairs of reserved regions, end addresses are
+    ///   inclusive as in [`PropertyTag::ReservedRegions`]
+    #[must_use]
+    pub fn new(ram_start: u32, ram_size: u32, reserved: &[(u32, u32)]) -> Self {
+        let ram = u64::from(ram_start)..u64::from(ram_start) + u64::from(ram_size);
+        ScratchAllocator {
+            free: memory::without_reserved(ram, reserved),
+        }
+    }
+
+    /// Creates an allocator from [`PropertyTag::RAMStartAddress`], [`PropertyTag::RAMSize`] and
+    /// [`PropertyTag::ReservedRegions`] of the device
+    ///
+    /// Reserved regions are optional, devices not reporting them are treated as having none.
+    ///
+    /// # Errors
+    /// Any [`CommunicationError`] of the property queries, [`CommunicationError::UnexpectedStatus`] if the
+    /// device does not report its RAM and [`CommunicationError::InvalidData`] if it responds with another
+    /// property.
+    pub fn from_device<T: Protocol>(boot: &mut McuBoot<T>) -> ResultComm<Self> {
+        let PropertyTag::RAMStartAddress(ram_start) = boot
+            .get_property(PropertyTagDiscriminants::RAMStartAddress, 0)?
+            .property
+        else {
+            return Err(CommunicationError::InvalidData);
+        };
+        let PropertyTag::RAMSize(ram_size) = boot.get_property(PropertyTagDiscriminants::RAMSize, 0)?.property else {
+            return Err(CommunicationError::InvalidData);
+        };
+        let reserved = boot.reserved_regions()?;
+        Ok(Self::new(ram_start, ram_size, &reserved))
+    }
+
+    /// Allocates `size` bytes aligned to `alignment`, returns the start address of the buffer
+    ///
+    /// The lowest suitable address is used, [`None`] is returned if no free region is large enough.
+    /// Alignment of zero is treated as one.
+    ///
+    /// # Panics
+    /// If `alignment` is not a power of two.
+    pub fn allocate(&mut self, size: u32, alignment: u32) -> Option<u32> {
+        let alignment = u64::from(alignment.max(1));
+        assert!(
+            alignment.is_power_of_two(),
+            "alignment {alignment} is not a power of two"
+        );
+        let size = u64::from(size);
+
+        let (index, start) = self.free.iter().enumerate().find_map(|(index, range)| {
+            let start = range.start.next_multiple_of(alignment);
+            (start + size <= range.end).then_some((index, start))
+        })?;
+        let range = self.free.remove(index);
+        let remaining = [range.start..start, start + size..range.end];
+        self.free
+            .splice(index..index, remaining.into_iter().filter(|range| !range.is_empty()));
+        debug!("allocated scratch buffer of {size} bytes at {start:#010X}");
+        u32::try_from(start).ok()
+    }
+
+    /// Free address ranges as (`start_address`, `size`) pairs
+    #[must_use]
+    pub fn free_regions(&self) -> Vec<(u32, u64)> {
+        self.free
+            .iter()
+            .map(|range| (range.start as u32, range.end - range.start))
+            .collect()
+    }
+
+    /// Size of the largest buffer which can be allocated without alignment
+    #[must_use]
+    pub fn largest_free(&self) -> u64 {
+        self.free.iter().map(|range| range.end - range.start).max().unwrap_or(0)
+    }
+
+    /// Whether `size` bytes at `address` lie in a single free region
+    #[must_use]
+    pub fn is_free(&self, address: u32, size: u32) -> bool {
+        let start = u64::from(address);
+        let end = start + u64::from(size);
+        self.free.iter().any(|range| range.start <= start && end <= range.end)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ScratchAllocator;
+
+    #[test]
+    fn reserved_regions_are_skipped() {
+        let scratch = ScratchAllocator::new(
+            0x2000_0000,
+            0x1_0000,
+            &[
+                (0x1000_0000, 0x2000_00FF),
+                (0x2000_8000, 0x2000_8FFF),
+                (0x3000_0000, 0x3000_0FFF),
+            ],
+        );
+        assert_eq!(scratch.free_regions(), [(0x2000_0100, 0x7F00), (0x2000_9000, 0x7000)]);
+        assert_eq!(scratch.largest_free(), 0x7F00);
+        assert!(scratch.is_free(0x2000_0100, 0x7F00));
+        assert!(!scratch.is_free(0x2000_7F00, 0x200));
+        assert!(!scratch.is_free(0x2000_0000, 0x10));
+    }
+
+    #[test]
+    fn allocations_do_not_overlap() {
+        let mut scratch = ScratchAllocator::new(0x2000_0000, 0x1000, &[(0x2000_0800, 0x2000_08FF)]);
+        assert_eq!(scratch.allocate(0x10, 4), Some(0x2000_0000));
+        assert_eq!(scratch.allocate(0x700, 0x100), Some(0x2000_0100));
+        // padding left by the alignment is used by later allocations
+        assert_eq!(scratch.allocate(0x80, 0), Some(0x2000_0010));
+        assert_eq!(scratch.allocate(0x800, 4), None);
+        assert_eq!(scratch.allocate(0x600, 4), Some(0x2000_0900));
+        assert_eq!(scratch.free_regions(), [(0x2000_0090, 0x70), (0x2000_0F00, 0x100)]);
+    }
+
+    #[test]
+    fn ram_at_the_end_of_address_space() {
+        let mut scratch = ScratchAllocator::new(0xFFFF_F000, 0x1000, &[(0xFFFF_FF00, 0xFFFF_FFFF)]);
+        assert_eq!(scratch.allocate(0xF00, 1), Some(0xFFFF_F000));
+        assert_eq!(scratch.allocate(1, 1), None);
+    }
+}
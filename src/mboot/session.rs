@@ -0,0 +1,157 @@
+// Copyright 2025 NXP
+//
+// SPDX-License-Identifier: BSD-3-Clause
+//! Configuration of a session with the device
+//!
+//! Knobs changing how [`McuBoot`](crate::McuBoot) talks to the device are kept together in
+//! [`SessionConfig`], which is built with `with_*` setters and passed to
+//! [`McuBoot::with_config`](crate::McuBoot::with_config), or changed later through
+//! [`McuBoot::config_mut`](crate::McuBoot::config_mut).
+
+use std::{fmt, time::Duration};
+
+use super::{
+    TransferOptions,
+    packets::Framing,
+    progress::{ProgressCallback, ProgressEvent},
+    protocols::usb::UsbLengthQuirk,
+    tags::property::Family,
+};
+use crate::CommunicationError;
+
+/// Retries of requests without side effects, like property queries
+///
+/// Only failures of the transport are retried, statuses reported by the device are final.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Number of retries after the first attempt, zero disables retries
+    pub retries: u32,
+    /// Delay before each retry
+    pub delay: Duration,
+}
+
+impl RetryPolicy {
+    /// Whether the request failing with `err` should be sent again
+    pub(crate) fn is_retried(err: &CommunicationError) -> bool {
+        matches!(
+            err,
+            CommunicationError::Timeout
+                | CommunicationError::InvalidCrc
+                | CommunicationError::InvalidHeader
+                | CommunicationError::NACKSent
+        )
+    }
+}
+
+/// Timeouts of command phases which differ from the timeout of the protocol
+///
+/// Phases without a timeout use the one the protocol was opened with.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct PhaseTimeouts {
+    /// Sending a command and waiting for its ACK and response
+    pub ack: Option<Duration>,
+    /// Sending and receiving data phase packets
+    pub data: Option<Duration>,
+    /// Waiting for the response of flash erase commands, which can take minutes on large external memories
+    pub erase: Option<Duration>,
+}
+
+/// Workarounds of bootloader and bridge variants deviating from the protocol specification
+///
+/// Quirks are applied to the transport through [`Protocol::apply_quirks`](crate::protocols::Protocol::apply_quirks)
+/// before the next command is sent, transports ignore quirks which do not concern them. Quirks left at
+/// [`None`] keep what the transport was opened with.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Quirks {
+    /// Handling of the length field of received USB-HID reports
+    pub usb_length: Option<UsbLengthQuirk>,
+    /// Framing of packets on byte-oriented transports, like UART and I2C
+    pub framing: Option<Framing>,
+}
+
+/// Options of a session with the device
+#[derive(Default)]
+pub struct SessionConfig {
+    /// Receiver of data transfer progress
+    pub progress: Option<ProgressCallback>,
+    /// Size of data packets and delays between them
+    pub transfer: TransferOptions,
+    /// Family whose profile changes codes of properties
+    pub family: Option<Family>,
+    /// Retries of requests without side effects
+    pub retry: RetryPolicy,
+    /// Timeouts of command phases
+    pub timeouts: PhaseTimeouts,
+    /// Workarounds of the transport
+    pub quirks: Quirks,
+}
+
+impl SessionConfig {
+    /// Creates the default configuration, same as [`SessionConfig::default`]
+    #[must_use]
+    pub fn new() -> Self {
+        SessionConfig::default()
+    }
+
+    /// Sets the receiver of data transfer progress
+    #[must_use]
+    pub fn with_progress(mut self, callback: impl FnMut(ProgressEvent) + Send + 'static) -> Self {
+        self.progress = Some(Box::new(callback));
+        self
+    }
+
+    /// Sets options of data phase transfers
+    #[must_use]
+    pub fn with_transfer(mut self, transfer: TransferOptions) -> Self {
+        self.transfer = transfer;
+        self
+    }
+
+    /// Sets the size of data packets used instead of the one reported by the device
+    #[must_use]
+    pub fn with_max_packet_size(mut self, size: Option<u32>) -> Self {
+        self.transfer.max_packet_size = size;
+        self
+    }
+
+    /// Sets the device family, see [`Family`]
+    #[must_use]
+    pub fn with_family(mut self, family: Option<Family>) -> Self {
+        self.family = family;
+        self
+    }
+
+    /// Sets retries of requests without side effects
+    #[must_use]
+    pub fn with_retry(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    /// Sets timeouts of command phases
+    #[must_use]
+    pub fn with_timeouts(mut self, timeouts: PhaseTimeouts) -> Self {
+        self.timeouts = timeouts;
+        self
+    }
+
+    /// Sets workarounds of the transport, see [`Quirks`]
+    #[must_use]
+    pub fn with_quirks(mut self, quirks: Quirks) -> Self {
+        self.quirks = quirks;
+        self
+    }
+}
+
+impl fmt::Debug for SessionConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SessionConfig")
+            .field("progress", &self.progress.is_some())
+            .field("transfer", &self.transfer)
+            .field("family", &self.family)
+            .field("retry", &self.retry)
+            .field("timeouts", &self.timeouts)
+            .field("quirks", &self.quirks)
+            .finish()
+    }
+}
@@ -17,9 +17,10 @@ use pyo3::pyclass;
 #[allow(clippy::wildcard_imports, reason = "Stub generation requires it like this")]
 use pyo3_stub_gen::derive::*;
 
-use crate::parsers;
+use crate::parse;
 
-use super::{ToAddress, property::PropertyTagDiscriminants};
+use super::{ToAddress, property::PropertyTagDiscriminants, status::StatusCode};
+use crate::mboot::{ResultComm, protocols::CommunicationError};
 /// MCU Bootloader Command Tags
 ///
 /// # Command Categories
@@ -31,7 +32,7 @@ use super::{ToAddress, property::PropertyTagDiscriminants};
 /// - **Protocol Configuration**: Setup for various communication interfaces
 #[repr(u8)]
 #[derive(Clone, Debug, derive_more::Display, strum::EnumDiscriminants)]
-#[strum_discriminants(derive(strum::EnumIter))]
+#[strum_discriminants(derive(strum::EnumIter, strum::EnumMessage), strum(serialize_all = "kebab-case"))]
 pub enum CommandTag<'a> {
     /// Used to load flashloader into the memory
     #[display("No Command")]
@@ -252,8 +253,8 @@ impl CommandToParams for CommandTag<'_> {
     /// # Returns
     /// A tuple where the first element contains command parameters and the second contains
     /// optional data phase bytes
-    fn to_params(&self) -> (Vec<u32>, Option<&[u8]>) {
-        match *self {
+    fn to_params(&self) -> ResultComm<(Vec<u32>, Option<&[u8]>)> {
+        Ok(match *self {
             CommandTag::FlashEraseAll { memory_id } => (vec![memory_id], None),
             CommandTag::ReadMemory {
                 start_address,
@@ -279,7 +280,10 @@ impl CommandToParams for CommandTag<'_> {
                 start_address,
                 memory_id,
                 bytes,
-            } => (vec![start_address, bytes.len() as u32, memory_id], Some(bytes)),
+            } => (
+                vec![start_address, CommunicationError::check_size(bytes.len())?, memory_id],
+                Some(bytes),
+            ),
             CommandTag::FillMemory {
                 start_address,
                 byte_count,
@@ -290,10 +294,10 @@ impl CommandToParams for CommandTag<'_> {
             CommandTag::SetProperty { tag, value } => (vec![u8::from(tag).into(), value], None),
             CommandTag::ConfigureMemory { memory_id, address } => (vec![memory_id, address], None),
             CommandTag::ReceiveSBFile { bytes } | CommandTag::NoCommand { bytes } => {
-                (vec![bytes.len() as u32], Some(bytes))
+                (vec![CommunicationError::check_size(bytes.len())?], Some(bytes))
             }
-            CommandTag::TrustProvisioning(operation) => operation.to_params(),
-            CommandTag::KeyProvisioning(operation) => operation.to_params(),
+            CommandTag::TrustProvisioning(operation) => operation.to_params()?,
+            CommandTag::KeyProvisioning(operation) => operation.to_params()?,
             CommandTag::FlashReadOnce { index, count } => (vec![index, count], None),
             CommandTag::FlashProgramOnce { index, count, data } => (vec![index, count, data], None),
             CommandTag::Execute {
@@ -307,7 +311,7 @@ impl CommandToParams for CommandTag<'_> {
             } => (vec![start_address, argument], None),
             // remove this once all commands are added
             _ => unimplemented!("this command has not yet been implemented"),
-        }
+        })
     }
 }
 
@@ -319,6 +323,29 @@ impl From<CommandTagDiscriminants> for u8 {
     }
 }
 
+impl CommandTagDiscriminants {
+    /// Status codes other than [`StatusCode::Success`] which the command returns when it succeeds
+    #[must_use]
+    pub fn accepted_statuses(self) -> &'static [StatusCode] {
+        match self {
+            CommandTagDiscriminants::ReadMemory => &[StatusCode::MemoryBlankPageReadDisallowed],
+            CommandTagDiscriminants::ReliableUpdate => &[StatusCode::ReliableUpdateSuccess],
+            CommandTagDiscriminants::FlashProgramOnce
+            | CommandTagDiscriminants::FlashReadOnce
+            | CommandTagDiscriminants::FuseProgram
+            | CommandTagDiscriminants::FuseRead => &[StatusCode::OtpCrcCheckPass],
+            CommandTagDiscriminants::Execute | CommandTagDiscriminants::Call => &[StatusCode::AppCrcCheckPassed],
+            _ => &[],
+        }
+    }
+
+    /// Whether `status` returned by the command means it succeeded
+    #[must_use]
+    pub fn accepts(self, status: StatusCode) -> bool {
+        status.is_success() || self.accepted_statuses().contains(&status)
+    }
+}
+
 impl ToAddress for CommandTag<'_> {}
 
 /// Trait for converting commands to parameters and data phase.
@@ -326,50 +353,53 @@ pub trait CommandToParams {
     /// Convert command to parameters and optional data phase.
     ///
     /// The first item in tuple are command parameters, the second are bytes to be sent in data phase.
-    #[must_use]
-    fn to_params(&self) -> (Vec<u32>, Option<&[u8]>);
+    ///
+    /// # Errors
+    /// [`CommunicationError::PayloadTooLarge`] if the data phase is longer than its 32-bit length parameter allows.
+    fn to_params(&self) -> ResultComm<(Vec<u32>, Option<&[u8]>)>;
 }
 /// Trust provisioning operations for device security setup.
 ///
 /// These operations handle OEM master share generation and configuration
 /// for establishing device trust relationships.
 #[cfg_attr(feature = "python", gen_stub_pyclass_enum, pyclass(eq, name = "TrustProvOperation"))]
-#[derive(PartialEq, Eq, clap::Subcommand, Clone, Copy, Debug, derive_more::Display)]
-#[command(rename_all = "snake_case")]
+#[derive(PartialEq, Eq, Clone, Copy, Debug, derive_more::Display)]
+#[cfg_attr(feature = "cli", derive(clap::Subcommand))]
+#[cfg_attr(feature = "cli", command(rename_all = "snake_case"))]
 pub enum TrustProvOperation {
     /// Generate OEM master share for initial trust provisioning
     #[display("Enroll Operation")]
     OemGenMasterShare {
         /// Input buffer address containing the OEM Share (entropy seed)
-        #[arg(value_parser=parsers::parse_number::<u32>)]
+        #[cfg_attr(feature = "cli", arg(value_parser=parse::parse_number::<u32>))]
         oem_share_input_addr: u32,
 
         /// Size of the OEM Share entropy seed in bytes
-        #[arg(value_parser=parsers::parse_number::<u32>)]
+        #[cfg_attr(feature = "cli", arg(value_parser=parse::parse_size::<u32>))]
         oem_share_input_size: u32,
 
         /// Output buffer address for the Encrypted OEM Share
-        #[arg(value_parser=parsers::parse_number::<u32>)]
+        #[cfg_attr(feature = "cli", arg(value_parser=parse::parse_number::<u32>))]
         oem_enc_share_output_addr: u32,
 
         /// Size of the encrypted OEM share output buffer in bytes
-        #[arg(value_parser=parsers::parse_number::<u32>)]
+        #[cfg_attr(feature = "cli", arg(value_parser=parse::parse_size::<u32>))]
         oem_enc_share_output_size: u32,
 
         /// Output buffer address for the Encrypted OEM Master Share
-        #[arg(value_parser=parsers::parse_number::<u32>)]
+        #[cfg_attr(feature = "cli", arg(value_parser=parse::parse_number::<u32>))]
         oem_enc_master_share_output_addr: u32,
 
         /// Size of the encrypted OEM master share output buffer in bytes
-        #[arg(value_parser=parsers::parse_number::<u32>)]
+        #[cfg_attr(feature = "cli", arg(value_parser=parse::parse_size::<u32>))]
         oem_enc_master_share_output_size: u32,
 
         /// Output buffer address for the OEM Customer Certificate Public Key
-        #[arg(value_parser=parsers::parse_number::<u32>)]
+        #[cfg_attr(feature = "cli", arg(value_parser=parse::parse_number::<u32>))]
         oem_cust_cert_puk_output_addr: u32,
 
         /// Size of the customer certificate public key output buffer in bytes
-        #[arg(value_parser=parsers::parse_number::<u32>)]
+        #[cfg_attr(feature = "cli", arg(value_parser=parse::parse_size::<u32>))]
         oem_cust_cert_puk_output_size: u32,
     },
 
@@ -377,29 +407,254 @@ pub enum TrustProvOperation {
     #[display("Set User Key Operation")]
     OemSetMasterShare {
         /// Input buffer address containing the OEM Share (entropy seed)
-        #[arg(value_parser=parsers::parse_number::<u32>)]
+        #[cfg_attr(feature = "cli", arg(value_parser=parse::parse_number::<u32>))]
         oem_share_input_addr: u32,
 
         /// Size of the OEM Share entropy seed in bytes
-        #[arg(value_parser=parsers::parse_number::<u32>)]
+        #[cfg_attr(feature = "cli", arg(value_parser=parse::parse_size::<u32>))]
         oem_share_input_size: u32,
 
         /// Input buffer address containing the Encrypted OEM Master Share
-        #[arg(value_parser=parsers::parse_number::<u32>)]
+        #[cfg_attr(feature = "cli", arg(value_parser=parse::parse_number::<u32>))]
         oem_enc_master_share_input_addr: u32,
 
         /// Size of the Encrypted OEM Master Share in bytes
-        #[arg(value_parser=parsers::parse_number::<u32>)]
+        #[cfg_attr(feature = "cli", arg(value_parser=parse::parse_size::<u32>))]
         oem_enc_master_share_input_size: u32,
     },
+
+    /// Read the OEM Customer Certificate Public Key for DICE
+    #[display("OEM Get Customer Certificate DICE Public Key Operation")]
+    OemGetCustCertDicePuk {
+        /// Input buffer address containing the OEM Root Key Table Hash
+        #[cfg_attr(feature = "cli", arg(value_parser=parse::parse_number::<u32>))]
+        oem_rkth_input_addr: u32,
+
+        /// Size of the OEM Root Key Table Hash in bytes
+        #[cfg_attr(feature = "cli", arg(value_parser=parse::parse_size::<u32>))]
+        oem_rkth_input_size: u32,
+
+        /// Output buffer address for the OEM Customer Certificate DICE Public Key
+        #[cfg_attr(feature = "cli", arg(value_parser=parse::parse_number::<u32>))]
+        oem_cust_cert_dice_puk_output_addr: u32,
+
+        /// Size of the public key output buffer in bytes
+        #[cfg_attr(feature = "cli", arg(value_parser=parse::parse_size::<u32>))]
+        oem_cust_cert_dice_puk_output_size: u32,
+    },
+
+    /// Generate a key in the HSM and export it as a key blob
+    #[display("HSM Generate Key Operation")]
+    HsmGenKey {
+        /// Type of the key to generate
+        #[cfg_attr(feature = "cli", arg(value_parser=parse::parse_number::<u32>))]
+        key_type: u32,
+
+        /// Reserved, should be 0
+        #[cfg_attr(feature = "cli", arg(value_parser=parse::parse_number::<u32>))]
+        reserved: u32,
+
+        /// Output buffer address for the key blob
+        #[cfg_attr(feature = "cli", arg(value_parser=parse::parse_number::<u32>))]
+        key_blob_output_addr: u32,
+
+        /// Size of the key blob output buffer in bytes
+        #[cfg_attr(feature = "cli", arg(value_parser=parse::parse_size::<u32>))]
+        key_blob_output_size: u32,
+
+        /// Output buffer address for the ECDSA public key
+        #[cfg_attr(feature = "cli", arg(value_parser=parse::parse_number::<u32>))]
+        ecdsa_puk_output_addr: u32,
+
+        /// Size of the ECDSA public key output buffer in bytes
+        #[cfg_attr(feature = "cli", arg(value_parser=parse::parse_size::<u32>))]
+        ecdsa_puk_output_size: u32,
+    },
+
+    /// Wrap a key given in plain text into a key blob
+    #[display("HSM Store Key Operation")]
+    HsmStoreKey {
+        /// Type of the key to store
+        #[cfg_attr(feature = "cli", arg(value_parser=parse::parse_number::<u32>))]
+        key_type: u32,
+
+        /// Properties of the key, e.g. its usage
+        #[cfg_attr(feature = "cli", arg(value_parser=parse::parse_number::<u32>))]
+        key_property: u32,
+
+        /// Input buffer address containing the key
+        #[cfg_attr(feature = "cli", arg(value_parser=parse::parse_number::<u32>))]
+        key_input_addr: u32,
+
+        /// Size of the key in bytes
+        #[cfg_attr(feature = "cli", arg(value_parser=parse::parse_size::<u32>))]
+        key_input_size: u32,
+
+        /// Output buffer address for the key blob
+        #[cfg_attr(feature = "cli", arg(value_parser=parse::parse_number::<u32>))]
+        key_blob_output_addr: u32,
+
+        /// Size of the key blob output buffer in bytes
+        #[cfg_attr(feature = "cli", arg(value_parser=parse::parse_size::<u32>))]
+        key_blob_output_size: u32,
+    },
+
+    /// Encrypt a block of an SB3 file with the HSM
+    #[display("HSM Encrypt Block Operation")]
+    HsmEncBlk {
+        /// Input buffer address containing the `CUST_MK_SK` key blob
+        #[cfg_attr(feature = "cli", arg(value_parser=parse::parse_number::<u32>))]
+        mfg_cust_mk_sk_0_blob_input_addr: u32,
+
+        /// Size of the `CUST_MK_SK` key blob in bytes
+        #[cfg_attr(feature = "cli", arg(value_parser=parse::parse_size::<u32>))]
+        mfg_cust_mk_sk_0_blob_input_size: u32,
+
+        /// ID of the key encryption key
+        #[cfg_attr(feature = "cli", arg(value_parser=parse::parse_number::<u32>))]
+        kek_id: u32,
+
+        /// Input buffer address containing the SB3 header
+        #[cfg_attr(feature = "cli", arg(value_parser=parse::parse_number::<u32>))]
+        sb3_header_input_addr: u32,
+
+        /// Size of the SB3 header in bytes
+        #[cfg_attr(feature = "cli", arg(value_parser=parse::parse_size::<u32>))]
+        sb3_header_input_size: u32,
+
+        /// Index of the encrypted block
+        #[cfg_attr(feature = "cli", arg(value_parser=parse::parse_number::<u32>))]
+        block_num: u32,
+
+        /// Address of the block data, encrypted in place
+        #[cfg_attr(feature = "cli", arg(value_parser=parse::parse_number::<u32>))]
+        block_data_addr: u32,
+
+        /// Size of the block data in bytes
+        #[cfg_attr(feature = "cli", arg(value_parser=parse::parse_size::<u32>))]
+        block_data_size: u32,
+    },
+
+    /// Sign data with a key blob in the HSM
+    #[display("HSM Sign Operation")]
+    HsmEncSign {
+        /// Input buffer address containing the key blob
+        #[cfg_attr(feature = "cli", arg(value_parser=parse::parse_number::<u32>))]
+        key_blob_input_addr: u32,
+
+        /// Size of the key blob in bytes
+        #[cfg_attr(feature = "cli", arg(value_parser=parse::parse_size::<u32>))]
+        key_blob_input_size: u32,
+
+        /// Input buffer address containing the data to sign
+        #[cfg_attr(feature = "cli", arg(value_parser=parse::parse_number::<u32>))]
+        block_data_input_addr: u32,
+
+        /// Size of the data to sign in bytes
+        #[cfg_attr(feature = "cli", arg(value_parser=parse::parse_size::<u32>))]
+        block_data_input_size: u32,
+
+        /// Output buffer address for the signature
+        #[cfg_attr(feature = "cli", arg(value_parser=parse::parse_number::<u32>))]
+        signature_output_addr: u32,
+
+        /// Size of the signature output buffer in bytes
+        #[cfg_attr(feature = "cli", arg(value_parser=parse::parse_size::<u32>))]
+        signature_output_size: u32,
+    },
+
+    /// Compute the DICE response to a challenge
+    #[display("OEM Get Customer DICE Response Operation")]
+    OemGetCustDiceResponse {
+        /// Input buffer address containing the challenge
+        #[cfg_attr(feature = "cli", arg(value_parser=parse::parse_number::<u32>))]
+        challenge_input_addr: u32,
+
+        /// Size of the challenge in bytes
+        #[cfg_attr(feature = "cli", arg(value_parser=parse::parse_size::<u32>))]
+        challenge_input_size: u32,
+
+        /// Output buffer address for the response
+        #[cfg_attr(feature = "cli", arg(value_parser=parse::parse_number::<u32>))]
+        response_output_addr: u32,
+
+        /// Size of the response output buffer in bytes
+        #[cfg_attr(feature = "cli", arg(value_parser=parse::parse_size::<u32>))]
+        response_output_size: u32,
+    },
+
+    /// Create a session of the device HSM from an OEM seed
+    #[display("DSC HSM Create Session Operation")]
+    DscHsmCreateSession {
+        /// Input buffer address containing the OEM seed
+        #[cfg_attr(feature = "cli", arg(value_parser=parse::parse_number::<u32>))]
+        oem_seed_input_addr: u32,
+
+        /// Size of the OEM seed in bytes
+        #[cfg_attr(feature = "cli", arg(value_parser=parse::parse_size::<u32>))]
+        oem_seed_input_size: u32,
+
+        /// Output buffer address for the OEM share
+        #[cfg_attr(feature = "cli", arg(value_parser=parse::parse_number::<u32>))]
+        oem_share_output_addr: u32,
+
+        /// Size of the OEM share output buffer in bytes
+        #[cfg_attr(feature = "cli", arg(value_parser=parse::parse_size::<u32>))]
+        oem_share_output_size: u32,
+    },
+
+    /// Encrypt a block of an SB file with the device HSM
+    #[display("DSC HSM Encrypt Block Operation")]
+    DscHsmEncBlk {
+        /// Input buffer address containing the SB file header
+        #[cfg_attr(feature = "cli", arg(value_parser=parse::parse_number::<u32>))]
+        sbx_header_input_addr: u32,
+
+        /// Size of the SB file header in bytes
+        #[cfg_attr(feature = "cli", arg(value_parser=parse::parse_size::<u32>))]
+        sbx_header_input_size: u32,
+
+        /// Index of the encrypted block
+        #[cfg_attr(feature = "cli", arg(value_parser=parse::parse_number::<u32>))]
+        block_num: u32,
+
+        /// Address of the block data, encrypted in place
+        #[cfg_attr(feature = "cli", arg(value_parser=parse::parse_number::<u32>))]
+        block_data_addr: u32,
+
+        /// Size of the block data in bytes
+        #[cfg_attr(feature = "cli", arg(value_parser=parse::parse_size::<u32>))]
+        block_data_size: u32,
+    },
+
+    /// Sign data with the device HSM
+    #[display("DSC HSM Sign Operation")]
+    DscHsmEncSign {
+        /// Input buffer address containing the data to sign
+        #[cfg_attr(feature = "cli", arg(value_parser=parse::parse_number::<u32>))]
+        block_data_input_addr: u32,
+
+        /// Size of the data to sign in bytes
+        #[cfg_attr(feature = "cli", arg(value_parser=parse::parse_size::<u32>))]
+        block_data_input_size: u32,
+
+        /// Output buffer address for the signature
+        #[cfg_attr(feature = "cli", arg(value_parser=parse::parse_number::<u32>))]
+        signature_output_addr: u32,
+
+        /// Size of the signature output buffer in bytes
+        #[cfg_attr(feature = "cli", arg(value_parser=parse::parse_size::<u32>))]
+        signature_output_size: u32,
+    },
 }
 impl CommandToParams for TrustProvOperation {
     /// Convert trust provisioning operation to command parameters.
     ///
     /// # Returns
     /// Tuple containing operation parameters and no data phase
-    fn to_params(&self) -> (Vec<u32>, Option<&[u8]>) {
-        match *self {
+    #[allow(clippy::too_many_lines, reason = "each operation lists all of its parameters")]
+    fn to_params(&self) -> ResultComm<(Vec<u32>, Option<&[u8]>)> {
+        Ok(match *self {
             TrustProvOperation::OemGenMasterShare {
                 oem_share_input_addr,
                 oem_share_input_size,
@@ -438,7 +693,164 @@ impl CommandToParams for TrustProvOperation {
                 ],
                 None,
             ),
-        }
+            TrustProvOperation::OemGetCustCertDicePuk {
+                oem_rkth_input_addr,
+                oem_rkth_input_size,
+                oem_cust_cert_dice_puk_output_addr,
+                oem_cust_cert_dice_puk_output_size,
+            } => (
+                vec![
+                    2,
+                    oem_rkth_input_addr,
+                    oem_rkth_input_size,
+                    oem_cust_cert_dice_puk_output_addr,
+                    oem_cust_cert_dice_puk_output_size,
+                ],
+                None,
+            ),
+            TrustProvOperation::HsmGenKey {
+                key_type,
+                reserved,
+                key_blob_output_addr,
+                key_blob_output_size,
+                ecdsa_puk_output_addr,
+                ecdsa_puk_output_size,
+            } => (
+                vec![
+                    3,
+                    key_type,
+                    reserved,
+                    key_blob_output_addr,
+                    key_blob_output_size,
+                    ecdsa_puk_output_addr,
+                    ecdsa_puk_output_size,
+                ],
+                None,
+            ),
+            TrustProvOperation::HsmStoreKey {
+                key_type,
+                key_property,
+                key_input_addr,
+                key_input_size,
+                key_blob_output_addr,
+                key_blob_output_size,
+            } => (
+                vec![
+                    4,
+                    key_type,
+                    key_property,
+                    key_input_addr,
+                    key_input_size,
+                    key_blob_output_addr,
+                    key_blob_output_size,
+                ],
+                None,
+            ),
+            TrustProvOperation::HsmEncBlk {
+                mfg_cust_mk_sk_0_blob_input_addr,
+                mfg_cust_mk_sk_0_blob_input_size,
+                kek_id,
+                sb3_header_input_addr,
+                sb3_header_input_size,
+                block_num,
+                block_data_addr,
+                block_data_size,
+            } => (
+                vec![
+                    5,
+                    mfg_cust_mk_sk_0_blob_input_addr,
+                    mfg_cust_mk_sk_0_blob_input_size,
+                    kek_id,
+                    sb3_header_input_addr,
+                    sb3_header_input_size,
+                    block_num,
+                    block_data_addr,
+                    block_data_size,
+                ],
+                None,
+            ),
+            TrustProvOperation::HsmEncSign {
+                key_blob_input_addr,
+                key_blob_input_size,
+                block_data_input_addr,
+                block_data_input_size,
+                signature_output_addr,
+                signature_output_size,
+            } => (
+                vec![
+                    6,
+                    key_blob_input_addr,
+                    key_blob_input_size,
+                    block_data_input_addr,
+                    block_data_input_size,
+                    signature_output_addr,
+                    signature_output_size,
+                ],
+                None,
+            ),
+            TrustProvOperation::OemGetCustDiceResponse {
+                challenge_input_addr,
+                challenge_input_size,
+                response_output_addr,
+                response_output_size,
+            } => (
+                vec![
+                    7,
+                    challenge_input_addr,
+                    challenge_input_size,
+                    response_output_addr,
+                    response_output_size,
+                ],
+                None,
+            ),
+            TrustProvOperation::DscHsmCreateSession {
+                oem_seed_input_addr,
+                oem_seed_input_size,
+                oem_share_output_addr,
+                oem_share_output_size,
+            } => (
+                vec![
+                    9,
+                    oem_seed_input_addr,
+                    oem_seed_input_size,
+                    oem_share_output_addr,
+                    oem_share_output_size,
+                ],
+                None,
+            ),
+            TrustProvOperation::DscHsmEncBlk {
+                sbx_header_input_addr,
+                sbx_header_input_size,
+                block_num,
+                block_data_addr,
+                block_data_size,
+            } => (
+                vec![
+                    10,
+                    sbx_header_input_addr,
+                    sbx_header_input_size,
+                    block_num,
+                    block_data_addr,
+                    block_data_size,
+                ],
+                None,
+            ),
+            TrustProvOperation::DscHsmEncSign {
+                block_data_input_addr,
+                block_data_input_size,
+                signature_output_addr,
+                signature_output_size,
+            } => (
+                vec![
+                    11,
+                    block_data_input_addr,
+                    block_data_input_size,
+                    signature_output_addr,
+                    signature_output_size,
+                ],
+                None,
+            ),
+        })
     }
 }
 
@@ -446,8 +858,9 @@ impl CommandToParams for TrustProvOperation {
 ///
 /// These operations handle enrollment, key setting, and key store management
 /// for various types of encryption and authentication keys.
-#[derive(clap::Subcommand, Clone, Debug, derive_more::Display)]
-#[command(rename_all = "snake_case")]
+#[derive(Clone, Debug, derive_more::Display)]
+#[cfg_attr(feature = "cli", derive(clap::Subcommand))]
+#[cfg_attr(feature = "cli", command(rename_all = "snake_case"))]
 pub enum KeyProvOperation {
     #[display("Enroll Operation")]
     /// Enrolls key provisioning feature. No arguments are required for this operation
@@ -464,14 +877,14 @@ pub enum KeyProvOperation {
     ///  9 or 'PRINCE2'     Prince region 2 encryption key
     /// 11 or 'USERKEK'     User/Boot-image encryption key
     /// 12 or 'UDS'         Universal Device Secret for DICE
-    #[command(verbatim_doc_comment)]
+    #[cfg_attr(feature = "cli", command(verbatim_doc_comment))]
     SetUserKey {
         /// Type of user key
-        #[arg(value_parser=KeyProvUserKeyType::parse, id = "key", verbatim_doc_comment)]
+        #[cfg_attr(feature = "cli", arg(value_parser=KeyProvUserKeyType::parse, id = "key", verbatim_doc_comment))]
         key_type: KeyProvUserKeyType,
 
-        /// Binary file containing user key plaintext
-        #[arg(value_parser = |s: &str| parsers::parse_file(s, None))]
+        /// Binary file containing user key plaintext, `-` for stdin or hex bytes in double braces
+        #[cfg_attr(feature = "cli", arg(value_parser = parse::parse_hex_values))]
         key_data: Box<[u8]>,
     },
 
@@ -490,14 +903,14 @@ pub enum KeyProvOperation {
     /// Note: The valid options of key type and corresponding size are documented
     /// in the target's Reference Manual or User Manual.
     /// Note: Names are case insensitive
-    #[command(verbatim_doc_comment)]
+    #[cfg_attr(feature = "cli", command(verbatim_doc_comment))]
     SetKey {
         /// Type of key to generate
-        #[arg(value_parser=KeyProvUserKeyType::parse)]
+        #[cfg_attr(feature = "cli", arg(value_parser=KeyProvUserKeyType::parse))]
         key_type: KeyProvUserKeyType,
 
         /// Size of key to generate in bytes
-        #[arg(value_parser=parsers::parse_number::<u32>)]
+        #[cfg_attr(feature = "cli", arg(value_parser=parse::parse_size::<u32>))]
         key_size: u32,
     },
 
@@ -505,7 +918,7 @@ pub enum KeyProvOperation {
     /// Writes data to non-volatile storage
     WriteKeyNonvolatile {
         /// ID of the non-volatile memory
-        #[arg(value_parser=parsers::parse_number::<u32>, default_value_t = 0)]
+        #[cfg_attr(feature = "cli", arg(value_parser=parse::parse_number::<u32>, default_value_t = 0))]
         memory_id: u32,
     },
 
@@ -513,27 +926,23 @@ pub enum KeyProvOperation {
     /// Loads the key from nonvolatile memory to bootloader.
     ReadKeyNonvolatile {
         /// ID of the non-volatile memory
-        #[arg(value_parser=parsers::parse_number::<u32>, default_value_t = 0)]
+        #[cfg_attr(feature = "cli", arg(value_parser=parse::parse_number::<u32>, default_value_t = 0))]
         memory_id: u32,
     },
     /// Write key store data to the bootloader
     #[display("Write Key Store Operation")]
     WriteKeyStore {
-        /// Binary file containing key store data
-        #[arg(value_parser = |s: &str| parsers::parse_file(s, None))]
+        /// Binary file containing key store data, `-` for stdin or hex bytes in double braces
+        #[cfg_attr(feature = "cli", arg(value_parser = parse::parse_hex_values))]
         keystore_data: Box<[u8]>,
     },
 
     #[display("Read Key Store Operation")]
     /// Reads the key store from the bootloader to host
-    ReadKeyStore {
-        /// Binary file to save the key store
-        file: String,
-
-        /// Use hexdump format
-        #[arg(long, short, default_value_t = false)]
-        use_hexdump: bool,
-    },
+    ///
+    /// The command line offers its own subcommand with the output file instead.
+    #[cfg_attr(feature = "cli", command(skip))]
+    ReadKeyStore,
 }
 
 impl CommandToParams for KeyProvOperation {
@@ -541,13 +950,16 @@ impl CommandToParams for KeyProvOperation {
     ///
     /// # Returns
     /// Tuple containing operation parameters and optional key data for data phase
-    fn to_params(&self) -> (Vec<u32>, Option<&[u8]>) {
-        match *self {
+    fn to_params(&self) -> ResultComm<(Vec<u32>, Option<&[u8]>)> {
+        Ok(match *self {
             KeyProvOperation::Enroll => (vec![0], None),
             KeyProvOperation::SetUserKey { key_type, ref key_data } => {
                 // For SetUserKey, we need to include the key type and length in the parameters
                 // and the actual key data in the data phase
-                (vec![1, key_type.into(), key_data.len() as u32], Some(key_data))
+                (
+                    vec![1, key_type.into(), CommunicationError::check_size(key_data.len())?],
+                    Some(key_data),
+                )
             }
             KeyProvOperation::SetKey { key_type, key_size } => {
                 // For SetKey (intrinsic key generation), we pass the key type and size
@@ -556,11 +968,12 @@ impl CommandToParams for KeyProvOperation {
             }
             KeyProvOperation::WriteKeyNonvolatile { memory_id } => (vec![3, memory_id], None),
             KeyProvOperation::ReadKeyNonvolatile { memory_id } => (vec![4, memory_id], None),
-            KeyProvOperation::WriteKeyStore { ref keystore_data } => {
-                (vec![5, 0, keystore_data.len() as u32], Some(keystore_data))
-            }
-            KeyProvOperation::ReadKeyStore { .. } => (vec![6], None),
-        }
+            KeyProvOperation::WriteKeyStore { ref keystore_data } => (
+                vec![5, 0, CommunicationError::check_size(keystore_data.len())?],
+                Some(keystore_data),
+            ),
+            KeyProvOperation::ReadKeyStore => (vec![6], None),
+        })
     }
 }
 
@@ -633,7 +1046,7 @@ impl KeyProvUserKeyType {
     /// Text containing the error, either invalid ID or invalid name for [`KeyProvUserKeyType`].
     pub fn parse(s: &str) -> Result<KeyProvUserKeyType, String> {
         if s.chars().all(char::is_numeric) {
-            let res = parsers::parse_number::<u32>(s)?;
+            let res = parse::parse_number::<u32>(s)?;
             KeyProvUserKeyType::try_from(res).or(Err("invalid ID".to_owned()))
         } else {
             KeyProvUserKeyType::from_str(s).or(Err("invalid name".to_owned()))
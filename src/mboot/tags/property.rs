@@ -28,7 +28,7 @@ use crate::{
         formatters::{BinaryBytesOne, OnOffBool},
         memory::{ExternalMemoryAttributes, ReservedRegions},
     },
-    parsers::parse_number,
+    parse::parse_number,
 };
 
 use super::{ToAddress, command::CommandTagDiscriminants, status::StatusCode};
@@ -46,6 +46,63 @@ impl Display for DeviceId {
     }
 }
 
+/// Lock bits of the fuse registers.
+///
+/// Every word covers a register of 32 fuse words, bit `n` of word `w` is set if fuse word `32 * w + n`
+/// is locked.
+#[derive(Clone, Debug)]
+pub struct FuseLocks(Box<[u32]>);
+
+impl FuseLocks {
+    /// Parse lock bits from the response words, one word per register.
+    #[must_use]
+    pub fn parse(data: &[u32]) -> Self {
+        FuseLocks(data.into())
+    }
+
+    /// Returns whether fuse word `index` is locked, unknown words are reported as unlocked.
+    #[must_use]
+    pub fn is_locked(&self, index: u32) -> bool {
+        usize::try_from(index / 32)
+            .ok()
+            .and_then(|register| self.0.get(register))
+            .is_some_and(|bits| bits & (1 << (index % 32)) != 0)
+    }
+
+    /// Returns indexes of the locked fuse words in ascending order.
+    pub fn locked(&self) -> impl Iterator<Item = u32> + '_ {
+        (0u32..).step_by(32).zip(&self.0).flat_map(|(first, &bits)| {
+            (0..32)
+                .filter(move |bit| bits & (1 << bit) != 0)
+                .map(move |bit| first + bit)
+        })
+    }
+}
+
+impl Display for FuseLocks {
+    /// Format lock bits of every register with the list of its locked fuse words.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (register, &bits) in (0u32..).zip(&self.0) {
+            let first = register * 32;
+            let locked: Vec<_> = (0..32)
+                .filter(|bit| bits & (1 << bit) != 0)
+                .map(|bit| (first + bit).to_string())
+                .collect();
+            let locked = if locked.is_empty() {
+                "none".to_owned()
+            } else {
+                locked.join(", ")
+            };
+            writeln!(
+                f,
+                "  Register {register} (fuses {first}-{}) = {bits:#010X}, locked: {locked}",
+                first + 31
+            )?;
+        }
+        Ok(())
+    }
+}
+
 /// Enumeration of bootloader property tags.
 ///
 /// These properties can be queried from the bootloader to get information about
@@ -53,11 +110,12 @@ impl Display for DeviceId {
 #[repr(u8)]
 #[derive(Clone, Debug, strum::EnumDiscriminants, derive_more::Display)]
 #[strum_discriminants(
-    derive(derive_more::TryFrom, strum::EnumString),
+    derive(derive_more::TryFrom, strum::EnumString, strum::EnumIter, strum::EnumMessage),
     try_from(repr),
     strum(serialize_all = "kebab-case"),
     cfg_attr(feature = "python", gen_stub_pyclass_enum, pyclass(eq, eq_int, name = "PropertyTag"))
 )]
+#[non_exhaustive]
 pub enum PropertyTag {
     /// Current version of the bootloader
     #[display("Current Version = {_0}")]
@@ -96,9 +154,9 @@ pub enum PropertyTag {
     /// Status of the last CRC check operation
     #[display("CRC Check Status = {_0:?}")]
     CRCCheckStatus(StatusCode) = 0x08,
-    /// Value of the last error that occurred
+    /// Status of the last error that occurred
     #[display("Last Error Value = {_0}")]
-    LastError(u32) = 0x09,
+    LastError(StatusCode) = 0x09,
     /// Whether write operations are verified after completion
     #[display("Verify Writes = {}", OnOffBool(*_0))]
     VerifyWrites(bool) = 0x0A,
@@ -163,9 +221,9 @@ pub enum PropertyTag {
     /// Timeout for byte write operations in milliseconds
     #[display("Byte Write Timeout in ms = {_0}")]
     ByteWriteTimeoutMs(u32) = 0x1E,
-    /// Status of fuse locked state
-    #[display("Fuse Locked Status")]
-    FuseLockedStatus = 0x1F,
+    /// Lock bits of the fuse registers
+    #[display("Fuse Locked Status =\n{_0}")]
+    FuseLockedStatus(FuseLocks) = 0x1F,
     /// Boot status register value
     #[display("Boot Status Register = {_0}")]
     BootStatusRegister(u32) = 0x20,
@@ -224,17 +282,7 @@ impl PTag {
             PTagDisc::FlashStartAddress => PTag::FlashStartAddress(data[0]),
             PTagDisc::FlashSize => PTag::FlashSize(data[0]),
             PTagDisc::FlashSectorSize => PTag::FlashSectorSize(data[0]),
-            PTagDisc::AvailableCommands => PTag::AvailableCommands(
-                CommandTagDiscriminants::iter()
-                    .filter(|tag| {
-                        let tag_value = u8::from(*tag);
-                        (0 < tag_value && tag_value < 0xA0) && {
-                            let mask = 1 << (tag_value - 1);
-                            data[0] & mask != 0
-                        }
-                    })
-                    .collect(),
-            ),
+            PTagDisc::AvailableCommands => PTag::AvailableCommands(available_commands(data)),
             PTagDisc::CRCCheckStatus => {
                 PTag::CRCCheckStatus(StatusCode::try_from(data[0]).expect("board returned invalid CRC status"))
             }
@@ -271,9 +319,11 @@ impl PTag {
             PTagDisc::ReliableUpdateStatus => PTag::ReliableUpdateStatus(
                 StatusCode::try_from(data[0]).expect("board returned invalid Reliable update status"),
             ),
-            // TODO: Implement parsing for any remaining property tag discriminants
-            PTagDisc::FuseLockedStatus => unimplemented!("Fuse Locked Status parsing not yet implemented"),
-            PTagDisc::LastError => unimplemented!("Last Error parsing not yet implemented"),
+            PTagDisc::FuseLockedStatus => PTag::FuseLockedStatus(FuseLocks::parse(data)),
+            // error codes of drivers need not be known to this version
+            PTagDisc::LastError => {
+                PTag::LastError(StatusCode::try_from(data[0]).unwrap_or(StatusCode::UnknownStatusCode))
+            }
         }
     }
 }
@@ -298,6 +348,71 @@ impl PTagDisc {
     }
 }
 
+/// Device family reusing codes of properties for other properties
+///
+/// Without a family, codes are interpreted as documented for most devices. With a family, properties
+/// which the family reports under another code are requested and parsed according to its profile.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, strum::EnumIter, derive_more::Display)]
+pub enum Family {
+    /// KW45 wireless MCUs
+    #[display("kw45xx")]
+    Kw45xx,
+    /// K32W1 wireless MCUs, sharing the ROM with KW45
+    #[display("k32w1xx")]
+    K32w1xx,
+    /// MCXA1 MCUs
+    #[display("mcxa1xx")]
+    Mcxa1xx,
+}
+
+impl Family {
+    /// Parses family name, case insensitive
+    ///
+    /// # Errors
+    /// Text listing the known families if `s` is not one of them.
+    pub fn parse(s: &str) -> Result<Family, String> {
+        Family::iter()
+            .find(|family| family.to_string().eq_ignore_ascii_case(s.trim()))
+            .ok_or_else(|| {
+                let known: Vec<_> = Family::iter().map(|family| family.to_string()).collect();
+                format!("unknown family {s}, expected one of {}", known.join(", "))
+            })
+    }
+
+    /// Properties the family reports under another code, as (code, property)
+    fn overrides(self) -> &'static [(u8, PTagDisc)] {
+        match self {
+            Family::Kw45xx | Family::K32w1xx => &[
+                (0x0A, PTagDisc::VerifyErase),
+                (0x14, PTagDisc::BootStatusRegister),
+                (0x15, PTagDisc::FirmwareVersion),
+                (0x16, PTagDisc::FuseProgramVoltage),
+            ],
+            Family::Mcxa1xx => &[(0x11, PTagDisc::LifeCycleState)],
+        }
+    }
+
+    /// Returns the property the family reports under the code of `tag`
+    #[must_use]
+    pub fn property(self, tag: PTagDisc) -> PTagDisc {
+        let code = u8::from(tag);
+        self.overrides()
+            .iter()
+            .find(|(override_code, _)| *override_code == code)
+            .map_or(tag, |&(_, property)| property)
+    }
+
+    /// Returns the property whose code the family uses for `property`
+    #[must_use]
+    pub fn code_tag(self, property: PTagDisc) -> PTagDisc {
+        self.overrides()
+            .iter()
+            .find(|(_, overridden)| *overridden == property)
+            .and_then(|&(code, _)| PTagDisc::try_from(code).ok())
+            .unwrap_or(property)
+    }
+}
+
 impl From<PTagDisc> for u8 {
     /// Convert property tag discriminant to its numeric representation.
     fn from(value: PTagDisc) -> Self {
@@ -307,6 +422,24 @@ impl From<PTagDisc> for u8 {
 
 impl ToAddress for PTag {}
 
+/// Decodes the mask of available commands
+///
+/// Bit `n` of the mask stands for the command with tag `n + 1`. Newer ROMs with the extended command set
+/// report the mask in multiple words, the second word starting with tag 0x21. Bits of unknown commands and
+/// missing words are ignored.
+fn available_commands(data: &[u32]) -> Box<[CommandTagDiscriminants]> {
+    CommandTagDiscriminants::iter()
+        .filter(|tag| {
+            let tag_value = u8::from(*tag);
+            if tag_value == 0 || tag_value >= 0xA0 {
+                return false;
+            }
+            let bit = usize::from(tag_value - 1);
+            data.get(bit / 32).is_some_and(|word| word & (1 << (bit % 32)) != 0)
+        })
+        .collect()
+}
+
 /// Version information structure.
 ///
 /// Contains version components including a character mark and numeric version parts.
@@ -359,7 +492,7 @@ impl Display for Version {
 /// These represent the different communication interfaces that can be used
 /// to communicate with the bootloader.
 #[repr(u8)]
-#[derive(Clone, Copy, strum::EnumIter, derive_more::Debug, derive_more::Display)]
+#[derive(Clone, Copy, PartialEq, Eq, strum::EnumIter, derive_more::Debug, derive_more::Display)]
 pub enum PeripheryTag {
     /// UART serial interface
     #[display("UART Interface")]
@@ -654,3 +787,68 @@ impl Display for LifeCycleState {
         write!(f, "{state}")
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fuse_locks() {
+        let PTag::FuseLockedStatus(locks) = PTag::from_code(PTagDisc::FuseLockedStatus, &[0x8000_0011, 0x2]) else {
+            panic!("fuse locked status expected");
+        };
+        assert_eq!(locks.locked().collect::<Vec<_>>(), [0, 4, 31, 33]);
+        assert!(locks.is_locked(33));
+        assert!(!locks.is_locked(32));
+        assert!(!locks.is_locked(64));
+        assert_eq!(
+            locks.to_string(),
+            "  Register 0 (fuses 0-31) = 0x80000011, locked: 0, 4, 31\n  Register 1 (fuses 32-63) = 0x00000002, locked: 33\n"
+        );
+
+        let last_error = PTag::from_code(PTagDisc::LastError, &[10_300]);
+        assert!(matches!(last_error, PTag::LastError(StatusCode::UnknownProperty)));
+        let unknown = PTag::from_code(PTagDisc::LastError, &[0x1234_5678]);
+        assert!(matches!(unknown, PTag::LastError(StatusCode::UnknownStatusCode)));
+    }
+
+    #[test]
+    fn available_commands_mask() {
+        assert!(available_commands(&[]).is_empty());
+        assert_eq!(
+            *available_commands(&[0b101]),
+            [
+                CommandTagDiscriminants::FlashEraseAll,
+                CommandTagDiscriminants::ReadMemory
+            ]
+        );
+        // EL2GO with tag 0x20 is the highest bit of the first word
+        assert_eq!(
+            *available_commands(&[0x8000_0000, 0xFFFF_FFFF]),
+            [CommandTagDiscriminants::EL2GO]
+        );
+    }
+
+    #[test]
+    fn family_profiles() {
+        assert_eq!(Family::parse(" KW45xx"), Ok(Family::Kw45xx));
+        assert!(Family::parse("rt1050").is_err());
+
+        let kw45 = Family::Kw45xx;
+        assert_eq!(kw45.code_tag(PTagDisc::VerifyErase), PTagDisc::VerifyWrites);
+        assert_eq!(kw45.property(PTagDisc::VerifyWrites), PTagDisc::VerifyErase);
+        assert_eq!(kw45.property(PTagDisc::MaxPacketSize), PTagDisc::MaxPacketSize);
+        assert_eq!(
+            kw45.property(PTagDisc::FlashAccessSegmentSize),
+            PTagDisc::BootStatusRegister
+        );
+        assert_eq!(u8::from(kw45.code_tag(PTagDisc::FuseProgramVoltage)), 0x16);
+
+        let mcxa = Family::Mcxa1xx;
+        assert_eq!(
+            mcxa.property(mcxa.code_tag(PTagDisc::LifeCycleState)),
+            PTagDisc::LifeCycleState
+        );
+        assert_eq!(u8::from(mcxa.code_tag(PTagDisc::LifeCycleState)), 0x11);
+    }
+}
@@ -0,0 +1,105 @@
+// Copyright 2025 NXP
+//
+// SPDX-License-Identifier: BSD-3-Clause
+//! Non-fatal Conditions
+//!
+//! Some commands succeed, but not exactly as requested, e.g. a verification of written data failed or more memory
+//! was erased than the image covers. Such conditions are not errors, they are collected by [`McuBoot`] as
+//! [`Warning`]s and taken with [`McuBoot::take_warnings`] once the command finishes, so the application can
+//! show them or treat them as soft failures.
+//!
+//! [`McuBoot`]: super::McuBoot
+//! [`McuBoot::take_warnings`]: super::McuBoot::take_warnings
+
+use super::tags::property::PropertyTagDiscriminants;
+
+/// Condition which did not make the command fail, but should not go unnoticed
+#[derive(Clone, Debug, PartialEq, Eq, derive_more::Display, strum::IntoStaticStr)]
+#[strum(serialize_all = "snake_case")]
+#[non_exhaustive]
+pub enum Warning {
+    /// Bits read back after programming OTP word `index` differ from the programmed ones
+    #[display("verification of OTP word {index:#X} failed, written value doesn't match read value")]
+    VerificationFailed {
+        /// Index of the OTP word
+        index: u32,
+    },
+    /// Erase was extended to sector boundaries, so it also cleared bytes not covered by the image
+    #[display(
+        "erase padded to sectors, {byte_count} bytes at {start_address:#010X} erased for {data_bytes} bytes of data"
+    )]
+    ErasePadded {
+        /// Start address of the erased region
+        start_address: u32,
+        /// Number of erased bytes
+        byte_count: u32,
+        /// Number of bytes of the image in the region
+        data_bytes: u32,
+    },
+    /// Gaps between image segments were filled, so the fill bytes are written as well
+    #[display("{byte_count} bytes of gaps between image segments filled with {fill:#04X}")]
+    GapsFilled {
+        /// Number of fill bytes
+        byte_count: u32,
+        /// Value of the fill bytes
+        fill: u8,
+    },
+    /// Device did not return data of a blank page, see
+    /// [`StatusCode::MemoryBlankPageReadDisallowed`][super::tags::status::StatusCode::MemoryBlankPageReadDisallowed]
+    #[display("reading of blank page was disallowed, returned data do not reflect memory content")]
+    BlankPageRead,
+    /// Device returned less data than requested
+    #[display("only {received} of {requested} requested bytes were read")]
+    ShortRead {
+        /// Number of requested bytes
+        requested: u32,
+        /// Number of received bytes
+        received: u32,
+    },
+    /// Key store loaded back from the nonvolatile memory differs from the written one
+    #[display("key store loaded back from nonvolatile memory {memory_id} differs from the written one")]
+    KeyStoreMismatch {
+        /// ID of the nonvolatile memory
+        memory_id: u32,
+    },
+    /// Written data could not be read back, so they were not verified
+    #[display("{byte_count} bytes at {start_address:#010X} could not be read back, they were not verified")]
+    VerifySkipped {
+        /// Start address of the skipped chunk
+        start_address: u32,
+        /// Number of skipped bytes
+        byte_count: u32,
+    },
+    /// Property is not available, so the output misses it
+    #[display("property {_0:?} is not available, it was skipped")]
+    PropertySkipped(PropertyTagDiscriminants),
+}
+
+impl Warning {
+    /// Stable identifier of the warning kind for machine readable output, e.g. `erase_padded`
+    #[must_use]
+    pub fn kind(&self) -> &'static str {
+        self.into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn kinds_are_snake_case() {
+        assert_eq!(
+            Warning::VerificationFailed { index: 0x10 }.kind(),
+            "verification_failed"
+        );
+        assert_eq!(
+            Warning::ShortRead {
+                requested: 16,
+                received: 8
+            }
+            .to_string(),
+            "only 8 of 16 requested bytes were read"
+        );
+    }
+}
@@ -0,0 +1,86 @@
+// Copyright 2025 NXP
+//
+// SPDX-License-Identifier: BSD-3-Clause
+//! Output of read data to an inherited file descriptor or a Unix domain socket
+//!
+//! Frontends spawning rblhost receive large dumps through a pipe or a socket they pass to it, without
+//! temporary files. Both are available on Unix only, elsewhere the options are not offered at all.
+
+use std::io::{self, Write};
+#[cfg(unix)]
+use std::{
+    fs::File,
+    os::{fd::FromRawFd, unix::net::UnixStream},
+    path::PathBuf,
+};
+
+/// Options selecting the stream the read bytes are written to
+#[derive(clap::Args, Clone, Debug, Default)]
+pub struct OutputStream {
+    /// Write the read bytes to the inherited file descriptor <FD>, e.g. a pipe, instead of <FILE>
+    ///
+    /// The descriptor is closed afterwards. Descriptors 0 to 2 are not accepted, use '-' to print to
+    /// stdout.
+    #[cfg(unix)]
+    #[arg(long, value_name = "FD", value_parser = clap::value_parser!(i32).range(3..), conflicts_with_all = ["file", "output_socket"])]
+    output_fd: Option<i32>,
+
+    /// Write the read bytes to the Unix domain socket <PATH> instead of <FILE>
+    #[cfg(unix)]
+    #[arg(long, value_name = "PATH", conflicts_with = "file")]
+    output_socket: Option<PathBuf>,
+}
+
+impl OutputStream {
+    /// Opens the selected stream, [`None`] if no stream is selected
+    #[cfg(unix)]
+    pub fn open(&self) -> io::Result<Option<Box<dyn Write>>> {
+        if let Some(fd) = self.output_fd {
+            // SAFETY: the descriptor is inherited from the parent process for this purpose and nothing
+            // else in rblhost uses it, so the file can own and close it
+            let file = unsafe { File::from_raw_fd(fd) };
+            return Ok(Some(Box::new(file)));
+        }
+        match &self.output_socket {
+            Some(path) => Ok(Some(Box::new(UnixStream::connect(path)?))),
+            None => Ok(None),
+        }
+    }
+
+    /// Opens the selected stream, [`None`] if no stream is selected
+    #[cfg(not(unix))]
+    #[expect(
+        clippy::unused_self,
+        clippy::unnecessary_wraps,
+        reason = "the options exist only on Unix"
+    )]
+    pub fn open(&self) -> io::Result<Option<Box<dyn Write>>> {
+        Ok(None)
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use std::{io::Read, os::unix::net::UnixListener};
+
+    use super::*;
+
+    #[test]
+    fn bytes_reach_the_socket() {
+        let path = std::env::temp_dir().join(format!("rblhost-output-{}.sock", std::process::id()));
+        let listener = UnixListener::bind(&path).unwrap();
+        let output = OutputStream {
+            output_fd: None,
+            output_socket: Some(path.clone()),
+        };
+        let mut stream = output.open().unwrap().unwrap();
+        stream.write_all(&[1, 2, 3]).unwrap();
+        drop(stream);
+
+        let mut received = Vec::new();
+        listener.accept().unwrap().0.read_to_end(&mut received).unwrap();
+        std::fs::remove_file(path).unwrap();
+        assert_eq!(received, [1, 2, 3]);
+        assert!(OutputStream::default().open().unwrap().is_none());
+    }
+}
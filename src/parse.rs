@@ -0,0 +1,289 @@
+// Copyright 2025 NXP
+//
+// SPDX-License-Identifier: BSD-3-Clause
+//! Parsing of user input
+//!
+//! The same rules are used by the command line tool, the bindings and any downstream tool, so the
+//! values are accepted in the same format everywhere:
+//! - numbers are decimal, hexadecimal with the `0x` prefix or binary with the `0b` prefix, see [`parse_number`]
+//! - sizes are numbers optionally followed by the `k` or `M` suffix, e.g. `64k`, see [`parse_size`]
+//! - byte blobs are hex digits in double braces, e.g. `{{11 22 33}}`, see [`parse_hex_bytes`]
+//! - file contents are given by the path with an optional byte limit, e.g. `image.bin,0x100`, see
+//!   [`parse_file_spec`]
+//! - `-` stands for the content of the standard input where data are accepted, see [`parse_hex_values`]
+//!
+//! All functions return the error as a text suitable for showing to the user.
+
+use std::{
+    fs::File,
+    io::{self, Read},
+    str::FromStr,
+    sync::OnceLock,
+};
+
+#[cfg(feature = "cli")]
+use color_print::cformat;
+use num_traits::Num;
+
+use crate::protocols::CommunicationError;
+
+/// Parses a decimal number, a hexadecimal number with the `0x` prefix or a binary number with the `0b` prefix
+///
+/// # Errors
+/// Text describing the invalid number, also when the number does not fit into `T`.
+///
+/// # Examples
+/// ```
+/// use mboot::parse::parse_number;
+///
+/// assert_eq!(parse_number::<u32>("0x1F"), Ok(31));
+/// assert_eq!(parse_number::<u8>("200"), Ok(200));
+/// assert_eq!(parse_number::<u8>("0b101"), Ok(5));
+/// assert!(parse_number::<u8>("0x100").is_err());
+/// ```
+pub fn parse_number<T: Num + FromStr>(s: &str) -> Result<T, String> {
+    let invalid = highlight(s);
+    if let Some(stripped) = s.strip_prefix("0x") {
+        T::from_str_radix(stripped, 16).or(Err(format!("hex number '{invalid}' is invalid or too large")))
+    } else if let Some(stripped) = s.strip_prefix("0b") {
+        T::from_str_radix(stripped, 2).or(Err(format!("binary number '{invalid}' is invalid or too large")))
+    } else {
+        s.parse()
+            .or(Err(format!("number '{invalid}' is invalid or too large!")))
+    }
+}
+
+/// Parses a memory address, a number as accepted by [`parse_number`]
+///
+/// Commands of the bootloader carry 32-bit addresses and the protocol has no variants with wider ones, so
+/// addresses above 4 GiB, e.g. DDR of i.MX 9 application cores, cannot be accessed. They are rejected with
+/// an error saying so rather than as too large numbers.
+///
+/// # Errors
+/// Text describing the invalid number or the address above 4 GiB.
+///
+/// # Examples
+/// ```
+/// use mboot::parse::parse_address;
+///
+/// assert_eq!(parse_address("0x20000000"), Ok(0x2000_0000));
+/// assert!(parse_address("0x880000000").unwrap_err().contains("32-bit"));
+/// ```
+pub fn parse_address(s: &str) -> Result<u32, String> {
+    let address = parse_number::<u64>(s)?;
+    u32::try_from(address).or(Err(format!(
+        "address '{}' is above 4 GiB, bootloader commands carry 32-bit addresses only",
+        highlight(s)
+    )))
+}
+
+/// Parses a size, a number as accepted by [`parse_number`] optionally followed by a binary multiple suffix
+///
+/// `k` or `K` multiplies the number by 1024, `M` by 1024 * 1024.
+///
+/// # Errors
+/// Text describing the invalid size, also when the size does not fit into `T`.
+///
+/// # Examples
+/// ```
+/// use mboot::parse::parse_size;
+///
+/// assert_eq!(parse_size::<u32>("64k"), Ok(0x1_0000));
+/// assert_eq!(parse_size::<u32>("0x2M"), Ok(0x20_0000));
+/// assert_eq!(parse_size::<u32>("100"), Ok(100));
+/// ```
+pub fn parse_size<T: TryFrom<u64>>(s: &str) -> Result<T, String> {
+    let (number, multiplier) = if let Some(number) = s.strip_suffix(['k', 'K']) {
+        (number, 1024)
+    } else if let Some(number) = s.strip_suffix('M') {
+        (number, 1024 * 1024)
+    } else {
+        (s, 1)
+    };
+    parse_number::<u64>(number)?
+        .checked_mul(multiplier)
+        .and_then(|size| T::try_from(size).ok())
+        .ok_or_else(|| format!("size '{}' is too large", highlight(s)))
+}
+
+/// Highlights invalid input in error messages, without the CLI the input is kept as is
+#[cfg(feature = "cli")]
+fn highlight(s: &str) -> String {
+    cformat!("<y>{s}</>")
+}
+
+#[cfg(not(feature = "cli"))]
+fn highlight(s: &str) -> String {
+    s.to_owned()
+}
+
+/// Reads the whole file, or exactly `limit` bytes from its start
+///
+/// The data are sent with a 32-bit length, so larger files are rejected before they are read.
+///
+/// # Errors
+/// Text of the I/O error, also when the file is shorter than `limit`, or of
+/// [`CommunicationError::PayloadTooLarge`] if the data are longer than 4 GiB - 1.
+pub fn parse_file(s: &str, limit: Option<usize>) -> Result<Box<[u8]>, String> {
+    let mut file = File::open(s).map_err(|err| err.to_string())?;
+    let size = match limit {
+        Some(limit) => limit,
+        None => usize::try_from(file.metadata().map_err(|err| err.to_string())?.len()).unwrap_or(usize::MAX),
+    };
+    CommunicationError::check_size::<u32>(size).map_err(|err| format!("{s}: {err}"))?;
+    Ok(if let Some(limit) = limit {
+        let mut buf = vec![0u8; limit];
+        file.read_exact(&mut buf).map_err(|err| err.to_string())?;
+        buf
+    } else {
+        let mut buf = Vec::new();
+        file.read_to_end(&mut buf).map_err(|err| err.to_string())?;
+        buf
+    }
+    .into_boxed_slice())
+}
+
+/// Reads a file given as `<path>[,<limit>]`, the limit is a size as accepted by [`parse_size`]
+///
+/// # Errors
+/// Text describing the invalid limit or the I/O error.
+pub fn parse_file_spec(s: &str) -> Result<Box<[u8]>, String> {
+    match s.rsplit_once(',') {
+        Some((path, limit)) => parse_file(path, Some(parse_size(limit.trim())?)),
+        None => parse_file(s, None),
+    }
+}
+
+/// Parses hex digits in double braces, spaces between the digits are ignored
+///
+/// # Errors
+/// Text describing the invalid digits.
+///
+/// # Examples
+/// ```
+/// use mboot::parse::parse_hex_bytes;
+///
+/// assert_eq!(parse_hex_bytes("{{11 22 3344}}").unwrap().as_ref(), [0x11, 0x22, 0x33, 0x44]);
+/// assert!(parse_hex_bytes("{{123}}").is_err());
+/// ```
+pub fn parse_hex_bytes(s: &str) -> Result<Box<[u8]>, String> {
+    let digits = s
+        .strip_prefix("{{")
+        .and_then(|s| s.strip_suffix("}}"))
+        .ok_or_else(|| format!("hex data '{}' must be enclosed in {{{{ }}}}", highlight(s)))?
+        .replace(' ', "");
+    if digits.len() % 2 != 0 {
+        return Err("hex data has an odd number of digits".to_owned());
+    }
+    (0..digits.len())
+        .step_by(2)
+        .map(|index| {
+            let byte = digits.get(index..index + 2).unwrap_or_default();
+            u8::from_str_radix(byte, 16).or(Err(format!("invalid byte: '{}'", highlight(byte))))
+        })
+        .collect()
+}
+
+/// Reads the whole standard input
+///
+/// The content is kept, so arguments parsed more than once get the same data.
+///
+/// # Errors
+/// Text of the I/O error, or of [`CommunicationError::PayloadTooLarge`] if the data are longer than 4 GiB - 1.
+pub fn parse_stdin() -> Result<Box<[u8]>, String> {
+    static CONTENT: OnceLock<Box<[u8]>> = OnceLock::new();
+    if let Some(content) = CONTENT.get() {
+        return Ok(content.clone());
+    }
+    let mut buf = Vec::new();
+    io::stdin()
+        .read_to_end(&mut buf)
+        .map_err(|err| format!("stdin: {err}"))?;
+    CommunicationError::check_size::<u32>(buf.len()).map_err(|err| format!("stdin: {err}"))?;
+    Ok(CONTENT.get_or_init(|| buf.into()).clone())
+}
+
+/// Parses data given as hex digits in double braces, `-` for the standard input, or as a file with an
+/// optional limit
+///
+/// See [`parse_hex_bytes`], [`parse_stdin`] and [`parse_file_spec`]. Data like keys can be piped from other
+/// tools this way, without storing them in a file.
+///
+/// # Errors
+/// Text describing the invalid data or the I/O error.
+pub fn parse_hex_values(s: &str) -> Result<Box<[u8]>, String> {
+    if s.starts_with("{{") {
+        parse_hex_bytes(s)
+    } else if s == "-" {
+        parse_stdin()
+    } else {
+        parse_file_spec(s)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use super::*;
+
+    #[test]
+    fn numbers() {
+        assert_eq!(parse_number::<u32>("0"), Ok(0));
+        assert_eq!(parse_number::<u32>("0xFFFFFFFF"), Ok(u32::MAX));
+        assert!(parse_number::<u32>("0x1_0000_0000").is_err());
+        assert!(parse_number::<u32>("-1").is_err());
+        assert!(parse_number::<u32>("12k").is_err());
+        assert_eq!(parse_number::<u32>("0b1010"), Ok(10));
+        assert!(parse_number::<u8>("0b1_0000_0000").is_err());
+        assert!(parse_number::<u32>("0b12").is_err());
+    }
+
+    #[test]
+    fn addresses() {
+        assert_eq!(parse_address("0xFFFFFFFF"), Ok(u32::MAX));
+        assert!(parse_address("0x100000000").unwrap_err().contains("above 4 GiB"));
+        assert!(!parse_address("0xZZ").unwrap_err().contains("above 4 GiB"));
+    }
+
+    #[test]
+    fn sizes() {
+        assert_eq!(parse_size::<u32>("1k"), Ok(1024));
+        assert_eq!(parse_size::<u32>("1K"), Ok(1024));
+        assert_eq!(parse_size::<usize>("1M"), Ok(1024 * 1024));
+        assert_eq!(parse_size::<u32>("0x10"), Ok(16));
+        assert_eq!(parse_size::<u32>("0b10k"), Ok(2048));
+        assert_eq!(parse_size::<u32>("64k"), Ok(0x1_0000));
+        assert!(parse_size::<u32>("4096M").is_err());
+        assert!(parse_size::<u32>("1m").is_err());
+        assert!(parse_size::<u32>("k").is_err());
+    }
+
+    #[test]
+    fn hex_bytes() {
+        assert_eq!(parse_hex_bytes("{{}}").unwrap().as_ref(), [0u8; 0]);
+        assert_eq!(parse_hex_bytes("{{aB cd}}").unwrap().as_ref(), [0xAB, 0xCD]);
+        assert!(parse_hex_bytes("{{zz}}").is_err());
+        assert!(parse_hex_bytes("{{éé}}").is_err());
+        assert!(parse_hex_bytes("{{11").is_err());
+    }
+
+    #[test]
+    fn file_specs() {
+        let path = std::env::temp_dir().join(format!("rblhost-parse-{}.bin", std::process::id()));
+        File::create(&path).unwrap().write_all(&[1, 2, 3, 4]).unwrap();
+        let path_str = path.to_str().unwrap();
+
+        assert_eq!(parse_hex_values(path_str).unwrap().as_ref(), [1, 2, 3, 4]);
+        assert_eq!(parse_hex_values(&format!("{path_str},2")).unwrap().as_ref(), [1, 2]);
+        assert!(parse_file_spec(&format!("{path_str},1k")).is_err());
+        assert!(
+            parse_file_spec(&format!("{path_str},4096M"))
+                .unwrap_err()
+                .ends_with("exceeds the limit of 4294967295 bytes")
+        );
+        assert_eq!(parse_hex_values("{{0102}}").unwrap().as_ref(), [1, 2]);
+
+        std::fs::remove_file(path).unwrap();
+    }
+}
@@ -0,0 +1,31 @@
+// Copyright 2025 NXP
+//
+// SPDX-License-Identifier: BSD-3-Clause
+//! Commonly used items
+//!
+//! Glob import this module to get the types needed by most programs communicating with a device:
+//!
+//! ```no_run
+//! use mboot::prelude::*;
+//!
+//! let port = UARTProtocol::open("/dev/ttyACM0")?;
+//! let mut boot = McuBoot::new(port);
+//! let response = boot.get_property(PropertyTagDiscriminants::CurrentVersion, 0)?;
+//! println!("{}", response.property);
+//! # Ok::<(), CommunicationError>(())
+//! ```
+
+pub use crate::{
+    CommandOutcome, CommunicationError, GetPropertyResponse, KeyProvisioningResponse, McuBoot, McuBootOps,
+    ReadMemoryResponse, TransferOptions,
+    capabilities::DeviceCapabilities,
+    progress::ProgressEvent,
+    protocols::{Protocol, ProtocolOpen, i2c::I2CProtocol, uart::UARTProtocol, usb::USBProtocol},
+    session::SessionConfig,
+    tags::{
+        command::CommandTagDiscriminants,
+        property::{PropertyTag, PropertyTagDiscriminants},
+        status::StatusCode,
+    },
+    warning::Warning,
+};
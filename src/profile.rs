@@ -0,0 +1,361 @@
+// Copyright 2025 NXP
+//
+// SPDX-License-Identifier: BSD-3-Clause
+//! Configuration file with default options and named device profiles
+//!
+//! The file is `rblhost/config.toml` in the configuration directory of the user, `~/.config` on Linux and
+//! macOS (or `$XDG_CONFIG_HOME`) and `%APPDATA%` on Windows. Keys are long names of the global options,
+//! top-level keys apply to every invocation and tables under `profiles` are selected with --profile:
+//!
+//! ```toml
+//! timeout = 2000
+//!
+//! [profiles.lpc55]
+//! usb = "0x1fc9,0x0021"
+//!
+//! [profiles.k32]
+//! port = "/dev/ttyACM0,115200"
+//! boot-pulse = ["dtr:100ms", "wait:50ms"]
+//! family = "k32w1xx"
+//! ```
+//!
+//! Values from the file are passed to the argument parser before the command line, so options given on the
+//! command line win. A device given on the command line replaces the device of the profile.
+
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Context, anyhow, bail};
+use clap::{ArgAction, ArgMatches, Command, parser::ValueSource};
+use toml::{Table, Value};
+
+/// Actions of the config command
+#[derive(clap::Subcommand, Debug, Clone)]
+pub enum ConfigAction {
+    /// Lists all options set in the file, options of profiles as <profile>.<option>
+    List,
+    /// Prints the value of an option, e.g. "timeout" or "lpc55.usb"
+    Get {
+        /// Option, optionally prefixed by the profile
+        key: String,
+    },
+    /// Sets an option, creating the file and the profile if needed
+    Set {
+        /// Option, optionally prefixed by the profile, e.g. "lpc55.usb"
+        key: String,
+        /// Value as given on the command line, numbers and true or false are stored as such
+        value: String,
+    },
+}
+
+/// Table with the named profiles
+const PROFILES: &str = "profiles";
+/// Options which cannot be set in the file
+const EXCLUDED: [&str; 3] = ["profile", "help", "version"];
+
+/// Path of the configuration file, [`None`] if the configuration directory is unknown
+pub fn path() -> Option<PathBuf> {
+    let directory = if cfg!(windows) {
+        env::var_os("APPDATA").map(PathBuf::from)
+    } else {
+        env::var_os("XDG_CONFIG_HOME")
+            .filter(|directory| !directory.is_empty())
+            .map(PathBuf::from)
+            .or_else(|| env::var_os("HOME").map(|home| Path::new(&home).join(".config")))
+    }?;
+    Some(directory.join("rblhost").join("config.toml"))
+}
+
+/// Reads the configuration file, a missing file is an empty configuration
+pub fn load(path: &Path) -> anyhow::Result<Table> {
+    match fs::read_to_string(path) {
+        Ok(text) => text
+            .parse()
+            .with_context(|| format!("invalid configuration file {}", path.display())),
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(Table::new()),
+        Err(err) => Err(err).with_context(|| format!("cannot read configuration file {}", path.display())),
+    }
+}
+
+/// Options taken from the configuration file
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct ProfileArguments {
+    /// IDs of the options set by the file
+    pub ids: Vec<String>,
+    /// Arguments passed to the parser before the command line
+    pub arguments: Vec<String>,
+}
+
+impl ProfileArguments {
+    /// Converts the default options of `config` and those of `profile` into arguments
+    ///
+    /// Options given on the command line, as told by `matches`, are left out. When the command line selects
+    /// a device, devices of the file are left out too, as only one device can be given.
+    pub fn new(
+        command: &Command,
+        matches: &ArgMatches,
+        config: &Table,
+        profile: Option<&str>,
+    ) -> anyhow::Result<ProfileArguments> {
+        let mut options: Vec<(&String, &Value)> = config.iter().filter(|(key, _)| key.as_str() != PROFILES).collect();
+        if let Some(name) = profile {
+            let profiles = config.get(PROFILES).and_then(Value::as_table);
+            let Some(profile) = profiles
+                .and_then(|profiles| profiles.get(name))
+                .and_then(Value::as_table)
+            else {
+                let known: Vec<&str> = profiles.into_iter().flat_map(Table::keys).map(String::as_str).collect();
+                bail!("unknown profile '{name}', known profiles: {}", known.join(", "));
+            };
+            options.extend(profile);
+        }
+
+        let devices: Vec<&str> = command
+            .get_groups()
+            .find(|group| group.get_id() == "Device")
+            .map(|group| group.get_args().map(clap::Id::as_str).collect())
+            .unwrap_or_default();
+        let device_given = devices
+            .iter()
+            .any(|&id| matches.value_source(id) == Some(ValueSource::CommandLine));
+
+        let mut profile_arguments = ProfileArguments::default();
+        for (key, value) in options {
+            let arg = command
+                .get_arguments()
+                .find(|arg| arg.get_long() == Some(key) && !arg.is_hide_set() && !EXCLUDED.contains(&key.as_str()))
+                .ok_or_else(|| anyhow!("'{key}' in the configuration file is not an option of rblhost"))?;
+            let id = arg.get_id().as_str();
+            if matches.value_source(id) == Some(ValueSource::CommandLine) || (device_given && devices.contains(&id)) {
+                continue;
+            }
+            let count = profile_arguments.arguments.len();
+            match (arg.get_action(), value) {
+                (ArgAction::SetTrue, Value::Boolean(true)) => profile_arguments.arguments.push(format!("--{key}")),
+                (ArgAction::SetTrue, Value::Boolean(false)) => {}
+                (ArgAction::Count, Value::Integer(count)) => {
+                    for _ in 0..*count {
+                        profile_arguments.arguments.push(format!("--{key}"));
+                    }
+                }
+                (ArgAction::SetTrue | ArgAction::Count, _) => {
+                    bail!(
+                        "'{key}' in the configuration file has to be {}",
+                        type_name(arg.get_action())
+                    );
+                }
+                (_, Value::Array(items)) => {
+                    for item in items {
+                        profile_arguments
+                            .arguments
+                            .push(format!("--{key}={}", scalar(key, item)?));
+                    }
+                }
+                (_, value) => profile_arguments
+                    .arguments
+                    .push(format!("--{key}={}", scalar(key, value)?)),
+            }
+            if profile_arguments.arguments.len() > count {
+                profile_arguments.ids.push(id.to_owned());
+            }
+        }
+        Ok(profile_arguments)
+    }
+
+    /// Inserts the arguments right after the program name in `arguments` of the command line
+    pub fn merge(&self, arguments: &[String]) -> Vec<String> {
+        let mut merged = arguments[..1].to_vec();
+        merged.extend(self.arguments.iter().cloned());
+        merged.extend(arguments[1..].iter().cloned());
+        merged
+    }
+}
+
+/// Describes the expected value of options with `action`
+fn type_name(action: &ArgAction) -> &'static str {
+    match action {
+        ArgAction::Count => "a number",
+        _ => "true or false",
+    }
+}
+
+/// Formats a value of option `key` as it is written on the command line
+fn scalar(key: &str, value: &Value) -> anyhow::Result<String> {
+    match value {
+        Value::String(text) => Ok(text.clone()),
+        Value::Integer(number) => Ok(number.to_string()),
+        Value::Float(number) => Ok(number.to_string()),
+        Value::Boolean(flag) => Ok(flag.to_string()),
+        _ => bail!("'{key}' in the configuration file has to be a string, a number or a list of them"),
+    }
+}
+
+/// Parses a value given to config set, numbers and booleans are stored as such, anything else as a string
+fn parse_value(text: &str) -> Value {
+    if let Ok(number) = text.parse::<i64>() {
+        Value::Integer(number)
+    } else if let Ok(flag) = text.parse::<bool>() {
+        Value::Boolean(flag)
+    } else {
+        Value::String(text.to_owned())
+    }
+}
+
+/// Splits `key` of config get and set into the profile and the option, e.g. `lpc55.usb`
+fn split_key(key: &str) -> (Option<&str>, &str) {
+    match key.split_once('.') {
+        Some((profile, option)) => (Some(profile), option),
+        None => (None, key),
+    }
+}
+
+/// Value of `key` in `config`, either an option like `timeout` or an option of a profile like `lpc55.usb`
+pub fn get<'a>(config: &'a Table, key: &str) -> Option<&'a Value> {
+    match split_key(key) {
+        (Some(profile), option) => config.get(PROFILES)?.get(profile)?.get(option),
+        (None, option) => config.get(option),
+    }
+}
+
+/// Sets `key` in `config` to `value`, creating the profile if needed
+///
+/// The option is checked to be a global option of `command`, the value is checked when it is used.
+pub fn set(command: &Command, config: &mut Table, key: &str, value: &str) -> anyhow::Result<()> {
+    let (profile, option) = split_key(key);
+    if option == PROFILES
+        || EXCLUDED.contains(&option)
+        || !command
+            .get_arguments()
+            .any(|arg| arg.get_long() == Some(option) && !arg.is_hide_set())
+    {
+        bail!("'{option}' is not an option of rblhost");
+    }
+    let table = match profile {
+        Some(profile) => config
+            .entry(PROFILES)
+            .or_insert_with(|| Value::Table(Table::new()))
+            .as_table_mut()
+            .ok_or_else(|| anyhow!("'{PROFILES}' in the configuration file is not a table"))?
+            .entry(profile)
+            .or_insert_with(|| Value::Table(Table::new()))
+            .as_table_mut()
+            .ok_or_else(|| anyhow!("profile '{profile}' in the configuration file is not a table"))?,
+        None => config,
+    };
+    table.insert(option.to_owned(), parse_value(value));
+    Ok(())
+}
+
+/// Writes `config` into the file at `path`, creating its directory
+pub fn save(path: &Path, config: &Table) -> anyhow::Result<()> {
+    if let Some(directory) = path.parent() {
+        fs::create_dir_all(directory).with_context(|| format!("cannot create {}", directory.display()))?;
+    }
+    fs::write(path, toml::to_string(config)?).with_context(|| format!("cannot write {}", path.display()))
+}
+
+/// Lists all options of `config`, options of profiles prefixed by the profile name
+pub fn list(config: &Table) -> Vec<(String, Value)> {
+    let mut entries = Vec::new();
+    for (key, value) in config {
+        match (key.as_str(), value) {
+            (PROFILES, Value::Table(profiles)) => {
+                for (profile, options) in profiles {
+                    for (option, value) in options.as_table().into_iter().flatten() {
+                        entries.push((format!("{profile}.{option}"), value.clone()));
+                    }
+                }
+            }
+            _ => entries.push((key.clone(), value.clone())),
+        }
+    }
+    entries
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::CommandFactory;
+
+    use super::*;
+    use crate::Args;
+
+    const CONFIG: &str = r#"
+        timeout = 2000
+        no-crc = true
+
+        [profiles.lpc55]
+        usb = "0x1fc9,0x0021"
+        verbose = 2
+
+        [profiles.k32]
+        port = "/dev/ttyACM0,115200"
+        boot-pulse = ["dtr:100ms", "wait:50ms"]
+    "#;
+
+    fn arguments(command_line: &[&str], profile: Option<&str>) -> anyhow::Result<Vec<String>> {
+        let config: Table = CONFIG.parse()?;
+        let command = Args::command();
+        let matches = command.clone().try_get_matches_from(command_line)?;
+        Ok(ProfileArguments::new(&command, &matches, &config, profile)?.arguments)
+    }
+
+    #[test]
+    fn profiles_become_arguments() {
+        assert_eq!(
+            arguments(&["rblhost", "-P", "lpc55", "reset"], Some("lpc55")).unwrap(),
+            [
+                "--no-crc",
+                "--timeout=2000",
+                "--usb=0x1fc9,0x0021",
+                "--verbose",
+                "--verbose"
+            ]
+        );
+        assert_eq!(
+            arguments(&["rblhost", "-t", "100", "reset"], Some("k32")).unwrap(),
+            [
+                "--no-crc",
+                "--boot-pulse=dtr:100ms",
+                "--boot-pulse=wait:50ms",
+                "--port=/dev/ttyACM0,115200"
+            ]
+        );
+        // the device on the command line replaces the one of the profile
+        assert_eq!(
+            arguments(&["rblhost", "-p", "COM3", "reset"], Some("lpc55")).unwrap(),
+            ["--no-crc", "--timeout=2000", "--verbose", "--verbose"]
+        );
+        assert!(arguments(&["rblhost", "reset"], Some("lpc56")).is_err());
+
+        let merged = ProfileArguments {
+            ids: vec!["timeout".to_owned()],
+            arguments: vec!["--timeout=2000".to_owned()],
+        }
+        .merge(&["rblhost".to_owned(), "reset".to_owned()]);
+        assert_eq!(merged, ["rblhost", "--timeout=2000", "reset"]);
+    }
+
+    #[test]
+    fn options_are_set() {
+        let command = Args::command();
+        let mut config = Table::new();
+        set(&command, &mut config, "timeout", "100").unwrap();
+        set(&command, &mut config, "lpc55.usb", "0x1fc9,0x0021").unwrap();
+        set(&command, &mut config, "lpc55.no-crc", "true").unwrap();
+        assert!(set(&command, &mut config, "lpc55.baudrate", "9600").is_err());
+
+        assert_eq!(get(&config, "timeout"), Some(&Value::Integer(100)));
+        assert_eq!(get(&config, "lpc55.no-crc"), Some(&Value::Boolean(true)));
+        assert_eq!(get(&config, "lpc55.port"), None);
+        assert_eq!(
+            list(&config),
+            [
+                ("lpc55.no-crc".to_owned(), Value::Boolean(true)),
+                ("lpc55.usb".to_owned(), Value::String("0x1fc9,0x0021".to_owned())),
+                ("timeout".to_owned(), Value::Integer(100)),
+            ]
+        );
+    }
+}
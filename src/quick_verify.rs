@@ -0,0 +1,94 @@
+// Copyright 2025 NXP
+//
+// SPDX-License-Identifier: BSD-3-Clause
+//! Sampling plan of the quick-verify subcommand
+//!
+//! Instead of reading back the whole image, only the first and last page of every sector it covers are compared,
+//! together with randomly chosen pages. Errors of erase and programming tend to affect whole sectors or pages, so
+//! the samples catch most of them at a fraction of the read time.
+
+use std::ops::Range;
+
+/// Returns sorted, non-overlapping address ranges of the image to compare with the memory
+///
+/// The image of `len` bytes starts at `start_address`. Sectors and pages are aligned to their size in the address
+/// space, samples are clipped to the image. `seed` selects the `random_pages`.
+pub fn sample_plan(
+    start_address: u32,
+    len: usize,
+    sector_size: u32,
+    page_size: u32,
+    random_pages: usize,
+    seed: u64,
+) -> Vec<Range<u64>> {
+    let start = u64::from(start_address);
+    let end = start + len as u64;
+    let sector_size = u64::from(sector_size.max(1));
+    let page_size = u64::from(page_size.max(1));
+    if start == end {
+        return Vec::new();
+    }
+    let page = |address: u64| {
+        let page_start = address - address % page_size;
+        page_start.max(start)..(page_start + page_size).min(end)
+    };
+
+    let mut samples = Vec::new();
+    let mut sector = start - start % sector_size;
+    while sector < end {
+        samples.push(page(sector.max(start)));
+        samples.push(page((sector + sector_size).min(end) - 1));
+        sector += sector_size;
+    }
+
+    let first_page = start - start % page_size;
+    let page_count = (end - first_page).div_ceil(page_size);
+    let mut state = seed | 1;
+    for _ in 0..random_pages {
+        // xorshift64, statistical quality is not important here
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        samples.push(page(first_page + state % page_count * page_size));
+    }
+
+    samples.sort_by_key(|sample| sample.start);
+    let mut plan: Vec<Range<u64>> = Vec::with_capacity(samples.len());
+    for sample in samples {
+        match plan.last_mut() {
+            Some(last) if sample.start <= last.end => last.end = last.end.max(sample.end),
+            _ => plan.push(sample),
+        }
+    }
+    plan
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sectors_are_sampled() {
+        assert_eq!(
+            sample_plan(0x1000, 0x3000, 0x1000, 0x100, 0, 0),
+            [0x1000..0x1100, 0x1F00..0x2100, 0x2F00..0x3100, 0x3F00..0x4000]
+        );
+        // unaligned image is clipped
+        assert_eq!(
+            sample_plan(0x1080, 0x1000, 0x1000, 0x100, 0, 0),
+            [0x1080..0x1100, 0x1F00..0x2080]
+        );
+        assert!(sample_plan(0x1000, 0, 0x1000, 0x100, 4, 0).is_empty());
+    }
+
+    #[test]
+    fn random_pages_stay_in_image() {
+        let plan = sample_plan(0x1080, 0x10_0000, 0x1_0000, 0x100, 64, 12345);
+        assert!(plan.len() > 17);
+        assert!(
+            plan.iter()
+                .all(|sample| sample.start >= 0x1080 && sample.end <= 0x10_1080)
+        );
+        assert!(plan.windows(2).all(|pair| pair[0].end < pair[1].start));
+    }
+}
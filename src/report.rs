@@ -0,0 +1,449 @@
+// Copyright 2025 NXP
+//
+// SPDX-License-Identifier: BSD-3-Clause
+//! Reports of performed operations for the --report option
+//!
+//! Every command sent to the device is recorded with its duration and final status, together with the
+//! identity of the device, SHA-256 digests of written images and all fuse changes. The report is written
+//! at the end of the invocation as Markdown or HTML, so it can be attached to manufacturing travelers or
+//! release records.
+
+use std::{
+    fmt::Write as _,
+    fs, io,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use clap::CommandFactory;
+use sha2::{Digest, Sha256};
+
+use crate::{Args, Commands, EfuseLock, Kw45Command, WriteData};
+
+/// Format of the report, chosen by the extension of the report file
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ReportFormat {
+    Markdown,
+    Html,
+}
+
+impl ReportFormat {
+    fn from_path(path: &Path) -> Option<Self> {
+        let extension = path.extension()?.to_str()?.to_ascii_lowercase();
+        match extension.as_str() {
+            "md" | "markdown" => Some(ReportFormat::Markdown),
+            "html" | "htm" => Some(ReportFormat::Html),
+            _ => None,
+        }
+    }
+}
+
+/// Parses the --report argument, the file must have an extension of a supported format
+pub fn parse_report_path(s: &str) -> Result<PathBuf, String> {
+    let path = PathBuf::from(s);
+    match ReportFormat::from_path(&path) {
+        Some(_) => Ok(path),
+        None => Err("report file must have .html or .md extension".to_owned()),
+    }
+}
+
+/// Part of the invocation starting with the subcommand, global options are left out
+pub fn command_line() -> String {
+    let command = Args::command();
+    let arguments: Vec<_> = std::env::args().skip(1).filter(|arg| arg != "--").collect();
+    let start = arguments
+        .iter()
+        .position(|arg| command.find_subcommand(arg).is_some())
+        .unwrap_or(0);
+    arguments[start..].join(" ")
+}
+
+/// Image or data written to the device
+#[derive(Debug)]
+struct Image {
+    /// File name or description of the data
+    name: String,
+    size: usize,
+    sha256: String,
+}
+
+impl Image {
+    fn new(name: String, bytes: &[u8]) -> Self {
+        let sha256 = Sha256::digest(bytes).iter().fold(String::new(), |mut hex, byte| {
+            let _ = write!(hex, "{byte:02x}");
+            hex
+        });
+        Image {
+            name,
+            size: bytes.len(),
+            sha256,
+        }
+    }
+}
+
+/// Programmed fuse or OTP word
+#[derive(Debug)]
+struct FuseChange {
+    /// Address or index of the fuse
+    location: String,
+    value: String,
+    locked: bool,
+}
+
+/// Single command sent to the device
+#[derive(Debug)]
+struct Operation {
+    command: String,
+    duration: Duration,
+    status: String,
+    passed: bool,
+    images: Vec<Image>,
+    fuses: Vec<FuseChange>,
+}
+
+/// Operations performed during one invocation of rblhost
+#[derive(Debug)]
+pub struct Report {
+    path: PathBuf,
+    started: jiff::Timestamp,
+    invocation: String,
+    device: Vec<(String, String)>,
+    operations: Vec<Operation>,
+    /// Images and fuses sent by the command in progress, added to it once it is recorded
+    sent: (Vec<Image>, Vec<FuseChange>),
+}
+
+impl Report {
+    /// Creates an empty report, which will be written to `path`
+    pub fn new(path: PathBuf, invocation: String) -> Self {
+        Report {
+            path,
+            started: jiff::Timestamp::now(),
+            invocation,
+            device: Vec::new(),
+            operations: Vec::new(),
+            sent: (Vec::new(), Vec::new()),
+        }
+    }
+
+    /// Adds a property identifying the device, like its version or unique ID
+    pub fn device_property(&mut self, name: &str, value: &str) {
+        self.device.push((name.to_owned(), value.to_owned()));
+    }
+
+    /// Adds an image read from a file to the command in progress, `bytes` are the data sent to the device
+    pub fn sent_image(&mut self, name: String, bytes: &[u8]) {
+        self.sent.0.push(Image::new(name, bytes));
+    }
+
+    /// Adds fuse words programmed with `bytes` at `start_address` to the command in progress
+    pub fn programmed_fuses(&mut self, start_address: u32, bytes: &[u8]) {
+        self.sent.1.push(FuseChange {
+            location: format!("fuse at {start_address:#010X}"),
+            value: bytes.iter().fold(String::new(), |mut hex, byte| {
+                let _ = write!(hex, "{byte:02X}");
+                hex
+            }),
+            locked: false,
+        });
+    }
+
+    /// Records a finished command together with images and fuses it wrote
+    pub fn record(&mut self, text: &str, command: &Commands, duration: Duration, status: String, passed: bool) {
+        let (mut images, mut fuses) = std::mem::take(&mut self.sent);
+        artifacts(command, &mut images, &mut fuses);
+        self.operations.push(Operation {
+            command: text.to_owned(),
+            duration,
+            status,
+            passed,
+            images,
+            fuses,
+        });
+    }
+
+    /// Writes the report in the format given by the extension of its file
+    pub fn write(&self) -> io::Result<()> {
+        let content = match ReportFormat::from_path(&self.path) {
+            Some(ReportFormat::Html) => self.html(),
+            _ => self.markdown(),
+        };
+        fs::write(&self.path, content)
+    }
+
+    fn passed(&self) -> bool {
+        self.operations.iter().all(|operation| operation.passed)
+    }
+
+    fn result(&self) -> &'static str {
+        if self.passed() { "PASSED" } else { "FAILED" }
+    }
+
+    fn markdown(&self) -> String {
+        let mut out = String::new();
+        let _ = writeln!(out, "# rblhost report\n");
+        let _ = writeln!(out, "- **Result:** {}", self.result());
+        let _ = writeln!(out, "- **Started:** {}", self.started);
+        let _ = writeln!(out, "- **Invocation:** `{}`", self.invocation);
+        let _ = writeln!(out, "- **rblhost version:** {}", env!("CARGO_PKG_VERSION"));
+
+        let _ = writeln!(out, "\n## Device\n");
+        if self.device.is_empty() {
+            let _ = writeln!(out, "Device identity is not available.");
+        } else {
+            let _ = writeln!(out, "| Property | Value |\n| --- | --- |");
+            for (name, value) in &self.device {
+                let _ = writeln!(out, "| {} | {} |", md_cell(name), md_cell(value));
+            }
+        }
+
+        let _ = writeln!(out, "\n## Operations\n");
+        let _ = writeln!(
+            out,
+            "| # | Command | Duration | Status | Result |\n| --- | --- | --- | --- | --- |"
+        );
+        for (index, operation) in self.operations.iter().enumerate() {
+            let _ = writeln!(
+                out,
+                "| {} | `{}` | {:.3} s | {} | {} |",
+                index + 1,
+                md_cell(&operation.command),
+                operation.duration.as_secs_f64(),
+                md_cell(&operation.status),
+                if operation.passed { "passed" } else { "failed" },
+            );
+        }
+
+        let images: Vec<_> = self.images().collect();
+        if !images.is_empty() {
+            let _ = writeln!(out, "\n## Images\n");
+            let _ = writeln!(out, "| # | Image | Size | SHA-256 |\n| --- | --- | --- | --- |");
+            for (index, image) in images {
+                let _ = writeln!(
+                    out,
+                    "| {index} | {} | {} | `{}` |",
+                    md_cell(&image.name),
+                    image.size,
+                    image.sha256
+                );
+            }
+        }
+
+        let fuses: Vec<_> = self.fuses().collect();
+        if !fuses.is_empty() {
+            let _ = writeln!(out, "\n## Fuse Changes\n");
+            let _ = writeln!(out, "| # | Fuse | Value | Locked |\n| --- | --- | --- | --- |");
+            for (index, fuse) in fuses {
+                let _ = writeln!(
+                    out,
+                    "| {index} | {} | `{}` | {} |",
+                    md_cell(&fuse.location),
+                    fuse.value,
+                    if fuse.locked { "yes" } else { "no" }
+                );
+            }
+        }
+        out
+    }
+
+    fn html(&self) -> String {
+        let mut out = String::new();
+        let _ = writeln!(
+            out,
+            "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>rblhost report</title>\n\
+             <style>table {{ border-collapse: collapse; }} th, td {{ border: 1px solid #999; padding: 2px 8px; }} \
+             .passed {{ color: green; }} .failed {{ color: red; }}</style>\n</head>\n<body>\n<h1>rblhost report</h1>"
+        );
+        let _ = writeln!(out, "<ul>");
+        let _ = writeln!(
+            out,
+            "<li><b>Result:</b> <span class=\"{}\">{}</span></li>",
+            self.result().to_ascii_lowercase(),
+            self.result()
+        );
+        let _ = writeln!(out, "<li><b>Started:</b> {}</li>", self.started);
+        let _ = writeln!(
+            out,
+            "<li><b>Invocation:</b> <code>{}</code></li>",
+            escape(&self.invocation)
+        );
+        let _ = writeln!(out, "<li><b>rblhost version:</b> {}</li>", env!("CARGO_PKG_VERSION"));
+        let _ = writeln!(out, "</ul>");
+
+        let _ = writeln!(out, "<h2>Device</h2>");
+        if self.device.is_empty() {
+            let _ = writeln!(out, "<p>Device identity is not available.</p>");
+        } else {
+            let _ = writeln!(out, "<table>\n<tr><th>Property</th><th>Value</th></tr>");
+            for (name, value) in &self.device {
+                let _ = writeln!(out, "<tr><td>{}</td><td>{}</td></tr>", escape(name), escape(value));
+            }
+            let _ = writeln!(out, "</table>");
+        }
+
+        let _ = writeln!(out, "<h2>Operations</h2>");
+        let _ = writeln!(
+            out,
+            "<table>\n<tr><th>#</th><th>Command</th><th>Duration</th><th>Status</th><th>Result</th></tr>"
+        );
+        for (index, operation) in self.operations.iter().enumerate() {
+            let result = if operation.passed { "passed" } else { "failed" };
+            let _ = writeln!(
+                out,
+                "<tr><td>{}</td><td><code>{}</code></td><td>{:.3} s</td><td>{}</td><td class=\"{result}\">{result}</td></tr>",
+                index + 1,
+                escape(&operation.command),
+                operation.duration.as_secs_f64(),
+                escape(&operation.status),
+            );
+        }
+        let _ = writeln!(out, "</table>");
+
+        let images: Vec<_> = self.images().collect();
+        if !images.is_empty() {
+            let _ = writeln!(out, "<h2>Images</h2>");
+            let _ = writeln!(
+                out,
+                "<table>\n<tr><th>#</th><th>Image</th><th>Size</th><th>SHA-256</th></tr>"
+            );
+            for (index, image) in images {
+                let _ = writeln!(
+                    out,
+                    "<tr><td>{index}</td><td>{}</td><td>{}</td><td><code>{}</code></td></tr>",
+                    escape(&image.name),
+                    image.size,
+                    image.sha256
+                );
+            }
+            let _ = writeln!(out, "</table>");
+        }
+
+        let fuses: Vec<_> = self.fuses().collect();
+        if !fuses.is_empty() {
+            let _ = writeln!(out, "<h2>Fuse Changes</h2>");
+            let _ = writeln!(
+                out,
+                "<table>\n<tr><th>#</th><th>Fuse</th><th>Value</th><th>Locked</th></tr>"
+            );
+            for (index, fuse) in fuses {
+                let _ = writeln!(
+                    out,
+                    "<tr><td>{index}</td><td>{}</td><td><code>{}</code></td><td>{}</td></tr>",
+                    escape(&fuse.location),
+                    fuse.value,
+                    if fuse.locked { "yes" } else { "no" }
+                );
+            }
+            let _ = writeln!(out, "</table>");
+        }
+        let _ = writeln!(out, "</body>\n</html>");
+        out
+    }
+
+    /// Images with the number of the operation which wrote them
+    fn images(&self) -> impl Iterator<Item = (usize, &Image)> {
+        self.operations
+            .iter()
+            .enumerate()
+            .flat_map(|(index, operation)| operation.images.iter().map(move |image| (index + 1, image)))
+    }
+
+    /// Fuse changes with the number of the operation which made them
+    fn fuses(&self) -> impl Iterator<Item = (usize, &FuseChange)> {
+        self.operations
+            .iter()
+            .enumerate()
+            .flat_map(|(index, operation)| operation.fuses.iter().map(move |fuse| (index + 1, fuse)))
+    }
+}
+
+/// Collects images and fuse values written by the command given on its command line
+///
+/// Data read from files are added by [`Report::sent_image`] and [`Report::programmed_fuses`] instead, so the
+/// digests are computed over the sent data rather than over the files read again.
+fn artifacts(command: &Commands, images: &mut Vec<Image>, fuses: &mut Vec<FuseChange>) {
+    match command {
+        Commands::WriteMemory {
+            start_address, data, ..
+        } => match data {
+            WriteData::Bytes(bytes) => images.push(Image::new(format!("data at {start_address:#010X}"), bytes)),
+            WriteData::Segments(segments) => images.extend(
+                segments
+                    .iter()
+                    .map(|segment| Image::new(format!("segment at {:#010X}", segment.address), &segment.data)),
+            ),
+        },
+        Commands::ReceiveSbFile {
+            bytes, dry_run: false, ..
+        } => images.push(Image::new("SB file".to_owned(), bytes)),
+        Commands::Kw45(Kw45Command::LoadNbu { sb_file }) => {
+            images.push(Image::new("NBU firmware".to_owned(), sb_file));
+        }
+        Commands::FlashProgramOnce { index, data, .. } => fuses.push(FuseChange {
+            location: format!("OTP index {index:#X}"),
+            value: format!("{data:#010X}"),
+            locked: false,
+        }),
+        Commands::EfuseProgramOnce { index, data, lock, .. } => fuses.push(FuseChange {
+            location: format!("OTP index {index:#X}"),
+            value: format!("{data:#010X}"),
+            locked: *lock == EfuseLock::Lock,
+        }),
+        _ => {}
+    }
+}
+
+fn md_cell(text: &str) -> String {
+    text.replace('|', "\\|").replace('\n', " ")
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn report_path() {
+        assert!(parse_report_path("report.md").is_ok());
+        assert!(parse_report_path("out/REPORT.HTML").is_ok());
+        assert!(parse_report_path("report.txt").is_err());
+        assert!(parse_report_path("report").is_err());
+    }
+
+    #[test]
+    fn sha256_digest() {
+        let image = Image::new("empty".to_owned(), b"");
+        assert_eq!(
+            image.sha256,
+            "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+        );
+        assert_eq!(image.size, 0);
+    }
+
+    #[test]
+    fn sent_data_belong_to_the_next_recorded_command() {
+        let mut report = Report::new(PathBuf::from("report.md"), String::new());
+        report.sent_image("image.hex".to_owned(), b"");
+        report.programmed_fuses(0x10, &[0xA5]);
+        report.record("load-image", &Commands::ListMemory, Duration::ZERO, String::new(), true);
+        report.record(
+            "list-memory",
+            &Commands::ListMemory,
+            Duration::ZERO,
+            String::new(),
+            true,
+        );
+        let [first, second] = &report.operations[..] else {
+            panic!("two operations expected");
+        };
+        assert_eq!(first.images[0].name, "image.hex");
+        assert_eq!(first.fuses[0].value, "A5");
+        assert!(second.images.is_empty() && second.fuses.is_empty());
+    }
+}
@@ -0,0 +1,815 @@
+// Copyright 2025 NXP
+//
+// SPDX-License-Identifier: BSD-3-Clause
+//! Command Output Reporting
+//!
+//! All results of the commands are printed through the [`Reporter`], which either prints them right
+//! away as human readable text, or collects them into a JSON document printed once the command finishes.
+
+use std::{fmt::Write, time::Duration};
+
+use color_print::cformat;
+use indicatif::{ProgressBar, ProgressStyle};
+use jiff::Timestamp;
+use mboot::{
+    CommandOutcome, GetPropertyResponse, ReadMemoryResponse,
+    checksum::ChecksumAlgorithm,
+    formatters::{BinaryBytesOne, HexDiff},
+    memory::{MemoryInfo, mem_id},
+    packets::ping::PingResponse,
+    progress::{ProgressEvent, TransferDirection},
+    protocols::uart::PortInfo,
+    sb::SbHeader,
+    tags::{
+        command::{CommandTagDiscriminants, TrustProvOperation},
+        property::{PropertyTagDiscriminants, Version},
+        status::StatusCode,
+    },
+    warning::Warning,
+};
+use pretty_hex::{HexConfig, PrettyHex};
+
+use crate::{
+    benchmark::Benchmark,
+    config::{Change, ConfigSnapshot},
+    latency::LatencyStats,
+};
+use serde_json::{Map, Value, json};
+
+/// Format of the command output
+#[derive(clap::ValueEnum, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// Human readable text
+    #[default]
+    Text,
+    /// Single JSON document per command
+    Json,
+}
+
+/// Start of SB timestamps, 2000-01-01 00:00:00 UTC, in microseconds since the Unix epoch
+const SB_EPOCH_MICROSECONDS: i64 = 946_684_800_000_000;
+
+/// Prints results of the commands in the selected [`OutputFormat`]
+pub struct Reporter {
+    format: OutputFormat,
+    silent: bool,
+    verbose: bool,
+    document: Map<String, Value>,
+}
+
+impl Reporter {
+    /// Creates a new reporter
+    ///
+    /// `silent` suppresses the human readable text, JSON document is printed regardless. `verbose` adds the
+    /// intermediate status to the text output.
+    pub fn new(format: OutputFormat, silent: bool, verbose: bool) -> Self {
+        Reporter {
+            format,
+            silent,
+            verbose,
+            document: Map::new(),
+        }
+    }
+
+    pub fn is_json(&self) -> bool {
+        self.format == OutputFormat::Json
+    }
+
+    /// Whether the human readable text should be printed
+    fn text(&self) -> bool {
+        !self.silent && !self.is_json()
+    }
+
+    /// Adds a value to the JSON document, does nothing for text output
+    pub fn insert(&mut self, key: &str, value: Value) {
+        if self.is_json() {
+            self.document.insert(key.to_owned(), value);
+        }
+    }
+
+    /// Prints a line of human readable text, which has no counterpart in the JSON document
+    pub fn line(&self, text: &str) {
+        if self.text() {
+            println!("{text}");
+        }
+    }
+
+    pub fn status(&mut self, status: StatusCode, outcome: Option<CommandOutcome>) {
+        let intermediate = outcome.and_then(|outcome| outcome.intermediate_status);
+        if self.is_json() {
+            self.insert("status", status_json(status));
+            if let Some(intermediate) = intermediate {
+                self.insert("intermediate_status", status_json(intermediate));
+            }
+        } else if !self.silent {
+            if self.verbose
+                && let Some(intermediate) = intermediate
+            {
+                println!(
+                    "Intermediate status = {0} ({0:#x}) {1}.",
+                    u32::from(intermediate),
+                    intermediate
+                );
+            }
+            println!("Response status = {0} ({0:#x}) {1}.", u32::from(status), status);
+        }
+    }
+
+    pub fn words(&mut self, response_words: &[u32]) {
+        self.insert("response_words", json!(response_words));
+        if self.text() {
+            for (i, word) in response_words.iter().enumerate() {
+                let i = i + 1;
+                println!("Response word {i} = {word} ({word:#x})");
+            }
+        }
+    }
+
+    pub fn status_words(&mut self, status: StatusCode, outcome: Option<CommandOutcome>, response_words: &[u32]) {
+        self.status(status, outcome);
+        self.words(response_words);
+    }
+
+    pub fn property(&mut self, response: &GetPropertyResponse, outcome: Option<CommandOutcome>) {
+        self.status_words(response.status, outcome, &response.response_words);
+        if self.is_json() {
+            self.insert("property", property_json(response));
+        } else {
+            // property is printed even in silent mode, it is the result of the command
+            println!("{}", response.property);
+        }
+    }
+
+    /// Reports all properties read from the device, one per line
+    pub fn properties(&mut self, responses: &[GetPropertyResponse]) {
+        if self.is_json() {
+            let properties = responses.iter().map(property_json).collect();
+            self.insert("properties", Value::Array(properties));
+        } else {
+            // properties are printed even in silent mode, they are the result of the command
+            for response in responses {
+                println!("{}", response.property);
+            }
+        }
+    }
+
+    /// Reports bytes read from the device, printing them to stdout
+    pub fn memory_bytes(
+        &mut self,
+        response: &ReadMemoryResponse,
+        outcome: Option<CommandOutcome>,
+        byte_count: u32,
+        hexdump: Option<HexConfig>,
+    ) {
+        if self.is_json() {
+            self.insert("data", Value::String(hex_string(&response.bytes)));
+        } else if let Some(config) = hexdump {
+            println!("{:?}", response.bytes.hex_conf(config));
+        } else {
+            for byte_line in response.bytes.chunks(16) {
+                for byte in byte_line {
+                    print!("{byte:02X?} ");
+                }
+                println!();
+            }
+        }
+        self.memory(response, outcome, byte_count);
+    }
+
+    /// Reports the result of a memory read, without the bytes themselves
+    pub fn memory(&mut self, response: &ReadMemoryResponse, outcome: Option<CommandOutcome>, byte_count: u32) {
+        self.status_words(response.status, outcome, &response.response_words);
+        // streamed reads have no bytes, the first response word is the number of bytes read
+        let bytes_read = response.response_words.first().copied().unwrap_or_default();
+        self.insert("bytes_read", json!(bytes_read));
+        self.insert("bytes_requested", json!(byte_count));
+        if self.text() {
+            println!("Read {bytes_read} of {byte_count} bytes.");
+        }
+    }
+
+    /// Reports how long the data phase of `byte_count` bytes took and its throughput
+    #[expect(clippy::cast_precision_loss, reason = "throughput is only printed with one decimal")]
+    pub fn transfer_stats(&mut self, byte_count: usize, elapsed: Duration) {
+        let seconds = elapsed.as_secs_f64();
+        let throughput = (seconds > 0.0).then(|| byte_count as f64 / 1024.0 / seconds);
+        self.insert(
+            "transfer",
+            json!({
+                "bytes": byte_count,
+                "elapsed_ms": elapsed.as_millis(),
+                "kib_per_s": throughput,
+            }),
+        );
+        if self.text() {
+            match throughput {
+                Some(throughput) => println!("Transferred in {seconds:.3} s ({throughput:.1} KiB/s)."),
+                None => println!("Transferred in {seconds:.3} s."),
+            }
+        }
+    }
+
+    /// Reports bytes written into a file, printing them as a hexdump with `hexdump`
+    pub fn file(&mut self, file: &str, bytes: &[u8], hexdump: Option<HexConfig>) {
+        self.insert("file", json!(file));
+        self.insert("bytes_written", json!(bytes.len()));
+        if self.text() {
+            println!("Successfully wrote {} bytes to file: {file}", bytes.len());
+            if let Some(config) = hexdump {
+                println!("{:?}", bytes.hex_conf(config));
+            }
+        }
+    }
+
+    /// Reports a single value read from the device
+    pub fn value(&mut self, value: u32) {
+        self.insert("value", json!(value));
+        if self.text() {
+            println!("Read value: {value} (0x{value:X})");
+        }
+    }
+
+    pub fn trust_prov(&mut self, operation: &TrustProvOperation, response: &[u32]) {
+        let fields = trust_prov_fields(operation);
+        if !fields.is_empty() {
+            let values: serde_json::Map<_, _> = fields
+                .iter()
+                .zip(response)
+                .map(|(&(key, _), &value)| (key.to_owned(), json!(value)))
+                .collect();
+            self.insert("trust_provisioning", values.into());
+        }
+        if self.text() {
+            println!("Output data size/value(s) is (are):");
+            for (&(_, label), value) in fields.iter().zip(response) {
+                println!("\t{label}: {value} ({value:#02X})");
+            }
+        }
+    }
+
+    /// Reports memories listed by the device as a table
+    pub fn memories(&mut self, memories: &[MemoryInfo]) {
+        let rows: Vec<MemoryRow> = memories.iter().map(MemoryRow::from).collect();
+        self.insert(
+            "memories",
+            rows.iter()
+                .map(|row| {
+                    json!({
+                        "name": row.name,
+                        "memory_id": row.memory_id,
+                        "start_address": row.start_address,
+                        "size": row.size,
+                        "page_size": row.page_size,
+                        "sector_size": row.sector_size,
+                        "block_size": row.block_size,
+                    })
+                })
+                .collect(),
+        );
+        if !self.text() {
+            return;
+        }
+        if rows.is_empty() {
+            println!("No memories reported by the device.");
+            return;
+        }
+        println!(
+            "{:<16} {:>4}  {:<13} {:>11} {:>11} {:>11} {:>11}",
+            "Memory", "ID", "Start Address", "Size", "Page Size", "Sector Size", "Block Size"
+        );
+        for row in rows {
+            let start_address = row
+                .start_address
+                .map_or_else(|| "-".to_owned(), |address| format!("{address:#010X}"));
+            println!(
+                "{:<16} {:>4}  {start_address:<13} {:>11} {:>11} {:>11} {:>11}",
+                row.name,
+                row.memory_id,
+                size_cell(row.size),
+                size_cell(row.page_size.map(u64::from)),
+                size_cell(row.sector_size.map(u64::from)),
+                size_cell(row.block_size.map(u64::from)),
+            );
+        }
+    }
+
+    /// Reports serial ports of the host, highlighting those likely belonging to NXP boards
+    pub fn ports(&mut self, ports: &[PortInfo]) {
+        self.insert(
+            "ports",
+            ports
+                .iter()
+                .map(|port| {
+                    json!({
+                        "name": port.name,
+                        "vid": port.usb.as_ref().map(|usb| usb.vid),
+                        "pid": port.usb.as_ref().map(|usb| usb.pid),
+                        "serial_number": port.usb.as_ref().and_then(|usb| usb.serial_number.clone()),
+                        "manufacturer": port.usb.as_ref().and_then(|usb| usb.manufacturer.clone()),
+                        "product": port.usb.as_ref().and_then(|usb| usb.product.clone()),
+                        "likely_nxp": port.is_likely_nxp(),
+                    })
+                })
+                .collect(),
+        );
+        if !self.text() {
+            return;
+        }
+        if ports.is_empty() {
+            println!("No serial ports found.");
+            return;
+        }
+        for port in ports {
+            let Some(usb) = &port.usb else {
+                println!("{}", port.name);
+                continue;
+            };
+            let mut line = format!("{:<20} {:#06X}:{:#06X}", port.name, usb.vid, usb.pid);
+            for text in [&usb.manufacturer, &usb.product].into_iter().flatten() {
+                line.push(' ');
+                line.push_str(text);
+            }
+            if let Some(serial_number) = &usb.serial_number {
+                let _ = write!(line, " (serial {serial_number})");
+            }
+            if port.is_likely_nxp() {
+                println!("{}", cformat!("<g>{line}</>"));
+            } else {
+                println!("{line}");
+            }
+        }
+    }
+
+    /// Reports options of the configuration file as `key = value` lines
+    pub fn config_entries(&mut self, entries: &[(String, toml::Value)]) {
+        self.insert(
+            "config",
+            entries
+                .iter()
+                .map(|(key, value)| (key.clone(), serde_json::to_value(value).unwrap_or(Value::Null)))
+                .collect::<Map<_, _>>()
+                .into(),
+        );
+        if !self.text() {
+            return;
+        }
+        for (key, value) in entries {
+            println!("{key} = {value}");
+        }
+    }
+
+    /// Reports the header of an SB file
+    pub fn sb_header(&mut self, header: &SbHeader) {
+        let created = i64::try_from(header.timestamp)
+            .ok()
+            .and_then(|timestamp| Timestamp::from_microsecond(SB_EPOCH_MICROSECONDS.checked_add(timestamp)?).ok());
+        self.insert(
+            "sb_file",
+            json!({
+                "version": format!("{}.{}", header.major, header.minor),
+                "flags": header.flags,
+                "block_count": header.block_count,
+                "block_size": header.block_size,
+                "signed": header.is_signed(),
+                "encryption": header.encryption.to_string(),
+                "created": created.map(|created| created.to_string()),
+                "firmware_version": header.firmware_version,
+                "image_type": header.image_type,
+                "description": header.description,
+            }),
+        );
+        if !self.text() {
+            return;
+        }
+        println!("Format: {header}");
+        println!("Flags: {:#010X}", header.flags);
+        println!("Blocks: {} x {} bytes", header.block_count, header.block_size);
+        println!("Signed: {}", if header.is_signed() { "yes" } else { "no" });
+        println!("Encryption: {}", header.encryption);
+        if let Some(created) = created {
+            println!("Created: {created}");
+        }
+        if let Some(firmware_version) = header.firmware_version {
+            println!("Firmware version: {firmware_version}");
+        }
+        if let Some(image_type) = header.image_type {
+            println!("Image type: {image_type}");
+        }
+        if let Some(description) = &header.description {
+            println!("Description: {description}");
+        }
+    }
+
+    /// Reports that the device is able to process the SB file
+    pub fn sb_supported(&mut self, header: &SbHeader, version: Version) {
+        self.insert("bootloader_version", json!(version.to_string()));
+        self.line(&format!("{header} file is supported by bootloader {version}."));
+    }
+
+    /// Reports the `response` of the device to the ping
+    pub fn ping(&mut self, response: PingResponse) {
+        let version = response.protocol_version();
+        self.insert(
+            "ping",
+            json!({
+                "protocol_version": version.to_string(),
+                "options": response.options,
+            }),
+        );
+        self.line(&format!("Protocol version = {version}"));
+        self.line(&format!("Options = {:#06X}", response.options));
+    }
+
+    /// Reports the frame the device responded with to a raw frame, empty if it had no response
+    pub fn frame(&mut self, response: &[u8]) {
+        self.insert("response_frame", json!(hex_string(response)));
+        if response.is_empty() {
+            self.line("No response frame");
+        } else {
+            self.line(&format!("Response frame = {response:02X?}"));
+        }
+    }
+
+    /// Reports round-trip time statistics of the transport
+    pub fn latency(&mut self, transport: &str, stats: &LatencyStats) {
+        let ms = |duration: Duration| duration.as_secs_f64() * 1000.0;
+        self.insert(
+            "latency",
+            json!({
+                "transport": transport,
+                "count": stats.count,
+                "min_ms": ms(stats.min),
+                "avg_ms": ms(stats.avg),
+                "max_ms": ms(stats.max),
+                "jitter_ms": ms(stats.jitter),
+                "suggested_timeout_ms": stats.suggested_timeout().as_millis(),
+                "suggested_polling_interval_ms": stats.suggested_polling_interval().as_millis(),
+            }),
+        );
+        if !self.text() {
+            return;
+        }
+        println!("Transport: {transport}");
+        println!("Round trips: {}", stats.count);
+        println!("Min: {:.3} ms", ms(stats.min));
+        println!("Avg: {:.3} ms", ms(stats.avg));
+        println!("Max: {:.3} ms", ms(stats.max));
+        println!("Jitter: {:.3} ms", ms(stats.jitter));
+        println!(
+            "Suggested options: --timeout {} --polling-interval {}",
+            stats.suggested_timeout().as_millis(),
+            stats.suggested_polling_interval().as_millis()
+        );
+    }
+
+    /// Reports latency and throughput measured with every packet size and the recommended packet size
+    pub fn benchmark(&mut self, transport: &str, benchmark: &Benchmark) {
+        let ms = |duration: Duration| duration.as_secs_f64() * 1000.0;
+        let recommended = benchmark.recommended_packet_size();
+        let measurements: Vec<_> = benchmark
+            .measurements
+            .iter()
+            .map(|measurement| {
+                json!({
+                    "packet_size": measurement.packet_size,
+                    "write_ms": ms(measurement.write),
+                    "read_ms": ms(measurement.read),
+                    "write_kib_per_s": benchmark.throughput(measurement.write),
+                    "read_kib_per_s": benchmark.throughput(measurement.read),
+                })
+            })
+            .collect();
+        self.insert(
+            "benchmark",
+            json!({
+                "transport": transport,
+                "address": benchmark.address,
+                "byte_count": benchmark.byte_count,
+                "latency": {
+                    "count": benchmark.latency.count,
+                    "min_ms": ms(benchmark.latency.min),
+                    "avg_ms": ms(benchmark.latency.avg),
+                    "max_ms": ms(benchmark.latency.max),
+                },
+                "measurements": measurements,
+                "recommended_max_packet_size": recommended,
+            }),
+        );
+        if !self.text() {
+            return;
+        }
+        println!("Transport: {transport}");
+        println!(
+            "Round trip: min {:.3} ms, avg {:.3} ms, max {:.3} ms",
+            ms(benchmark.latency.min),
+            ms(benchmark.latency.avg),
+            ms(benchmark.latency.max)
+        );
+        println!(
+            "Transferred {} bytes at {:#010X} in each direction:",
+            benchmark.byte_count, benchmark.address
+        );
+        println!("{:>11}  {:>15}  {:>15}", "PACKET SIZE", "WRITE KiB/s", "READ KiB/s");
+        let kib_per_s = |duration| {
+            benchmark
+                .throughput(duration)
+                .map_or("-".to_owned(), |value| format!("{value:.1}"))
+        };
+        for measurement in &benchmark.measurements {
+            println!(
+                "{:>11}  {:>15}  {:>15}",
+                measurement.packet_size,
+                kib_per_s(measurement.write),
+                kib_per_s(measurement.read)
+            );
+        }
+        if let Some(packet_size) = recommended {
+            println!("Suggested option: --max-packet-size {packet_size}");
+        }
+    }
+
+    /// Reports that `compared` bytes out of `total` matched the memory, the rest was not read
+    pub fn verified(&mut self, compared: u64, total: u64) {
+        self.insert("compared_bytes", json!(compared));
+        self.insert("total_bytes", json!(total));
+        self.line(&format!("Memory matches, compared {compared} of {total} bytes."));
+    }
+
+    /// Reports whether `byte_count` bytes are erased, `programmed` is the first byte which is not
+    pub fn blank_check(&mut self, byte_count: u32, programmed: Option<u32>) {
+        self.insert("erased", json!(programmed.is_none()));
+        self.insert("first_programmed_address", json!(programmed));
+        if programmed.is_none() {
+            self.line(&format!("Memory is erased, checked {byte_count} bytes."));
+        }
+    }
+
+    /// Reports the `checksum` of a memory region computed with `algorithm`
+    pub fn checksum(&mut self, algorithm: ChecksumAlgorithm, checksum: &[u8]) {
+        let hex = checksum.iter().fold(String::new(), |mut hex, byte| {
+            let _ = write!(hex, "{byte:02x}");
+            hex
+        });
+        self.insert("algorithm", json!(algorithm.to_string()));
+        self.insert("checksum", json!(hex));
+        self.line(&format!("{algorithm}: {hex}"));
+    }
+
+    /// Reports the first difference between the file and the memory, found at `address`
+    pub fn mismatch(&mut self, address: u64, diff: &HexDiff) {
+        self.insert("mismatch_address", json!(address));
+        if self.text() {
+            eprint!("{diff}");
+        }
+    }
+
+    /// Reports a configuration snapshot written to `file`
+    pub fn config_exported(&mut self, file: &str, snapshot: &ConfigSnapshot) {
+        self.insert("file", json!(file));
+        self.insert("properties", json!(snapshot.properties.len()));
+        self.insert("fuses", json!(snapshot.fuses.len()));
+        self.line(&format!(
+            "Saved {} properties and {} fuse words to {file}.",
+            snapshot.properties.len(),
+            snapshot.fuses.len()
+        ));
+    }
+
+    /// Reports differences between a configuration snapshot and the device
+    pub fn config_changes(&mut self, changes: &[Change]) {
+        self.insert("changes", changes.iter().copied().map(Change::to_json).collect());
+        if !self.text() {
+            return;
+        }
+        if changes.is_empty() {
+            println!("Device configuration matches the snapshot.");
+        }
+        for change in changes {
+            println!("{change}");
+        }
+    }
+
+    /// Reports a likely cause of a failure
+    pub fn hint(&mut self, hint: &str) {
+        self.insert("hint", json!(hint));
+        if self.text() {
+            eprintln!("{}", cformat!("<y>hint:</> {hint}"));
+        }
+    }
+
+    /// Reports a non-fatal condition, collected under `warnings` in the JSON document
+    pub fn warning(&mut self, warning: &Warning) {
+        if self.is_json() {
+            let warning = json!({"kind": warning.kind(), "message": warning.to_string()});
+            match self.document.get_mut("warnings") {
+                Some(Value::Array(warnings)) => warnings.push(warning),
+                _ => self.insert("warnings", json!([warning])),
+            }
+        } else if !self.silent {
+            eprintln!("{}", cformat!("<y>warning:</> {warning}"));
+        }
+    }
+
+    /// Reports a failed command, errors in text output are left to the caller
+    pub fn error(&mut self, message: &str, status: Option<StatusCode>) {
+        self.insert("error", json!(message));
+        if let Some(status) = status {
+            self.insert("status", status_json(status));
+        }
+    }
+
+    /// Takes the collected JSON document, leaving the reporter empty for the next command
+    ///
+    /// Returns [`None`] for text output.
+    pub fn take_document(&mut self) -> Option<Value> {
+        self.is_json()
+            .then(|| Value::Object(std::mem::take(&mut self.document)))
+    }
+
+    /// Prints the collected JSON document, does nothing for text output
+    pub fn flush(&mut self) {
+        if let Some(document) = self.take_document() {
+            println!("{document:#}");
+        }
+    }
+}
+
+/// Creates a progress callback drawing a progress bar of data transfers to the terminal
+pub fn progress_bar() -> impl FnMut(ProgressEvent) + Send + 'static {
+    let mut bar: Option<ProgressBar> = None;
+    move |event| match event {
+        ProgressEvent::Started {
+            command,
+            direction,
+            total,
+        } => {
+            let new_bar = ProgressBar::new(total);
+            new_bar.set_style(
+                ProgressStyle::with_template("{prefix} [{bar:40}] {binary_bytes:>}/{binary_total_bytes}")
+                    .unwrap()
+                    .progress_chars("##-"),
+            );
+            new_bar.set_prefix(match (command, direction) {
+                (Some(CommandTagDiscriminants::WriteMemory), _) => "Writing memory",
+                (Some(CommandTagDiscriminants::ReadMemory), _) => "Reading memory",
+                (Some(CommandTagDiscriminants::FuseProgram), _) => "Programming fuses",
+                (Some(CommandTagDiscriminants::FuseRead), _) => "Reading fuses",
+                (Some(CommandTagDiscriminants::ReceiveSBFile), _) => "Sending SB file",
+                (Some(CommandTagDiscriminants::NoCommand), _) => "Loading image",
+                (_, TransferDirection::Send) => "Sending data",
+                (_, TransferDirection::Receive) => "Receiving data",
+            });
+            bar = Some(new_bar);
+        }
+        ProgressEvent::Chunk { transferred, .. } => {
+            if let Some(bar) = &bar {
+                bar.set_position(transferred);
+            }
+        }
+        ProgressEvent::Finished { .. } => {
+            if let Some(bar) = bar.take() {
+                bar.finish();
+            }
+        }
+        ProgressEvent::Aborted { .. } => {
+            if let Some(bar) = bar.take() {
+                bar.abandon();
+            }
+        }
+    }
+}
+
+/// Row of the memory table, with the same columns for all kinds of memories
+struct MemoryRow {
+    name: &'static str,
+    memory_id: u32,
+    start_address: Option<u32>,
+    size: Option<u64>,
+    page_size: Option<u32>,
+    sector_size: Option<u32>,
+    block_size: Option<u32>,
+}
+
+impl From<&MemoryInfo> for MemoryRow {
+    fn from(memory: &MemoryInfo) -> Self {
+        match *memory {
+            MemoryInfo::InternalFlash {
+                start_address,
+                size,
+                sector_size,
+            } => MemoryRow {
+                name: "Internal Flash",
+                memory_id: mem_id::INTERNAL_MEMORY,
+                start_address: Some(start_address),
+                size: Some(size.into()),
+                page_size: None,
+                sector_size,
+                block_size: None,
+            },
+            MemoryInfo::InternalRam { start_address, size } => MemoryRow {
+                name: "Internal RAM",
+                memory_id: mem_id::INTERNAL_MEMORY,
+                start_address: Some(start_address),
+                size: Some(size.into()),
+                page_size: None,
+                sector_size: None,
+                block_size: None,
+            },
+            MemoryInfo::External {
+                memory_id,
+                name,
+                attributes,
+            } => MemoryRow {
+                name,
+                memory_id,
+                start_address: attributes.start_address(),
+                size: attributes.total_size(),
+                page_size: attributes.page_size(),
+                sector_size: attributes.sector_size(),
+                block_size: attributes.block_size(),
+            },
+        }
+    }
+}
+
+fn size_cell(size: Option<u64>) -> String {
+    size.map_or_else(|| "-".to_owned(), |size| BinaryBytesOne(size).to_string())
+}
+
+/// Returns the property as JSON object with its tag, name and value
+fn property_json(response: &GetPropertyResponse) -> Value {
+    let tag = PropertyTagDiscriminants::from(&response.property);
+    let text = response.property.to_string();
+    let value = text.split_once(" = ").map_or(text.as_str(), |(_, value)| value);
+    json!({
+        "tag": tag as u8,
+        "name": format!("{tag:?}"),
+        "value": value,
+    })
+}
+
+fn status_json(status: StatusCode) -> Value {
+    json!({
+        "code": u32::from(status),
+        "name": format!("{status:?}"),
+        "description": status.to_string(),
+    })
+}
+
+fn hex_string(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .fold(String::with_capacity(bytes.len() * 2), |mut hex, byte| {
+            let _ = write!(hex, "{byte:02x}");
+            hex
+        })
+}
+
+/// Layout of hexdumps printed with --use-hexdump
+#[derive(clap::Args, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct HexdumpLayout {
+    /// Bytes per line of the hexdump
+    #[arg(long, value_name = "BYTES", default_value_t = 16, value_parser = clap::value_parser!(u16).range(1..))]
+    hexdump_width: u16,
+    /// Bytes per group of the hexdump, groups are separated by an extra space, 0 disables grouping
+    #[arg(long, value_name = "BYTES", default_value_t = 8)]
+    hexdump_group: u16,
+}
+
+impl HexdumpLayout {
+    /// Configuration of the hexdump, lines are labeled with `base_address` plus the offset of their first byte
+    pub fn config(self, base_address: u32) -> HexConfig {
+        HexConfig {
+            title: false,
+            group: self.hexdump_group.into(),
+            width: self.hexdump_width.into(),
+            ascii: true,
+            display_offset: base_address as usize,
+            ..HexConfig::default()
+        }
+    }
+}
+
+/// Returns JSON keys and labels of the response words of a trust provisioning operation
+fn trust_prov_fields(operation: &TrustProvOperation) -> &'static [(&'static str, &'static str)] {
+    match operation {
+        TrustProvOperation::OemGenMasterShare { .. } => &[
+            ("oem_share_size", "OEM Share size"),
+            ("oem_master_share_size", "OEM Master Share size"),
+            ("cust_cert_puk_size", "Cust Cert Puk size"),
+        ],
+        TrustProvOperation::OemGetCustCertDicePuk { .. } => &[("cust_cert_dice_puk_size", "Cust Cert DICE Puk size")],
+        TrustProvOperation::HsmGenKey { .. } => {
+            &[("key_blob_size", "Key Blob size"), ("ecdsa_puk_size", "ECDSA Puk size")]
+        }
+        TrustProvOperation::HsmStoreKey { .. } => &[("key_header", "Key Header"), ("key_blob_size", "Key Blob size")],
+        TrustProvOperation::HsmEncSign { .. } | TrustProvOperation::DscHsmEncSign { .. } => {
+            &[("signature_size", "Signature size")]
+        }
+        TrustProvOperation::OemGetCustDiceResponse { .. } => &[("dice_response_size", "DICE Response size")],
+        TrustProvOperation::DscHsmCreateSession { .. } => &[("oem_share_size", "OEM Share size")],
+        TrustProvOperation::OemSetMasterShare { .. }
+        | TrustProvOperation::HsmEncBlk { .. }
+        | TrustProvOperation::DscHsmEncBlk { .. } => &[],
+    }
+}
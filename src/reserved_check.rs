@@ -0,0 +1,73 @@
+// Copyright 2025 NXP
+//
+// SPDX-License-Identifier: BSD-3-Clause
+//! Check of writes, fills and erases against regions reserved by the bootloader
+//!
+//! The bootloader keeps its stack and variables in reserved regions of RAM. Writes there crash it and the
+//! device mostly rejects them with a status which does not tell why. With --check-reserved, the reserved
+//! regions are requested before flash-erase-region, fill-memory and write-memory, and commands overlapping
+//! them are not sent unless they are given --force.
+
+use mboot::{formats::Segment, memory::mem_id};
+
+use crate::{Commands, WriteData};
+
+/// (`start_address`, `byte_count`, `memory_id`) ranges changed by the command
+///
+/// Empty for commands which do not change the memory or which are given --force.
+pub fn changed_ranges(command: &Commands) -> Vec<(u32, u32, u32)> {
+    match command {
+        Commands::FlashEraseRegion {
+            start_address,
+            byte_count,
+            memory_id,
+            force: false,
+        } => vec![(*start_address, *byte_count, *memory_id)],
+        Commands::FillMemory {
+            start_address,
+            byte_count,
+            force: false,
+            ..
+        } => vec![(*start_address, *byte_count, mem_id::INTERNAL_MEMORY)],
+        Commands::WriteMemory {
+            start_address,
+            data,
+            memory_id,
+            force: false,
+            ..
+        } => match data {
+            WriteData::Bytes(bytes) => vec![(*start_address, byte_count(bytes), *memory_id)],
+            WriteData::Segments(segments) => segments
+                .iter()
+                .map(|Segment { address, data }| (*address, byte_count(data), *memory_id))
+                .collect(),
+        },
+        _ => Vec::new(),
+    }
+}
+
+/// Length of written data, saturated as the device rejects larger writes anyway
+fn byte_count(data: &[u8]) -> u32 {
+    u32::try_from(data.len()).unwrap_or(u32::MAX)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::batch::parse_batch_script;
+
+    #[test]
+    fn ranges_of_commands() {
+        let script = "write-memory 0x20000000 {{010203}}\nfill-memory 0x20000100 8 0 --force\n\
+                      flash-erase-region 0x1000 0x2000 1\nreset\n";
+        let ranges: Vec<_> = parse_batch_script(script)
+            .unwrap()
+            .iter()
+            .map(|command| changed_ranges(&command.command))
+            .collect();
+        assert_eq!(
+            ranges,
+            [vec![(0x2000_0000, 3, 0)], vec![], vec![(0x1000, 0x2000, 1)], vec![]]
+        );
+    }
+}
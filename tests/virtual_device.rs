@@ -0,0 +1,708 @@
+// Copyright 2025 NXP
+//
+// SPDX-License-Identifier: BSD-3-Clause
+use std::time::Duration;
+
+use mboot::{
+    CommunicationError, McuBoot,
+    checksum::ChecksumAlgorithm,
+    formats::Segment,
+    memory::mem_id,
+    progress::ProgressEvent,
+    protocols::{
+        Protocol,
+        mock::{CMD, DATA, MockProtocol, VirtualDevice, command_payload, frame},
+    },
+    scratch::ScratchAllocator,
+    session::{PhaseTimeouts, RetryPolicy, SessionConfig},
+    tags::{
+        command::{CommandTagDiscriminants, TrustProvOperation},
+        property::{Family, PeripheryTag, PropertyTag, PropertyTagDiscriminants},
+        status::StatusCode,
+    },
+    warning::Warning,
+};
+use proptest::prelude::*;
+
+#[test]
+fn get_property_packets() {
+    let device = MockProtocol::new([])
+        .expect_command(0x07, 0, &[0x01, 0])
+        .respond(CMD, command_payload(0xA7, 0, &[0, 0x4B03_0100]));
+    let mut boot = McuBoot::new(device);
+
+    let response = boot.get_property(PropertyTagDiscriminants::CurrentVersion, 0).unwrap();
+    let PropertyTag::CurrentVersion(version) = response.property else {
+        panic!("unexpected property {:?}", response.property);
+    };
+    assert_eq!(version.to_string(), "K3.1.0");
+    boot.device().assert_finished();
+}
+
+#[test]
+fn get_property_is_retried() {
+    let device = MockProtocol::new([])
+        .expect_command(0x07, 0, &[0x01, 0])
+        .fail(CommunicationError::Timeout)
+        .expect_command(0x07, 0, &[0x01, 0])
+        .respond(CMD, command_payload(0xA7, 0, &[0, 0x4B03_0100]));
+    let retry = RetryPolicy {
+        retries: 1,
+        delay: Duration::ZERO,
+    };
+    let mut boot = McuBoot::with_config(device, SessionConfig::new().with_retry(retry));
+    boot.get_property(PropertyTagDiscriminants::CurrentVersion, 0).unwrap();
+    boot.device().assert_finished();
+    assert_eq!(boot.retries(), 1);
+    assert!(boot.take_last_outcome().is_some());
+    assert_eq!(boot.last_outcome(), None);
+
+    let device = MockProtocol::new([])
+        .expect_command(0x07, 0, &[0x01, 0])
+        .fail(CommunicationError::Timeout);
+    let mut boot = McuBoot::new(device);
+    assert!(matches!(
+        boot.get_property(PropertyTagDiscriminants::CurrentVersion, 0),
+        Err(CommunicationError::Timeout)
+    ));
+    assert_eq!(boot.retries(), 0);
+}
+
+#[test]
+fn family_changes_property_codes() {
+    let device = MockProtocol::new([])
+        .expect_command(0x07, 0, &[0x0A, 0])
+        .respond(CMD, command_payload(0xA7, 0, &[0, 1]));
+    let mut boot = McuBoot::with_config(device, SessionConfig::new().with_family(Some(Family::Kw45xx)));
+
+    let response = boot.get_property(PropertyTagDiscriminants::VerifyErase, 0).unwrap();
+    assert!(matches!(response.property, PropertyTag::VerifyErase(true)));
+    boot.device().assert_finished();
+}
+
+#[test]
+fn all_properties_skip_unsupported() {
+    let mut boot = McuBoot::new(VirtualDevice::new());
+    let tags: Vec<_> = boot
+        .get_all_properties(0)
+        .unwrap()
+        .iter()
+        .map(|response| PropertyTagDiscriminants::from(&response.property))
+        .collect();
+    assert_eq!(tags.first(), Some(&PropertyTagDiscriminants::CurrentVersion));
+    assert!(tags.contains(&PropertyTagDiscriminants::TargetVersion));
+    assert!(!tags.contains(&PropertyTagDiscriminants::VerifyErase));
+
+    let device = VirtualDevice::new().with_property(PropertyTagDiscriminants::VerifyWrites, &[1]);
+    let mut boot = McuBoot::new(device);
+    boot.config_mut().family = Some(Family::Kw45xx);
+    let responses = boot.get_all_properties(0).unwrap();
+    assert!(
+        responses
+            .iter()
+            .any(|response| matches!(response.property, PropertyTag::VerifyErase(true)))
+    );
+    assert!(
+        !responses
+            .iter()
+            .any(|response| matches!(response.property, PropertyTag::VerifyWrites(_)))
+    );
+}
+
+#[test]
+fn capabilities_are_cached() {
+    let mut boot = McuBoot::new(VirtualDevice::new());
+    let capabilities = boot.capabilities().unwrap();
+    assert!(capabilities.supports(CommandTagDiscriminants::WriteMemory));
+    assert!(!capabilities.supports(CommandTagDiscriminants::KeyProvisioning));
+    assert!(capabilities.has_peripheral(PeripheryTag::Uart));
+    assert_eq!(capabilities.max_packet_size, Some(32));
+    let queries = boot.device().commands().len();
+
+    boot.write_memory(0x2000_0000, 0, &[1; 40]).unwrap();
+    assert!(boot.capabilities().unwrap().version.is_some());
+    assert_eq!(boot.device().commands().len(), queries + 1);
+
+    boot.reset().unwrap();
+    boot.capabilities().unwrap();
+    assert_eq!(boot.device().commands().len(), 2 * queries + 2);
+}
+
+#[test]
+fn write_memory_packets() {
+    let device = MockProtocol::new([])
+        .expect_command(0x04, 1, &[0x2000_0000, 6, 0])
+        .respond_generic(StatusCode::Success, 0x04)
+        .expect_data(&[1, 2, 3, 4])
+        .expect_data(&[5, 6])
+        .respond_generic(StatusCode::Success, 0x04);
+    let mut boot = McuBoot::with_config(device, SessionConfig::new().with_max_packet_size(Some(4)));
+
+    boot.write_memory(0x2000_0000, 0, &[1, 2, 3, 4, 5, 6]).unwrap();
+    boot.device().assert_finished();
+}
+
+#[test]
+fn command_specific_success_status() {
+    let device = MockProtocol::new([])
+        .expect_command(0x09, 0, &[0x1000, 0, 0x2000])
+        .respond_generic(StatusCode::AppCrcCheckPassed, 0x09)
+        .expect_command(0x0A, 0, &[0x1000, 0])
+        .respond_generic(StatusCode::OtpCrcCheckPass, 0x0A);
+    let mut boot = McuBoot::new(device);
+
+    assert_eq!(boot.execute(0x1000, 0, 0x2000).unwrap(), StatusCode::AppCrcCheckPassed);
+    assert!(matches!(
+        boot.call(0x1000, 0),
+        Err(CommunicationError::UnexpectedStatus(StatusCode::OtpCrcCheckPass, _))
+    ));
+    boot.device().assert_finished();
+}
+
+#[test]
+fn raw_frames_are_transacted() {
+    let get_property = frame(CMD, &command_payload(0x07, 0, &[0x01, 0]));
+    let mut boot = McuBoot::new(VirtualDevice::new());
+    let response = boot.device_mut().transact_frame(&get_property).unwrap();
+    assert_eq!(response[..4], [0x5A, CMD, 12, 0]);
+    assert_eq!(response[6..10], [0xA7, 0, 0, 2]);
+
+    let mut corrupted = get_property;
+    corrupted[4] ^= 0xFF;
+    assert!(matches!(
+        boot.device_mut().transact_frame(&corrupted),
+        Err(CommunicationError::NACKSent)
+    ));
+    assert!(matches!(
+        boot.device_mut().transact_frame(&[0x5A]),
+        Err(CommunicationError::InvalidHeader)
+    ));
+}
+
+#[test]
+fn silent_device_times_out() {
+    let mut boot = McuBoot::new(MockProtocol::new([]).expect_command(0x0B, 0, &[]));
+    assert!(matches!(boot.reset(), Err(CommunicationError::Timeout)));
+}
+
+#[test]
+fn write_and_read_memory() {
+    let mut boot = McuBoot::new(VirtualDevice::new());
+    let data: Vec<u8> = (0..100).collect();
+
+    boot.write_memory(0x2000_0100, 0, &data).unwrap();
+    assert_eq!(boot.read_memory(0x2000_0100, 100, 0).unwrap().bytes.as_ref(), data);
+    assert_eq!(boot.device().memory(0x2000_0100, 100), data);
+
+    boot.fill_memory(0x1000, 6, 0xAABB_CCDD).unwrap();
+    assert_eq!(boot.device().memory(0x1000, 6), [0xDD, 0xCC, 0xBB, 0xAA, 0xDD, 0xCC]);
+    boot.flash_erase_region(0x1000, 0x2000, 0).unwrap();
+    assert_eq!(boot.device().memory(0x1000, 2), [0xFF, 0xFF]);
+}
+
+#[test]
+fn read_memory_is_streamed_into_writer() {
+    let mut boot = McuBoot::new(VirtualDevice::new());
+    let data: Vec<u8> = (0..=255).cycle().take(1000).collect();
+    boot.write_memory(0x2000_0000, 0, &data).unwrap();
+
+    let mut streamed = Vec::new();
+    let response = boot.read_memory_into(0x2000_0000, 1000, 0, &mut streamed).unwrap();
+    assert_eq!(streamed, data);
+    assert!(response.bytes.is_empty());
+    assert_eq!(response.response_words[..], [1000]);
+    assert_eq!(streamed, boot.read_memory(0x2000_0000, 1000, 0).unwrap().bytes.as_ref());
+}
+
+#[test]
+fn large_read_is_split_into_chunks() {
+    let mut boot = McuBoot::new(VirtualDevice::new());
+    let data: Vec<u8> = (0..=255).cycle().take(1000).collect();
+    boot.write_memory(0x2000_0000, 0, &data).unwrap();
+
+    let response = boot.read_memory_large(0x2000_0000, 1000, 0, 300).unwrap();
+    assert_eq!(response.bytes.as_ref(), data);
+    assert_eq!(response.response_words[..], [1000]);
+
+    let mut streamed = Vec::new();
+    boot.read_memory_large_into(0x2000_0010, 984, 0, 0, &mut streamed)
+        .unwrap();
+    assert_eq!(streamed, data[0x10..]);
+}
+
+#[test]
+fn invalid_range_is_rejected() {
+    let mut boot = McuBoot::new(VirtualDevice::new());
+    assert!(matches!(
+        boot.write_memory(0x1000_0000, 0, &[1]),
+        Err(CommunicationError::UnexpectedStatus(StatusCode::MemoryRangeInvalid, _))
+    ));
+    assert!(boot.read_memory(0x1000_0000, 4, 0).is_err());
+}
+
+#[test]
+fn properties_and_otp() {
+    let device = VirtualDevice::new().with_property(PropertyTagDiscriminants::MaxPacketSize, &[512]);
+    let mut boot = McuBoot::new(device);
+
+    let response = boot
+        .get_property(PropertyTagDiscriminants::AvailableCommands, 0)
+        .unwrap();
+    let PropertyTag::AvailableCommands(commands) = response.property else {
+        panic!("unexpected property {:?}", response.property);
+    };
+    assert!(commands.contains(&CommandTagDiscriminants::ReceiveSBFile));
+    assert!(matches!(
+        boot.get_property(PropertyTagDiscriminants::MaxPacketSize, 0)
+            .unwrap()
+            .property,
+        PropertyTag::MaxPacketSize(512)
+    ));
+
+    boot.set_property(PropertyTagDiscriminants::VerifyWrites, 0).unwrap();
+    assert!(boot.set_property(PropertyTagDiscriminants::CurrentVersion, 0).is_err());
+
+    boot.flash_program_once(0x10, 4, 0x1234_5678, true).unwrap();
+    assert_eq!(boot.flash_read_once(0x10, 4).unwrap(), 0x1234_5678);
+}
+
+#[test]
+fn receive_sb_file() {
+    let mut boot = McuBoot::new(VirtualDevice::new());
+    let data = vec![0x5A; 300];
+
+    boot.receive_sb_file(&data).unwrap();
+    assert_eq!(boot.device().streamed_data(), data);
+    assert_eq!(boot.device().commands().last(), Some(&(0x08, vec![300])));
+}
+
+#[test]
+fn receive_sb_file_is_resumed() {
+    let mut boot = McuBoot::new(VirtualDevice::new());
+    let data: Vec<u8> = (0..=255).cycle().take(300).collect();
+
+    boot.receive_sb_file_resume(&data, 100).unwrap();
+    assert_eq!(boot.device().streamed_data(), &data[100..]);
+    assert_eq!(boot.device().commands().last(), Some(&(0x08, vec![200])));
+
+    let commands = boot.device().commands().len();
+    assert!(matches!(
+        boot.receive_sb_file_resume(&data, 301),
+        Err(CommunicationError::ParseError(_))
+    ));
+    assert_eq!(boot.device().commands().len(), commands);
+}
+
+#[test]
+fn execute_with_args_blob() {
+    let mut boot = McuBoot::new(VirtualDevice::new());
+    let args = [1, 2, 3, 4, 5, 6, 7, 8];
+
+    boot.execute_with_args(0x1000, &args, Some(0x2000_1000), 0).unwrap();
+    assert_eq!(boot.device().memory(0x2000_1000, args.len()), args);
+    assert_eq!(
+        boot.device().commands().last(),
+        Some(&(0x09, vec![0x1000, 0x2000_1000, 0]))
+    );
+
+    boot.execute_with_args(0x1000, &args, None, 0x2000_8000).unwrap();
+    assert_eq!(
+        boot.device().commands().last(),
+        Some(&(0x09, vec![0x1000, 0x2000_0000, 0x2000_8000]))
+    );
+
+    // the blob would overlap the end of RAM
+    assert!(matches!(
+        boot.execute_with_args(0x1000, &args, Some(0x2003_FFFC), 0),
+        Err(CommunicationError::ParseError(_))
+    ));
+}
+
+#[test]
+fn injected_failure() {
+    let device = VirtualDevice::new().with_failure(0x0B, StatusCode::InvalidArgument);
+    let mut boot = McuBoot::new(device);
+    assert!(matches!(
+        boot.reset(),
+        Err(CommunicationError::UnexpectedStatus(StatusCode::InvalidArgument, _))
+    ));
+}
+
+#[test]
+fn scratch_from_device() {
+    let device = VirtualDevice::new().with_property(
+        PropertyTagDiscriminants::ReservedRegions,
+        &[0, 0x1FFF, 0x2000_0000, 0x2000_3FFF],
+    );
+    let mut boot = McuBoot::new(device);
+
+    let mut scratch = ScratchAllocator::from_device(&mut boot).unwrap();
+    assert_eq!(scratch.free_regions(), [(0x2000_4000, 0x3_C000)]);
+    let buffer = scratch.allocate(0x100, 4).unwrap();
+    boot.write_memory(buffer, 0, &[1, 2, 3, 4]).unwrap();
+    assert_eq!(scratch.allocate(0x100, 4), Some(buffer + 0x100));
+}
+
+#[test]
+fn erase_padding_is_reported() {
+    let mut boot = McuBoot::new(VirtualDevice::new());
+    let segments = [Segment {
+        address: 0x2100,
+        data: vec![0x5A; 0x100],
+    }];
+
+    boot.flash_image(&segments, true, 0).unwrap();
+    assert_eq!(
+        boot.take_warnings(),
+        [Warning::ErasePadded {
+            start_address: 0x2000,
+            byte_count: 0x2000,
+            data_bytes: 0x100
+        }]
+    );
+    assert!(boot.take_warnings().is_empty());
+
+    let segments = [Segment {
+        address: 0x4000,
+        data: vec![0x5A; 0x2000],
+    }];
+    boot.flash_image(&segments, true, 0).unwrap();
+    assert!(boot.take_warnings().is_empty());
+}
+
+#[test]
+fn external_memory_is_erased_by_its_sectors() {
+    // sector size is the fifth word of the attributes, 0x1000 unlike 0x2000 of the internal flash
+    let device = VirtualDevice::new().with_property(
+        PropertyTagDiscriminants::ExternalMemoryAttributes,
+        &[0x08, 0, 0, 0, 0x1000, 0],
+    );
+    let mut boot = McuBoot::new(device);
+    let segments = [Segment {
+        address: 0x2100,
+        data: vec![0x5A; 0x100],
+    }];
+
+    boot.flash_image(&segments, true, mem_id::FLEX_SPI_NOR).unwrap();
+    assert_eq!(
+        boot.take_warnings(),
+        [Warning::ErasePadded {
+            start_address: 0x2000,
+            byte_count: 0x1000,
+            data_bytes: 0x100
+        }]
+    );
+    let commands = boot.device().commands();
+    assert!(commands.contains(&(0x07, vec![0x19, mem_id::FLEX_SPI_NOR])));
+    assert!(commands.contains(&(0x02, vec![0x2000, 0x1000, mem_id::FLEX_SPI_NOR])));
+}
+
+#[test]
+fn written_memory_is_verified() {
+    let device = VirtualDevice::new().with_property(
+        PropertyTagDiscriminants::ReservedRegions,
+        &[0, 0x1FFF, 0x2000_0000, 0x2000_00FF],
+    );
+    let mut boot = McuBoot::new(device);
+    let data: Vec<u8> = (0..=255).cycle().take(0x1800).collect();
+
+    boot.write_memory_verified(0x2000_0000, 0, &data).unwrap();
+    assert_eq!(boot.verify_memory(0x2000_0000, 0, &data).unwrap(), 0x1700);
+
+    let mut changed = data.clone();
+    changed[0x1234] ^= 1;
+    match boot.verify_memory(0x2000_0000, 0, &changed) {
+        Err(CommunicationError::VerifyMismatch {
+            address: 0x2000_1234,
+            window_address: 0x2000_1230,
+            expected,
+            actual,
+        }) => {
+            assert_eq!(expected[..], changed[0x1230..0x1270]);
+            assert_eq!(actual[..], data[0x1230..0x1270]);
+        }
+        result => panic!("unexpected result {result:?}"),
+    }
+    // bytes in the reserved region are not compared
+    changed[0x1234] ^= 1;
+    changed[0x10] ^= 1;
+    boot.verify_memory(0x2000_0000, 0, &changed).unwrap();
+    assert!(boot.take_warnings().is_empty());
+
+    let device = VirtualDevice::new().with_failure(0x03, StatusCode::MemoryRangeInvalid);
+    let mut boot = McuBoot::new(device);
+    assert_eq!(
+        boot.write_memory_verified(0x2000_0000, 0, &[1, 2, 3]).unwrap(),
+        StatusCode::Success
+    );
+    assert_eq!(
+        boot.take_warnings(),
+        [Warning::VerifySkipped {
+            start_address: 0x2000_0000,
+            byte_count: 3
+        }]
+    );
+}
+
+#[test]
+fn erased_pages_are_verified() {
+    // the device refuses to read erased pages, the final status tells why no data were sent
+    let device = MockProtocol::new([])
+        .expect_command(0x03, 0, &[0x2000, 0x20, 1])
+        .respond(CMD, command_payload(0xA3, 1, &[0, 0]))
+        .respond_generic(StatusCode::MemoryBlankPageReadDisallowed, 0x03)
+        .expect_command(0x03, 0, &[0x2000, 0x20, 1])
+        .respond(CMD, command_payload(0xA3, 1, &[0, 0]))
+        .respond_generic(StatusCode::MemoryBlankPageReadDisallowed, 0x03);
+    let mut boot = McuBoot::new(device);
+    assert_eq!(boot.verify_memory(0x2000, 1, &[0xFF; 0x20]).unwrap(), 0x20);
+    assert!(boot.take_warnings().is_empty());
+
+    let mut data = [0xFF; 0x20];
+    data[0x11] = 0;
+    match boot.verify_memory(0x2000, 1, &data) {
+        Err(CommunicationError::VerifyMismatch {
+            address: 0x2011,
+            window_address: 0x2010,
+            actual,
+            ..
+        }) => assert_eq!(actual[..], [0xFF; 0x10]),
+        result => panic!("unexpected result {result:?}"),
+    }
+    assert_eq!(boot.device().remaining(), 0);
+}
+
+#[test]
+fn blank_check() {
+    let mut boot = McuBoot::new(VirtualDevice::new());
+    boot.flash_erase_region(0x2000, 0x4000, 0).unwrap();
+    assert_eq!(boot.blank_check(0x2000, 0x4000, 0).unwrap(), None);
+
+    boot.write_memory(0x5001, 0, &[0x7F]).unwrap();
+    assert_eq!(boot.blank_check(0x2000, 0x4000, 0).unwrap(), Some(0x5001));
+    assert_eq!(boot.blank_check(0x2000, 0x3001, 0).unwrap(), None);
+}
+
+#[test]
+fn checksum_of_memory() {
+    let mut boot = McuBoot::new(VirtualDevice::new());
+    boot.write_memory(0x2000_0000, 0, b"123456789").unwrap();
+
+    let checksum = boot
+        .checksum_region(0x2000_0000, 9, 0, ChecksumAlgorithm::Crc32)
+        .unwrap();
+    assert_eq!(*checksum, [0xCB, 0xF4, 0x39, 0x26]);
+    let checksum = boot
+        .checksum_region(0x2000_0000, 0x2_0000, 0, ChecksumAlgorithm::Sha256)
+        .unwrap();
+    assert_eq!(checksum.len(), 32);
+}
+
+#[test]
+fn reserved_regions_are_checked() {
+    let device = VirtualDevice::new().with_property(
+        PropertyTagDiscriminants::ReservedRegions,
+        &[0, 0x1FFF, 0x2000_0000, 0x2000_3FFF],
+    );
+    let mut boot = McuBoot::new(device);
+
+    boot.check_reserved(0x2000_4000, 0x100, 0).unwrap();
+    boot.check_reserved(0x2000_0000, 0x100, 1).unwrap();
+    assert!(matches!(
+        boot.check_reserved(0x2000_3F00, 0x200, 0),
+        Err(CommunicationError::ReservedRegion {
+            start_address: 0x2000_0000,
+            end_address: 0x2000_3FFF
+        })
+    ));
+
+    let mut boot = McuBoot::new(VirtualDevice::new());
+    boot.check_reserved(0x2000_0000, 0x100, 0).unwrap();
+}
+
+#[test]
+fn phase_timeouts() {
+    /// Records the timeout of every read packet
+    struct TimedDevice {
+        inner: VirtualDevice,
+        reads: Vec<(u8, Duration)>,
+    }
+
+    impl Protocol for TimedDevice {
+        fn get_timeout(&self) -> Duration {
+            self.inner.get_timeout()
+        }
+
+        fn set_timeout(&mut self, timeout: Duration) -> Result<(), CommunicationError> {
+            self.inner.set_timeout(timeout)
+        }
+
+        fn get_polling_interval(&self) -> Duration {
+            self.inner.get_polling_interval()
+        }
+
+        fn get_identifier(&self) -> &str {
+            self.inner.get_identifier()
+        }
+
+        fn read(&mut self, bytes: usize) -> Result<Vec<u8>, CommunicationError> {
+            self.inner.read(bytes)
+        }
+
+        fn write_packet_raw(&mut self, data: &[u8]) -> Result<(), CommunicationError> {
+            self.inner.write_packet_raw(data)
+        }
+
+        fn read_packet_raw(&mut self, packet_code: u8) -> Result<Vec<u8>, CommunicationError> {
+            self.reads.push((packet_code, self.inner.get_timeout()));
+            self.inner.read_packet_raw(packet_code)
+        }
+    }
+
+    let device = TimedDevice {
+        inner: VirtualDevice::new(),
+        reads: Vec::new(),
+    };
+    let timeouts = PhaseTimeouts {
+        ack: None,
+        data: Some(Duration::from_secs(2)),
+        erase: Some(Duration::from_secs(60)),
+    };
+    let mut boot = McuBoot::with_config(device, SessionConfig::new().with_timeouts(timeouts));
+    let default = boot.device().get_timeout();
+
+    boot.flash_erase_region(0, 0x2000, 0).unwrap();
+    assert_eq!(boot.device().reads, [(CMD, Duration::from_secs(60))]);
+    boot.read_memory(0x2000_0000, 64, 0).unwrap();
+    assert_eq!(
+        boot.device().reads[1..],
+        [
+            (CMD, default),
+            (DATA, Duration::from_secs(2)),
+            (DATA, Duration::from_secs(2)),
+            (CMD, default)
+        ]
+    );
+    assert_eq!(boot.device().get_timeout(), default);
+}
+
+#[test]
+fn key_store_persistence_is_verified() {
+    fn read_key_store(device: MockProtocol, key_store: &[u8]) -> MockProtocol {
+        let length = u32::try_from(key_store.len()).unwrap();
+        device
+            .expect_command(0x15, 0, &[6])
+            .respond(CMD, command_payload(0xB5, 1, &[0, length]))
+            .respond(DATA, key_store.to_vec())
+            .respond_generic(StatusCode::Success, 0x15)
+    }
+
+    let device = read_key_store(MockProtocol::new([]), &[1, 2, 3, 4])
+        .expect_command(0x15, 0, &[3, 9])
+        .respond_generic(StatusCode::Success, 0x15)
+        .expect_command(0x15, 0, &[4, 9])
+        .respond_generic(StatusCode::Success, 0x15);
+    let mut boot = McuBoot::new(read_key_store(device, &[1, 2, 3, 0]));
+
+    assert_eq!(boot.write_key_nonvolatile(9, true).unwrap(), StatusCode::Success);
+    assert_eq!(boot.take_warnings(), [Warning::KeyStoreMismatch { memory_id: 9 }]);
+    boot.device().assert_finished();
+}
+
+#[test]
+fn trust_provisioning_hsm_operations() {
+    let device = MockProtocol::new([])
+        .expect_command(0x16, 0, &[3, 1, 0, 0x2000_0000, 0x80, 0x2000_0100, 0x40])
+        .respond(CMD, command_payload(0xB6, 0, &[0, 0x60, 0x40]))
+        .expect_command(0x16, 0, &[11, 0x2000_0000, 0x100, 0x2000_0200, 0x40])
+        .respond(CMD, command_payload(0xB6, 0, &[0, 0x40]));
+    let mut boot = McuBoot::new(device);
+
+    let gen_key = TrustProvOperation::HsmGenKey {
+        key_type: 1,
+        reserved: 0,
+        key_blob_output_addr: 0x2000_0000,
+        key_blob_output_size: 0x80,
+        ecdsa_puk_output_addr: 0x2000_0100,
+        ecdsa_puk_output_size: 0x40,
+    };
+    let (status, words) = boot.trust_provisioning(&gen_key).unwrap();
+    assert_eq!((status, &*words), (StatusCode::Success, &[0x60, 0x40][..]));
+
+    let sign = TrustProvOperation::DscHsmEncSign {
+        block_data_input_addr: 0x2000_0000,
+        block_data_input_size: 0x100,
+        signature_output_addr: 0x2000_0200,
+        signature_output_size: 0x40,
+    };
+    assert_eq!(*boot.trust_provisioning(&sign).unwrap().1, [0x40]);
+    boot.device().assert_finished();
+}
+
+#[test]
+fn key_store_is_read_without_file() {
+    let device = MockProtocol::new([])
+        .expect_command(0x15, 0, &[6])
+        .respond(CMD, command_payload(0xB5, 1, &[0, 3]))
+        .respond(DATA, vec![7, 8, 9])
+        .respond_generic(StatusCode::Success, 0x15);
+    let mut boot = McuBoot::new(device);
+
+    assert_eq!(boot.read_key_store().unwrap(), [7, 8, 9]);
+    boot.device().assert_finished();
+}
+
+#[test]
+fn data_phases_are_aborted() {
+    let mut boot = McuBoot::new(VirtualDevice::new());
+    let abort = boot.abort_handle();
+    assert!(!abort.abort());
+    let handle = abort.clone();
+    boot.set_progress_callback(move |event| {
+        if let ProgressEvent::Chunk { transferred: 32, .. } = event {
+            assert!(handle.abort());
+        }
+    });
+
+    let err = boot.write_memory(0x2000_0000, 0, &[0xA5; 0x100]).unwrap_err();
+    assert!(matches!(err, CommunicationError::Aborted));
+    assert_eq!(boot.acked_bytes(), 32);
+    let err = boot.read_memory(0x2000_0000, 0x100, 0).unwrap_err();
+    assert!(matches!(err, CommunicationError::Aborted));
+    let err = boot.receive_sb_file(&[0x5A; 0x100]).unwrap_err();
+    assert!(matches!(err, CommunicationError::Aborted));
+    assert_eq!(boot.acked_bytes(), 32);
+
+    // the device is ready for the next command
+    boot.set_progress_callback(|_| {});
+    let response = boot.read_memory(0x2000_0000, 0x40, 0).unwrap();
+    assert_eq!(response.bytes[..], [0xFF; 0x40]);
+    assert!(!abort.abort());
+}
+
+proptest! {
+    #[test]
+    fn generic_responses_round_trip(status in 0u32..100_000, params in prop::collection::vec(any::<u32>(), 0..6)) {
+        let mut response = vec![status, 0x0B];
+        response.extend(&params);
+        let device = MockProtocol::new([])
+            .expect_command(0x0B, 0, &[])
+            .respond(CMD, command_payload(0xA0, 0, &response));
+        let mut boot = McuBoot::new(device);
+
+        match boot.reset() {
+            Ok(status) => prop_assert_eq!(status, StatusCode::Success),
+            Err(CommunicationError::UnexpectedStatus(_, code)) => prop_assert_eq!(code, status),
+            Err(err) => prop_assert!(false, "unexpected error {err}"),
+        }
+    }
+
+    #[test]
+    fn arbitrary_responses_do_not_panic(flag in 0u8..2, mut payload in prop::collection::vec(any::<u8>(), 0..64)) {
+        if let Some(byte) = payload.get_mut(1) {
+            *byte = flag;
+        }
+        let device = MockProtocol::new([]).expect_command(0x0B, 0, &[]).respond(CMD, payload);
+        let _ = McuBoot::new(device).reset();
+    }
+}